@@ -0,0 +1,188 @@
+//! Sequential vs. parallel execution of a full 2B-gas block of synthetic transfers.
+//!
+//! Two workloads: `independent` sends every transfer between a fresh pair of accounts, so no
+//! transaction depends on another; `hot-recipients` sends everything to a handful of accounts, so
+//! most transactions conflict and have to be re-executed. Sequential runs execute and commit every
+//! transaction in turn. Parallel runs execute builder-sized batches with earlier batches' writes
+//! carried over and commit each result the way the payload builder does, executing it again when
+//! it no longer applies. Per-transaction results are checked against the sequential run before
+//! timings are printed.
+//!
+//! Run with `cargo bench --features parallel-exec --bench parallel_exec`.
+
+use std::{collections::HashMap, hint::black_box, sync::Arc, time::Instant};
+
+use alloy_consensus::{transaction::Recovered, Header, SignableTransaction, TxEip1559};
+use alloy_primitives::{Address, Signature, TxKind, U256};
+use load_reth::{
+    chainspec::{LoadChainSpec, LOAD_EXECUTION_GAS_LIMIT},
+    engine::parallel::{
+        execute_parallel,
+        speculative::{EvmBatch, EvmStateKey, EvmStateValue},
+        state::SharedStateCache,
+        ParallelStats, DEFAULT_PARALLEL_BATCH_SIZE,
+    },
+    evm::LoadEvmConfig,
+};
+use reth::revm::{
+    context_interface::result::ExecutionResult,
+    database_interface::WrapDatabaseRef,
+    db::{CacheDB, EmptyDB},
+    state::AccountInfo,
+    DatabaseCommit, State,
+};
+use reth_ethereum_primitives::TransactionSigned;
+use reth_evm::{ConfigureEvm, Evm, EvmEnvFor};
+
+const TRANSFER_GAS: u64 = 21_000;
+const HOT_RECIPIENTS: u64 = 8;
+const THREADS: [usize; 4] = [2, 4, 8, 16];
+
+struct Workload {
+    name: &'static str,
+    transactions: Vec<Recovered<TransactionSigned>>,
+    state: SharedStateCache<CacheDB<EmptyDB>>,
+}
+
+fn address(n: u64) -> Address {
+    let mut bytes = [0u8; 20];
+    bytes[..8].copy_from_slice(&(n + 1).to_be_bytes());
+    Address::from(bytes)
+}
+
+/// One transfer per sender, filling the block gas limit. The signature is never checked since
+/// the sender is supplied directly.
+fn workload(name: &'static str, chain_id: u64, recipient: impl Fn(u64) -> Address) -> Workload {
+    let count = LOAD_EXECUTION_GAS_LIMIT / TRANSFER_GAS;
+    let mut db = CacheDB::new(EmptyDB::default());
+    let transactions = (0..count)
+        .map(|n| {
+            let sender = address(n);
+            db.insert_account_info(
+                sender,
+                AccountInfo { balance: U256::from(u128::MAX), ..Default::default() },
+            );
+            let tx = TxEip1559 {
+                chain_id,
+                nonce: 0,
+                gas_limit: TRANSFER_GAS,
+                max_fee_per_gas: 1_000_000_000,
+                max_priority_fee_per_gas: 1,
+                to: TxKind::Call(recipient(n)),
+                value: U256::from(1),
+                ..Default::default()
+            };
+            let signed: TransactionSigned = tx.into_signed(Signature::test_signature()).into();
+            Recovered::new_unchecked(signed, sender)
+        })
+        .collect();
+    Workload { name, transactions, state: SharedStateCache::new(db) }
+}
+
+/// Executes and commits every transaction in turn.
+fn run_sequential(
+    evm_config: &LoadEvmConfig,
+    evm_env: &EvmEnvFor<LoadEvmConfig>,
+    workload: &Workload,
+) -> Vec<ExecutionResult> {
+    let mut db = State::builder().with_database(WrapDatabaseRef(&workload.state)).build();
+    let mut evm = evm_config.evm_with_env(&mut db, evm_env.clone());
+    workload
+        .transactions
+        .iter()
+        .map(|tx| evm.transact_commit(evm_config.tx_env(tx.as_recovered_ref())).expect("transfer"))
+        .collect()
+}
+
+/// Executes the workload in builder-sized batches and commits the results, executing again the
+/// ones that no longer apply. Returns per-transaction results, total stats and the fallback count.
+fn run_parallel(
+    evm_config: &LoadEvmConfig,
+    evm_env: &EvmEnvFor<LoadEvmConfig>,
+    workload: &Workload,
+    threads: usize,
+) -> (Vec<ExecutionResult>, ParallelStats, usize) {
+    let mut db = State::builder().with_database(WrapDatabaseRef(&workload.state)).build();
+    let mut evm = evm_config.evm_with_env(&mut db, evm_env.clone());
+    let mut overlay: HashMap<EvmStateKey, EvmStateValue> = HashMap::new();
+    let mut results = Vec::with_capacity(workload.transactions.len());
+    let mut stats = ParallelStats::default();
+    let mut fallbacks = 0;
+    for batch in workload.transactions.chunks(DEFAULT_PARALLEL_BATCH_SIZE) {
+        let task =
+            EvmBatch::new(evm_config, evm_env, &workload.state, &overlay, Address::ZERO, batch);
+        let outcome = execute_parallel(&task, threads);
+        stats.executions += outcome.stats.executions;
+        stats.waves += outcome.stats.waves;
+        overlay.extend(outcome.writes);
+        for (tx, output) in batch.iter().zip(outcome.outputs) {
+            let committable =
+                output.and_then(|output| output.into_committable(evm.db_mut(), Address::ZERO));
+            let result = match committable {
+                Some(output) => {
+                    evm.db_mut().commit(output.state);
+                    output.result
+                }
+                None => {
+                    fallbacks += 1;
+                    evm.transact_commit(evm_config.tx_env(tx.as_recovered_ref())).expect("transfer")
+                }
+            };
+            results.push(result);
+        }
+    }
+    (results, stats, fallbacks)
+}
+
+fn main() {
+    let evm_config = LoadEvmConfig::new(Arc::new(LoadChainSpec::default()));
+    let header = Header {
+        gas_limit: LOAD_EXECUTION_GAS_LIMIT,
+        base_fee_per_gas: Some(1),
+        timestamp: 1,
+        ..Default::default()
+    };
+    let evm_env = evm_config.evm_env(&header).expect("evm env");
+    let chain_id = evm_env.cfg_env.chain_id;
+    let count = LOAD_EXECUTION_GAS_LIMIT / TRANSFER_GAS;
+
+    let workloads = [
+        workload("independent", chain_id, |n| address(count + n)),
+        workload("hot-recipients", chain_id, |n| address(count + n % HOT_RECIPIENTS)),
+    ];
+
+    for workload in &workloads {
+        // Warm the shared cache so every run measures execution, not state loading.
+        let expected = run_sequential(&evm_config, &evm_env, workload);
+
+        let start = Instant::now();
+        black_box(run_sequential(&evm_config, &evm_env, workload));
+        let sequential = start.elapsed();
+        println!(
+            "{:<16} {} txs  sequential   {:>8.1} ms",
+            workload.name,
+            workload.transactions.len(),
+            sequential.as_secs_f64() * 1e3,
+        );
+
+        for threads in THREADS {
+            let start = Instant::now();
+            let (results, stats, fallbacks) =
+                run_parallel(&evm_config, &evm_env, workload, threads);
+            let elapsed = start.elapsed();
+            assert_eq!(results, expected, "{}: parallel results differ", workload.name);
+            println!(
+                "{:<16} {} txs  {:>2} threads  {:>8.1} ms  {:>5.2}x  {} re-executions  {} waves  \
+                 {} fallbacks",
+                workload.name,
+                workload.transactions.len(),
+                threads,
+                elapsed.as_secs_f64() * 1e3,
+                sequential.as_secs_f64() / elapsed.as_secs_f64(),
+                stats.reexecutions(workload.transactions.len()),
+                stats.waves,
+                fallbacks,
+            );
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Allocation comparison for turning a full-cap payload into a `getPayloadV3` response.
+//!
+//! The legacy path copies every sidecar into the built payload, then clones the sidecars and the
+//! whole block again while converting to the envelope. The Load path keeps the pool's `Arc`
+//! sidecars and encodes the execution payload from the shared block without cloning it. The
+//! `block copy` row is the deep copy of the block alone, which the Load path no longer pays.
+//!
+//! Run with `cargo bench --bench payload_sidecars`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use alloy_consensus::{BlockBody, Header, SignableTransaction, TxEip4844};
+use alloy_eips::eip4844::{Blob, BlobTransactionSidecar, BlobTransactionSidecarVariant, Bytes48};
+use alloy_primitives::{Signature, B256, U256};
+use alloy_rpc_types_engine::{BlobsBundleV1, ExecutionPayloadEnvelopeV3, ExecutionPayloadV3};
+use load_reth::{chainspec::LOAD_MAX_BLOB_COUNT, engine::payload::LoadBuiltPayload};
+use reth_ethereum_engine_primitives::{BlobSidecars, EthBuiltPayload};
+use reth_ethereum_primitives::{Block, TransactionSigned};
+use reth_payload_primitives::BuiltPayload;
+use reth_primitives_traits::SealedBlock;
+
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ITERATIONS: u32 = 10;
+
+/// A block with one single-blob transaction per blob slot, and the matching pool sidecars.
+fn full_cap_payload() -> (Arc<SealedBlock<Block>>, Vec<Arc<BlobTransactionSidecarVariant>>) {
+    let blob_count = LOAD_MAX_BLOB_COUNT as usize;
+    let transactions = (0..blob_count as u64)
+        .map(|nonce| {
+            let tx = TxEip4844 {
+                chain_id: 16_383,
+                nonce,
+                gas_limit: 21_000,
+                max_fee_per_blob_gas: 1,
+                blob_versioned_hashes: vec![B256::with_last_byte(1)],
+                ..Default::default()
+            };
+            TransactionSigned::from(tx.into_signed(Signature::test_signature()))
+        })
+        .collect();
+    let block = Block {
+        header: Header {
+            base_fee_per_gas: Some(1),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        },
+        body: BlockBody { transactions, ommers: Vec::new(), withdrawals: Some(Default::default()) },
+    };
+    let sidecars = (0..blob_count)
+        .map(|_| {
+            Arc::new(BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::new(
+                vec![Blob::ZERO],
+                vec![Bytes48::ZERO],
+                vec![Bytes48::ZERO],
+            )))
+        })
+        .collect();
+    (Arc::new(SealedBlock::seal_slow(block)), sidecars)
+}
+
+/// Previous path: owned sidecars in `EthBuiltPayload`, block and sidecars cloned on conversion.
+fn legacy(
+    block: &Arc<SealedBlock<Block>>,
+    sidecars: &[Arc<BlobTransactionSidecarVariant>],
+) -> ExecutionPayloadEnvelopeV3 {
+    let mut owned = BlobSidecars::Empty;
+    for sidecar in sidecars {
+        owned.push_sidecar_variant(sidecar.as_ref().clone());
+    }
+    let payload = EthBuiltPayload::new(Default::default(), block.clone(), U256::ZERO, None)
+        .with_sidecars(owned);
+    let blobs_bundle = match payload.sidecars() {
+        BlobSidecars::Eip4844(sidecars) => BlobsBundleV1::from(sidecars.clone()),
+        _ => BlobsBundleV1::empty(),
+    };
+    ExecutionPayloadEnvelopeV3 {
+        execution_payload: ExecutionPayloadV3::from_block_unchecked(
+            payload.block().hash(),
+            &payload.block().clone().into_block(),
+        ),
+        block_value: payload.fees(),
+        should_override_builder: false,
+        blobs_bundle,
+    }
+}
+
+/// Load path: `Arc` sidecars in the built payload, copied once into the envelope.
+fn shared(
+    block: &Arc<SealedBlock<Block>>,
+    sidecars: &[Arc<BlobTransactionSidecarVariant>],
+) -> ExecutionPayloadEnvelopeV3 {
+    let payload = EthBuiltPayload::new(Default::default(), block.clone(), U256::ZERO, None);
+    let payload = LoadBuiltPayload::new(payload).with_sidecars(sidecars.to_vec());
+    payload.try_into().expect("full-cap payload converts to V3")
+}
+
+fn measure<T>(name: &str, f: impl Fn() -> T) {
+    ALLOCATED.store(0, Ordering::Relaxed);
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    let bytes = ALLOCATED.load(Ordering::Relaxed) / ITERATIONS as usize;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) / ITERATIONS as usize;
+    println!(
+        "{name:>10}: {:>8.1} MiB allocated, {allocations:>6} allocations, {elapsed:?} per payload",
+        bytes as f64 / (1024.0 * 1024.0),
+    );
+}
+
+fn main() {
+    let (block, sidecars) = full_cap_payload();
+    println!("getPayloadV3 for a {}-blob payload:", sidecars.len());
+    measure("legacy", || legacy(&block, &sidecars));
+    measure("shared", || shared(&block, &sidecars));
+    measure("block copy", || block.clone_block());
+}
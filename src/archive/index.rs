@@ -0,0 +1,527 @@
+//! Index from blob versioned hash to the block and transaction that carried it.
+//!
+//! Kept in its own MDBX environment next to the node's database, since reth's table set is fixed.
+//! On start the index backfills from its checkpoint to the head, then follows canonical
+//! notifications, dropping reverted blocks. A failed backfill or update is retried from the
+//! checkpoint with backoff; until a backfill succeeds the index is marked stalled.
+//!
+//! It is pruned together with reth's transaction lookup index (`--prune.transactionlookup.*`):
+//! once reth drops the hash lookups of a block, the block's blob inclusions go too.
+
+use std::{ops::RangeInclusive, path::Path, sync::Arc, time::Duration};
+
+use alloy_consensus::Transaction;
+use alloy_primitives::{BlockNumber, TxHash, B256};
+use reth::providers::{BlockReader, CanonStateNotifications, PruneCheckpointReader};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    mdbx::{init_db_for, DatabaseArguments},
+    models::ClientVersion,
+    table::{Compress, Decompress, Table, TableInfo, TableSet},
+    transaction::{DbTx, DbTxMut},
+    Database, DatabaseEnv, DatabaseError,
+};
+use reth_prune_types::PruneSegment;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::{
+    metrics::LoadBlobIndexMetrics,
+    primitives::{LoadBlock, LoadPrimitives, LoadTransactionSigned},
+};
+
+/// Blocks read and written per backfill transaction.
+const BACKFILL_BATCH_BLOCKS: u64 = 1_000;
+/// Blocks removed per prune pass; the rest follow on the next canonical update.
+const PRUNE_BATCH_BLOCKS: usize = 10_000;
+/// Key of the single checkpoint entry.
+const CHECKPOINT_KEY: u64 = 0;
+/// Delay before the first backfill retry; it doubles up to [`BACKFILL_RETRY_MAX`].
+const BACKFILL_RETRY_MIN: Duration = Duration::from_secs(1);
+/// Longest delay between backfill retries.
+const BACKFILL_RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Where a blob was included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadBlobInclusion {
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: BlockNumber,
+    pub block_hash: B256,
+    pub transaction_hash: TxHash,
+    #[serde(with = "alloy_serde::quantity")]
+    pub transaction_index: u64,
+    /// Position of the blob among all blobs of the block, as in `eth_getBlobSidecars`.
+    #[serde(with = "alloy_serde::quantity")]
+    pub blob_index: u64,
+}
+
+/// Versioned hashes a block added to the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockBlobs {
+    block_hash: B256,
+    versioned_hashes: Vec<B256>,
+}
+
+/// Number, hash and transactions of a block to index.
+pub type IndexedBlock<'a> = (BlockNumber, B256, &'a [LoadTransactionSigned]);
+
+/// Highest block the index covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexCheckpoint(BlockNumber);
+
+impl Compress for LoadBlobInclusion {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(&self, buf: &mut B) {
+        buf.put_u64(self.block_number);
+        buf.put_slice(self.block_hash.as_slice());
+        buf.put_slice(self.transaction_hash.as_slice());
+        buf.put_u64(self.transaction_index);
+        buf.put_u64(self.blob_index);
+    }
+}
+
+impl Decompress for LoadBlobInclusion {
+    fn decompress(value: &[u8]) -> Result<Self, DatabaseError> {
+        if value.len() != 8 + 32 + 32 + 8 + 8 {
+            return Err(DatabaseError::Decode);
+        }
+        let u64_at = |at: usize| u64::from_be_bytes(value[at..at + 8].try_into().unwrap());
+        Ok(Self {
+            block_number: u64_at(0),
+            block_hash: B256::from_slice(&value[8..40]),
+            transaction_hash: B256::from_slice(&value[40..72]),
+            transaction_index: u64_at(72),
+            blob_index: u64_at(80),
+        })
+    }
+}
+
+impl Compress for BlockBlobs {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(&self, buf: &mut B) {
+        buf.put_slice(self.block_hash.as_slice());
+        for versioned_hash in &self.versioned_hashes {
+            buf.put_slice(versioned_hash.as_slice());
+        }
+    }
+}
+
+impl Decompress for BlockBlobs {
+    fn decompress(value: &[u8]) -> Result<Self, DatabaseError> {
+        if value.len() < 32 || value.len() % 32 != 0 {
+            return Err(DatabaseError::Decode);
+        }
+        let mut hashes = value.chunks_exact(32).map(B256::from_slice);
+        let block_hash = hashes.next().ok_or(DatabaseError::Decode)?;
+        Ok(Self { block_hash, versioned_hashes: hashes.collect() })
+    }
+}
+
+impl Compress for IndexCheckpoint {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(&self, buf: &mut B) {
+        buf.put_u64(self.0);
+    }
+}
+
+impl Decompress for IndexCheckpoint {
+    fn decompress(value: &[u8]) -> Result<Self, DatabaseError> {
+        let bytes = value.try_into().map_err(|_| DatabaseError::Decode)?;
+        Ok(Self(u64::from_be_bytes(bytes)))
+    }
+}
+
+/// Versioned hash to inclusion.
+#[derive(Debug)]
+pub struct BlobInclusions;
+
+impl Table for BlobInclusions {
+    const NAME: &'static str = "BlobInclusions";
+    const DUPSORT: bool = false;
+    type Key = B256;
+    type Value = LoadBlobInclusion;
+}
+
+/// Block number to the versioned hashes it indexed; drives reverts and pruning.
+#[derive(Debug)]
+pub struct BlockBlobHashes;
+
+impl Table for BlockBlobHashes {
+    const NAME: &'static str = "BlockBlobHashes";
+    const DUPSORT: bool = false;
+    type Key = BlockNumber;
+    type Value = BlockBlobs;
+}
+
+/// Highest indexed block, under `CHECKPOINT_KEY`.
+#[derive(Debug)]
+pub struct BlobIndexCheckpoint;
+
+impl Table for BlobIndexCheckpoint {
+    const NAME: &'static str = "BlobIndexCheckpoint";
+    const DUPSORT: bool = false;
+    type Key = u64;
+    type Value = IndexCheckpoint;
+}
+
+/// Tables of the blob index environment.
+#[derive(Debug)]
+pub struct LoadBlobIndexTables;
+
+#[derive(Debug)]
+struct IndexTable(&'static str);
+
+impl TableInfo for IndexTable {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn is_dupsort(&self) -> bool {
+        false
+    }
+}
+
+impl TableSet for LoadBlobIndexTables {
+    fn tables() -> Box<dyn Iterator<Item = Box<dyn TableInfo>>> {
+        Box::new(
+            [BlobInclusions::NAME, BlockBlobHashes::NAME, BlobIndexCheckpoint::NAME]
+                .into_iter()
+                .map(|name| Box::new(IndexTable(name)) as Box<dyn TableInfo>),
+        )
+    }
+}
+
+/// Handle to the blob index; clones share the same environment.
+#[derive(Debug, Clone)]
+pub struct LoadBlobIndex {
+    db: Arc<DatabaseEnv>,
+    metrics: LoadBlobIndexMetrics,
+}
+
+impl LoadBlobIndex {
+    /// Opens the index at `dir`, creating it if needed.
+    pub fn open(dir: impl AsRef<Path>) -> eyre::Result<Self> {
+        let db = init_db_for::<_, LoadBlobIndexTables>(
+            dir,
+            DatabaseArguments::new(ClientVersion::default()),
+        )?;
+        let index = Self { db: Arc::new(db), metrics: LoadBlobIndexMetrics::new() };
+        if let Some(checkpoint) = index.checkpoint()? {
+            index.metrics.set_checkpoint(checkpoint);
+        }
+        Ok(index)
+    }
+
+    /// Returns where a blob was included, if the index covers it.
+    pub fn inclusion(
+        &self,
+        versioned_hash: B256,
+    ) -> Result<Option<LoadBlobInclusion>, DatabaseError> {
+        self.db.tx()?.get::<BlobInclusions>(versioned_hash)
+    }
+
+    /// Highest block the index covers, or `None` before the first write.
+    pub fn checkpoint(&self) -> Result<Option<BlockNumber>, DatabaseError> {
+        Ok(self.db.tx()?.get::<BlobIndexCheckpoint>(CHECKPOINT_KEY)?.map(|checkpoint| checkpoint.0))
+    }
+
+    /// Drops `reverted` blocks, indexes `committed` ones and moves the checkpoint to `checkpoint`,
+    /// all in one write. Returns the number of blobs indexed.
+    pub fn apply(
+        &self,
+        reverted: &[(BlockNumber, B256)],
+        committed: &[IndexedBlock<'_>],
+        checkpoint: BlockNumber,
+    ) -> Result<usize, DatabaseError> {
+        let tx = self.db.tx_mut()?;
+        for (number, block_hash) in reverted {
+            remove_block(&tx, *number, *block_hash)?;
+        }
+
+        let mut indexed = 0;
+        for &(block_number, block_hash, transactions) in committed {
+            // A block we missed the revert of still occupies this height.
+            if let Some(previous) = tx.get::<BlockBlobHashes>(block_number)? {
+                remove_block(&tx, block_number, previous.block_hash)?;
+            }
+
+            let mut versioned_hashes = Vec::new();
+            for (transaction_index, transaction) in transactions.iter().enumerate() {
+                for versioned_hash in transaction.blob_versioned_hashes().unwrap_or_default() {
+                    let inclusion = LoadBlobInclusion {
+                        block_number,
+                        block_hash,
+                        transaction_hash: *transaction.tx_hash(),
+                        transaction_index: transaction_index as u64,
+                        blob_index: versioned_hashes.len() as u64,
+                    };
+                    tx.put::<BlobInclusions>(*versioned_hash, inclusion)?;
+                    versioned_hashes.push(*versioned_hash);
+                }
+            }
+            if !versioned_hashes.is_empty() {
+                indexed += versioned_hashes.len();
+                tx.put::<BlockBlobHashes>(
+                    block_number,
+                    BlockBlobs { block_hash, versioned_hashes },
+                )?;
+            }
+        }
+
+        tx.put::<BlobIndexCheckpoint>(CHECKPOINT_KEY, IndexCheckpoint(checkpoint))?;
+        tx.commit()?;
+        self.metrics.record_indexed(indexed);
+        self.metrics.set_checkpoint(checkpoint);
+        Ok(indexed)
+    }
+
+    /// Removes the blocks below `before`, at most `PRUNE_BATCH_BLOCKS` of them per call, and
+    /// returns how many were removed.
+    pub fn prune(&self, before: BlockNumber) -> Result<usize, DatabaseError> {
+        let tx = self.db.tx_mut()?;
+        let mut blocks = tx.cursor_write::<BlockBlobHashes>()?;
+        let mut pruned = 0;
+        while pruned < PRUNE_BATCH_BLOCKS {
+            let Some((number, entry)) = blocks.first()? else { break };
+            if number >= before {
+                break;
+            }
+            remove_inclusions(&tx, &entry)?;
+            blocks.delete_current()?;
+            pruned += 1;
+        }
+        drop(blocks);
+        tx.commit()?;
+        self.metrics.record_pruned(pruned);
+        Ok(pruned)
+    }
+
+    /// Backfills from the checkpoint to the head, then indexes every new canonical block.
+    pub async fn run<Provider>(
+        self,
+        provider: Provider,
+        mut notifications: CanonStateNotifications<LoadPrimitives>,
+    ) where
+        Provider: BlockReader<Block = LoadBlock> + PruneCheckpointReader + Clone + 'static,
+    {
+        let mut backfill = true;
+        let mut retry = BACKFILL_RETRY_MIN;
+        loop {
+            if backfill {
+                let index = self.clone();
+                let provider = provider.clone();
+                let failure =
+                    match tokio::task::spawn_blocking(move || index.backfill(&provider)).await {
+                        Ok(Ok(())) => None,
+                        Ok(Err(err)) => Some(err),
+                        Err(err) => Some(eyre::Report::new(err)),
+                    };
+                self.metrics.set_stalled(failure.is_some());
+                if let Some(err) = failure {
+                    // Lookups keep being served from what is indexed; notifications missed while
+                    // waiting are covered by the next backfill.
+                    warn!(
+                        target: "load_reth::blob_index",
+                        %err,
+                        ?retry,
+                        "Blob index backfill failed"
+                    );
+                    tokio::time::sleep(retry).await;
+                    retry = (retry * 2).min(BACKFILL_RETRY_MAX);
+                    continue;
+                }
+                backfill = false;
+                retry = BACKFILL_RETRY_MIN;
+            }
+
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                // Catch up from the checkpoint instead.
+                Err(RecvError::Lagged(_)) => {
+                    backfill = true;
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+
+            let index = self.clone();
+            let provider = provider.clone();
+            let result = tokio::task::spawn_blocking(move || -> eyre::Result<()> {
+                let reverted: Vec<_> = notification
+                    .reverted()
+                    .map(|chain| {
+                        chain
+                            .blocks_iter()
+                            .map(|block| (block.header().number, block.hash()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let blocks: Vec<_> = notification
+                    .committed()
+                    .blocks_iter()
+                    .map(|block| {
+                        (block.header().number, block.hash(), block.body().transactions.as_slice())
+                    })
+                    .collect();
+                index.apply(&reverted, &blocks, notification.tip().header().number)?;
+                index.prune(pruned_before(&provider)?)?;
+                Ok(())
+            })
+            .await;
+            // The checkpoint did not move, so a backfill picks the block up again.
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    warn!(target: "load_reth::blob_index", %err, "Blob index update failed");
+                    backfill = true;
+                }
+                Err(err) => {
+                    warn!(target: "load_reth::blob_index", %err, "Blob index task panicked");
+                    backfill = true;
+                }
+            }
+        }
+    }
+
+    /// Indexes the blocks between the checkpoint (or the oldest unpruned block) and the head.
+    fn backfill<Provider>(&self, provider: &Provider) -> eyre::Result<()>
+    where
+        Provider: BlockReader<Block = LoadBlock> + PruneCheckpointReader,
+    {
+        let oldest = pruned_before(provider)?;
+        let start = self.checkpoint()?.map_or(oldest, |checkpoint| (checkpoint + 1).max(oldest));
+        let head = provider.best_block_number()?;
+        if start > head {
+            return Ok(());
+        }
+        info!(target: "load_reth::blob_index", start, head, "Backfilling blob index");
+        for batch in batches(start..=head) {
+            let end = *batch.end();
+            let blocks = provider.block_range(batch)?;
+            let indexed: Vec<_> = blocks
+                .iter()
+                .map(|block| {
+                    (
+                        block.header.number,
+                        block.header.hash_slow(),
+                        block.body.transactions.as_slice(),
+                    )
+                })
+                .collect();
+            self.apply(&[], &indexed, end)?;
+        }
+        self.prune(oldest)?;
+        Ok(())
+    }
+}
+
+/// Splits a block range into backfill batches.
+fn batches(
+    range: RangeInclusive<BlockNumber>,
+) -> impl Iterator<Item = RangeInclusive<BlockNumber>> {
+    let end = *range.end();
+    range
+        .step_by(BACKFILL_BATCH_BLOCKS as usize)
+        .map(move |start| start..=(start + BACKFILL_BATCH_BLOCKS - 1).min(end))
+}
+
+/// First block whose transaction lookups reth still keeps.
+fn pruned_before<Provider: PruneCheckpointReader>(
+    provider: &Provider,
+) -> eyre::Result<BlockNumber> {
+    Ok(provider
+        .get_prune_checkpoint(PruneSegment::TransactionLookup)?
+        .and_then(|checkpoint| checkpoint.block_number)
+        .map_or(0, |number| number + 1))
+}
+
+/// Removes an indexed block if it is still the one at `number`.
+fn remove_block<Tx: DbTxMut + DbTx>(
+    tx: &Tx,
+    number: BlockNumber,
+    block_hash: B256,
+) -> Result<(), DatabaseError> {
+    let Some(entry) = tx.get::<BlockBlobHashes>(number)? else { return Ok(()) };
+    if entry.block_hash != block_hash {
+        return Ok(());
+    }
+    remove_inclusions(tx, &entry)?;
+    tx.delete::<BlockBlobHashes>(number, None)?;
+    Ok(())
+}
+
+/// Removes the inclusions a block recorded, keeping those a later block overwrote.
+fn remove_inclusions<Tx: DbTxMut + DbTx>(tx: &Tx, entry: &BlockBlobs) -> Result<(), DatabaseError> {
+    for versioned_hash in &entry.versioned_hashes {
+        if tx
+            .get::<BlobInclusions>(*versioned_hash)?
+            .is_some_and(|inclusion| inclusion.block_hash == entry.block_hash)
+        {
+            tx.delete::<BlobInclusions>(*versioned_hash, None)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{Signed, TxEip4844};
+    use alloy_primitives::Signature;
+
+    use super::*;
+
+    fn blob_tx(versioned_hashes: Vec<B256>) -> LoadTransactionSigned {
+        let tx = TxEip4844 { blob_versioned_hashes: versioned_hashes, ..Default::default() };
+        LoadTransactionSigned::Eip4844(Signed::new_unhashed(tx, Signature::test_signature()))
+    }
+
+    #[test]
+    fn indexes_reverts_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = LoadBlobIndex::open(dir.path()).unwrap();
+        assert_eq!(index.checkpoint().unwrap(), None);
+
+        let (a, b, c) =
+            (B256::with_last_byte(0xa), B256::with_last_byte(0xb), B256::with_last_byte(0xc));
+        let block1 = vec![blob_tx(vec![a]), blob_tx(vec![b, c])];
+        let hash1 = B256::with_last_byte(1);
+        assert_eq!(index.apply(&[], &[(1, hash1, &block1)], 1).unwrap(), 3);
+        assert_eq!(index.checkpoint().unwrap(), Some(1));
+
+        let inclusion = index.inclusion(c).unwrap().expect("indexed");
+        assert_eq!(inclusion.block_number, 1);
+        assert_eq!(inclusion.block_hash, hash1);
+        assert_eq!(inclusion.transaction_hash, *block1[1].tx_hash());
+        assert_eq!(inclusion.transaction_index, 1);
+        assert_eq!(inclusion.blob_index, 2);
+
+        // A reorg replaces block 1; `a` moves to the new block, `b` and `c` go away.
+        let block1b = vec![blob_tx(vec![a])];
+        let hash1b = B256::with_last_byte(0x1b);
+        index.apply(&[(1, hash1)], &[(1, hash1b, &block1b)], 1).unwrap();
+        assert_eq!(index.inclusion(a).unwrap().unwrap().block_hash, hash1b);
+        assert!(index.inclusion(b).unwrap().is_none());
+
+        let block2 = vec![blob_tx(vec![b])];
+        index.apply(&[], &[(2, B256::with_last_byte(2), &block2)], 2).unwrap();
+        assert_eq!(index.prune(2).unwrap(), 1);
+        assert!(index.inclusion(a).unwrap().is_none());
+        assert!(index.inclusion(b).unwrap().is_some());
+
+        // Reopening keeps the checkpoint.
+        drop(index);
+        let index = LoadBlobIndex::open(dir.path()).unwrap();
+        assert_eq!(index.checkpoint().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn splits_backfill_batches() {
+        let batches: Vec<_> = batches(5..=2_010).collect();
+        assert_eq!(batches, vec![5..=1_004, 1_005..=2_004, 2_005..=2_010]);
+    }
+}
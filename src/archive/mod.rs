@@ -0,0 +1,864 @@
+//! On-disk archive of blob sidecars for canonical blocks.
+//!
+//! The pool's blob store drops a sidecar once its transaction is finalized, which on Load is right
+//! after inclusion. The archive copies the sidecars of every canonical block to disk and keeps them
+//! for a configurable number of blocks, so `engine_getBlobs*` can still serve them.
+//!
+//! Sidecars are taken from the pool when the block arrives through `engine_newPayload`, before
+//! the forkchoice update that canonicalizes and finalizes it, so the pool cannot have dropped them
+//! yet; only the disk writes run in the background. Canonical notifications archive whatever did
+//! not come through the Engine API (local dev blocks, for instance) and, after the task fell
+//! behind, backfill the blocks it missed. Payloads that never become canonical are pruned with
+//! their height.
+//!
+//! Layout under the archive directory:
+//! - `blobs/<versioned hash>`: one blob with its commitment and proofs, tagged with the hash of the
+//!   block that archived it.
+//! - `blocks/<number>-<block hash>`: the versioned hashes a block archived. It is written after the
+//!   blobs, so it never lists a blob that is not on disk; pruning and reorgs delete through it.
+//!
+//! Archiving is best effort: a sidecar the pool no longer holds is counted as missing rather than
+//! retried.
+
+pub mod index;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    io::{self, Read},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError},
+    time::Instant,
+};
+
+use alloy_consensus::Transaction;
+use alloy_eips::{
+    eip4844::{
+        kzg_to_versioned_hash, Blob, BlobAndProofV1, BlobAndProofV2, BlobTransactionSidecar,
+        BlobTransactionSidecarVariant, Bytes48, BYTES_PER_BLOB,
+    },
+    eip7594::{BlobTransactionSidecarEip7594, CELLS_PER_EXT_BLOB},
+};
+use alloy_primitives::{hex, TxHash, B256};
+use eyre::{Context, OptionExt};
+use reth::providers::{BlockReader, CanonStateNotifications};
+use reth_transaction_pool::TransactionPool;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+use crate::{
+    metrics::LoadBlobArchiveMetrics,
+    primitives::{LoadBlock, LoadPrimitives, LoadTransactionSigned},
+};
+
+/// Blocks of blobs kept by default.
+pub const DEFAULT_BLOB_ARCHIVE_RETENTION_BLOCKS: u64 = 4096;
+
+const BLOBS_DIR: &str = "blobs";
+const BLOCKS_DIR: &str = "blocks";
+/// Suffix of files being written; they are renamed into place once complete.
+const TMP_SUFFIX: &str = ".tmp";
+/// Kind byte, archiving block hash and commitment ahead of the blob.
+const BLOB_HEADER_LEN: usize = 1 + 32 + 48;
+/// Versioned hash and kind byte.
+const MANIFEST_ENTRY_LEN: usize = 32 + 1;
+
+/// Proof format of an archived blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlobKind {
+    /// One blob proof (EIP-4844).
+    Eip4844 = 0,
+    /// `CELLS_PER_EXT_BLOB` cell proofs (EIP-7594).
+    Eip7594 = 1,
+}
+
+impl BlobKind {
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Eip4844),
+            1 => Some(Self::Eip7594),
+            _ => None,
+        }
+    }
+
+    const fn proofs(self) -> usize {
+        match self {
+            Self::Eip4844 => 1,
+            Self::Eip7594 => CELLS_PER_EXT_BLOB,
+        }
+    }
+
+    const fn file_len(self) -> u64 {
+        (BLOB_HEADER_LEN + BYTES_PER_BLOB + self.proofs() * 48) as u64
+    }
+}
+
+/// Settings for `--load.blob-archive`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadBlobArchiveConfig {
+    /// Blocks of blobs kept; older blocks are pruned.
+    pub retention_blocks: u64,
+    /// Archive directory. `None` places it under the node's data directory.
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for LoadBlobArchiveConfig {
+    fn default() -> Self {
+        Self { retention_blocks: DEFAULT_BLOB_ARCHIVE_RETENTION_BLOCKS, dir: None }
+    }
+}
+
+impl LoadBlobArchiveConfig {
+    pub const fn with_retention_blocks(mut self, retention_blocks: u64) -> Self {
+        self.retention_blocks = retention_blocks;
+        self
+    }
+
+    pub fn with_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.dir = dir;
+        self
+    }
+}
+
+/// A blob read back from the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadArchivedBlob {
+    pub commitment: Bytes48,
+    pub blob: Box<Blob>,
+    /// The blob proof (EIP-4844) or its cell proofs (EIP-7594).
+    pub proofs: Vec<Bytes48>,
+}
+
+impl LoadArchivedBlob {
+    /// Splits a sidecar into its blobs, in order.
+    pub fn from_sidecar(sidecar: &BlobTransactionSidecarVariant) -> eyre::Result<Vec<Self>> {
+        let mut blobs = Vec::new();
+        for_each_blob(sidecar, |_, commitment, blob, proofs| {
+            blobs.push(Self {
+                commitment: *commitment,
+                blob: Box::new(*blob),
+                proofs: proofs.to_vec(),
+            });
+            Ok(())
+        })?;
+        Ok(blobs)
+    }
+
+    /// Joins blobs back into one sidecar, in order. `None` if there are no blobs or they mix
+    /// proof kinds.
+    pub fn into_sidecar(blobs: Vec<Self>) -> Option<BlobTransactionSidecarVariant> {
+        let cell_proofs = blobs.first()?.proofs.len() == CELLS_PER_EXT_BLOB;
+        let proofs_per_blob = if cell_proofs { CELLS_PER_EXT_BLOB } else { 1 };
+        if blobs.iter().any(|blob| blob.proofs.len() != proofs_per_blob) {
+            return None;
+        }
+
+        let mut sidecar_blobs = Vec::with_capacity(blobs.len());
+        let mut commitments = Vec::with_capacity(blobs.len());
+        let mut proofs = Vec::with_capacity(blobs.len() * proofs_per_blob);
+        for blob in blobs {
+            sidecar_blobs.push(*blob.blob);
+            commitments.push(blob.commitment);
+            proofs.extend(blob.proofs);
+        }
+        Some(if cell_proofs {
+            BlobTransactionSidecarVariant::Eip7594(BlobTransactionSidecarEip7594::new(
+                sidecar_blobs,
+                commitments,
+                proofs,
+            ))
+        } else {
+            BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::new(
+                sidecar_blobs,
+                commitments,
+                proofs,
+            ))
+        })
+    }
+
+    /// Versioned hash of the blob's commitment.
+    pub fn versioned_hash(&self) -> B256 {
+        kzg_to_versioned_hash(self.commitment.as_slice())
+    }
+
+    /// Returns the blob in `engine_getBlobsV1` form if it carries an EIP-4844 proof.
+    pub fn into_v1(self) -> Option<BlobAndProofV1> {
+        let [proof] = self.proofs.as_slice() else { return None };
+        Some(BlobAndProofV1 { blob: self.blob, proof: *proof })
+    }
+
+    /// Returns the blob in `engine_getBlobsV2/V3` form if it carries cell proofs.
+    pub fn into_v2(self) -> Option<BlobAndProofV2> {
+        (self.proofs.len() == CELLS_PER_EXT_BLOB)
+            .then(|| BlobAndProofV2 { blob: self.blob, proofs: self.proofs })
+    }
+}
+
+/// The blob archive, shared between the Engine API that opens it and the RPC handlers that read
+/// it. Empty unless `--load.blob-archive` is set.
+#[derive(Debug, Clone, Default)]
+pub struct LoadBlobArchiveSlot(Arc<OnceLock<LoadBlobArchive>>);
+
+impl LoadBlobArchiveSlot {
+    /// Publishes the opened archive. Later calls are ignored.
+    pub fn set(&self, archive: LoadBlobArchive) {
+        let _ = self.0.set(archive);
+    }
+
+    pub fn get(&self) -> Option<&LoadBlobArchive> {
+        self.0.get()
+    }
+}
+
+/// Blob count and disk usage of one archived block.
+#[derive(Debug, Clone, Copy, Default)]
+struct BlockUsage {
+    blobs: usize,
+    bytes: u64,
+}
+
+/// Handle to the blob archive; clones share the same directory and bookkeeping.
+#[derive(Debug, Clone)]
+pub struct LoadBlobArchive {
+    inner: Arc<ArchiveInner>,
+}
+
+#[derive(Debug)]
+struct ArchiveInner {
+    dir: PathBuf,
+    retention_blocks: u64,
+    /// Archived blocks by number and hash.
+    blocks: Mutex<BTreeMap<(u64, B256), BlockUsage>>,
+    /// Blocks whose sidecars were taken from the pool and are still being written.
+    pending: Mutex<HashSet<(u64, B256)>>,
+    metrics: LoadBlobArchiveMetrics,
+}
+
+impl LoadBlobArchive {
+    /// Opens the archive at `dir`, creating it if needed and loading the blocks already on disk.
+    pub fn open(dir: impl Into<PathBuf>, retention_blocks: u64) -> eyre::Result<Self> {
+        let dir = dir.into();
+        for sub in [BLOBS_DIR, BLOCKS_DIR] {
+            fs::create_dir_all(dir.join(sub))
+                .wrap_err_with(|| format!("creating blob archive directory {}", dir.display()))?;
+        }
+
+        let mut blocks = BTreeMap::new();
+        for entry in fs::read_dir(dir.join(BLOCKS_DIR))? {
+            let path = entry?.path();
+            // Leftovers of interrupted writes are skipped along with anything else unexpected.
+            let Some(key) =
+                path.file_name().and_then(|name| name.to_str()).and_then(parse_block_key)
+            else {
+                continue;
+            };
+            let usage = read_manifest(&path)?.iter().fold(BlockUsage::default(), |usage, entry| {
+                BlockUsage { blobs: usage.blobs + 1, bytes: usage.bytes + entry.1.file_len() }
+            });
+            blocks.insert(key, usage);
+        }
+
+        let archive = Self {
+            inner: Arc::new(ArchiveInner {
+                dir,
+                retention_blocks,
+                blocks: Mutex::new(blocks),
+                pending: Mutex::default(),
+                metrics: LoadBlobArchiveMetrics::new(),
+            }),
+        };
+        archive.publish_usage(&archive.lock());
+        Ok(archive)
+    }
+
+    /// Directory the archive lives in.
+    pub fn dir(&self) -> &Path {
+        &self.inner.dir
+    }
+
+    /// Number of blocks and blobs archived, and their size on disk in bytes.
+    pub fn usage(&self) -> (usize, usize, u64) {
+        let blocks = self.lock();
+        let (blobs, bytes) = blocks
+            .values()
+            .fold((0, 0), |(blobs, bytes), usage| (blobs + usage.blobs, bytes + usage.bytes));
+        (blocks.len(), blobs, bytes)
+    }
+
+    /// Writes the blobs of a block's sidecars, adding to what the block already archived, and
+    /// returns how many were written.
+    pub fn archive_block(
+        &self,
+        number: u64,
+        block_hash: B256,
+        sidecars: &[Arc<BlobTransactionSidecarVariant>],
+    ) -> eyre::Result<usize> {
+        let started = Instant::now();
+        let mut manifest = Vec::new();
+        let mut archived = HashSet::new();
+        let mut usage = BlockUsage::default();
+        for sidecar in sidecars {
+            for_each_blob(sidecar, |kind, commitment, blob, proofs| {
+                let versioned_hash = kzg_to_versioned_hash(commitment.as_slice());
+                let mut data = Vec::with_capacity(kind.file_len() as usize);
+                data.push(kind as u8);
+                data.extend_from_slice(block_hash.as_slice());
+                data.extend_from_slice(commitment.as_slice());
+                data.extend_from_slice(blob.as_slice());
+                for proof in proofs {
+                    data.extend_from_slice(proof.as_slice());
+                }
+                write_atomic(&self.blob_path(&versioned_hash), &data)?;
+
+                if archived.insert(versioned_hash) {
+                    manifest.extend_from_slice(versioned_hash.as_slice());
+                    manifest.push(kind as u8);
+                    usage.blobs += 1;
+                    usage.bytes += kind.file_len();
+                }
+                Ok(())
+            })?;
+        }
+        let written = usage.blobs;
+
+        // A block's sidecars can arrive in parts (the pool on commit, the consensus layer through
+        // `engine_loadImportBlobsV1`), so keep what the block already archived.
+        let path = self.block_path(number, block_hash);
+        let mut blocks = self.lock();
+        match read_manifest(&path) {
+            Ok(entries) => {
+                for (versioned_hash, kind) in entries {
+                    if archived.insert(versioned_hash) {
+                        manifest.extend_from_slice(versioned_hash.as_slice());
+                        manifest.push(kind as u8);
+                        usage.blobs += 1;
+                        usage.bytes += kind.file_len();
+                    }
+                }
+            }
+            Err(err) if is_not_found(&err) => {}
+            Err(err) => return Err(err),
+        }
+        write_atomic(&path, &manifest)?;
+
+        blocks.insert((number, block_hash), usage);
+        self.publish_usage(&blocks);
+        self.inner.metrics.record_archived(written, started.elapsed());
+        Ok(written)
+    }
+
+    /// Deletes an archived block and the blobs it archived. Blobs re-archived by a later block
+    /// are kept.
+    pub fn remove_block(&self, number: u64, block_hash: B256) -> eyre::Result<()> {
+        let path = self.block_path(number, block_hash);
+        let manifest = match read_manifest(&path) {
+            Ok(manifest) => manifest,
+            Err(err) if is_not_found(&err) => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        for (versioned_hash, _) in manifest {
+            let blob_path = self.blob_path(&versioned_hash);
+            if read_archiving_block(&blob_path)? == Some(block_hash) {
+                remove_if_exists(&blob_path)?;
+            }
+        }
+        remove_if_exists(&path)?;
+
+        let mut blocks = self.lock();
+        blocks.remove(&(number, block_hash));
+        self.publish_usage(&blocks);
+        Ok(())
+    }
+
+    /// Deletes blocks that fell out of the retention window behind `tip` and returns how many.
+    pub fn prune(&self, tip: u64) -> eyre::Result<usize> {
+        let Some(cutoff) = tip.checked_sub(self.inner.retention_blocks) else { return Ok(0) };
+        let expired: Vec<_> =
+            self.lock().range(..=(cutoff, B256::repeat_byte(0xff))).map(|(key, _)| *key).collect();
+        for (number, block_hash) in &expired {
+            self.remove_block(*number, *block_hash)?;
+        }
+        self.inner.metrics.record_pruned(expired.len());
+        Ok(expired.len())
+    }
+
+    /// Reads an archived blob. Unreadable entries are logged and treated as absent.
+    pub fn blob(&self, versioned_hash: &B256) -> Option<LoadArchivedBlob> {
+        match read_blob(&self.blob_path(versioned_hash)) {
+            Ok(blob) => blob,
+            Err(err) => {
+                warn!(
+                    target: "load_reth::blob_archive",
+                    %versioned_hash,
+                    %err,
+                    "Unreadable archived blob"
+                );
+                None
+            }
+        }
+    }
+
+    /// Reassembles a transaction's sidecar from its archived blobs, or `None` if any is missing.
+    pub fn sidecar(&self, versioned_hashes: &[B256]) -> Option<BlobTransactionSidecarVariant> {
+        let blobs = versioned_hashes.iter().map(|hash| self.blob(hash)).collect::<Option<_>>()?;
+        LoadArchivedBlob::into_sidecar(blobs)
+    }
+
+    /// Archived blob in `engine_getBlobsV1` form.
+    pub fn blob_v1(&self, versioned_hash: &B256) -> Option<BlobAndProofV1> {
+        let blob = self.blob(versioned_hash).and_then(LoadArchivedBlob::into_v1);
+        if blob.is_some() {
+            self.inner.metrics.record_hit();
+        }
+        blob
+    }
+
+    /// Archived blob in `engine_getBlobsV2/V3` form.
+    pub fn blob_v2(&self, versioned_hash: &B256) -> Option<BlobAndProofV2> {
+        let blob = self.blob(versioned_hash).and_then(LoadArchivedBlob::into_v2);
+        if blob.is_some() {
+            self.inner.metrics.record_hit();
+        }
+        blob
+    }
+
+    /// Whether a block was archived or its sidecars are being written.
+    pub fn contains_block(&self, number: u64, block_hash: B256) -> bool {
+        self.lock().contains_key(&(number, block_hash)) ||
+            self.lock_pending().contains(&(number, block_hash))
+    }
+
+    /// Archives the sidecars of a block received through `engine_newPayload`.
+    ///
+    /// The sidecars are read from the pool before returning, while the block cannot be final
+    /// yet. Both the pool read and the disk writes run on blocking threads.
+    pub async fn archive_payload<Pool: TransactionPool + 'static>(
+        &self,
+        pool: &Pool,
+        number: u64,
+        block_hash: B256,
+        transactions: &[LoadTransactionSigned],
+    ) -> eyre::Result<()> {
+        let blob_txs = blob_transactions(transactions);
+        if blob_txs.is_empty() || self.contains_block(number, block_hash) {
+            return Ok(());
+        }
+        // Up to a block's worth of sidecars are copied out of the blob store.
+        let archive = self.clone();
+        let pool = pool.clone();
+        let sidecars = tokio::task::spawn_blocking(move || {
+            archive.pooled_sidecars(&pool, number, block_hash, blob_txs)
+        })
+        .await??;
+        if sidecars.is_empty() {
+            return Ok(());
+        }
+
+        self.lock_pending().insert((number, block_hash));
+        let archive = self.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(err) = archive.archive_block(number, block_hash, &sidecars) {
+                warn!(
+                    target: "load_reth::blob_archive",
+                    number,
+                    %block_hash,
+                    %err,
+                    "Failed to archive payload sidecars"
+                );
+            }
+            archive.lock_pending().remove(&(number, block_hash));
+        });
+        Ok(())
+    }
+
+    /// Archives the sidecars of new canonical blocks not archived through the Engine API,
+    /// dropping reverted blocks and pruning behind the tip. Blocks announced while the task lagged
+    /// are backfilled from `provider`.
+    pub async fn run<Provider, Pool>(
+        self,
+        provider: Provider,
+        mut notifications: CanonStateNotifications<LoadPrimitives>,
+        pool: Pool,
+    ) where
+        Provider: BlockReader<Block = LoadBlock> + Clone + 'static,
+        Pool: TransactionPool + Clone + 'static,
+    {
+        // Highest canonical block handled, where a backfill resumes after lagging.
+        let mut last_tip = None;
+        let mut lagged = false;
+        loop {
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(
+                        target: "load_reth::blob_archive",
+                        skipped,
+                        "Blob archive fell behind canonical updates, backfilling"
+                    );
+                    lagged = true;
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+
+            let reverted: Vec<_> = notification
+                .reverted()
+                .map(|chain| {
+                    chain.blocks_iter().map(|block| (block.header().number, block.hash())).collect()
+                })
+                .unwrap_or_default();
+            let committed: Vec<_> = notification
+                .committed()
+                .blocks_iter()
+                .map(|block| {
+                    let blob_txs = blob_transactions(&block.body().transactions);
+                    (block.header().number, block.hash(), blob_txs)
+                })
+                .collect();
+            let first = notification.committed().first().header().number;
+            let tip = notification.tip().header().number;
+            let backfill = match (last_tip, std::mem::take(&mut lagged)) {
+                (Some(last), true) => (last + 1)..first,
+                _ => first..first,
+            };
+            last_tip = Some(tip);
+
+            // Sidecar reads and blob writes both hit the disk.
+            let archive = self.clone();
+            let provider = provider.clone();
+            let pool = pool.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                for (number, block_hash) in reverted {
+                    archive.remove_block(number, block_hash)?;
+                }
+                archive.backfill(&provider, &pool, backfill, tip)?;
+                for (number, block_hash, blob_txs) in committed {
+                    archive.archive_from_pool(&pool, number, block_hash, blob_txs)?;
+                }
+                archive.prune(tip)
+            })
+            .await;
+            match result {
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => {
+                    warn!(target: "load_reth::blob_archive", %err, "Blob archive update failed")
+                }
+                Err(err) => {
+                    warn!(target: "load_reth::blob_archive", %err, "Blob archive task panicked")
+                }
+            }
+        }
+    }
+
+    /// Archives the canonical blocks in `range` that are still within retention of `tip`.
+    fn backfill<Provider, Pool>(
+        &self,
+        provider: &Provider,
+        pool: &Pool,
+        range: Range<u64>,
+        tip: u64,
+    ) -> eyre::Result<()>
+    where
+        Provider: BlockReader<Block = LoadBlock>,
+        Pool: TransactionPool,
+    {
+        let start = range.start.max(tip.saturating_sub(self.inner.retention_blocks) + 1);
+        if start >= range.end {
+            return Ok(());
+        }
+        let blocks = provider.block_range(start..=range.end - 1)?;
+        for block in &blocks {
+            let blob_txs = blob_transactions(&block.body.transactions);
+            self.archive_from_pool(pool, block.header.number, block.header.hash_slow(), blob_txs)?;
+        }
+        self.inner.metrics.record_backfilled(blocks.len());
+        debug!(
+            target: "load_reth::blob_archive",
+            start,
+            end = range.end - 1,
+            "Backfilled blob archive"
+        );
+        Ok(())
+    }
+
+    /// Archives a block's sidecars still held by the pool unless the block was already archived.
+    fn archive_from_pool<Pool: TransactionPool>(
+        &self,
+        pool: &Pool,
+        number: u64,
+        block_hash: B256,
+        blob_txs: Vec<(TxHash, usize)>,
+    ) -> eyre::Result<()> {
+        if blob_txs.is_empty() || self.contains_block(number, block_hash) {
+            return Ok(());
+        }
+        let sidecars = self.pooled_sidecars(pool, number, block_hash, blob_txs)?;
+        if !sidecars.is_empty() {
+            self.archive_block(number, block_hash, &sidecars)?;
+        }
+        Ok(())
+    }
+
+    /// Sidecars the pool holds for a block's blob transactions, counting the rest as missing.
+    fn pooled_sidecars<Pool: TransactionPool>(
+        &self,
+        pool: &Pool,
+        number: u64,
+        block_hash: B256,
+        blob_txs: Vec<(TxHash, usize)>,
+    ) -> eyre::Result<Vec<Arc<BlobTransactionSidecarVariant>>> {
+        let mut pooled: HashMap<_, _> = pool
+            .get_all_blobs(blob_txs.iter().map(|(hash, _)| *hash).collect())?
+            .into_iter()
+            .collect();
+        let mut sidecars = Vec::with_capacity(blob_txs.len());
+        let mut missing = 0;
+        for (tx_hash, blobs) in blob_txs {
+            match pooled.remove(&tx_hash) {
+                Some(sidecar) => sidecars.push(sidecar),
+                None => missing += blobs,
+            }
+        }
+        if missing > 0 {
+            self.inner.metrics.record_missing(missing);
+            debug!(
+                target: "load_reth::blob_archive",
+                number,
+                %block_hash,
+                missing,
+                "Sidecars gone before archiving"
+            );
+        }
+        Ok(sidecars)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<(u64, B256), BlockUsage>> {
+        self.inner.blocks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, HashSet<(u64, B256)>> {
+        self.inner.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn publish_usage(&self, blocks: &BTreeMap<(u64, B256), BlockUsage>) {
+        let (blobs, bytes) = blocks
+            .values()
+            .fold((0, 0), |(blobs, bytes), usage| (blobs + usage.blobs, bytes + usage.bytes));
+        self.inner.metrics.set_usage(blocks.len(), blobs, bytes);
+    }
+
+    fn blob_path(&self, versioned_hash: &B256) -> PathBuf {
+        self.inner.dir.join(BLOBS_DIR).join(hex::encode(versioned_hash))
+    }
+
+    fn block_path(&self, number: u64, block_hash: B256) -> PathBuf {
+        self.inner.dir.join(BLOCKS_DIR).join(format!("{number:020}-{}", hex::encode(block_hash)))
+    }
+}
+
+/// Hashes and blob counts of a block's blob transactions.
+fn blob_transactions(transactions: &[LoadTransactionSigned]) -> Vec<(TxHash, usize)> {
+    transactions
+        .iter()
+        .filter_map(|tx| {
+            let blobs = tx.blob_versioned_hashes()?.len();
+            (blobs > 0).then(|| (*tx.tx_hash(), blobs))
+        })
+        .collect()
+}
+
+/// Calls `f` with the kind, commitment, blob and proofs of every blob in a sidecar.
+fn for_each_blob(
+    sidecar: &BlobTransactionSidecarVariant,
+    mut f: impl FnMut(BlobKind, &Bytes48, &Blob, &[Bytes48]) -> eyre::Result<()>,
+) -> eyre::Result<()> {
+    match sidecar {
+        BlobTransactionSidecarVariant::Eip4844(sidecar) => {
+            for ((blob, commitment), proof) in
+                sidecar.blobs.iter().zip(&sidecar.commitments).zip(&sidecar.proofs)
+            {
+                f(BlobKind::Eip4844, commitment, blob, std::slice::from_ref(proof))?;
+            }
+        }
+        BlobTransactionSidecarVariant::Eip7594(sidecar) => {
+            for (idx, (blob, commitment)) in
+                sidecar.blobs.iter().zip(&sidecar.commitments).enumerate()
+            {
+                let proofs = sidecar
+                    .cell_proofs
+                    .get(idx * CELLS_PER_EXT_BLOB..(idx + 1) * CELLS_PER_EXT_BLOB)
+                    .ok_or_eyre("sidecar is missing cell proofs")?;
+                f(BlobKind::Eip7594, commitment, blob, proofs)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses a `blocks/` file name back into the block number and hash.
+fn parse_block_key(name: &str) -> Option<(u64, B256)> {
+    let (number, hash) = name.split_once('-')?;
+    Some((number.parse().ok()?, B256::from_str(hash).ok()?))
+}
+
+fn read_manifest(path: &Path) -> eyre::Result<Vec<(B256, BlobKind)>> {
+    let data = fs::read(path)?;
+    data.chunks_exact(MANIFEST_ENTRY_LEN)
+        .map(|entry| {
+            let kind = BlobKind::from_byte(entry[32]).ok_or_eyre("unknown blob kind")?;
+            Ok((B256::from_slice(&entry[..32]), kind))
+        })
+        .collect()
+}
+
+fn read_blob(path: &Path) -> eyre::Result<Option<LoadArchivedBlob>> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let kind =
+        data.first().copied().and_then(BlobKind::from_byte).ok_or_eyre("unknown blob kind")?;
+    if data.len() as u64 != kind.file_len() {
+        eyre::bail!("archived blob has {} bytes, expected {}", data.len(), kind.file_len());
+    }
+    let (commitment, rest) = data[33..].split_at(48);
+    let (blob, proofs) = rest.split_at(BYTES_PER_BLOB);
+    Ok(Some(LoadArchivedBlob {
+        commitment: Bytes48::from_slice(commitment),
+        blob: Box::new(Blob::from_slice(blob)),
+        proofs: proofs.chunks_exact(48).map(Bytes48::from_slice).collect(),
+    }))
+}
+
+/// Reads the hash of the block that archived a blob file, if the file exists.
+fn read_archiving_block(path: &Path) -> eyre::Result<Option<B256>> {
+    let mut header = [0u8; 33];
+    match fs::File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => Ok(Some(B256::from_slice(&header[1..]))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn write_atomic(path: &Path, data: &[u8]) -> eyre::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(TMP_SUFFIX);
+    fs::write(&tmp, data).wrap_err_with(|| format!("writing {}", path.display()))?;
+    fs::rename(&tmp, path).wrap_err_with(|| format!("writing {}", path.display()))?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> eyre::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+fn is_not_found(err: &eyre::Report) -> bool {
+    err.downcast_ref::<io::Error>().is_some_and(|err| err.kind() == io::ErrorKind::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eip4844_sidecar(seed: u8) -> Arc<BlobTransactionSidecarVariant> {
+        Arc::new(BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::new(
+            vec![Blob::repeat_byte(seed)],
+            vec![Bytes48::repeat_byte(seed)],
+            vec![Bytes48::repeat_byte(seed.wrapping_add(1))],
+        )))
+    }
+
+    fn eip7594_sidecar(seed: u8) -> Arc<BlobTransactionSidecarVariant> {
+        Arc::new(BlobTransactionSidecarVariant::Eip7594(BlobTransactionSidecarEip7594::new(
+            vec![Blob::repeat_byte(seed)],
+            vec![Bytes48::repeat_byte(seed)],
+            vec![Bytes48::repeat_byte(seed.wrapping_add(1)); CELLS_PER_EXT_BLOB],
+        )))
+    }
+
+    fn versioned_hash(seed: u8) -> B256 {
+        kzg_to_versioned_hash(Bytes48::repeat_byte(seed).as_slice())
+    }
+
+    #[test]
+    fn archives_and_serves_both_sidecar_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = LoadBlobArchive::open(dir.path(), 16).unwrap();
+        let archived = archive
+            .archive_block(1, B256::with_last_byte(1), &[eip4844_sidecar(1), eip7594_sidecar(2)])
+            .unwrap();
+        assert_eq!(archived, 2);
+
+        let v1 = archive.blob_v1(&versioned_hash(1)).expect("EIP-4844 blob archived");
+        assert_eq!(*v1.blob, Blob::repeat_byte(1));
+        assert_eq!(v1.proof, Bytes48::repeat_byte(2));
+        assert!(archive.blob_v2(&versioned_hash(1)).is_none(), "no cell proofs for EIP-4844");
+
+        let v2 = archive.blob_v2(&versioned_hash(2)).expect("EIP-7594 blob archived");
+        assert_eq!(v2.proofs.len(), CELLS_PER_EXT_BLOB);
+        assert!(archive.blob_v1(&versioned_hash(2)).is_none());
+        assert!(archive.blob(&versioned_hash(3)).is_none());
+
+        // Transactions' sidecars are reassembled from their blobs.
+        assert_eq!(archive.sidecar(&[versioned_hash(1)]).as_ref(), Some(&*eip4844_sidecar(1)));
+        assert_eq!(archive.sidecar(&[versioned_hash(2)]).as_ref(), Some(&*eip7594_sidecar(2)));
+        assert!(archive.sidecar(&[versioned_hash(1), versioned_hash(2)]).is_none(), "mixed kinds");
+        assert!(archive.sidecar(&[versioned_hash(1), versioned_hash(3)]).is_none(), "missing blob");
+
+        // A reopened archive picks up what is on disk.
+        let reopened = LoadBlobArchive::open(dir.path(), 16).unwrap();
+        assert_eq!(reopened.usage(), archive.usage());
+        assert_eq!(reopened.usage().1, 2);
+    }
+
+    #[test]
+    fn prunes_outside_retention_and_keeps_reused_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = LoadBlobArchive::open(dir.path(), 2).unwrap();
+        archive.archive_block(1, B256::with_last_byte(1), &[eip4844_sidecar(1)]).unwrap();
+        archive.archive_block(2, B256::with_last_byte(2), &[eip4844_sidecar(2)]).unwrap();
+        // Block 3 carries blob 1 again.
+        archive
+            .archive_block(3, B256::with_last_byte(3), &[eip4844_sidecar(1), eip4844_sidecar(3)])
+            .unwrap();
+
+        assert_eq!(archive.prune(3).unwrap(), 1);
+        assert!(archive.blob(&versioned_hash(1)).is_some(), "still archived by block 3");
+        assert!(archive.blob(&versioned_hash(2)).is_some());
+
+        assert_eq!(archive.prune(5).unwrap(), 2);
+        assert_eq!(archive.usage(), (0, 0, 0));
+        assert!(archive.blob(&versioned_hash(1)).is_none());
+    }
+
+    #[test]
+    fn archiving_a_block_again_merges_its_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = LoadBlobArchive::open(dir.path(), 16).unwrap();
+        let block_hash = B256::with_last_byte(1);
+        archive.archive_block(1, block_hash, &[eip4844_sidecar(1)]).unwrap();
+        assert_eq!(archive.archive_block(1, block_hash, &[eip4844_sidecar(2)]).unwrap(), 1);
+        assert_eq!(archive.usage().1, 2);
+
+        // Both parts leave with the block.
+        archive.remove_block(1, block_hash).unwrap();
+        assert!(archive.blob(&versioned_hash(1)).is_none());
+        assert!(archive.blob(&versioned_hash(2)).is_none());
+    }
+
+    #[test]
+    fn reverted_block_loses_its_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = LoadBlobArchive::open(dir.path(), 16).unwrap();
+        archive.archive_block(1, B256::with_last_byte(1), &[eip4844_sidecar(1)]).unwrap();
+        archive.remove_block(1, B256::with_last_byte(1)).unwrap();
+        assert!(archive.blob(&versioned_hash(1)).is_none());
+        assert_eq!(archive.usage(), (0, 0, 0));
+    }
+}
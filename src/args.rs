@@ -0,0 +1,488 @@
+//! Load-specific CLI arguments.
+//!
+//! These are appended to `load-reth node` as the reth CLI extension so operators can tune Load
+//! behaviour without patching the binary. Everything here is optional; defaults match the
+//! production Load Network configuration.
+
+use std::{path::PathBuf, time::Duration};
+
+use alloy_primitives::{Address, Bytes};
+use clap::Args;
+
+#[cfg(feature = "parallel-exec")]
+use crate::engine::parallel::LoadParallelConfig;
+use crate::{
+    archive::{LoadBlobArchiveConfig, DEFAULT_BLOB_ARCHIVE_RETENTION_BLOCKS},
+    engine::{
+        budget::{LoadBuildLimiter, LoadSidecarBudget},
+        fee_recipient::LoadFeeRecipientPolicy,
+        finality::LoadFinalityGuard,
+        payload::validate_extra_data,
+        payload_cache::{LoadPayloadResponseCache, DEFAULT_PAYLOAD_CACHE_ENTRIES},
+        prefetch::LoadStatePrefetcher,
+        recorder::{
+            LoadEngineRecorderConfig, DEFAULT_ENGINE_RECORDER_MAX_FILES,
+            DEFAULT_ENGINE_RECORDER_MAX_FILE_BYTES,
+        },
+    },
+    node::dev::LoadDevConfig,
+    version::load_client_version_string,
+};
+
+/// Placeholder replaced with the load-reth client version string.
+const EXTRA_DATA_CLIENT_PLACEHOLDER: &str = "{client}";
+/// Placeholder replaced with `--load.validator-name`.
+const EXTRA_DATA_VALIDATOR_PLACEHOLDER: &str = "{validator}";
+/// Template used when no `--load.extra-data` is provided.
+const DEFAULT_EXTRA_DATA_TEMPLATE: &str = EXTRA_DATA_CLIENT_PLACEHOLDER;
+
+/// Load Network node arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Args)]
+#[command(next_help_heading = "Load")]
+pub struct LoadArgs {
+    /// Template for the `extraData` field of locally built blocks (max 32 bytes once rendered).
+    ///
+    /// Supports `{client}` (load-reth version string) and `{validator}` (`--load.validator-name`).
+    /// A value supplied by the CL in the payload attributes takes precedence. Defaults to
+    /// `{client}`.
+    #[arg(long = "load.extra-data", value_name = "TEMPLATE")]
+    pub extra_data: Option<String>,
+
+    /// Validator name substituted for `{validator}` in `--load.extra-data`.
+    #[arg(long = "load.validator-name", value_name = "NAME")]
+    pub validator_name: Option<String>,
+
+    /// Comma-separated fee recipients accepted in payload attributes.
+    ///
+    /// When set, forkchoice updates suggesting any other recipient are rejected.
+    #[arg(long = "load.fee-recipient.allowlist", value_name = "ADDRESSES", value_delimiter = ',')]
+    pub fee_recipient_allowlist: Vec<Address>,
+
+    /// Reject payload attributes that suggest the zero address as fee recipient.
+    #[arg(long = "load.fee-recipient.deny-zero")]
+    pub fee_recipient_deny_zero: bool,
+
+    /// Memory budget in MiB for blob sidecars held by all in-flight payload jobs.
+    ///
+    /// Builds stop adding blob transactions once the budget is exhausted. Defaults to 1024.
+    #[arg(long = "load.payload.sidecar-budget-mb", value_name = "MIB")]
+    pub sidecar_budget_mb: Option<u64>,
+
+    /// Maximum number of payload builds running at the same time. Defaults to 4.
+    #[arg(long = "load.payload.max-concurrent-builds", value_name = "COUNT")]
+    pub max_concurrent_builds: Option<usize>,
+
+    /// Memory limit in MiB for pre-serialized `engine_getPayloadV3/V4` responses. Defaults to 512.
+    #[arg(long = "load.engine.payload-cache-mb", value_name = "MIB")]
+    pub payload_cache_mb: Option<u64>,
+
+    /// Run a standalone devnet: the node produces and instantly finalizes its own blocks instead
+    /// of waiting for a consensus client.
+    #[arg(long = "load.dev")]
+    pub dev: bool,
+
+    /// Block interval in milliseconds for `--load.dev`. Without it a block is built as soon as
+    /// transactions are pending.
+    #[arg(long = "load.dev.block-time-ms", value_name = "MS", requires = "dev")]
+    pub dev_block_time_ms: Option<u64>,
+
+    /// Fee recipient of `--load.dev` blocks. Defaults to the zero address.
+    #[arg(long = "load.dev.fee-recipient", value_name = "ADDRESS", requires = "dev")]
+    pub dev_fee_recipient: Option<Address>,
+
+    /// Pending transactions executed per pass of the background state prefetcher, which warms the
+    /// state read by the next payload build. `0` disables prefetching. Defaults to 1024.
+    #[arg(long = "load.payload.prefetch-txs", value_name = "COUNT")]
+    pub prefetch_txs: Option<usize>,
+
+    /// Keep the blob sidecars of canonical blocks on disk after they leave the pool, so
+    /// `engine_getBlobs*` can still serve them.
+    #[arg(long = "load.blob-archive")]
+    pub blob_archive: bool,
+
+    /// Blocks of blobs kept by `--load.blob-archive`. Defaults to 4096.
+    #[arg(
+        long = "load.blob-archive.retention-blocks",
+        value_name = "BLOCKS",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "blob_archive"
+    )]
+    pub blob_archive_retention_blocks: Option<u64>,
+
+    /// Directory of `--load.blob-archive`. Defaults to `blob-archive` in the data directory.
+    #[arg(long = "load.blob-archive.dir", value_name = "PATH", requires = "blob_archive")]
+    pub blob_archive_dir: Option<PathBuf>,
+
+    /// Index blob versioned hashes to the block and transaction that included them, served by
+    /// `load_getBlobInclusion`. Stored under `blob-index` in the data directory.
+    #[arg(long = "load.blob-index")]
+    pub blob_index: bool,
+
+    /// Record every forkchoiceUpdated, newPayload, getPayload and getBlobs call to a rotating
+    /// log that `load-reth engine replay` can feed into a fresh node.
+    #[arg(long = "load.engine-recorder")]
+    pub engine_recorder: bool,
+
+    /// Directory of `--load.engine-recorder`. Defaults to `engine-recordings` in the data
+    /// directory.
+    #[arg(long = "load.engine-recorder.dir", value_name = "PATH", requires = "engine_recorder")]
+    pub engine_recorder_dir: Option<PathBuf>,
+
+    /// Size in MiB at which the recorder starts a new file. Defaults to 256.
+    #[arg(
+        long = "load.engine-recorder.max-file-mb",
+        value_name = "MIB",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "engine_recorder"
+    )]
+    pub engine_recorder_max_file_mb: Option<u64>,
+
+    /// Recording files kept; the oldest is deleted when a new one starts. Defaults to 16.
+    #[arg(
+        long = "load.engine-recorder.max-files",
+        value_name = "COUNT",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "engine_recorder"
+    )]
+    pub engine_recorder_max_files: Option<u64>,
+
+    /// Refuse to build payloads until the consensus client has advertised every `load.*`
+    /// capability derived from the chain spec, with matching values, through
+    /// `engine_exchangeCapabilities`. A refused forkchoice update is still applied without its
+    /// attributes. Without it a mismatch is only logged and counted.
+    #[arg(long = "load.engine.require-capabilities")]
+    pub require_capabilities: bool,
+
+    /// Reject forkchoice updates whose head, safe and finalized blocks differ. Updates that move
+    /// the finalized block backwards or off the finalized chain are always rejected.
+    #[arg(long = "load.engine.instant-finality")]
+    pub instant_finality: bool,
+
+    /// Worker threads for parallel transaction execution in the payload builder, shared by all
+    /// concurrent builds. `1` turns it off. Defaults to the number of CPUs.
+    #[cfg(feature = "parallel-exec")]
+    #[arg(long = "load.payload.parallel-threads", value_name = "COUNT")]
+    pub parallel_threads: Option<usize>,
+}
+
+impl LoadArgs {
+    /// Renders the configured `extraData` template and checks it fits in a header.
+    pub fn resolve_extra_data(&self) -> eyre::Result<Bytes> {
+        let template = self.extra_data.as_deref().unwrap_or(DEFAULT_EXTRA_DATA_TEMPLATE);
+        let mut rendered =
+            template.replace(EXTRA_DATA_CLIENT_PLACEHOLDER, load_client_version_string());
+
+        if rendered.contains(EXTRA_DATA_VALIDATOR_PLACEHOLDER) {
+            let Some(name) = self.validator_name.as_deref() else {
+                eyre::bail!(
+                    "--load.extra-data uses {{validator}} but --load.validator-name is unset"
+                );
+            };
+            rendered = rendered.replace(EXTRA_DATA_VALIDATOR_PLACEHOLDER, name);
+        }
+
+        let extra_data = Bytes::from(rendered.into_bytes());
+        validate_extra_data(&extra_data)?;
+        Ok(extra_data)
+    }
+
+    /// Sidecar memory budget shared by all payload jobs.
+    pub fn sidecar_budget(&self) -> LoadSidecarBudget {
+        self.sidecar_budget_mb
+            .map(|mib| LoadSidecarBudget::new(mib.saturating_mul(1024 * 1024)))
+            .unwrap_or_default()
+    }
+
+    /// Limiter for concurrently running payload builds.
+    pub fn build_limiter(&self) -> eyre::Result<LoadBuildLimiter> {
+        match self.max_concurrent_builds {
+            Some(0) => eyre::bail!("--load.payload.max-concurrent-builds must be at least 1"),
+            Some(max) => Ok(LoadBuildLimiter::new(max)),
+            None => Ok(LoadBuildLimiter::default()),
+        }
+    }
+
+    /// Parallel execution settings, or `None` when it is turned off.
+    #[cfg(feature = "parallel-exec")]
+    pub fn parallel_config(&self) -> eyre::Result<Option<LoadParallelConfig>> {
+        match self.parallel_threads {
+            Some(0) => eyre::bail!("--load.payload.parallel-threads must be at least 1"),
+            Some(threads) => Ok(Some(LoadParallelConfig::new(threads)).filter(|c| c.threads > 1)),
+            None => Ok(Some(LoadParallelConfig::default()).filter(|c| c.threads > 1)),
+        }
+    }
+
+    /// Block production settings when `--load.dev` is set.
+    pub fn dev_config(&self) -> eyre::Result<Option<LoadDevConfig>> {
+        if !self.dev {
+            return Ok(None);
+        }
+        if self.dev_block_time_ms == Some(0) {
+            eyre::bail!("--load.dev.block-time-ms must be at least 1");
+        }
+        let fee_recipient = self.dev_fee_recipient.unwrap_or_default();
+        // Our own forkchoice updates go through the same attribute checks as a CL's.
+        self.fee_recipient_policy().check(fee_recipient).map_err(|err| {
+            eyre::eyre!("--load.dev.fee-recipient {fee_recipient} is not accepted: {err}")
+        })?;
+        Ok(Some(
+            LoadDevConfig::default()
+                .with_block_time(self.dev_block_time_ms.map(Duration::from_millis))
+                .with_fee_recipient(fee_recipient),
+        ))
+    }
+
+    /// Background state prefetcher for the payload builder.
+    pub fn state_prefetcher(&self) -> LoadStatePrefetcher {
+        self.prefetch_txs.map(LoadStatePrefetcher::new).unwrap_or_default()
+    }
+
+    /// Cache of encoded `getPayload` responses served by the Engine API.
+    pub fn payload_response_cache(&self) -> LoadPayloadResponseCache {
+        self.payload_cache_mb
+            .map(|mib| {
+                LoadPayloadResponseCache::new(
+                    DEFAULT_PAYLOAD_CACHE_ENTRIES,
+                    mib.saturating_mul(1024 * 1024),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Blob archive settings when `--load.blob-archive` is set.
+    pub fn blob_archive_config(&self) -> Option<LoadBlobArchiveConfig> {
+        self.blob_archive.then(|| {
+            LoadBlobArchiveConfig::default()
+                .with_retention_blocks(
+                    self.blob_archive_retention_blocks
+                        .unwrap_or(DEFAULT_BLOB_ARCHIVE_RETENTION_BLOCKS),
+                )
+                .with_dir(self.blob_archive_dir.clone())
+        })
+    }
+
+    /// Engine API recorder settings when `--load.engine-recorder` is set.
+    pub fn engine_recorder_config(&self) -> Option<LoadEngineRecorderConfig> {
+        self.engine_recorder.then(|| {
+            LoadEngineRecorderConfig::default()
+                .with_max_file_bytes(
+                    self.engine_recorder_max_file_mb
+                        .map_or(DEFAULT_ENGINE_RECORDER_MAX_FILE_BYTES, |mib| {
+                            mib.saturating_mul(1024 * 1024)
+                        }),
+                )
+                .with_max_files(
+                    self.engine_recorder_max_files
+                        .map_or(DEFAULT_ENGINE_RECORDER_MAX_FILES, |files| files as usize),
+                )
+                .with_dir(self.engine_recorder_dir.clone())
+        })
+    }
+
+    /// Finality guard applied to forkchoice updates.
+    pub const fn finality_guard(&self) -> LoadFinalityGuard {
+        LoadFinalityGuard::default().with_instant_finality(self.instant_finality)
+    }
+
+    /// Fee-recipient policy enforced on payload attributes.
+    pub fn fee_recipient_policy(&self) -> LoadFeeRecipientPolicy {
+        LoadFeeRecipientPolicy::default()
+            .with_allowlist(self.fee_recipient_allowlist.iter().copied())
+            .with_deny_zero(self.fee_recipient_deny_zero)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::engine::prefetch::DEFAULT_PREFETCH_TRANSACTIONS;
+
+    #[derive(Parser)]
+    struct CommandParser {
+        #[command(flatten)]
+        load: LoadArgs,
+    }
+
+    #[test]
+    fn default_extra_data_is_client_version() {
+        let args = CommandParser::parse_from(["load-reth"]).load;
+        let extra_data = args.resolve_extra_data().expect("default fits");
+        assert_eq!(extra_data.as_ref(), load_client_version_string().as_bytes());
+    }
+
+    #[test]
+    fn renders_validator_placeholder() {
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.extra-data",
+            "load/{validator}",
+            "--load.validator-name",
+            "val-7",
+        ])
+        .load;
+        assert_eq!(args.resolve_extra_data().unwrap().as_ref(), b"load/val-7");
+    }
+
+    #[test]
+    fn validator_placeholder_requires_name() {
+        let args =
+            CommandParser::parse_from(["load-reth", "--load.extra-data", "{validator}"]).load;
+        assert!(args.resolve_extra_data().is_err());
+    }
+
+    #[test]
+    fn parses_fee_recipient_policy() {
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.fee-recipient.allowlist",
+            "0x00000000000000000000000000000000000000aa,0x00000000000000000000000000000000000000bb",
+            "--load.fee-recipient.deny-zero",
+        ])
+        .load;
+        assert_eq!(args.fee_recipient_allowlist.len(), 2);
+
+        let policy = args.fee_recipient_policy();
+        assert!(policy.check(Address::ZERO).is_err());
+        assert!(policy.check(Address::repeat_byte(0x11)).is_err());
+        assert!(policy.check(args.fee_recipient_allowlist[0]).is_ok());
+    }
+
+    #[test]
+    fn parses_payload_memory_limits() {
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.payload.sidecar-budget-mb",
+            "256",
+            "--load.payload.max-concurrent-builds",
+            "2",
+        ])
+        .load;
+        assert_eq!(args.sidecar_budget().capacity(), 256 * 1024 * 1024);
+        assert!(args.build_limiter().is_ok());
+
+        let args =
+            CommandParser::parse_from(["load-reth", "--load.payload.max-concurrent-builds", "0"])
+                .load;
+        assert!(args.build_limiter().is_err());
+    }
+
+    #[test]
+    fn parses_dev_mode() {
+        let args = CommandParser::parse_from(["load-reth"]).load;
+        assert_eq!(args.dev_config().unwrap(), None);
+
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.dev",
+            "--load.dev.block-time-ms",
+            "500",
+            "--load.dev.fee-recipient",
+            "0x00000000000000000000000000000000000000aa",
+        ])
+        .load;
+        let config = args.dev_config().unwrap().expect("dev mode enabled");
+        assert_eq!(config.block_time, Some(Duration::from_millis(500)));
+        assert_eq!(config.fee_recipient, Address::with_last_byte(0xaa));
+
+        let args = CommandParser::parse_from(["load-reth", "--load.dev"]).load;
+        assert_eq!(args.dev_config().unwrap().unwrap().block_time, None);
+
+        assert!(
+            CommandParser::try_parse_from(["load-reth", "--load.dev.block-time-ms", "1"]).is_err()
+        );
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.dev",
+            "--load.fee-recipient.deny-zero",
+        ])
+        .load;
+        assert!(args.dev_config().is_err(), "zero fee recipient denied by policy");
+    }
+
+    #[test]
+    fn parses_blob_archive() {
+        let args = CommandParser::parse_from(["load-reth"]).load;
+        assert_eq!(args.blob_archive_config(), None);
+
+        let args = CommandParser::parse_from(["load-reth", "--load.blob-archive"]).load;
+        assert_eq!(args.blob_archive_config(), Some(LoadBlobArchiveConfig::default()));
+
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.blob-archive",
+            "--load.blob-archive.retention-blocks",
+            "64",
+            "--load.blob-archive.dir",
+            "/tmp/blobs",
+        ])
+        .load;
+        let config = args.blob_archive_config().expect("archive enabled");
+        assert_eq!(config.retention_blocks, 64);
+        assert_eq!(config.dir, Some(PathBuf::from("/tmp/blobs")));
+
+        assert!(CommandParser::try_parse_from([
+            "load-reth",
+            "--load.blob-archive",
+            "--load.blob-archive.retention-blocks",
+            "0",
+        ])
+        .is_err());
+        assert!(CommandParser::try_parse_from([
+            "load-reth",
+            "--load.blob-archive.retention-blocks",
+            "64"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_engine_recorder() {
+        let args = CommandParser::parse_from(["load-reth"]).load;
+        assert_eq!(args.engine_recorder_config(), None);
+
+        let args = CommandParser::parse_from(["load-reth", "--load.engine-recorder"]).load;
+        assert_eq!(args.engine_recorder_config(), Some(LoadEngineRecorderConfig::default()));
+
+        let args = CommandParser::parse_from([
+            "load-reth",
+            "--load.engine-recorder",
+            "--load.engine-recorder.dir",
+            "/tmp/engine",
+            "--load.engine-recorder.max-file-mb",
+            "8",
+            "--load.engine-recorder.max-files",
+            "3",
+        ])
+        .load;
+        let config = args.engine_recorder_config().expect("recorder enabled");
+        assert_eq!(config.max_file_bytes, 8 * 1024 * 1024);
+        assert_eq!(config.max_files, 3);
+        assert_eq!(config.dir, Some(PathBuf::from("/tmp/engine")));
+
+        assert!(CommandParser::try_parse_from([
+            "load-reth",
+            "--load.engine-recorder.max-files",
+            "3"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_prefetch_transactions() {
+        let args = CommandParser::parse_from(["load-reth"]).load;
+        assert_eq!(args.state_prefetcher().max_transactions(), DEFAULT_PREFETCH_TRANSACTIONS);
+
+        let args =
+            CommandParser::parse_from(["load-reth", "--load.payload.prefetch-txs", "0"]).load;
+        assert!(!args.state_prefetcher().is_enabled());
+    }
+
+    #[test]
+    fn rejects_extra_data_over_32_bytes() {
+        let args =
+            CommandParser::parse_from(["load-reth", "--load.extra-data", &"x".repeat(33)]).load;
+        assert!(args.resolve_extra_data().is_err());
+    }
+}
@@ -0,0 +1,627 @@
+//! Local bundle pool for `eth_sendBundle`.
+//!
+//! Searchers and sequencers submit ordered groups of transactions that must land together or not
+//! at all. Bundles target a single block number; the payload builder pulls the bundles for the
+//! block it is building, simulates each one on top of the state accepted so far and includes it
+//! atomically when it pays the fee recipient. Outcomes are tracked per bundle hash, which covers
+//! the target block and timestamp window as well as the transactions, until the target block is
+//! canonical and then kept for [`BUNDLE_STATUS_RETENTION_BLOCKS`] blocks so
+//! submitters can poll `load_getBundleStatus`.
+//!
+//! `eth_sendBundle` is unauthenticated, so the pool only accepts bundles for the next
+//! [`MAX_BUNDLE_BLOCKS_AHEAD`] blocks and caps the encoded size of everything pending, blob
+//! sidecars included, at [`MAX_PENDING_BUNDLE_BYTES`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloy_consensus::{
+    transaction::{Recovered, SignerRecoverable},
+    Signed, Transaction,
+};
+use alloy_eips::{
+    eip2718::Decodable2718,
+    eip4844::{env_settings::EnvKzgSettings, BlobTransactionSidecarVariant},
+};
+use alloy_primitives::{keccak256, Bytes, B256};
+use alloy_rpc_types_engine::PayloadId;
+use reth_ethereum_primitives::{PooledTransactionVariant, TransactionSigned};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{chainspec::LOAD_MAX_BLOBS_PER_TX, metrics::LoadBundleMetrics};
+
+/// Maximum number of transactions in a single bundle.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 64;
+/// Maximum number of bundles waiting for inclusion.
+pub const MAX_PENDING_BUNDLES: usize = 1024;
+/// How far past the canonical head a bundle may target.
+pub const MAX_BUNDLE_BLOCKS_AHEAD: u64 = 8;
+/// Maximum total network-encoded size of pending bundles, blob sidecars included.
+pub const MAX_PENDING_BUNDLE_BYTES: usize = 256 * 1024 * 1024;
+/// Blocks a finished bundle's status stays queryable after its target block.
+pub const BUNDLE_STATUS_RETENTION_BLOCKS: u64 = 64;
+
+/// `eth_sendBundle` request body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadSendBundle {
+    /// EIP-2718 network encodings; blob transactions must carry their sidecar.
+    pub txs: Vec<Bytes>,
+    /// Block number the bundle is valid for.
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: u64,
+    /// Earliest block timestamp the bundle may be included at.
+    #[serde(default, with = "alloy_serde::quantity::opt", skip_serializing_if = "Option::is_none")]
+    pub min_timestamp: Option<u64>,
+    /// Latest block timestamp the bundle may be included at.
+    #[serde(default, with = "alloy_serde::quantity::opt", skip_serializing_if = "Option::is_none")]
+    pub max_timestamp: Option<u64>,
+    /// Transactions allowed to revert without invalidating the bundle.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverting_tx_hashes: Vec<B256>,
+}
+
+/// `eth_sendBundle` response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadSendBundleResponse {
+    pub bundle_hash: B256,
+}
+
+/// Lifecycle of a submitted bundle as reported by `load_getBundleStatus`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum LoadBundleStatus {
+    /// Waiting for its target block. `last_error` explains the latest build that skipped it.
+    Pending {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_error: Option<String>,
+    },
+    /// Included in a payload built for the target block; not canonical yet.
+    Built { payload_id: PayloadId },
+    /// Included in the canonical chain.
+    Landed {
+        #[serde(with = "alloy_serde::quantity")]
+        block_number: u64,
+        block_hash: B256,
+    },
+    /// Removed before its target block because the builder could not include it atomically.
+    Dropped { reason: String },
+    /// The target block became canonical without the bundle.
+    Expired {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_error: Option<String>,
+    },
+}
+
+/// Bundle submission errors.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LoadBundleError {
+    #[error("bundle has no transactions")]
+    Empty,
+    #[error("bundle has {0} transactions (max {MAX_BUNDLE_TRANSACTIONS})")]
+    TooManyTransactions(usize),
+    #[error("transaction {index}: {reason}")]
+    InvalidTransaction { index: usize, reason: String },
+    #[error("bundle targets block {block_number} but the chain is already at {head}")]
+    StaleBlock { block_number: u64, head: u64 },
+    #[error(
+        "bundle targets block {block_number}, more than {MAX_BUNDLE_BLOCKS_AHEAD} blocks past \
+         the head {head}"
+    )]
+    TooFarAhead { block_number: u64, head: u64 },
+    #[error("minTimestamp {min} is after maxTimestamp {max}")]
+    InvalidTimestampRange { min: u64, max: u64 },
+    #[error("bundle pool is full ({MAX_PENDING_BUNDLES} pending bundles)")]
+    PoolFull,
+    #[error(
+        "bundle of {size} bytes does not fit the pool ({pending} of {MAX_PENDING_BUNDLE_BYTES} \
+         bytes pending)"
+    )]
+    PoolBytesExceeded { size: usize, pending: usize },
+}
+
+/// A bundle transaction together with its blob sidecar, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadBundleTransaction {
+    pub tx: Recovered<TransactionSigned>,
+    pub sidecar: Option<Arc<BlobTransactionSidecarVariant>>,
+}
+
+/// A decoded, signature- and KZG-checked bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadBundle {
+    hash: B256,
+    transactions: Vec<LoadBundleTransaction>,
+    /// Network-encoded size of the transactions, blob sidecars included.
+    encoded_size: usize,
+    block_number: u64,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+    reverting_tx_hashes: HashSet<B256>,
+}
+
+impl LoadBundle {
+    /// Decodes an `eth_sendBundle` request, recovering senders and verifying blob sidecars.
+    pub fn try_from_request(request: LoadSendBundle) -> Result<Self, LoadBundleError> {
+        let LoadSendBundle { txs, block_number, min_timestamp, max_timestamp, reverting_tx_hashes } =
+            request;
+
+        if txs.is_empty() {
+            return Err(LoadBundleError::Empty);
+        }
+        if txs.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(LoadBundleError::TooManyTransactions(txs.len()));
+        }
+        if let (Some(min), Some(max)) = (min_timestamp, max_timestamp) {
+            if min > max {
+                return Err(LoadBundleError::InvalidTimestampRange { min, max });
+            }
+        }
+
+        let transactions = txs
+            .iter()
+            .enumerate()
+            .map(|(index, encoded)| {
+                decode_bundle_transaction(encoded)
+                    .map_err(|reason| LoadBundleError::InvalidTransaction { index, reason })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            hash: bundle_hash(&transactions, block_number, min_timestamp, max_timestamp),
+            transactions,
+            encoded_size: txs.iter().map(Bytes::len).sum(),
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes: reverting_tx_hashes.into_iter().collect(),
+        })
+    }
+
+    /// Bundle hash: keccak256 of the concatenated transaction hashes followed by the big-endian
+    /// target block number, minimum timestamp (0 when unset) and maximum timestamp (`u64::MAX`
+    /// when unset), so resubmitting the same transactions for another block or window is a new
+    /// bundle.
+    pub const fn hash(&self) -> B256 {
+        self.hash
+    }
+
+    pub const fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    pub fn transactions(&self) -> &[LoadBundleTransaction] {
+        &self.transactions
+    }
+
+    /// Network-encoded size of the transactions, blob sidecars included.
+    pub const fn encoded_size(&self) -> usize {
+        self.encoded_size
+    }
+
+    /// Returns `true` if the transaction may revert without invalidating the bundle.
+    pub fn may_revert(&self, tx_hash: &B256) -> bool {
+        self.reverting_tx_hashes.contains(tx_hash)
+    }
+
+    /// Sum of the transactions' gas limits.
+    pub fn gas_limit(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.tx.gas_limit()).sum()
+    }
+
+    /// Number of blobs carried by the bundle.
+    pub fn blob_count(&self) -> u64 {
+        self.transactions
+            .iter()
+            .filter_map(|tx| tx.tx.blob_versioned_hashes())
+            .map(|hashes| hashes.len() as u64)
+            .sum()
+    }
+
+    /// Returns `true` if the bundle may be included in a block with this number and timestamp.
+    pub fn is_eligible(&self, block_number: u64, timestamp: u64) -> bool {
+        self.block_number == block_number &&
+            self.min_timestamp.is_none_or(|min| timestamp >= min) &&
+            self.max_timestamp.is_none_or(|max| timestamp <= max)
+    }
+}
+
+/// Hashes the transaction hashes together with the target block and timestamp window.
+fn bundle_hash(
+    transactions: &[LoadBundleTransaction],
+    block_number: u64,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+) -> B256 {
+    let mut preimage = Vec::with_capacity(transactions.len() * 32 + 24);
+    for tx in transactions {
+        preimage.extend_from_slice(tx.tx.hash().as_slice());
+    }
+    preimage.extend_from_slice(&block_number.to_be_bytes());
+    preimage.extend_from_slice(&min_timestamp.unwrap_or(0).to_be_bytes());
+    preimage.extend_from_slice(&max_timestamp.unwrap_or(u64::MAX).to_be_bytes());
+    keccak256(preimage)
+}
+
+/// Decodes a network-encoded transaction, splitting off and verifying any blob sidecar.
+fn decode_bundle_transaction(encoded: &Bytes) -> Result<LoadBundleTransaction, String> {
+    let pooled =
+        PooledTransactionVariant::decode_2718_exact(encoded).map_err(|err| err.to_string())?;
+
+    let (tx, sidecar) = match pooled {
+        PooledTransactionVariant::Legacy(tx) => (TransactionSigned::Legacy(tx), None),
+        PooledTransactionVariant::Eip2930(tx) => (TransactionSigned::Eip2930(tx), None),
+        PooledTransactionVariant::Eip1559(tx) => (TransactionSigned::Eip1559(tx), None),
+        PooledTransactionVariant::Eip7702(tx) => (TransactionSigned::Eip7702(tx), None),
+        PooledTransactionVariant::Eip4844(signed) => {
+            let (tx, signature, hash) = signed.into_parts();
+            let (tx, sidecar) = tx.into_parts();
+            if tx.blob_versioned_hashes.len() > LOAD_MAX_BLOBS_PER_TX as usize {
+                return Err(format!(
+                    "{} blobs exceeds the per-transaction limit of {LOAD_MAX_BLOBS_PER_TX}",
+                    tx.blob_versioned_hashes.len()
+                ));
+            }
+            sidecar
+                .validate(&tx.blob_versioned_hashes, EnvKzgSettings::Default.get())
+                .map_err(|err| format!("invalid blob sidecar: {err}"))?;
+            (
+                TransactionSigned::Eip4844(Signed::new_unchecked(tx, signature, hash)),
+                Some(Arc::new(sidecar)),
+            )
+        }
+    };
+
+    let tx = tx.try_into_recovered().map_err(|_| "invalid signature".to_string())?;
+    Ok(LoadBundleTransaction { tx, sidecar })
+}
+
+#[derive(Debug)]
+struct BundleRecord {
+    block_number: u64,
+    status: LoadBundleStatus,
+}
+
+#[derive(Debug, Default)]
+struct BundlePoolInner {
+    /// Highest canonical block seen so far.
+    head: u64,
+    pending: Vec<Arc<LoadBundle>>,
+    /// Sum of [`LoadBundle::encoded_size`] over `pending`.
+    pending_bytes: usize,
+    records: HashMap<B256, BundleRecord>,
+}
+
+impl BundlePoolInner {
+    /// Removes the pending bundles `select` matches and returns them.
+    fn take_pending(&mut self, select: impl Fn(&LoadBundle) -> bool) -> Vec<Arc<LoadBundle>> {
+        let (taken, pending): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.pending).into_iter().partition(|bundle| select(bundle));
+        self.pending = pending;
+        self.pending_bytes -= taken.iter().map(|bundle| bundle.encoded_size()).sum::<usize>();
+        taken
+    }
+}
+
+/// Shared pool of bundles waiting for their target block.
+///
+/// Cloning is cheap; every clone refers to the same pool.
+#[derive(Debug, Clone, Default)]
+pub struct LoadBundlePool {
+    inner: Arc<Mutex<BundlePoolInner>>,
+    metrics: LoadBundleMetrics,
+}
+
+impl LoadBundlePool {
+    fn lock(&self) -> MutexGuard<'_, BundlePoolInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Adds a bundle and returns its hash. Re-submitting a known bundle is a no-op.
+    pub fn insert(&self, bundle: LoadBundle) -> Result<B256, LoadBundleError> {
+        let mut inner = self.lock();
+        let hash = bundle.hash();
+
+        if inner.records.contains_key(&hash) {
+            return Ok(hash);
+        }
+        if bundle.block_number() <= inner.head {
+            return Err(LoadBundleError::StaleBlock {
+                block_number: bundle.block_number(),
+                head: inner.head,
+            });
+        }
+        if bundle.block_number() > inner.head + MAX_BUNDLE_BLOCKS_AHEAD {
+            return Err(LoadBundleError::TooFarAhead {
+                block_number: bundle.block_number(),
+                head: inner.head,
+            });
+        }
+        if inner.pending.len() >= MAX_PENDING_BUNDLES {
+            return Err(LoadBundleError::PoolFull);
+        }
+        if inner.pending_bytes + bundle.encoded_size() > MAX_PENDING_BUNDLE_BYTES {
+            return Err(LoadBundleError::PoolBytesExceeded {
+                size: bundle.encoded_size(),
+                pending: inner.pending_bytes,
+            });
+        }
+
+        inner.records.insert(
+            hash,
+            BundleRecord {
+                block_number: bundle.block_number(),
+                status: LoadBundleStatus::Pending { last_error: None },
+            },
+        );
+        inner.pending_bytes += bundle.encoded_size();
+        inner.pending.push(Arc::new(bundle));
+        self.metrics.record_received(inner.pending.len(), inner.pending_bytes);
+        Ok(hash)
+    }
+
+    /// Moves the head forward without settling any block, e.g. to the canonical head at
+    /// startup before the first notification arrives.
+    pub fn set_head(&self, head: u64) {
+        let mut inner = self.lock();
+        inner.head = inner.head.max(head);
+    }
+
+    /// Bundles eligible for a block, in submission order.
+    pub fn bundles_for(&self, block_number: u64, timestamp: u64) -> Vec<Arc<LoadBundle>> {
+        self.lock()
+            .pending
+            .iter()
+            .filter(|bundle| bundle.is_eligible(block_number, timestamp))
+            .cloned()
+            .collect()
+    }
+
+    /// Records that a bundle was included in a built payload.
+    pub fn mark_built(&self, hash: B256, payload_id: PayloadId) {
+        if let Some(record) = self.lock().records.get_mut(&hash) {
+            record.status = LoadBundleStatus::Built { payload_id };
+            self.metrics.record_built();
+        }
+    }
+
+    /// Records why the latest build skipped a bundle. The bundle stays pending.
+    pub fn mark_rejected(&self, hash: B256, reason: String) {
+        if let Some(record) = self.lock().records.get_mut(&hash) {
+            record.status = LoadBundleStatus::Pending { last_error: Some(reason) };
+            self.metrics.record_rejected();
+        }
+    }
+
+    /// Removes a bundle that cannot be included atomically so later builds skip it.
+    pub fn drop_bundle(&self, hash: B256, reason: String) {
+        let mut inner = self.lock();
+        inner.take_pending(|bundle| bundle.hash() == hash);
+        let (pending, pending_bytes) = (inner.pending.len(), inner.pending_bytes);
+        if let Some(record) = inner.records.get_mut(&hash) {
+            record.status = LoadBundleStatus::Dropped { reason };
+            self.metrics.record_dropped(pending, pending_bytes);
+        }
+    }
+
+    /// Settles bundles targeting `block_number` or earlier once that block is canonical.
+    pub fn on_canonical_block(
+        &self,
+        block_number: u64,
+        block_hash: B256,
+        tx_hashes: &HashSet<B256>,
+    ) {
+        let mut inner = self.lock();
+        inner.head = inner.head.max(block_number);
+
+        let settled = inner.take_pending(|bundle| bundle.block_number() <= block_number);
+
+        for bundle in settled {
+            let landed = bundle.block_number() == block_number &&
+                bundle.transactions().iter().all(|tx| tx_hashes.contains(tx.tx.hash()));
+            let Some(record) = inner.records.get_mut(&bundle.hash()) else { continue };
+            record.status = if landed {
+                self.metrics.record_landed();
+                LoadBundleStatus::Landed { block_number, block_hash }
+            } else {
+                self.metrics.record_expired();
+                let last_error = match &record.status {
+                    LoadBundleStatus::Pending { last_error } => last_error.clone(),
+                    _ => None,
+                };
+                LoadBundleStatus::Expired { last_error }
+            };
+        }
+
+        let cutoff = block_number.saturating_sub(BUNDLE_STATUS_RETENTION_BLOCKS);
+        inner.records.retain(|_, record| record.block_number > cutoff);
+        self.metrics.set_pending(inner.pending.len(), inner.pending_bytes);
+    }
+
+    /// Current status of a bundle, if it is known.
+    pub fn status(&self, hash: &B256) -> Option<LoadBundleStatus> {
+        self.lock().records.get(hash).map(|record| record.status.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{SignableTransaction, TxEip1559};
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    use super::*;
+
+    fn signed_transfer(signer: &PrivateKeySigner, nonce: u64) -> Bytes {
+        let tx = TxEip1559 { chain_id: 16_383, nonce, gas_limit: 21_000, ..Default::default() };
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).expect("signing succeeds");
+        TransactionSigned::from(tx.into_signed(signature)).encoded_2718().into()
+    }
+
+    fn bundle(block_number: u64) -> LoadBundle {
+        let signer = PrivateKeySigner::random();
+        LoadBundle::try_from_request(LoadSendBundle {
+            txs: vec![signed_transfer(&signer, 0), signed_transfer(&signer, 1)],
+            block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+        })
+        .expect("valid bundle")
+    }
+
+    #[test]
+    fn decodes_bundle_and_derives_hash() {
+        let bundle = bundle(1);
+        assert_eq!(bundle.transactions().len(), 2);
+        assert_eq!(bundle.gas_limit(), 42_000);
+        assert_eq!(bundle.blob_count(), 0);
+
+        let mut preimage = Vec::new();
+        for tx in bundle.transactions() {
+            preimage.extend_from_slice(tx.tx.hash().as_slice());
+        }
+        preimage.extend_from_slice(&1u64.to_be_bytes());
+        preimage.extend_from_slice(&0u64.to_be_bytes());
+        preimage.extend_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(bundle.hash(), keccak256(preimage));
+    }
+
+    #[test]
+    fn resubmission_for_another_block_is_a_new_bundle() {
+        let signer = PrivateKeySigner::random();
+        let request = |block_number, max_timestamp| LoadSendBundle {
+            txs: vec![signed_transfer(&signer, 0)],
+            block_number,
+            min_timestamp: None,
+            max_timestamp,
+            reverting_tx_hashes: Vec::new(),
+        };
+        let first = LoadBundle::try_from_request(request(2, None)).unwrap();
+        let next_block = LoadBundle::try_from_request(request(3, None)).unwrap();
+        let bounded = LoadBundle::try_from_request(request(2, Some(100))).unwrap();
+        assert_ne!(first.hash(), next_block.hash());
+        assert_ne!(first.hash(), bounded.hash());
+
+        let pool = LoadBundlePool::default();
+        let first_hash = pool.insert(first).unwrap();
+        let next_hash = pool.insert(next_block).unwrap();
+        assert_ne!(first_hash, next_hash);
+        assert_eq!(pool.bundles_for(2, 0).len(), 1);
+        assert_eq!(pool.bundles_for(3, 0).len(), 1);
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let request = |txs| LoadSendBundle {
+            txs,
+            block_number: 1,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+        };
+        assert_eq!(LoadBundle::try_from_request(request(Vec::new())), Err(LoadBundleError::Empty));
+        assert!(matches!(
+            LoadBundle::try_from_request(request(vec![Bytes::from_static(&[0x02, 0x00])])),
+            Err(LoadBundleError::InvalidTransaction { index: 0, .. })
+        ));
+        assert!(matches!(
+            LoadBundle::try_from_request(request(vec![Bytes::new(); MAX_BUNDLE_TRANSACTIONS + 1])),
+            Err(LoadBundleError::TooManyTransactions(_))
+        ));
+    }
+
+    #[test]
+    fn eligibility_respects_block_and_timestamp_window() {
+        let mut bundle = bundle(5);
+        bundle.min_timestamp = Some(10);
+        bundle.max_timestamp = Some(20);
+        assert!(bundle.is_eligible(5, 10));
+        assert!(bundle.is_eligible(5, 20));
+        assert!(!bundle.is_eligible(5, 21));
+        assert!(!bundle.is_eligible(6, 15));
+    }
+
+    #[test]
+    fn pool_tracks_bundle_lifecycle() {
+        let pool = LoadBundlePool::default();
+        let landing = bundle(2);
+        let missing = bundle(2);
+        let landing_hash = pool.insert(landing.clone()).unwrap();
+        let missing_hash = pool.insert(missing).unwrap();
+        assert_eq!(pool.insert(landing.clone()), Ok(landing_hash), "resubmission is idempotent");
+        assert_eq!(pool.bundles_for(2, 0).len(), 2);
+
+        pool.mark_built(landing_hash, PayloadId::new([1; 8]));
+        pool.mark_rejected(missing_hash, "not profitable".to_string());
+        assert_eq!(
+            pool.status(&missing_hash),
+            Some(LoadBundleStatus::Pending { last_error: Some("not profitable".to_string()) })
+        );
+
+        let tx_hashes = landing.transactions().iter().map(|tx| *tx.tx.hash()).collect();
+        pool.on_canonical_block(2, B256::repeat_byte(2), &tx_hashes);
+        assert_eq!(
+            pool.status(&landing_hash),
+            Some(LoadBundleStatus::Landed { block_number: 2, block_hash: B256::repeat_byte(2) })
+        );
+        assert_eq!(
+            pool.status(&missing_hash),
+            Some(LoadBundleStatus::Expired { last_error: Some("not profitable".to_string()) })
+        );
+        assert!(pool.bundles_for(2, 0).is_empty());
+
+        let dropped = pool.insert(bundle(3)).unwrap();
+        pool.drop_bundle(dropped, "diverged".to_string());
+        assert!(pool.bundles_for(3, 0).is_empty());
+        assert_eq!(
+            pool.status(&dropped),
+            Some(LoadBundleStatus::Dropped { reason: "diverged".to_string() })
+        );
+
+        assert!(matches!(pool.insert(bundle(2)), Err(LoadBundleError::StaleBlock { .. })));
+
+        pool.on_canonical_block(2 + BUNDLE_STATUS_RETENTION_BLOCKS, B256::ZERO, &HashSet::new());
+        assert_eq!(pool.status(&landing_hash), None);
+    }
+
+    #[test]
+    fn pool_bounds_target_block_and_pending_bytes() {
+        let pool = LoadBundlePool::default();
+        pool.set_head(10);
+        assert!(pool.insert(bundle(10 + MAX_BUNDLE_BLOCKS_AHEAD)).is_ok());
+        assert_eq!(
+            pool.insert(bundle(11 + MAX_BUNDLE_BLOCKS_AHEAD)).map(|_| ()),
+            Err(LoadBundleError::TooFarAhead {
+                block_number: 11 + MAX_BUNDLE_BLOCKS_AHEAD,
+                head: 10
+            })
+        );
+
+        let mut large = bundle(11);
+        large.encoded_size = MAX_PENDING_BUNDLE_BYTES;
+        assert!(matches!(pool.insert(large), Err(LoadBundleError::PoolBytesExceeded { .. })));
+
+        // Settling the head block frees the bytes of the bundles it settles.
+        let mut half = bundle(11);
+        half.encoded_size = MAX_PENDING_BUNDLE_BYTES / 2;
+        pool.insert(half.clone()).unwrap();
+        pool.on_canonical_block(11, B256::ZERO, &HashSet::new());
+        let mut other = bundle(12);
+        other.encoded_size = MAX_PENDING_BUNDLE_BYTES - 1_000;
+        assert!(pool.insert(other).is_ok());
+    }
+
+    #[test]
+    fn status_serializes_with_tag() {
+        let status = LoadBundleStatus::Landed { block_number: 16, block_hash: B256::ZERO };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["status"], "landed");
+        assert_eq!(json["blockNumber"], "0x10");
+    }
+}
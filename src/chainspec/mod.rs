@@ -14,11 +14,12 @@ use alloy_eips::{
 };
 use alloy_genesis::Genesis;
 use alloy_primitives::U256;
-use derive_more::{Constructor, Into};
 use eyre::Context;
 use reth::chainspec::{Chain, EthereumHardforks, ForkCondition, Hardfork};
 use reth_chainspec::{ChainSpec, EthChainSpec, Hardforks};
 use reth_cli::chainspec::ChainSpecParser;
+use reth_consensus_common::validation::MAX_RLP_BLOCK_SIZE;
+use reth_primitives_traits::constants::MINIMUM_GAS_LIMIT;
 use serde_json;
 use tracing::{debug, info};
 
@@ -30,27 +31,136 @@ pub const LOAD_MAX_BLOBS_PER_TX: u64 = 32;
 pub const LOAD_BLOB_UPDATE_FRACTION: u128 = 5_007_716;
 /// Default execution gas limit for Load blocks.
 pub const LOAD_EXECUTION_GAS_LIMIT: u64 = 2_000_000_000;
+/// Default maximum RLP-encoded block size for Load blocks, blob sidecars excluded.
+///
+/// Matches the EIP-7934 limit but applies from genesis rather than only after Osaka.
+pub const LOAD_MAX_RLP_BLOCK_SIZE: usize = MAX_RLP_BLOCK_SIZE;
+/// Minimum step between consecutive block timestamps.
+///
+/// Load blocks carry whole-second timestamps that strictly increase, so the block built on top of
+/// a head is never earlier than the head plus one slot. Fork gates that apply to the block being
+/// built compare against that timestamp rather than the head's.
+pub const LOAD_SLOT_SECS: u64 = 1;
+/// Genesis `config` field overriding [`LOAD_MAX_RLP_BLOCK_SIZE`].
+pub const LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY: &str = "loadMaxRlpBlockSize";
+/// Genesis `config` field delaying when imported blocks must respect the max RLP block size.
+///
+/// Defaults to genesis. Chains that already carry larger blocks set it to a future timestamp so
+/// the limit becomes a scheduled rule for imports; the payload builder and txpool apply the limit
+/// from genesis regardless.
+pub const LOAD_MAX_RLP_BLOCK_SIZE_TIME_GENESIS_KEY: &str = "loadMaxRlpBlockSizeTime";
+/// Genesis `config` field overriding the maximum gas limit the CL may request per block.
+pub const LOAD_MAX_GAS_LIMIT_GENESIS_KEY: &str = "loadMaxGasLimit";
+
+/// Load parameters that the upstream chain spec traits do not cover.
+pub trait LoadChainParams {
+    /// Maximum RLP-encoded block size (blob sidecars excluded) at the given timestamp.
+    fn max_rlp_block_size_at_timestamp(&self, timestamp: u64) -> usize;
+
+    /// Whether imported blocks at the given timestamp are held to
+    /// [`max_rlp_block_size_at_timestamp`](Self::max_rlp_block_size_at_timestamp).
+    fn is_max_rlp_block_size_enforced_at_timestamp(&self, timestamp: u64) -> bool;
+
+    /// Maximum block gas limit accepted in payload attributes.
+    ///
+    /// Defaults to [`LOAD_EXECUTION_GAS_LIMIT`].
+    fn max_gas_limit(&self) -> u64;
+}
 
 /// Load chain specification wrapping reth's `ChainSpec`.
-#[derive(Debug, Clone, Into, Constructor, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LoadChainSpec {
     /// The underlying reth chain specification.
     pub inner: ChainSpec,
+    /// Load parameters read from the genesis `config` extra fields.
+    params: LoadGenesisParams,
+}
+
+/// Load-specific genesis `config` fields, parsed once when the chain spec is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LoadGenesisParams {
+    max_rlp_block_size: usize,
+    max_rlp_block_size_time: u64,
+    max_gas_limit: u64,
+}
+
+impl Default for LoadGenesisParams {
+    fn default() -> Self {
+        Self {
+            max_rlp_block_size: LOAD_MAX_RLP_BLOCK_SIZE,
+            max_rlp_block_size_time: 0,
+            max_gas_limit: LOAD_EXECUTION_GAS_LIMIT,
+        }
+    }
+}
+
+impl LoadGenesisParams {
+    fn from_genesis(genesis: &Genesis) -> eyre::Result<Self> {
+        let max_rlp_block_size =
+            genesis_field::<usize>(genesis, LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY)?
+                .unwrap_or(LOAD_MAX_RLP_BLOCK_SIZE);
+        if max_rlp_block_size == 0 {
+            eyre::bail!("{LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY} must be greater than zero");
+        }
+
+        let max_rlp_block_size_time =
+            genesis_field::<u64>(genesis, LOAD_MAX_RLP_BLOCK_SIZE_TIME_GENESIS_KEY)?.unwrap_or(0);
+
+        let max_gas_limit = genesis_field::<u64>(genesis, LOAD_MAX_GAS_LIMIT_GENESIS_KEY)?
+            .unwrap_or(LOAD_EXECUTION_GAS_LIMIT);
+        if max_gas_limit < MINIMUM_GAS_LIMIT {
+            eyre::bail!(
+                "{LOAD_MAX_GAS_LIMIT_GENESIS_KEY} must be at least {MINIMUM_GAS_LIMIT}. Got: {max_gas_limit}"
+            );
+        }
+
+        Ok(Self { max_rlp_block_size, max_rlp_block_size_time, max_gas_limit })
+    }
+}
+
+/// Reads an optional unsigned integer from the genesis `config` extra fields.
+fn genesis_field<T: serde::de::DeserializeOwned>(
+    genesis: &Genesis,
+    key: &str,
+) -> eyre::Result<Option<T>> {
+    genesis
+        .config
+        .extra_fields
+        .get_deserialized::<T>(key)
+        .transpose()
+        .with_context(|| format!("{key} must be an unsigned integer"))
+}
+
+impl From<LoadChainSpec> for ChainSpec {
+    fn from(spec: LoadChainSpec) -> Self {
+        spec.inner
+    }
 }
 
 impl LoadChainSpec {
+    /// Wraps an already built reth chain spec.
+    ///
+    /// Fails if the Load fields in its genesis `config` do not parse.
+    pub fn new(inner: ChainSpec) -> eyre::Result<Self> {
+        let params =
+            LoadGenesisParams::from_genesis(&inner.genesis).context("Genesis validation failed")?;
+        Ok(Self { inner, params })
+    }
+
     /// Create Load chain spec from genesis configuration.
     pub fn from_genesis(mut genesis: Genesis) -> eyre::Result<Self> {
         info!(chain_id = genesis.config.chain_id, "Creating Load chain spec from genesis");
 
         // Validate Load-specific requirements and normalize pre-Cancun forks.
         Self::validate_genesis(&mut genesis).context("Genesis validation failed")?;
+        let params =
+            LoadGenesisParams::from_genesis(&genesis).context("Genesis validation failed")?;
 
         // Build the inner chain spec from the normalized genesis.
         let mut inner: ChainSpec = genesis.clone().into();
         inner.chain = Chain::from_id_unchecked(genesis.config.chain_id);
 
-        // Override blob params for Cancun/Prague with Load limits.
+        // Override blob params for Cancun/Prague/Osaka with Load limits.
         let load_blob_params = BlobParams {
             target_blob_count: LOAD_TARGET_BLOB_COUNT,
             max_blob_count: LOAD_MAX_BLOB_COUNT,
@@ -64,7 +174,11 @@ impl LoadChainSpec {
         inner.blob_params = BlobScheduleBlobParams {
             cancun: load_blob_params,
             prague: load_blob_params,
-            osaka: BlobParams::osaka(),
+            // PeerDAS keeps Load's blob scale; only the EIP-7918 reserve price is taken from Osaka.
+            osaka: BlobParams {
+                blob_base_cost: BlobParams::osaka().blob_base_cost,
+                ..load_blob_params
+            },
             scheduled: Default::default(),
         };
 
@@ -74,7 +188,7 @@ impl LoadChainSpec {
             "Load chain spec created successfully"
         );
 
-        Ok(Self { inner })
+        Ok(Self { inner, params })
     }
 
     /// Validate genesis configuration meets Load Network requirements and mutates defaults for
@@ -164,6 +278,28 @@ impl LoadChainSpec {
     }
 }
 
+impl LoadChainParams for LoadChainSpec {
+    fn max_rlp_block_size_at_timestamp(&self, timestamp: u64) -> usize {
+        let configured = self.params.max_rlp_block_size;
+
+        // Upstream consensus enforces EIP-7934 once Osaka is active, so a larger Load limit would
+        // only produce blocks that peers reject.
+        if self.is_osaka_active_at_timestamp(timestamp) {
+            configured.min(MAX_RLP_BLOCK_SIZE)
+        } else {
+            configured
+        }
+    }
+
+    fn is_max_rlp_block_size_enforced_at_timestamp(&self, timestamp: u64) -> bool {
+        timestamp >= self.params.max_rlp_block_size_time
+    }
+
+    fn max_gas_limit(&self) -> u64 {
+        self.params.max_gas_limit
+    }
+}
+
 impl Default for LoadChainSpec {
     fn default() -> Self {
         // Default to a minimal dev genesis (Cancun at 0, chain ID 16383)
@@ -321,6 +457,19 @@ mod tests {
         assert_eq!(params.update_fraction, LOAD_BLOB_UPDATE_FRACTION);
     }
 
+    #[test]
+    fn test_blob_params_after_osaka() {
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis.config.osaka_time = Some(100);
+        let spec = LoadChainSpec::from_genesis(genesis).expect("valid genesis");
+        let params = spec.blob_params_at_timestamp(100).expect("Osaka active");
+        assert_eq!(params.max_blob_count, LOAD_MAX_BLOB_COUNT);
+        assert_eq!(params.target_blob_count, LOAD_TARGET_BLOB_COUNT);
+        assert_eq!(params.max_blobs_per_tx, LOAD_MAX_BLOBS_PER_TX);
+        assert_eq!(params.blob_base_cost, BlobParams::osaka().blob_base_cost);
+    }
+
     #[test]
     fn test_genesis_validation_requires_cancun_at_zero() {
         let mut genesis = Genesis::default();
@@ -361,6 +510,99 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn max_rlp_block_size_defaults_and_applies_before_osaka() {
+        let spec = LoadChainSpec::default();
+        assert!(!spec.is_osaka_active_at_timestamp(0));
+        assert_eq!(spec.max_rlp_block_size_at_timestamp(0), LOAD_MAX_RLP_BLOCK_SIZE);
+    }
+
+    #[test]
+    fn max_rlp_block_size_genesis_override() {
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis.config.osaka_time = Some(100);
+        genesis
+            .config
+            .extra_fields
+            .insert(LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY.to_string(), serde_json::json!(1_048_576));
+        let spec = LoadChainSpec::from_genesis(genesis).expect("valid override");
+        assert_eq!(spec.max_rlp_block_size_at_timestamp(0), 1_048_576);
+
+        // A limit above EIP-7934 is clamped once Osaka activates.
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis.config.osaka_time = Some(100);
+        genesis.config.extra_fields.insert(
+            LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY.to_string(),
+            serde_json::json!(MAX_RLP_BLOCK_SIZE * 4),
+        );
+        let spec = LoadChainSpec::from_genesis(genesis).expect("valid override");
+        assert_eq!(spec.max_rlp_block_size_at_timestamp(0), MAX_RLP_BLOCK_SIZE * 4);
+        assert_eq!(spec.max_rlp_block_size_at_timestamp(100), MAX_RLP_BLOCK_SIZE);
+    }
+
+    #[test]
+    fn max_rlp_block_size_rejects_invalid_override() {
+        for value in [serde_json::json!(0), serde_json::json!("big")] {
+            let mut genesis = Genesis::default();
+            genesis.config.cancun_time = Some(0);
+            genesis
+                .config
+                .extra_fields
+                .insert(LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY.to_string(), value);
+            assert!(LoadChainSpec::from_genesis(genesis).is_err());
+        }
+    }
+
+    #[test]
+    fn max_rlp_block_size_import_enforcement_is_scheduled() {
+        let spec = LoadChainSpec::default();
+        assert!(spec.is_max_rlp_block_size_enforced_at_timestamp(0));
+
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis
+            .config
+            .extra_fields
+            .insert(LOAD_MAX_RLP_BLOCK_SIZE_TIME_GENESIS_KEY.to_string(), serde_json::json!(50));
+        let spec = LoadChainSpec::from_genesis(genesis).expect("valid schedule");
+        assert!(!spec.is_max_rlp_block_size_enforced_at_timestamp(49));
+        assert!(spec.is_max_rlp_block_size_enforced_at_timestamp(50));
+        assert_eq!(LoadChainSpec::new(spec.inner.clone()).expect("valid schedule"), spec);
+
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis
+            .config
+            .extra_fields
+            .insert(LOAD_MAX_RLP_BLOCK_SIZE_TIME_GENESIS_KEY.to_string(), serde_json::json!(-1));
+        assert!(LoadChainSpec::new(genesis.clone().into()).is_err());
+        assert!(LoadChainSpec::from_genesis(genesis).is_err());
+    }
+
+    #[test]
+    fn max_gas_limit_defaults_and_genesis_override() {
+        assert_eq!(LoadChainSpec::default().max_gas_limit(), LOAD_EXECUTION_GAS_LIMIT);
+
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis
+            .config
+            .extra_fields
+            .insert(LOAD_MAX_GAS_LIMIT_GENESIS_KEY.to_string(), serde_json::json!(500_000_000));
+        let spec = LoadChainSpec::from_genesis(genesis).expect("valid override");
+        assert_eq!(spec.max_gas_limit(), 500_000_000);
+
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis
+            .config
+            .extra_fields
+            .insert(LOAD_MAX_GAS_LIMIT_GENESIS_KEY.to_string(), serde_json::json!(1_000));
+        assert!(LoadChainSpec::from_genesis(genesis).is_err());
+    }
+
     #[test]
     fn test_hardforks_schedule() {
         let spec = LoadChainSpec::default();
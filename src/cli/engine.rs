@@ -0,0 +1,335 @@
+//! `load-reth engine replay`: feed a recorded Engine API session into a node.
+//!
+//! Reads the log written by `--load.engine-recorder` and sends its `newPayload`,
+//! `forkchoiceUpdated` and `getPayload` calls, in arrival order, to the authenticated Engine API of
+//! another node, typically a fresh one started from the same genesis. Each outcome is compared
+//! with the recording: payload status and latest valid hash for `newPayload`, payload status and
+//! payload ID for `forkchoiceUpdated`, and the error code of calls that failed. `getPayload`
+//! results depend on the pool of the recorded node and are only checked for success. `getBlobs`
+//! calls are skipped, since a recording carries no blobs.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use clap::{Args, Subcommand};
+use eyre::{eyre, WrapErr};
+use jsonrpsee::{
+    core::{
+        client::{ClientT, Error as ClientError},
+        params::ArrayParams,
+    },
+    http_client::HttpClientBuilder,
+};
+use reth_rpc_layer::{AuthClientLayer, JwtSecret};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::engine::recorder::{recording_files, LoadEngineRecord, LoadRecordedError};
+
+/// Records held back to restore arrival order; lines are written in completion order.
+const REORDER_WINDOW: usize = 1024;
+
+/// `load-reth engine` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum EngineCommand {
+    /// Replay a recorded Engine API session against a node.
+    Replay(ReplayCommand),
+}
+
+impl EngineCommand {
+    pub fn run(self) -> eyre::Result<()> {
+        match self {
+            Self::Replay(command) => command.run(),
+        }
+    }
+}
+
+/// `load-reth engine replay`.
+#[derive(Debug, Args)]
+pub struct ReplayCommand {
+    /// Recording to replay: the `--load.engine-recorder` directory or a single file from it.
+    #[arg(value_name = "PATH")]
+    recording: PathBuf,
+
+    /// Authenticated Engine API endpoint of the node to replay into.
+    #[arg(long = "engine.url", value_name = "URL", default_value = "http://127.0.0.1:8551")]
+    url: String,
+
+    /// JWT secret of that endpoint (its `--authrpc.jwtsecret`).
+    #[arg(long = "engine.jwt-secret", value_name = "PATH")]
+    jwt_secret: PathBuf,
+
+    /// Keep the recorded gaps between calls instead of sending them back to back.
+    #[arg(long)]
+    realtime: bool,
+
+    /// Stop at the first call whose outcome differs from the recording.
+    #[arg(long)]
+    fail_fast: bool,
+}
+
+impl ReplayCommand {
+    pub fn run(self) -> eyre::Result<()> {
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+        let report = runtime.block_on(self.replay())?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if report.divergences.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre!("{} replayed calls diverged from the recording", report.divergences.len()))
+        }
+    }
+
+    async fn replay(&self) -> eyre::Result<ReplayReport> {
+        let secret = JwtSecret::from_file(&self.jwt_secret)
+            .map_err(|err| eyre!("failed to read {}: {err}", self.jwt_secret.display()))?;
+        let client = HttpClientBuilder::default()
+            .max_request_size(u32::MAX)
+            .max_response_size(u32::MAX)
+            .request_timeout(Duration::from_secs(600))
+            .set_http_middleware(tower::ServiceBuilder::new().layer(AuthClientLayer::new(secret)))
+            .build(&self.url)
+            .wrap_err_with(|| format!("invalid engine URL {}", self.url))?;
+
+        let mut report = ReplayReport::default();
+        let mut pending = BTreeMap::new();
+        let mut last: Option<(u64, Instant)> = None;
+        let mut records = read_recording(&self.recording)?;
+        loop {
+            // Refill the window, then replay the earliest arrival.
+            while pending.len() < REORDER_WINDOW {
+                match records.next().transpose()? {
+                    Some(record) => {
+                        pending.insert(record.seq, record);
+                    }
+                    None => break,
+                }
+            }
+            let Some((_, record)) = pending.pop_first() else { break };
+            if !is_replayed(&record.method) {
+                report.skipped += 1;
+                continue;
+            }
+
+            if self.realtime {
+                if let Some((started_at_ms, sent)) = last {
+                    let gap =
+                        Duration::from_millis(record.started_at_ms.saturating_sub(started_at_ms));
+                    tokio::time::sleep(gap.saturating_sub(sent.elapsed())).await;
+                }
+                last = Some((record.started_at_ms, Instant::now()));
+            }
+
+            let mut params = ArrayParams::new();
+            for param in record.params.as_array().into_iter().flatten() {
+                params.insert(param)?;
+            }
+            let outcome = match client.request::<Value, _>(&record.method, params).await {
+                Ok(result) => Ok(result),
+                Err(ClientError::Call(err)) => {
+                    Err(LoadRecordedError { code: err.code(), message: err.message().to_string() })
+                }
+                Err(err) => return Err(eyre!("seq {} ({}): {err}", record.seq, record.method)),
+            };
+            report.replayed += 1;
+
+            if let Some(divergence) = compare(&record, &outcome) {
+                eprintln!(
+                    "seq {} {}: expected {}, got {}",
+                    divergence.seq, divergence.method, divergence.expected, divergence.actual
+                );
+                report.divergences.push(divergence);
+                if self.fail_fast {
+                    break;
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// What `engine replay` prints.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayReport {
+    replayed: u64,
+    skipped: u64,
+    divergences: Vec<Divergence>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Divergence {
+    seq: u64,
+    method: String,
+    expected: Value,
+    actual: Value,
+}
+
+/// Calls that drive the node's state; `getBlobs` only reads the pool.
+fn is_replayed(method: &str) -> bool {
+    ["engine_newPayload", "engine_forkchoiceUpdated", "engine_getPayload"]
+        .iter()
+        .any(|prefix| method.starts_with(prefix))
+}
+
+/// Compares a replayed outcome with the recorded one, returning what differs.
+fn compare(
+    record: &LoadEngineRecord,
+    outcome: &Result<Value, LoadRecordedError>,
+) -> Option<Divergence> {
+    let (expected, actual) = match (&record.error, outcome) {
+        (Some(expected), Err(actual)) if expected.code == actual.code => return None,
+        (Some(expected), _) => (serde_json::json!({ "error": expected }), outcome_json(outcome)),
+        (None, Err(actual)) => (serde_json::json!({ "result": record.result }), error_json(actual)),
+        (None, Ok(result)) => {
+            let compared: &[&str] = if record.method.starts_with("engine_newPayload") {
+                &["/status", "/latestValidHash"]
+            } else if record.method.starts_with("engine_forkchoiceUpdated") {
+                &["/payloadStatus/status", "/payloadStatus/latestValidHash", "/payloadId"]
+            } else {
+                &[]
+            };
+            let recorded = record.result.clone().unwrap_or(Value::Null);
+            let differs =
+                compared.iter().any(|pointer| recorded.pointer(pointer) != result.pointer(pointer));
+            if !differs {
+                return None;
+            }
+            let pick = |value: &Value| -> Value {
+                Value::Object(
+                    compared
+                        .iter()
+                        .map(|pointer| {
+                            (
+                                pointer.to_string(),
+                                value.pointer(pointer).cloned().unwrap_or_default(),
+                            )
+                        })
+                        .collect(),
+                )
+            };
+            (pick(&recorded), pick(result))
+        }
+    };
+    Some(Divergence { seq: record.seq, method: record.method.clone(), expected, actual })
+}
+
+fn outcome_json(outcome: &Result<Value, LoadRecordedError>) -> Value {
+    match outcome {
+        Ok(result) => serde_json::json!({ "result": result }),
+        Err(err) => error_json(err),
+    }
+}
+
+fn error_json(err: &LoadRecordedError) -> Value {
+    serde_json::json!({ "error": err })
+}
+
+/// Reads the records of a recording file, or of every file in a recording directory in order.
+fn read_recording(
+    path: &Path,
+) -> eyre::Result<impl Iterator<Item = eyre::Result<LoadEngineRecord>>> {
+    let files = if path.is_dir() {
+        recording_files(path)?.into_iter().map(|(_, path)| path).collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+    let mut lines = Vec::with_capacity(files.len());
+    for file in files {
+        let reader = BufReader::new(
+            File::open(&file).wrap_err_with(|| format!("failed to open {}", file.display()))?,
+        );
+        lines.push(reader.lines().enumerate().map(move |(line, text)| {
+            let text = text.wrap_err_with(|| format!("failed to read {}", file.display()))?;
+            serde_json::from_str(&text)
+                .wrap_err_with(|| format!("{}:{}: invalid record", file.display(), line + 1))
+        }));
+    }
+    Ok(lines.into_iter().flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn record(
+        method: &str,
+        result: Option<Value>,
+        error: Option<LoadRecordedError>,
+    ) -> LoadEngineRecord {
+        LoadEngineRecord {
+            seq: 7,
+            started_at_ms: 0,
+            duration_us: 0,
+            method: method.to_string(),
+            params: json!([]),
+            result,
+            error,
+            payload_id: None,
+        }
+    }
+
+    #[test]
+    fn compares_status_and_payload_id() {
+        let recorded = record(
+            "engine_forkchoiceUpdatedV3",
+            Some(json!({
+                "payloadStatus": { "status": "VALID", "latestValidHash": "0x01" },
+                "payloadId": "0x0000000000000001",
+            })),
+            None,
+        );
+        let same = json!({
+            "payloadStatus": {
+                "status": "VALID",
+                "latestValidHash": "0x01",
+                "validationError": null,
+            },
+            "payloadId": "0x0000000000000001",
+        });
+        assert!(compare(&recorded, &Ok(same)).is_none());
+
+        let other_id = json!({
+            "payloadStatus": { "status": "VALID", "latestValidHash": "0x01" },
+            "payloadId": "0x0000000000000002",
+        });
+        let divergence = compare(&recorded, &Ok(other_id)).expect("payload ID differs");
+        assert_eq!(divergence.seq, 7);
+        assert_eq!(divergence.actual["/payloadId"], "0x0000000000000002");
+
+        let syncing = record("engine_newPayloadV4", Some(json!({ "status": "SYNCING" })), None);
+        assert!(compare(&syncing, &Ok(json!({ "status": "VALID" }))).is_some());
+
+        // getPayload only has to succeed.
+        let built = record("engine_getPayloadV4", Some(json!({ "blockValue": "0x1" })), None);
+        assert!(compare(&built, &Ok(json!({ "blockValue": "0x2" }))).is_none());
+    }
+
+    #[test]
+    fn compares_error_codes() {
+        let error = |code| LoadRecordedError { code, message: String::new() };
+        let failed = record("engine_newPayloadV3", None, Some(error(-38003)));
+        assert!(compare(&failed, &Err(error(-38003))).is_none());
+        assert!(compare(&failed, &Err(error(-32602))).is_some());
+        assert!(compare(&failed, &Ok(json!({ "status": "VALID" }))).is_some());
+
+        let succeeded = record("engine_newPayloadV3", Some(json!({ "status": "VALID" })), None);
+        assert!(compare(&succeeded, &Err(error(-32000))).is_some());
+    }
+
+    #[test]
+    fn replays_state_changing_calls_only() {
+        assert!(is_replayed("engine_newPayloadV4"));
+        assert!(is_replayed("engine_forkchoiceUpdatedV3"));
+        assert!(is_replayed("engine_getPayloadV5"));
+        assert!(!is_replayed("engine_getBlobsV1"));
+    }
+}
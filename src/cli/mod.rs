@@ -0,0 +1,62 @@
+//! Load-only CLI subcommands.
+//!
+//! The reth CLI owns `node`, `init`, `db` and friends. Tools that only make sense for Load live
+//! here and are dispatched from `main` before the reth parser sees the arguments.
+
+pub mod engine;
+pub mod payload;
+
+use clap::{Parser, Subcommand};
+
+use self::{engine::EngineCommand, payload::PayloadCommand};
+
+/// Top-level names handled by [`LoadCli`] instead of the reth CLI.
+pub const LOAD_SUBCOMMANDS: &[&str] = &["engine", "payload"];
+
+/// Returns `true` if the first CLI argument selects a Load-only subcommand.
+pub fn is_load_subcommand(mut args: impl Iterator<Item = String>) -> bool {
+    args.nth(1).is_some_and(|command| LOAD_SUBCOMMANDS.contains(&command.as_str()))
+}
+
+/// Load-only command line.
+#[derive(Debug, Parser)]
+#[command(name = "load-reth", version)]
+pub struct LoadCli {
+    #[command(subcommand)]
+    pub command: LoadCommand,
+}
+
+/// Load-only subcommands.
+#[derive(Debug, Subcommand)]
+pub enum LoadCommand {
+    /// Engine API tooling.
+    #[command(subcommand)]
+    Engine(EngineCommand),
+    /// Payload builder tooling.
+    #[command(subcommand)]
+    Payload(PayloadCommand),
+}
+
+impl LoadCli {
+    /// Runs the selected subcommand.
+    pub fn run(self) -> eyre::Result<()> {
+        match self.command {
+            LoadCommand::Engine(command) => command.run(),
+            LoadCommand::Payload(command) => command.run(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_load_subcommands() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(is_load_subcommand(args(&["load-reth", "payload", "rebuild"]).into_iter()));
+        assert!(is_load_subcommand(args(&["load-reth", "engine", "replay"]).into_iter()));
+        assert!(!is_load_subcommand(args(&["load-reth", "node"]).into_iter()));
+        assert!(!is_load_subcommand(args(&["load-reth"]).into_iter()));
+    }
+}
@@ -0,0 +1,489 @@
+//! `load-reth payload rebuild`: replay a payload build offline.
+//!
+//! Opens the datadir read-only, rebuilds the payload for a parent hash and a set of payload
+//! attributes with [`default_load_payload`], and prints what the builder produced and why it
+//! skipped transactions. Candidates come from a JSON list of raw transactions or, by default,
+//! from the txpool backup reth writes to the datadir on shutdown. Nothing is written to the
+//! datadir, so it is safe to point at a stopped node's data.
+//!
+//! Blob sidecars are read from the datadir's blob store, which holds the sidecars of
+//! transactions that were pending at shutdown, and with `--load.blob-archive` from the blob
+//! archive, which holds those of canonical blocks. The builder reaches them through a pool that
+//! holds no transactions and only serves these sidecars.
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use alloy_consensus::{
+    transaction::{Recovered, SignerRecoverable},
+    BlockHeader, Header, Transaction,
+};
+use alloy_eips::{
+    eip2718::{Decodable2718, Encodable2718},
+    eip4844::BlobTransactionSidecarVariant,
+};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rlp::Decodable;
+use clap::{Args, Subcommand};
+use eyre::{eyre, WrapErr};
+use reth_basic_payload_builder::{BuildArguments, BuildOutcome, PayloadConfig};
+use reth_cli_commands::common::{AccessRights, EnvironmentArgs};
+use reth_ethereum_payload_builder::EthereumBuilderConfig;
+use reth_ethereum_primitives::TransactionSigned;
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_primitives_traits::{SealedHeader, SignedTransaction};
+use reth_provider::{ChainSpecProvider, HeaderProvider};
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore, error::InvalidPoolTransactionError, identifier::TransactionId,
+    noop::MockTransactionValidator, BestTransactions, BlobStore, CoinbaseTipOrdering,
+    EthPooledTransaction, Pool, PoolConfig, TransactionOrigin, ValidPoolTransaction,
+};
+use serde::Serialize;
+
+use crate::{
+    archive::LoadBlobArchive,
+    args::LoadArgs,
+    chainspec::{LoadChainSpecParser, LOAD_EXECUTION_GAS_LIMIT},
+    engine::{
+        builder::{default_load_payload, LoadBuilderConfig},
+        payload::{LoadPayloadAttributes, LoadPayloadBuilderAttributes},
+    },
+    evm::LoadEvmConfig,
+    metrics::LoadPayloadBuilderMetrics,
+    node::LoadNode,
+};
+
+/// Payload attributes version used to derive the payload ID (`engine_forkchoiceUpdatedV3`).
+const ATTRIBUTES_VERSION: u8 = 3;
+
+/// Pool handed to the builder: it holds no transactions and serves the candidates' sidecars
+/// through `get_blob`.
+type RebuildPool = Pool<
+    MockTransactionValidator<EthPooledTransaction>,
+    CoinbaseTipOrdering<EthPooledTransaction>,
+    InMemoryBlobStore,
+>;
+
+/// `load-reth payload` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum PayloadCommand {
+    /// Rebuild a payload offline from a datadir and print the result.
+    Rebuild(Box<RebuildCommand>),
+}
+
+impl PayloadCommand {
+    pub fn run(self) -> eyre::Result<()> {
+        match self {
+            Self::Rebuild(command) => command.run(),
+        }
+    }
+}
+
+/// `load-reth payload rebuild`.
+#[derive(Debug, Args)]
+pub struct RebuildCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs<LoadChainSpecParser>,
+
+    /// Hash of the parent block to build on.
+    #[arg(long, value_name = "HASH")]
+    parent: B256,
+
+    /// Payload attributes as JSON (as sent in `engine_forkchoiceUpdatedV3`), or a path to a file
+    /// containing them.
+    #[arg(long, value_name = "JSON|PATH")]
+    attributes: String,
+
+    /// JSON file with an array of EIP-2718 encoded transactions, offered to the builder in
+    /// order. Defaults to the txpool backup the node wrote at its last shutdown.
+    #[arg(long, value_name = "PATH", conflicts_with = "shutdown_pool_backup")]
+    transactions: Option<PathBuf>,
+
+    /// Txpool backup written at shutdown to read candidates from instead of the datadir default.
+    /// It holds the node's local transactions as of that shutdown, not the pool a payload was
+    /// originally built from.
+    #[arg(long, value_name = "PATH")]
+    shutdown_pool_backup: Option<PathBuf>,
+
+    /// Gas limit target used when the attributes do not pin one (`--builder.gaslimit`).
+    #[arg(long = "builder.gaslimit", value_name = "GAS_LIMIT")]
+    gas_limit: Option<u64>,
+
+    #[command(flatten)]
+    load: LoadArgs,
+}
+
+impl RebuildCommand {
+    pub fn run(self) -> eyre::Result<()> {
+        let environment = self.env.init::<LoadNode>(AccessRights::RO)?;
+        let provider_factory = environment.provider_factory;
+        let chain_spec = provider_factory.chain_spec();
+
+        let parent = provider_factory
+            .header(self.parent)?
+            .ok_or_else(|| eyre!("parent block {} not found in the datadir", self.parent))?;
+        let parent = Arc::new(SealedHeader::new(parent, self.parent));
+
+        let attributes = parse_attributes(&self.attributes)?;
+        let attributes =
+            LoadPayloadBuilderAttributes::try_new(self.parent, attributes, ATTRIBUTES_VERSION)?;
+
+        let candidates = match &self.transactions {
+            Some(path) => read_transaction_list(path)?,
+            None => {
+                let path = self
+                    .shutdown_pool_backup
+                    .clone()
+                    .unwrap_or_else(|| environment.data_dir.txpool_transactions());
+                read_pool_backup(&path)?
+            }
+        };
+
+        let builder_config = LoadBuilderConfig::new(
+            EthereumBuilderConfig::new()
+                .with_gas_limit(self.gas_limit.unwrap_or(LOAD_EXECUTION_GAS_LIMIT)),
+        )
+        .with_extra_data(self.load.resolve_extra_data()?)
+        .with_fee_recipient_policy(self.load.fee_recipient_policy());
+
+        let archive = match self.load.blob_archive_config() {
+            Some(config) => {
+                let dir = config
+                    .dir
+                    .unwrap_or_else(|| environment.data_dir.data_dir().join("blob-archive"));
+                // Opening creates missing directories; the datadir is never written to.
+                if !dir.is_dir() {
+                    return Err(eyre!("blob archive {} does not exist", dir.display()));
+                }
+                Some(LoadBlobArchive::open(dir, config.retention_blocks)?)
+            }
+            None => None,
+        };
+        let blob_store =
+            load_sidecars(&candidates, &environment.data_dir.blobstore(), archive.as_ref())?;
+        let pool = RebuildPool::new(
+            MockTransactionValidator::default(),
+            CoinbaseTipOrdering::default(),
+            blob_store,
+            PoolConfig::default(),
+        );
+
+        let skipped = Arc::new(Mutex::new(Vec::new()));
+        let best_txs = ReplayBestTransactions::new(candidates.clone(), skipped.clone());
+        let args = BuildArguments::new(
+            Default::default(),
+            PayloadConfig::new(parent, attributes),
+            Default::default(),
+            None,
+        );
+
+        let outcome = default_load_payload(
+            LoadEvmConfig::new(chain_spec),
+            provider_factory,
+            pool,
+            builder_config,
+            &LoadPayloadBuilderMetrics::new(),
+            args,
+            |_| Box::new(best_txs),
+        )?;
+        let BuildOutcome::Better { payload, .. } = outcome else {
+            return Err(eyre!("payload builder did not produce a payload: {outcome:?}"));
+        };
+
+        let block = payload.block();
+        let included: HashSet<B256> =
+            block.body().transactions.iter().map(|tx| *tx.tx_hash()).collect();
+        let mut skipped = std::mem::take(&mut *skipped.lock().expect("not poisoned"));
+        // Stale nonces are skipped without a pool callback; report them by elimination.
+        for tx in &candidates {
+            let hash = *tx.tx_hash();
+            if !included.contains(&hash) && !skipped.iter().any(|skip| skip.hash == hash) {
+                skipped.push(RebuildSkip {
+                    hash,
+                    sender: tx.signer(),
+                    reason: "nonce too low".to_string(),
+                });
+            }
+        }
+
+        let report = RebuildReport {
+            payload_id: payload.inner.id().to_string(),
+            block_hash: block.hash(),
+            header: block.header().clone(),
+            transactions: block
+                .body()
+                .transactions
+                .iter()
+                .map(|tx| RebuildTransaction {
+                    hash: *tx.tx_hash(),
+                    sender: tx.recover_signer().ok(),
+                    gas_limit: tx.gas_limit(),
+                    blob_count: tx.blob_versioned_hashes().map_or(0, |hashes| hashes.len()),
+                })
+                .collect(),
+            blob_count: block
+                .body()
+                .transactions
+                .iter()
+                .filter_map(|tx| tx.blob_versioned_hashes())
+                .map(|hashes| hashes.len())
+                .sum(),
+            gas_used: block.header().gas_used(),
+            fees: payload.fees(),
+            skipped,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+}
+
+/// Parses inline JSON attributes, or reads them from a file.
+fn parse_attributes(value: &str) -> eyre::Result<LoadPayloadAttributes> {
+    let json = if value.trim_start().starts_with('{') {
+        value.to_string()
+    } else {
+        std::fs::read_to_string(value)
+            .wrap_err_with(|| format!("failed to read attributes from {value}"))?
+    };
+    serde_json::from_str(&json).wrap_err("invalid payload attributes")
+}
+
+/// Reads a JSON array of EIP-2718 encoded transactions.
+fn read_transaction_list(path: &PathBuf) -> eyre::Result<Vec<Recovered<TransactionSigned>>> {
+    let json = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read transactions from {}", path.display()))?;
+    let encoded: Vec<Bytes> = serde_json::from_str(&json).wrap_err("invalid transaction list")?;
+    encoded
+        .iter()
+        .enumerate()
+        .map(|(index, encoded)| {
+            TransactionSigned::decode_2718_exact(encoded)
+                .map_err(|err| eyre!("transaction {index}: {err}"))?
+                .try_into_recovered()
+                .map_err(|_| eyre!("transaction {index}: invalid signature"))
+        })
+        .collect()
+}
+
+/// Reads the RLP txpool backup reth writes on shutdown.
+fn read_pool_backup(path: &PathBuf) -> eyre::Result<Vec<Recovered<TransactionSigned>>> {
+    let data = std::fs::read(path)
+        .wrap_err_with(|| format!("failed to read txpool backup {}", path.display()))?;
+    let transactions =
+        Vec::<TransactionSigned>::decode(&mut data.as_slice()).wrap_err("invalid txpool backup")?;
+    transactions
+        .into_iter()
+        .map(|tx| {
+            tx.try_into_recovered().map_err(|_| eyre!("txpool backup has an invalid signature"))
+        })
+        .collect()
+}
+
+/// Collects the sidecars of the candidates' blob transactions, preferring the datadir's blob
+/// store over the archive. Transactions found in neither are left without one and reported by
+/// the builder as missing their sidecar.
+fn load_sidecars(
+    candidates: &[Recovered<TransactionSigned>],
+    blob_store_dir: &Path,
+    archive: Option<&LoadBlobArchive>,
+) -> eyre::Result<InMemoryBlobStore> {
+    let store = InMemoryBlobStore::default();
+    for tx in candidates {
+        let Some(versioned_hashes) = tx.blob_versioned_hashes() else { continue };
+        let hash = *tx.tx_hash();
+        let sidecar = match read_blob_store_sidecar(blob_store_dir, hash)? {
+            Some(sidecar) => Some(sidecar),
+            None => archive.and_then(|archive| archive.sidecar(versioned_hashes)),
+        };
+        if let Some(sidecar) = sidecar {
+            store.insert(hash, sidecar)?;
+        }
+    }
+    Ok(store)
+}
+
+/// Reads a sidecar from the directory of reth's disk blob store without opening the store,
+/// which would clear it.
+fn read_blob_store_sidecar(
+    dir: &Path,
+    tx_hash: B256,
+) -> eyre::Result<Option<BlobTransactionSidecarVariant>> {
+    let path = dir.join(format!("{tx_hash:x}"));
+    let data = match fs::read(&path) {
+        Ok(data) => data,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    BlobTransactionSidecarVariant::rlp_decode_fields(&mut data.as_slice())
+        .map(Some)
+        .wrap_err_with(|| format!("invalid blob sidecar {}", path.display()))
+}
+
+/// What `payload rebuild` prints.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RebuildReport {
+    payload_id: String,
+    block_hash: B256,
+    header: Header,
+    transactions: Vec<RebuildTransaction>,
+    blob_count: usize,
+    gas_used: u64,
+    fees: U256,
+    skipped: Vec<RebuildSkip>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RebuildTransaction {
+    hash: B256,
+    sender: Option<Address>,
+    gas_limit: u64,
+    blob_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RebuildSkip {
+    hash: B256,
+    sender: Address,
+    reason: String,
+}
+
+/// Offers a fixed transaction list to the builder in order and records why it rejects any.
+///
+/// Mirrors the pool iterator contract: once a transaction is marked invalid, later
+/// transactions from the same sender are withheld.
+struct ReplayBestTransactions {
+    transactions: std::vec::IntoIter<Recovered<TransactionSigned>>,
+    invalid_senders: HashSet<Address>,
+    skip_blobs: bool,
+    skipped: Arc<Mutex<Vec<RebuildSkip>>>,
+}
+
+impl ReplayBestTransactions {
+    fn new(
+        transactions: Vec<Recovered<TransactionSigned>>,
+        skipped: Arc<Mutex<Vec<RebuildSkip>>>,
+    ) -> Self {
+        Self {
+            transactions: transactions.into_iter(),
+            invalid_senders: HashSet::new(),
+            skip_blobs: false,
+            skipped,
+        }
+    }
+
+    fn record(&self, hash: B256, sender: Address, reason: String) {
+        self.skipped.lock().expect("not poisoned").push(RebuildSkip { hash, sender, reason });
+    }
+}
+
+impl Iterator for ReplayBestTransactions {
+    type Item = Arc<ValidPoolTransaction<EthPooledTransaction>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let tx = self.transactions.next()?;
+            if self.invalid_senders.contains(&tx.signer()) {
+                self.record(
+                    *tx.tx_hash(),
+                    tx.signer(),
+                    "descendant of an invalid transaction".into(),
+                );
+                continue;
+            }
+            if self.skip_blobs && tx.blob_versioned_hashes().is_some() {
+                self.record(*tx.tx_hash(), tx.signer(), "block blob cap reached".into());
+                continue;
+            }
+
+            let nonce = tx.nonce();
+            let sender = tx.signer();
+            let encoded_length = tx.encode_2718_len();
+            return Some(Arc::new(ValidPoolTransaction {
+                transaction: EthPooledTransaction::new(tx, encoded_length),
+                transaction_id: TransactionId::new(
+                    u64::from_be_bytes(sender.0[..8].try_into().expect("address is 20 bytes"))
+                        .into(),
+                    nonce,
+                ),
+                propagate: false,
+                timestamp: Instant::now(),
+                origin: TransactionOrigin::External,
+                authority_ids: None,
+            }));
+        }
+    }
+}
+
+impl BestTransactions for ReplayBestTransactions {
+    fn mark_invalid(&mut self, transaction: &Self::Item, kind: &InvalidPoolTransactionError) {
+        let sender = transaction.sender();
+        self.invalid_senders.insert(sender);
+        self.record(*transaction.hash(), sender, kind.to_string());
+    }
+
+    fn no_updates(&mut self) {}
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.skip_blobs = skip_blobs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{SignableTransaction, TxEip1559};
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    use super::*;
+
+    fn transfer(signer: &PrivateKeySigner, nonce: u64) -> Recovered<TransactionSigned> {
+        let tx = TxEip1559 { chain_id: 16_383, nonce, gas_limit: 21_000, ..Default::default() };
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).expect("signing succeeds");
+        TransactionSigned::from(tx.into_signed(signature)).try_into_recovered().unwrap()
+    }
+
+    #[test]
+    fn parses_inline_attributes() {
+        let attributes = parse_attributes(
+            r#"{
+                "timestamp": "0x10",
+                "prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "suggestedFeeRecipient": "0x00000000000000000000000000000000000000aa",
+                "withdrawals": [],
+                "parentBeaconBlockRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "gasLimit": "0x1c9c380"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(attributes.timestamp(), 0x10);
+        assert_eq!(attributes.gas_limit(), Some(30_000_000));
+    }
+
+    #[test]
+    fn replay_withholds_descendants_of_invalid_transactions() {
+        let alice = PrivateKeySigner::random();
+        let bob = PrivateKeySigner::random();
+        let skipped = Arc::new(Mutex::new(Vec::new()));
+        let mut best = ReplayBestTransactions::new(
+            vec![transfer(&alice, 0), transfer(&bob, 0), transfer(&alice, 1)],
+            skipped.clone(),
+        );
+
+        let first = best.next().unwrap();
+        best.mark_invalid(&first, &InvalidPoolTransactionError::Underpriced);
+        assert_eq!(best.next().unwrap().sender(), bob.address());
+        assert!(best.next().is_none());
+
+        let skipped = skipped.lock().unwrap();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[1].reason, "descendant of an invalid transaction");
+    }
+}
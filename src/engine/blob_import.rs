@@ -0,0 +1,223 @@
+//! `engine_loadImportBlobsV1`: blob sidecars pushed by the consensus layer.
+//!
+//! Ultramarine propagates blob data through its own sync, so a validator that did not build a block
+//! only holds the sidecars that happened to reach its pool and `engine_getBlobs*` misses the rest.
+//! This method lets the consensus layer hand the EL every blob of a canonical block. The bundle is
+//! checked against the versioned hashes of the block's transactions, verified with KZG and written
+//! to the blob archive (`--load.blob-archive`), which then serves it like any archived blob.
+
+use alloy_consensus::Transaction;
+use alloy_eips::{
+    eip4844::{
+        env_settings::EnvKzgSettings, kzg_to_versioned_hash, BlobTransactionSidecar,
+        BlobTransactionSidecarVariant, BlobTransactionValidationError,
+    },
+    eip7594::{BlobTransactionSidecarEip7594, CELLS_PER_EXT_BLOB},
+};
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::BlobsBundleV1;
+use jsonrpsee::types::{
+    error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+    ErrorObjectOwned,
+};
+use reth::providers::ProviderError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task::JoinError;
+
+use crate::{primitives::LoadBlock, rpc::blobs::RESOURCE_NOT_FOUND_CODE};
+
+/// Method name, also advertised through `engine_exchangeCapabilities`.
+pub const LOAD_IMPORT_BLOBS_V1: &str = "engine_loadImportBlobsV1";
+
+/// `engine_loadImportBlobsV1` response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadImportBlobsStatus {
+    /// Blobs written to the archive.
+    #[serde(with = "alloy_serde::quantity")]
+    pub imported: u64,
+}
+
+/// Reasons a blob import is refused.
+#[derive(Debug, Error)]
+pub enum LoadImportBlobsError {
+    #[error("blob archive is disabled; start the node with --load.blob-archive")]
+    ArchiveDisabled,
+    #[error("unknown block {0}")]
+    UnknownBlock(B256),
+    #[error(
+        "bundle has {blobs} blobs, {commitments} commitments and {proofs} proofs for a block with \
+         {expected} blobs"
+    )]
+    Malformed { expected: usize, blobs: usize, commitments: usize, proofs: usize },
+    #[error("block {block_hash} needs {expected} proofs")]
+    WrongProofKind { block_hash: B256, expected: &'static str },
+    #[error("commitment {index} does not match versioned hash {expected}")]
+    VersionedHashMismatch { index: usize, expected: B256 },
+    #[error("invalid KZG proofs: {0}")]
+    Kzg(#[from] BlobTransactionValidationError),
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    #[error("failed to archive blobs: {0}")]
+    Archive(eyre::Report),
+    #[error(transparent)]
+    Task(#[from] JoinError),
+}
+
+impl From<LoadImportBlobsError> for ErrorObjectOwned {
+    fn from(err: LoadImportBlobsError) -> Self {
+        let code = match &err {
+            LoadImportBlobsError::ArchiveDisabled | LoadImportBlobsError::UnknownBlock(_) => {
+                RESOURCE_NOT_FOUND_CODE
+            }
+            LoadImportBlobsError::Malformed { .. } |
+            LoadImportBlobsError::WrongProofKind { .. } |
+            LoadImportBlobsError::VersionedHashMismatch { .. } |
+            LoadImportBlobsError::Kzg(_) => INVALID_PARAMS_CODE,
+            _ => INTERNAL_ERROR_CODE,
+        };
+        Self::owned(code, err.to_string(), None::<()>)
+    }
+}
+
+/// Versioned hashes of every blob in a block, in transaction order.
+pub fn block_versioned_hashes(block: &LoadBlock) -> Vec<B256> {
+    block
+        .body
+        .transactions
+        .iter()
+        .filter_map(|tx| tx.blob_versioned_hashes())
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Checks a bundle against the versioned hashes of a block and returns it as one sidecar.
+///
+/// Blocks from Osaka on need `CELLS_PER_EXT_BLOB` cell proofs per blob, earlier blocks one blob
+/// proof, matching what the pool stores for either fork.
+pub fn verify_bundle(
+    block_hash: B256,
+    versioned_hashes: &[B256],
+    bundle: BlobsBundleV1,
+    osaka: bool,
+    kzg_settings: &EnvKzgSettings,
+) -> Result<BlobTransactionSidecarVariant, LoadImportBlobsError> {
+    let BlobsBundleV1 { commitments, proofs, blobs } = bundle;
+    let (proofs_per_blob, other_per_blob) =
+        if osaka { (CELLS_PER_EXT_BLOB, 1) } else { (1, CELLS_PER_EXT_BLOB) };
+    let counts_match = blobs.len() == versioned_hashes.len() && commitments.len() == blobs.len();
+    if !counts_match || proofs.len() != blobs.len() * proofs_per_blob {
+        if counts_match && !blobs.is_empty() && proofs.len() == blobs.len() * other_per_blob {
+            let expected = if osaka { "cell" } else { "blob" };
+            return Err(LoadImportBlobsError::WrongProofKind { block_hash, expected });
+        }
+        return Err(LoadImportBlobsError::Malformed {
+            expected: versioned_hashes.len(),
+            blobs: blobs.len(),
+            commitments: commitments.len(),
+            proofs: proofs.len(),
+        });
+    }
+    for (index, (commitment, expected)) in commitments.iter().zip(versioned_hashes).enumerate() {
+        if kzg_to_versioned_hash(commitment.as_slice()) != *expected {
+            return Err(LoadImportBlobsError::VersionedHashMismatch { index, expected: *expected });
+        }
+    }
+
+    let sidecar = if osaka {
+        BlobTransactionSidecarVariant::Eip7594(BlobTransactionSidecarEip7594::new(
+            blobs,
+            commitments,
+            proofs,
+        ))
+    } else {
+        BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::new(
+            blobs,
+            commitments,
+            proofs,
+        ))
+    };
+    sidecar.validate(versioned_hashes, kzg_settings.get())?;
+    Ok(sidecar)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_consensus::{SidecarBuilder, SimpleCoder};
+    use alloy_eips::eip4844::Bytes48;
+
+    use super::*;
+
+    fn sidecar(osaka: bool) -> BlobTransactionSidecarVariant {
+        let sidecar = SidecarBuilder::<SimpleCoder>::from_slice(b"load blob import")
+            .build()
+            .expect("sidecar");
+        let sidecar = BlobTransactionSidecarVariant::Eip4844(sidecar);
+        if osaka {
+            BlobTransactionSidecarVariant::Eip7594(
+                sidecar.try_into_7594(EnvKzgSettings::Default.get()).expect("cell proofs"),
+            )
+        } else {
+            sidecar
+        }
+    }
+
+    fn versioned_hashes(bundle: &BlobsBundleV1) -> Vec<B256> {
+        bundle.commitments.iter().map(|c| kzg_to_versioned_hash(c.as_slice())).collect()
+    }
+
+    fn bundle(sidecar: &BlobTransactionSidecarVariant) -> BlobsBundleV1 {
+        match sidecar {
+            BlobTransactionSidecarVariant::Eip4844(sidecar) => BlobsBundleV1 {
+                commitments: sidecar.commitments.clone(),
+                proofs: sidecar.proofs.clone(),
+                blobs: sidecar.blobs.clone(),
+            },
+            BlobTransactionSidecarVariant::Eip7594(sidecar) => BlobsBundleV1 {
+                commitments: sidecar.commitments.clone(),
+                proofs: sidecar.cell_proofs.clone(),
+                blobs: sidecar.blobs.clone(),
+            },
+        }
+    }
+
+    #[test]
+    fn accepts_bundles_of_either_fork() {
+        for osaka in [false, true] {
+            let sidecar = sidecar(osaka);
+            let hashes = versioned_hashes(&bundle(&sidecar));
+            let verified = verify_bundle(
+                B256::ZERO,
+                &hashes,
+                bundle(&sidecar),
+                osaka,
+                &EnvKzgSettings::Default,
+            )
+            .expect("valid bundle");
+            assert_eq!(verified, sidecar);
+        }
+    }
+
+    #[test]
+    fn rejects_bundles_that_do_not_match_the_block() {
+        let sidecar = sidecar(false);
+        let hashes = versioned_hashes(&bundle(&sidecar));
+        let kzg = EnvKzgSettings::Default;
+
+        let err = verify_bundle(B256::ZERO, &[B256::ZERO], bundle(&sidecar), false, &kzg);
+        assert!(matches!(err, Err(LoadImportBlobsError::VersionedHashMismatch { index: 0, .. })));
+
+        let err = verify_bundle(B256::ZERO, &[], bundle(&sidecar), false, &kzg);
+        assert!(matches!(err, Err(LoadImportBlobsError::Malformed { expected: 0, .. })));
+
+        let err = verify_bundle(B256::ZERO, &hashes, bundle(&sidecar), true, &kzg);
+        assert!(matches!(err, Err(LoadImportBlobsError::WrongProofKind { expected: "cell", .. })));
+
+        let mut forged = bundle(&sidecar);
+        forged.proofs[0] = Bytes48::repeat_byte(1);
+        let err = verify_bundle(B256::ZERO, &hashes, forged, false, &kzg);
+        assert!(matches!(err, Err(LoadImportBlobsError::Kzg(_))));
+    }
+}
@@ -0,0 +1,235 @@
+//! Memory limits for in-flight payload jobs.
+//!
+//! A full Load block carries 1024 blobs, so a single payload holds ~128 MiB of sidecars, and
+//! every FCU with attributes starts a job that keeps its best payload until it is resolved or
+//! dropped. [`LoadSidecarBudget`] caps the sidecar bytes held across all jobs: builds reserve
+//! bytes before including a blob transaction, the reservation travels with the built payload and
+//! is released when the last copy of that payload is dropped. A build that cannot reserve more
+//! stops adding blobs instead of growing memory. [`LoadBuildLimiter`] bounds how many builds run
+//! at once so concurrent jobs cannot each hold an in-progress block.
+
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+use alloy_eips::eip4844::{BlobTransactionSidecarVariant, BYTES_PER_BLOB, BYTES_PER_COMMITMENT};
+
+use crate::metrics::LoadSidecarBudgetMetrics;
+
+/// Default sidecar budget across all payload jobs (8 full 1024-blob payloads).
+pub const DEFAULT_SIDECAR_BUDGET_BYTES: u64 = 1024 * 1024 * 1024;
+/// Default number of payload builds allowed to run at the same time.
+pub const DEFAULT_MAX_CONCURRENT_BUILDS: usize = 4;
+
+/// Size of a KZG proof in bytes.
+const BYTES_PER_PROOF: usize = 48;
+
+/// In-memory size of a blob sidecar: blobs, commitments and proofs.
+pub fn sidecar_size(sidecar: &BlobTransactionSidecarVariant) -> u64 {
+    let size = match sidecar {
+        BlobTransactionSidecarVariant::Eip4844(sidecar) => {
+            sidecar.blobs.len() * BYTES_PER_BLOB +
+                sidecar.commitments.len() * BYTES_PER_COMMITMENT +
+                sidecar.proofs.len() * BYTES_PER_PROOF
+        }
+        BlobTransactionSidecarVariant::Eip7594(sidecar) => {
+            sidecar.blobs.len() * BYTES_PER_BLOB +
+                sidecar.commitments.len() * BYTES_PER_COMMITMENT +
+                sidecar.cell_proofs.len() * BYTES_PER_PROOF
+        }
+    };
+    size as u64
+}
+
+#[derive(Debug)]
+struct BudgetInner {
+    capacity: u64,
+    reserved: AtomicU64,
+    metrics: LoadSidecarBudgetMetrics,
+}
+
+/// Global byte budget for blob sidecars held by payload jobs.
+///
+/// Cloning is cheap; every clone draws from the same budget.
+#[derive(Debug, Clone)]
+pub struct LoadSidecarBudget {
+    inner: Arc<BudgetInner>,
+}
+
+impl LoadSidecarBudget {
+    pub fn new(capacity: u64) -> Self {
+        let metrics = LoadSidecarBudgetMetrics::default();
+        metrics.set_capacity(capacity);
+        Self { inner: Arc::new(BudgetInner { capacity, reserved: AtomicU64::new(0), metrics }) }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.inner.capacity
+    }
+
+    /// Bytes currently reserved by live payloads and running builds.
+    pub fn reserved(&self) -> u64 {
+        self.inner.reserved.load(Ordering::Relaxed)
+    }
+
+    /// Starts an empty reservation for one build.
+    pub fn reservation(&self) -> SidecarReservation {
+        SidecarReservation { budget: self.clone(), bytes: 0 }
+    }
+
+    fn try_reserve(&self, bytes: u64) -> bool {
+        let reserved =
+            self.inner.reserved.fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(bytes).filter(|total| *total <= self.inner.capacity)
+            });
+        match reserved {
+            Ok(used) => {
+                self.inner.metrics.set_reserved(used + bytes);
+                true
+            }
+            Err(_) => {
+                self.inner.metrics.record_exhausted();
+                false
+            }
+        }
+    }
+
+    fn release(&self, bytes: u64) {
+        let used = self.inner.reserved.fetch_sub(bytes, Ordering::AcqRel);
+        self.inner.metrics.set_reserved(used - bytes);
+    }
+}
+
+impl Default for LoadSidecarBudget {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIDECAR_BUDGET_BYTES)
+    }
+}
+
+/// Sidecar bytes reserved by one build, released on drop.
+#[derive(Debug)]
+pub struct SidecarReservation {
+    budget: LoadSidecarBudget,
+    bytes: u64,
+}
+
+impl SidecarReservation {
+    /// Reserves `bytes` more. Returns `false`, leaving the reservation unchanged, if the budget
+    /// cannot cover them.
+    pub fn try_grow(&mut self, bytes: u64) -> bool {
+        if bytes == 0 {
+            return true;
+        }
+        if !self.budget.try_reserve(bytes) {
+            return false;
+        }
+        self.bytes += bytes;
+        true
+    }
+
+    /// Returns `bytes` that turned out not to be needed.
+    pub fn shrink(&mut self, bytes: u64) {
+        let bytes = bytes.min(self.bytes);
+        self.budget.release(bytes);
+        self.bytes -= bytes;
+    }
+
+    pub const fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl Drop for SidecarReservation {
+    fn drop(&mut self) {
+        if self.bytes > 0 {
+            self.budget.release(self.bytes);
+        }
+    }
+}
+
+/// Caps the number of payload builds running at the same time.
+#[derive(Debug, Clone)]
+pub struct LoadBuildLimiter {
+    active: Arc<AtomicUsize>,
+    max: usize,
+}
+
+impl LoadBuildLimiter {
+    pub fn new(max: usize) -> Self {
+        Self { active: Arc::new(AtomicUsize::new(0)), max }
+    }
+
+    /// Claims a build slot, or returns `None` if all slots are busy.
+    pub fn try_acquire(&self) -> Option<BuildPermit> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < self.max).then_some(active + 1)
+            })
+            .ok()
+            .map(|_| BuildPermit { active: self.active.clone() })
+    }
+}
+
+impl Default for LoadBuildLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_BUILDS)
+    }
+}
+
+/// A claimed build slot, released on drop.
+#[derive(Debug)]
+pub struct BuildPermit {
+    active: Arc<AtomicUsize>,
+}
+
+impl Drop for BuildPermit {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_eips::eip4844::{Blob, BlobTransactionSidecar, Bytes48};
+
+    use super::*;
+
+    #[test]
+    fn sidecar_size_counts_blobs_and_proofs() {
+        let sidecar = BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::new(
+            vec![Blob::ZERO; 2],
+            vec![Bytes48::ZERO; 2],
+            vec![Bytes48::ZERO; 2],
+        ));
+        assert_eq!(sidecar_size(&sidecar), (2 * BYTES_PER_BLOB + 4 * 48) as u64);
+    }
+
+    #[test]
+    fn reservations_are_bounded_and_released_on_drop() {
+        let budget = LoadSidecarBudget::new(100);
+        let mut first = budget.reservation();
+        assert!(first.try_grow(60));
+
+        let mut second = budget.reservation();
+        assert!(!second.try_grow(50), "over budget");
+        assert_eq!(second.bytes(), 0);
+        assert!(second.try_grow(40));
+        assert_eq!(budget.reserved(), 100);
+
+        second.shrink(40);
+        assert_eq!(budget.reserved(), 60);
+
+        drop(first);
+        assert_eq!(budget.reserved(), 0);
+    }
+
+    #[test]
+    fn limiter_caps_concurrent_builds() {
+        let limiter = LoadBuildLimiter::new(1);
+        let permit = limiter.try_acquire().expect("slot free");
+        assert!(limiter.try_acquire().is_none());
+        drop(permit);
+        assert!(limiter.try_acquire().is_some());
+    }
+}
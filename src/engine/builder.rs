@@ -2,16 +2,26 @@
 //!
 //! Mirrors the upstream Ethereum builder but clamps blob selection to the Load
 //! constants (1024 blobs per block) and uses the Load payload wrapper types.
+//!
+//! Block order is: CL-forced transactions, then profitable `eth_sendBundle` bundles (each
+//! simulated on a scratch copy of the state before it is committed), then the mempool. A bundle
+//! whose execution in the block diverges from its simulation is dropped and the build restarts
+//! without it.
 
-use std::sync::Arc;
+use std::{any::Any, collections::HashSet, sync::Arc, time::Instant};
 
-use alloy_consensus::Transaction;
+use alloy_consensus::{transaction::Recovered, BlockHeader, Transaction};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use alloy_rlp::Encodable;
 use reth::{
     api::{FullNodeTypes, NodeTypes, PayloadBuilderError, PayloadTypes, TxTy},
     consensus::ConsensusError,
-    providers::{ChainSpecProvider, StateProviderFactory},
-    revm::{context::Block, database::StateProviderDatabase, State},
+    providers::{BlockNumReader, CanonStateSubscriptions, ChainSpecProvider, StateProviderFactory},
+    revm::{
+        context::Block, context_interface::result::InvalidTransaction,
+        database::StateProviderDatabase, db::CacheDB, state::EvmState, Database, DatabaseCommit,
+        State,
+    },
     transaction_pool::{PoolTransaction, TransactionPool},
 };
 use reth_basic_payload_builder::{
@@ -19,28 +29,50 @@ use reth_basic_payload_builder::{
     PayloadBuilder, PayloadConfig,
 };
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
-use reth_consensus_common::validation::MAX_RLP_BLOCK_SIZE;
-use reth_ethereum_engine_primitives::{BlobSidecars, EthBuiltPayload};
+use reth_ethereum_engine_primitives::EthBuiltPayload;
 use reth_ethereum_payload_builder::EthereumBuilderConfig;
+use reth_ethereum_primitives::TransactionSigned;
 use reth_evm::{
-    block::{BlockExecutionError, BlockValidationError},
-    execute::{BlockBuilder, BlockBuilderOutcome},
-    ConfigureEvm, Evm,
+    block::{BlockExecutionError, BlockValidationError, SystemCaller},
+    execute::{BasicBlockBuilder, BlockBuilder, BlockBuilderOutcome},
+    ConfigureEvm, Evm, EvmEnvFor,
 };
 use reth_node_builder::{components::PayloadBuilderBuilder, BuilderContext, PayloadBuilderConfig};
 use reth_payload_primitives::PayloadBuilderAttributes;
-use reth_primitives_traits::transaction::error::InvalidTransactionError;
+use reth_primitives_traits::{
+    transaction::error::InvalidTransactionError, BlockBody, SignedTransaction,
+};
 use reth_transaction_pool::{
-    error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
+    error::{Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolTransactionError},
     BestTransactions, BestTransactionsAttributes, ValidPoolTransaction,
 };
-use tracing::{debug, trace, warn};
+use thiserror::Error;
+use tracing::{debug, info, trace, warn};
 
+#[cfg(feature = "parallel-exec")]
+use crate::engine::parallel::{
+    speculative::{ParallelTransactions, SpeculativeResults},
+    state::SharedStateCache,
+    LoadParallelConfig, LoadParallelWorkers,
+};
 use crate::{
-    chainspec::{LoadChainSpec, LOAD_EXECUTION_GAS_LIMIT, LOAD_MAX_BLOB_COUNT},
-    engine::payload::{LoadBuiltPayload, LoadPayloadBuilderAttributes},
+    args::LoadArgs,
+    bundle::{LoadBundle, LoadBundlePool},
+    chainspec::{LoadChainParams, LoadChainSpec, LOAD_EXECUTION_GAS_LIMIT, LOAD_MAX_BLOB_COUNT},
+    engine::{
+        budget::{sidecar_size, LoadBuildLimiter, LoadSidecarBudget},
+        fee_recipient::LoadFeeRecipientPolicy,
+        payload::{LoadBuiltPayload, LoadPayloadBuilderAttributes},
+        prefetch::{LoadPrefetchedDb, LoadReadTally, LoadStatePrefetcher, TallyingCachedReads},
+    },
+    metrics::{LoadPayloadBuilderMetrics, LoadPayloadSkipReason},
     primitives::LoadPrimitives,
 };
+#[cfg(feature = "parallel-exec")]
+use reth_evm::block::BlockExecutor;
+
+/// Bytes reserved for the header and list framing when estimating a block's RLP size.
+pub(crate) const LOAD_BLOCK_SIZE_MARGIN: usize = 1024;
 
 type BestTransactionsIter<Pool> = Box<
     dyn BestTransactions<Item = Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>,
@@ -49,7 +81,23 @@ type BestTransactionsIter<Pool> = Box<
 /// Service builder for Load payload builders.
 #[derive(Clone, Default, Debug)]
 #[non_exhaustive]
-pub struct LoadPayloadServiceBuilder;
+pub struct LoadPayloadServiceBuilder {
+    args: LoadArgs,
+    bundle_pool: LoadBundlePool,
+}
+
+impl LoadPayloadServiceBuilder {
+    /// Creates a service builder using the operator-provided Load arguments.
+    pub fn new(args: LoadArgs) -> Self {
+        Self { args, bundle_pool: LoadBundlePool::default() }
+    }
+
+    /// Bundle pool fed by `eth_sendBundle`; shared with the RPC handler.
+    pub fn with_bundle_pool(mut self, bundle_pool: LoadBundlePool) -> Self {
+        self.bundle_pool = bundle_pool;
+        self
+    }
+}
 
 impl<Types, Node, Pool, Evm> PayloadBuilderBuilder<Node, Pool, Evm> for LoadPayloadServiceBuilder
 where
@@ -82,31 +130,184 @@ where
         // Use Load's 2B gas limit instead of reth's 36M default for custom chains
         let gas_limit = conf.gas_limit().unwrap_or(LOAD_EXECUTION_GAS_LIMIT);
 
-        let builder_config = EthereumBuilderConfig::new()
-            .with_gas_limit(gas_limit)
-            .with_await_payload_on_missing(true);
+        let extra_data = self.args.resolve_extra_data()?;
+        info!(target: "load_reth::payload", extra_data = %extra_data, "Payload builder extraData configured");
+
+        let sidecar_budget = self.args.sidecar_budget();
+        let prefetcher = self.args.state_prefetcher();
+        let build_limiter = self.args.build_limiter()?;
+        info!(
+            target: "load_reth::payload",
+            sidecar_budget_bytes = sidecar_budget.capacity(),
+            "Payload builder sidecar memory budget configured"
+        );
+
+        let builder_config = LoadBuilderConfig::new(
+            EthereumBuilderConfig::new()
+                .with_gas_limit(gas_limit)
+                .with_await_payload_on_missing(true),
+        )
+        .with_extra_data(extra_data)
+        .with_fee_recipient_policy(self.args.fee_recipient_policy())
+        .with_bundle_pool(self.bundle_pool.clone())
+        .with_sidecar_budget(sidecar_budget)
+        .with_build_limiter(build_limiter)
+        .with_prefetcher(prefetcher.clone());
+        #[cfg(feature = "parallel-exec")]
+        let builder_config = {
+            let parallel = self.args.parallel_config()?;
+            info!(
+                target: "load_reth::payload",
+                threads = parallel.map(|parallel| parallel.threads),
+                "Payload builder parallel execution configured"
+            );
+            builder_config.with_parallel(parallel)
+        };
+
+        // Settle bundles as their target blocks become canonical.
+        let bundle_pool = self.bundle_pool;
+        bundle_pool.set_head(ctx.provider().best_block_number()?);
+        let mut canon_state = ctx.provider().subscribe_to_canonical_state();
+        ctx.task_executor().spawn(Box::pin(async move {
+            loop {
+                let notification = match canon_state.recv().await {
+                    Ok(notification) => notification,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(target: "load_reth::bundle", skipped, "bundle pool maintenance lagged");
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                for block in notification.committed().blocks_iter() {
+                    let tx_hashes: HashSet<B256> =
+                        block.body().transactions().iter().map(|tx| *tx.tx_hash()).collect();
+                    bundle_pool.on_canonical_block(
+                        block.header().number(),
+                        block.hash(),
+                        &tx_hashes,
+                    );
+                }
+            }
+        }));
+
+        if prefetcher.is_enabled() {
+            info!(
+                target: "load_reth::payload",
+                max_transactions = prefetcher.max_transactions(),
+                "Pending transaction state prefetcher enabled"
+            );
+            ctx.task_executor().spawn(Box::pin(prefetcher.run(
+                ctx.provider().clone(),
+                pool.clone(),
+                evm_config.clone(),
+            )));
+        }
 
         Ok(LoadPayloadBuilder::new(ctx.provider().clone(), pool, evm_config, builder_config))
     }
 }
 
+/// Load payload builder configuration.
+///
+/// Wraps the upstream Ethereum knobs (gas limit target, await-on-missing) and adds the
+/// Load-specific block identity.
+#[derive(Debug, Clone)]
+pub struct LoadBuilderConfig {
+    /// Upstream Ethereum builder configuration.
+    pub inner: EthereumBuilderConfig,
+    /// `extraData` stamped on built blocks unless the CL supplies one in the attributes.
+    pub extra_data: Bytes,
+    /// Fee recipients the builder is willing to credit.
+    pub fee_recipient_policy: LoadFeeRecipientPolicy,
+    /// Bundles submitted through `eth_sendBundle`.
+    pub bundle_pool: LoadBundlePool,
+    /// Byte budget for blob sidecars held by all payload jobs.
+    pub sidecar_budget: LoadSidecarBudget,
+    /// Limit on payload builds running at the same time.
+    pub build_limiter: LoadBuildLimiter,
+    /// Warm state for the first build of each job.
+    pub prefetcher: LoadStatePrefetcher,
+    /// Parallel execution of pool transactions; `None` turns it off.
+    #[cfg(feature = "parallel-exec")]
+    pub parallel: Option<LoadParallelConfig>,
+    /// Batch workers shared by concurrent builds.
+    #[cfg(feature = "parallel-exec")]
+    pub parallel_workers: LoadParallelWorkers,
+}
+
+impl LoadBuilderConfig {
+    pub fn new(inner: EthereumBuilderConfig) -> Self {
+        Self {
+            inner,
+            extra_data: Bytes::new(),
+            fee_recipient_policy: Default::default(),
+            bundle_pool: Default::default(),
+            sidecar_budget: Default::default(),
+            build_limiter: Default::default(),
+            prefetcher: LoadStatePrefetcher::disabled(),
+            #[cfg(feature = "parallel-exec")]
+            parallel: None,
+            #[cfg(feature = "parallel-exec")]
+            parallel_workers: Default::default(),
+        }
+    }
+
+    pub fn with_extra_data(mut self, extra_data: Bytes) -> Self {
+        self.extra_data = extra_data;
+        self
+    }
+
+    pub fn with_fee_recipient_policy(mut self, policy: LoadFeeRecipientPolicy) -> Self {
+        self.fee_recipient_policy = policy;
+        self
+    }
+
+    pub fn with_bundle_pool(mut self, bundle_pool: LoadBundlePool) -> Self {
+        self.bundle_pool = bundle_pool;
+        self
+    }
+
+    pub fn with_sidecar_budget(mut self, sidecar_budget: LoadSidecarBudget) -> Self {
+        self.sidecar_budget = sidecar_budget;
+        self
+    }
+
+    pub fn with_build_limiter(mut self, build_limiter: LoadBuildLimiter) -> Self {
+        self.build_limiter = build_limiter;
+        self
+    }
+
+    pub fn with_prefetcher(mut self, prefetcher: LoadStatePrefetcher) -> Self {
+        self.prefetcher = prefetcher;
+        self
+    }
+
+    #[cfg(feature = "parallel-exec")]
+    pub fn with_parallel(mut self, parallel: Option<LoadParallelConfig>) -> Self {
+        self.parallel_workers = LoadParallelWorkers::new(parallel.map_or(1, |p| p.threads));
+        self.parallel = parallel;
+        self
+    }
+}
+
 /// Load payload builder that enforces blob caps during transaction selection.
 #[derive(Debug, Clone)]
 pub struct LoadPayloadBuilder<Pool, Client, EvmConfig = crate::evm::LoadEvmConfig> {
     client: Client,
     pool: Pool,
     evm_config: EvmConfig,
-    builder_config: EthereumBuilderConfig,
+    builder_config: LoadBuilderConfig,
+    metrics: LoadPayloadBuilderMetrics,
 }
 
 impl<Pool, Client, EvmConfig> LoadPayloadBuilder<Pool, Client, EvmConfig> {
-    pub const fn new(
+    pub fn new(
         client: Client,
         pool: Pool,
         evm_config: EvmConfig,
-        builder_config: EthereumBuilderConfig,
+        builder_config: LoadBuilderConfig,
     ) -> Self {
-        Self { client, pool, evm_config, builder_config }
+        Self { client, pool, evm_config, builder_config, metrics: LoadPayloadBuilderMetrics::new() }
     }
 }
 
@@ -129,11 +330,22 @@ where
         &self,
         args: BuildArguments<Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
+        // Upstream has no throttled outcome and `Cancelled` means the job itself was cancelled,
+        // so a throttled attempt is reported as not better than the current best: the job keeps
+        // its payload and cached reads and retries on its next tick. The `throttled` build
+        // outcome metric tells the two apart.
+        let Some(_permit) = self.builder_config.build_limiter.try_acquire() else {
+            trace!(target: "payload_builder", "all build slots busy, skipping this attempt");
+            self.metrics.record_throttled();
+            return Ok(BuildOutcome::Aborted { fees: U256::ZERO, cached_reads: args.cached_reads });
+        };
+
         default_load_payload(
             self.evm_config.clone(),
             self.client.clone(),
             self.pool.clone(),
             self.builder_config.clone(),
+            &self.metrics,
             args,
             |attributes| self.pool.best_transactions_with_attributes(attributes),
         )
@@ -143,7 +355,7 @@ where
         &self,
         _args: BuildArguments<Self::Attributes, Self::BuiltPayload>,
     ) -> MissingPayloadBehaviour<Self::BuiltPayload> {
-        if self.builder_config.await_payload_on_missing {
+        if self.builder_config.inner.await_payload_on_missing {
             MissingPayloadBehaviour::AwaitInProgress
         } else {
             MissingPayloadBehaviour::RaceEmptyPayload
@@ -160,6 +372,7 @@ where
             self.client.clone(),
             self.pool.clone(),
             self.builder_config.clone(),
+            &self.metrics,
             args,
             |attributes| self.pool.best_transactions_with_attributes(attributes),
         )?
@@ -174,7 +387,8 @@ pub fn default_load_payload<EvmConfig, Client, Pool, F>(
     evm_config: EvmConfig,
     client: Client,
     pool: Pool,
-    builder_config: EthereumBuilderConfig,
+    builder_config: LoadBuilderConfig,
+    metrics: &LoadPayloadBuilderMetrics,
     args: BuildArguments<LoadPayloadBuilderAttributes, LoadBuiltPayload>,
     best_txs: F,
 ) -> Result<BuildOutcome<LoadBuiltPayload>, PayloadBuilderError>
@@ -192,32 +406,88 @@ where
     let BuildArguments { mut cached_reads, config, cancel, best_payload } = args;
     let PayloadConfig { parent_header, attributes } = config;
 
-    super::payload::validate_payload_attributes(parent_header.timestamp, &attributes)
-        .map_err(PayloadBuilderError::other)?;
+    super::payload::validate_payload_attributes(
+        parent_header.timestamp,
+        &attributes,
+        &builder_config.fee_recipient_policy,
+    )
+    .map_err(PayloadBuilderError::other)?;
+
+    // A job's first build reads through the state prefetched for its parent.
+    let prefetched = if best_payload.is_none() {
+        builder_config.prefetcher.seed(parent_header.hash())
+    } else {
+        None
+    };
+    let seeded = prefetched.is_some();
+    let read_tally = LoadReadTally::default();
 
     let state_provider = client.state_by_block_hash(parent_header.hash())?;
+    // Batch workers and the builder read the parent state through one cache, so whatever the
+    // workers load is already in memory when the builder checks or executes the transaction.
+    #[cfg(feature = "parallel-exec")]
+    let shared_state = SharedStateCache::new(StateProviderDatabase::new(&state_provider));
+    #[cfg(feature = "parallel-exec")]
+    let state = &shared_state;
+    #[cfg(not(feature = "parallel-exec"))]
     let state = StateProviderDatabase::new(&state_provider);
-    let mut db =
-        State::builder().with_database(cached_reads.as_db_mut(state)).with_bundle_update().build();
+    let mut db = State::builder()
+        .with_database(TallyingCachedReads::new(
+            cached_reads.as_db_mut(LoadPrefetchedDb::new(prefetched, state, &read_tally)),
+            &read_tally,
+        ))
+        .with_bundle_update()
+        .build();
 
     let chain_spec = client.chain_spec();
-    let extra_data = chain_spec.inner.genesis.extra_data.clone();
-
-    let mut builder = evm_config
-        .builder_for_next_block(
-            &mut db,
-            &parent_header,
-            reth_evm::NextBlockEnvAttributes {
-                timestamp: attributes.timestamp(),
-                suggested_fee_recipient: attributes.suggested_fee_recipient(),
-                prev_randao: attributes.prev_randao(),
-                gas_limit: builder_config.gas_limit(parent_header.gas_limit),
-                parent_beacon_block_root: attributes.parent_beacon_block_root(),
-                withdrawals: Some(attributes.withdrawals().clone()),
-                extra_data,
-            },
-        )
+    let extra_data =
+        attributes.extra_data().cloned().unwrap_or_else(|| builder_config.extra_data.clone());
+    // A CL-pinned gas limit is used exactly; it must be reachable from the parent in one step.
+    let gas_limit = match attributes.gas_limit() {
+        Some(gas_limit) => {
+            super::payload::validate_gas_limit(gas_limit, chain_spec.max_gas_limit())
+                .map_err(PayloadBuilderError::other)?;
+            super::payload::validate_gas_limit_against_parent(gas_limit, parent_header.gas_limit)
+                .map_err(PayloadBuilderError::other)?;
+            gas_limit
+        }
+        None => builder_config.inner.gas_limit(parent_header.gas_limit),
+    };
+
+    let next_block_attributes = reth_evm::NextBlockEnvAttributes {
+        timestamp: attributes.timestamp(),
+        suggested_fee_recipient: attributes.suggested_fee_recipient(),
+        prev_randao: attributes.prev_randao(),
+        gas_limit,
+        parent_beacon_block_root: attributes.parent_beacon_block_root(),
+        withdrawals: Some(attributes.withdrawals().clone()),
+        extra_data,
+    };
+    let evm_env = evm_config
+        .next_evm_env(&parent_header, &next_block_attributes)
         .map_err(PayloadBuilderError::other)?;
+    let bundles =
+        builder_config.bundle_pool.bundles_for(parent_header.number + 1, attributes.timestamp());
+    // Bundle simulation runs outside the block builder, so it needs its own copy of the env.
+    let bundle_evm_env = (!bundles.is_empty()).then(|| evm_env.clone());
+    #[cfg(feature = "parallel-exec")]
+    let parallel = builder_config.parallel.map(|parallel| {
+        (parallel, evm_env.clone(), SpeculativeResults::new(attributes.suggested_fee_recipient()))
+    });
+
+    // Built by hand rather than through `builder_for_next_block` so parallel execution can
+    // commit batch results through the executor; this is what that method constructs.
+    let ctx = evm_config
+        .context_for_next_block(&parent_header, next_block_attributes)
+        .map_err(PayloadBuilderError::other)?;
+    let mut builder: BasicBlockBuilder<'_, _, _, _, LoadPrimitives> = BasicBlockBuilder {
+        executor: evm_config
+            .create_executor(evm_config.evm_with_env(&mut db, evm_env), ctx.clone()),
+        transactions: Vec::new(),
+        ctx,
+        parent: &*parent_header,
+        assembler: evm_config.block_assembler(),
+    };
 
     debug!(
         target: "payload_builder",
@@ -230,28 +500,285 @@ where
     let mut cumulative_gas_used = 0;
     let block_gas_limit: u64 = builder.evm_mut().block().gas_limit();
     let base_fee = builder.evm_mut().block().basefee();
+    let blob_gas_price = builder.evm_mut().block().blob_gasprice().map(|gasprice| gasprice as u64);
 
-    let mut best_txs = best_txs(BestTransactionsAttributes::new(
-        base_fee,
-        builder.evm_mut().block().blob_gasprice().map(|gasprice| gasprice as u64),
-    ));
     let mut total_fees = alloy_primitives::U256::ZERO;
 
+    let pre_execution_start = Instant::now();
     builder.apply_pre_execution_changes().map_err(|err| {
         warn!(target: "payload_builder", %err, "failed to apply pre-execution changes");
         PayloadBuilderError::Internal(err.into())
     })?;
+    metrics.record_pre_execution(pre_execution_start.elapsed());
 
-    let mut blob_sidecars = BlobSidecars::Empty;
+    // Sidecars stay behind the blob store's `Arc`s; the payload never owns a copy.
+    let mut blob_sidecars = Vec::new();
+    let mut sidecar_reservation = builder_config.sidecar_budget.reservation();
     let mut block_blob_count = 0;
     let mut block_transactions_rlp_length = 0;
+    let mut block_tx_count = 0;
 
     let max_blob_count = compute_load_blob_cap(chain_spec.as_ref(), attributes.timestamp());
 
     let is_osaka = chain_spec.is_osaka_active_at_timestamp(attributes.timestamp());
+    // Enforced for every fork, not only Osaka; blob sidecars travel outside the block.
+    let max_rlp_block_size = chain_spec.max_rlp_block_size_at_timestamp(attributes.timestamp());
+
+    // CL-forced transactions run first, in the order given. They were decoded and recovered
+    // when the attributes arrived; one that does not fit or fails is left out, like a pool
+    // transaction, so a bad entry cannot stall block production.
+    let mut included_forced_txs = Vec::with_capacity(attributes.transactions().len());
+    for (index, forced_tx) in attributes.transactions().iter().enumerate() {
+        let tx = forced_tx.value();
+        if cumulative_gas_used + tx.gas_limit() > block_gas_limit {
+            warn!(
+                target: "payload_builder",
+                index,
+                tx = %tx.tx_hash(),
+                gas_limit = tx.gas_limit(),
+                "skipping forced transaction that exceeds the remaining block gas"
+            );
+            metrics.record_skip(LoadPayloadSkipReason::ForcedTransaction);
+            continue;
+        }
+
+        let estimated_block_size = block_transactions_rlp_length +
+            tx.inner().length() +
+            attributes.withdrawals().length() +
+            LOAD_BLOCK_SIZE_MARGIN;
+        if estimated_block_size > max_rlp_block_size {
+            warn!(
+                target: "payload_builder",
+                index,
+                tx = %tx.tx_hash(),
+                estimated_block_size,
+                max_rlp_block_size,
+                "skipping forced transaction that exceeds the block size limit"
+            );
+            metrics.record_skip(LoadPayloadSkipReason::ForcedTransaction);
+            continue;
+        }
+
+        let gas_used = match builder.execute_transaction(tx.clone()) {
+            Ok(gas_used) => gas_used,
+            Err(BlockExecutionError::Validation(BlockValidationError::InvalidTx {
+                error, ..
+            })) => {
+                warn!(
+                    target: "payload_builder",
+                    index,
+                    tx = %tx.tx_hash(),
+                    %error,
+                    "skipping invalid forced transaction"
+                );
+                metrics.record_skip(LoadPayloadSkipReason::ForcedTransaction);
+                continue;
+            }
+            Err(err) => return Err(PayloadBuilderError::evm(err)),
+        };
+
+        block_transactions_rlp_length += tx.inner().length();
+        block_tx_count += 1;
+        included_forced_txs.push(tx);
+
+        let miner_fee =
+            tx.effective_tip_per_gas(base_fee).expect("fee is valid after successful execution");
+        total_fees +=
+            alloy_primitives::U256::from(miner_fee) * alloy_primitives::U256::from(gas_used);
+        cumulative_gas_used += gas_used;
+    }
+
+    if let Some(evm_env) = bundle_evm_env {
+        let coinbase = attributes.suggested_fee_recipient();
+        // Scratch state tracking everything committed so far, used to trial bundles without
+        // touching the block builder: the parent state, the EIP-2935 and EIP-4788 system calls
+        // the builder ran in pre-execution, and the forced transactions it included.
+        let mut sim_db = CacheDB::new(StateProviderDatabase::new(&state_provider));
+        {
+            let mut evm = evm_config.evm_with_env(&mut sim_db, evm_env.clone());
+            let mut system_caller = SystemCaller::new(chain_spec.as_ref());
+            system_caller
+                .apply_blockhashes_contract_call(parent_header.hash(), &mut evm)
+                .and_then(|()| {
+                    system_caller.apply_beacon_root_contract_call(
+                        attributes.parent_beacon_block_root(),
+                        &mut evm,
+                    )
+                })
+                .map_err(PayloadBuilderError::evm)?;
+        }
+        simulate_transactions(
+            &evm_config,
+            &evm_env,
+            &mut sim_db,
+            coinbase,
+            included_forced_txs.iter().copied(),
+            |_| true,
+        )
+        .map_err(|err| PayloadBuilderError::other(LoadBundleBuildError(err)))?;
+
+        for bundle in bundles {
+            let bundle_hash = bundle.hash();
+            let reject = |reason: String| {
+                trace!(target: "payload_builder", bundle=%bundle_hash, %reason, "skipping bundle");
+                builder_config.bundle_pool.mark_rejected(bundle_hash, reason);
+            };
+
+            if cancel.is_cancelled() {
+                metrics.record_cancelled();
+                return Ok(BuildOutcome::Cancelled);
+            }
+
+            if cumulative_gas_used + bundle.gas_limit() > block_gas_limit {
+                reject(format!(
+                    "bundle gas limit {} exceeds remaining block gas {}",
+                    bundle.gas_limit(),
+                    block_gas_limit - cumulative_gas_used
+                ));
+                continue;
+            }
+
+            if block_blob_count + bundle.blob_count() > max_blob_count {
+                reject(format!(
+                    "bundle blobs {} exceed remaining blob capacity {}",
+                    bundle.blob_count(),
+                    max_blob_count - block_blob_count
+                ));
+                continue;
+            }
+
+            let wrong_sidecar = bundle.transactions().iter().any(|tx| {
+                tx.sidecar.as_ref().is_some_and(|sidecar| {
+                    if is_osaka {
+                        !sidecar.is_eip7594()
+                    } else {
+                        !sidecar.is_eip4844()
+                    }
+                })
+            });
+            if wrong_sidecar {
+                reject("blob sidecar format does not match the active fork".to_string());
+                continue;
+            }
+
+            let bundle_rlp_length: usize =
+                bundle.transactions().iter().map(|tx| tx.tx.inner().length()).sum();
+            let estimated_block_size = block_transactions_rlp_length +
+                bundle_rlp_length +
+                attributes.withdrawals().length() +
+                LOAD_BLOCK_SIZE_MARGIN;
+            if estimated_block_size > max_rlp_block_size {
+                reject(format!(
+                    "block size {estimated_block_size} would exceed limit {max_rlp_block_size}"
+                ));
+                continue;
+            }
+
+            // The trial runs on a layer over the scratch state and its changes are replayed onto
+            // it only once the bundle is in the block, so each bundle executes once per side.
+            let changes = match simulate_bundle(
+                &evm_config,
+                &evm_env,
+                CacheDB::new(&sim_db),
+                coinbase,
+                &bundle,
+            ) {
+                Ok((profit, _)) if profit.is_zero() => {
+                    reject("bundle does not pay the fee recipient".to_string());
+                    continue;
+                }
+                Ok((_, changes)) => changes,
+                Err(reason) => {
+                    reject(reason);
+                    continue;
+                }
+            };
+            let bundle_sidecar_bytes: u64 = bundle
+                .transactions()
+                .iter()
+                .filter_map(|tx| tx.sidecar.as_deref())
+                .map(sidecar_size)
+                .sum();
+            if !sidecar_reservation.try_grow(bundle_sidecar_bytes) {
+                reject("payload sidecar memory budget exhausted".to_string());
+                continue;
+            }
 
+            let bundle_gas_used = match execute_bundle(
+                &mut builder,
+                &bundle,
+                base_fee,
+                &mut total_fees,
+            ) {
+                Ok(gas_used) => gas_used,
+                Err(reason) => {
+                    // Part of the bundle may already be in the block and the builder cannot undo
+                    // it. Drop the bundle and start this build over without it.
+                    warn!(target: "payload_builder", bundle=%bundle_hash, %reason, "bundle diverged from simulation");
+                    builder_config.bundle_pool.drop_bundle(bundle_hash, reason);
+                    drop(builder);
+                    drop(db);
+                    drop(sim_db);
+                    drop(sidecar_reservation);
+                    return default_load_payload(
+                        evm_config,
+                        client,
+                        pool,
+                        builder_config,
+                        metrics,
+                        BuildArguments::new(
+                            cached_reads,
+                            PayloadConfig::new(parent_header, attributes),
+                            cancel,
+                            best_payload,
+                        ),
+                        best_txs,
+                    );
+                }
+            };
+            for change in changes {
+                sim_db.commit(change);
+            }
+
+            cumulative_gas_used += bundle_gas_used;
+            block_transactions_rlp_length += bundle_rlp_length;
+            block_tx_count += bundle.transactions().len();
+            block_blob_count += bundle.blob_count();
+            for tx in bundle.transactions() {
+                if let Some(sidecar) = &tx.sidecar {
+                    blob_sidecars.push(sidecar.clone());
+                }
+            }
+            builder_config.bundle_pool.mark_built(bundle_hash, attributes.payload_id());
+        }
+    }
+
+    // Pool transactions are requested only after the bundles are in, so a restarted build
+    // still owns `best_txs`.
+    let mut best_txs: Box<
+        dyn BestTransactions<Item = Arc<ValidPoolTransaction<Pool::Transaction>>> + '_,
+    > = best_txs(BestTransactionsAttributes::new(base_fee, blob_gas_price));
+    #[cfg(feature = "parallel-exec")]
+    if let Some((parallel, evm_env, results)) = &parallel {
+        best_txs = Box::new(ParallelTransactions::new(
+            best_txs,
+            &evm_config,
+            evm_env.clone(),
+            &shared_state,
+            results,
+            *parallel,
+            &builder_config.parallel_workers,
+            block_gas_limit - cumulative_gas_used,
+        ));
+    }
+    if block_blob_count == max_blob_count {
+        best_txs.skip_blobs();
+    }
+
+    let tx_loop_start = Instant::now();
     while let Some(pool_tx) = best_txs.next() {
         if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
+            metrics.record_skip(LoadPayloadSkipReason::GasLimit);
             best_txs.mark_invalid(
                 &pool_tx,
                 &InvalidPoolTransactionError::ExceedsGasLimit(pool_tx.gas_limit(), block_gas_limit),
@@ -260,6 +787,7 @@ where
         }
 
         if cancel.is_cancelled() {
+            metrics.record_cancelled();
             return Ok(BuildOutcome::Cancelled);
         }
 
@@ -268,14 +796,15 @@ where
         let estimated_block_size_with_tx = block_transactions_rlp_length +
             tx.inner().length() +
             attributes.withdrawals().length() +
-            1024;
+            LOAD_BLOCK_SIZE_MARGIN;
 
-        if is_osaka && estimated_block_size_with_tx > MAX_RLP_BLOCK_SIZE {
+        if estimated_block_size_with_tx > max_rlp_block_size {
+            metrics.record_skip(LoadPayloadSkipReason::BlockSize);
             best_txs.mark_invalid(
                 &pool_tx,
                 &InvalidPoolTransactionError::OversizedData {
                     size: estimated_block_size_with_tx,
-                    limit: MAX_RLP_BLOCK_SIZE,
+                    limit: max_rlp_block_size,
                 },
             );
             continue;
@@ -292,6 +821,7 @@ where
                     ?block_blob_count,
                     "skipping blob transaction because it would exceed load blob cap"
                 );
+                metrics.record_skip(LoadPayloadSkipReason::BlobCap);
                 best_txs.mark_invalid(
                     &pool_tx,
                     &InvalidPoolTransactionError::Eip4844(
@@ -327,27 +857,79 @@ where
             blob_tx_sidecar = match blob_sidecar_result {
                 Ok(sidecar) => Some(sidecar),
                 Err(error) => {
+                    metrics.record_skip(LoadPayloadSkipReason::BlobSidecar);
                     best_txs.mark_invalid(&pool_tx, &InvalidPoolTransactionError::Eip4844(error));
                     continue;
                 }
             };
         }
 
-        let gas_used = match builder.execute_transaction(tx.clone()) {
+        // Degrade to fewer blobs instead of holding more sidecar memory than budgeted.
+        let tx_sidecar_bytes = blob_tx_sidecar.as_deref().map_or(0, sidecar_size);
+        if !sidecar_reservation.try_grow(tx_sidecar_bytes) {
+            trace!(
+                target: "payload_builder",
+                tx=?tx.hash(),
+                reserved = builder_config.sidecar_budget.reserved(),
+                "skipping blob transactions because the sidecar memory budget is exhausted"
+            );
+            metrics.record_skip(LoadPayloadSkipReason::SidecarBudget);
+            best_txs.mark_invalid(
+                &pool_tx,
+                &InvalidPoolTransactionError::Other(Box::new(LoadTxExecutionError(
+                    "payload sidecar memory budget exhausted".to_string(),
+                ))),
+            );
+            best_txs.skip_blobs();
+            continue;
+        }
+
+        // A batch result that still applies to the builder's state is committed as is; anything
+        // else is executed again, so the block is the one sequential building produces.
+        #[cfg(feature = "parallel-exec")]
+        let committed = match &parallel {
+            Some((_, _, results)) => {
+                match results.take_committable(tx.tx_hash(), builder.evm_mut().db_mut()) {
+                    Some(output) => {
+                        let gas_used = builder
+                            .executor
+                            .commit_transaction(output, &tx)
+                            .map_err(PayloadBuilderError::evm)?;
+                        builder.transactions.push(tx.clone());
+                        Some(gas_used)
+                    }
+                    None => None,
+                }
+            }
+            None => None,
+        };
+        #[cfg(not(feature = "parallel-exec"))]
+        let committed = None;
+        let executed = match committed {
+            Some(gas_used) => Ok(gas_used),
+            None => builder.execute_transaction(tx.clone()),
+        };
+        let gas_used = match executed {
             Ok(gas_used) => gas_used,
             Err(BlockExecutionError::Validation(BlockValidationError::InvalidTx {
                 error, ..
             })) => {
-                if error.is_nonce_too_low() {
-                    trace!(target: "payload_builder", %error, ?tx, "skipping nonce too low transaction");
-                } else {
-                    trace!(target: "payload_builder", %error, ?tx, "skipping invalid transaction and its descendants");
-                    best_txs.mark_invalid(
-                        &pool_tx,
-                        &InvalidPoolTransactionError::Consensus(
-                            InvalidTransactionError::TxTypeNotSupported,
-                        ),
-                    );
+                let action = classify_invalid_tx(error.as_invalid_tx_err()).unwrap_or_else(|| {
+                    InvalidTxAction::MarkInvalid(InvalidPoolTransactionError::Other(Box::new(
+                        LoadTxExecutionError(error.to_string()),
+                    )))
+                });
+                sidecar_reservation.shrink(tx_sidecar_bytes);
+                match action {
+                    InvalidTxAction::Skip => {
+                        trace!(target: "payload_builder", %error, ?tx, "skipping transaction for this block");
+                        metrics.record_skip(LoadPayloadSkipReason::NonceTooLow);
+                    }
+                    InvalidTxAction::MarkInvalid(reason) => {
+                        trace!(target: "payload_builder", %error, %reason, ?tx, "skipping invalid transaction and its descendants");
+                        metrics.record_skip(LoadPayloadSkipReason::InvalidTransaction);
+                        best_txs.mark_invalid(&pool_tx, &reason);
+                    }
                 }
                 continue;
             }
@@ -362,6 +944,7 @@ where
         }
 
         block_transactions_rlp_length += tx.inner().length();
+        block_tx_count += 1;
 
         let miner_fee =
             tx.effective_tip_per_gas(base_fee).expect("fee is valid after successful execution");
@@ -370,16 +953,32 @@ where
         cumulative_gas_used += gas_used;
 
         if let Some(sidecar) = blob_tx_sidecar {
-            blob_sidecars.push_sidecar_variant(sidecar.as_ref().clone());
+            blob_sidecars.push(sidecar);
         }
     }
 
+    metrics.record_tx_loop(tx_loop_start.elapsed());
+    if seeded {
+        builder_config.prefetcher.record_reads(&read_tally);
+    }
+    metrics.record_contents(
+        cumulative_gas_used,
+        block_gas_limit,
+        block_blob_count,
+        max_blob_count,
+        block_tx_count,
+        total_fees,
+    );
+
     if !is_better_payload(best_payload.as_ref(), total_fees) {
         drop(builder);
+        metrics.record_aborted();
         return Ok(BuildOutcome::Aborted { fees: total_fees, cached_reads });
     }
 
+    let finish_start = Instant::now();
     let BlockBuilderOutcome { execution_result, block, .. } = builder.finish(&state_provider)?;
+    metrics.record_finish(finish_start.elapsed());
 
     let requests = chain_spec
         .is_prague_active_at_timestamp(attributes.timestamp())
@@ -393,17 +992,197 @@ where
         "sealed load payload"
     );
 
-    if is_osaka && sealed_block.rlp_length() > MAX_RLP_BLOCK_SIZE {
+    if sealed_block.rlp_length() > max_rlp_block_size {
         return Err(PayloadBuilderError::other(ConsensusError::BlockTooLarge {
             rlp_length: sealed_block.rlp_length(),
-            max_rlp_length: MAX_RLP_BLOCK_SIZE,
+            max_rlp_length: max_rlp_block_size,
         }));
     }
 
-    let payload = EthBuiltPayload::new(attributes.payload_id(), sealed_block, total_fees, requests)
-        .with_sidecars(blob_sidecars);
+    let payload = EthBuiltPayload::new(attributes.payload_id(), sealed_block, total_fees, requests);
+    let payload = LoadBuiltPayload::new(payload)
+        .with_sidecars(blob_sidecars)
+        .with_sidecar_reservation(sidecar_reservation);
+    metrics.record_better();
+    Ok(BuildOutcome::Better { payload, cached_reads })
+}
 
-    Ok(BuildOutcome::Better { payload: LoadBuiltPayload::new(payload), cached_reads })
+/// Builder reaction to a transaction rejected by the EVM during payload building.
+#[derive(Debug)]
+enum InvalidTxAction {
+    /// Leave the transaction out of this block but keep its descendants eligible.
+    Skip,
+    /// Drop the transaction and all of its descendants from this build.
+    MarkInvalid(InvalidPoolTransactionError),
+}
+
+/// Fallback pool error for EVM rejections without a dedicated pool variant.
+#[derive(Debug, Error)]
+#[error("transaction failed execution: {0}")]
+struct LoadTxExecutionError(String);
+
+impl PoolTransactionError for LoadTxExecutionError {
+    fn is_bad_transaction(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The scratch state for `eth_sendBundle` simulation could not be prepared.
+#[derive(Debug, Error)]
+#[error("bundle simulation failed: {0}")]
+struct LoadBundleBuildError(String);
+
+/// Maps an EVM rejection to the pool error reported for it.
+///
+/// Only a stale nonce is skipped on its own: the transaction was already mined, so the
+/// sender's later nonces are still executable. Every other rejection leaves a nonce gap for
+/// the sender in this block, so descendants are invalidated together with the transaction.
+/// Returns `None` for errors that are not plain EVM transaction errors.
+fn classify_invalid_tx(error: Option<&InvalidTransaction>) -> Option<InvalidTxAction> {
+    use InvalidPoolTransactionError as PoolError;
+    use InvalidTransaction as Evm;
+    use InvalidTransactionError as Consensus;
+
+    let reason = match error? {
+        Evm::NonceTooLow { .. } => return Some(InvalidTxAction::Skip),
+        Evm::NonceTooHigh { tx, state } => {
+            PoolError::Consensus(Consensus::NonceNotConsistent { tx: *tx, state: *state })
+        }
+        Evm::LackOfFundForMaxFee { fee, balance } => {
+            PoolError::Overdraft { cost: **fee, balance: **balance }
+        }
+        Evm::PriorityFeeGreaterThanMaxFee => PoolError::Consensus(Consensus::TipAboveFeeCap),
+        Evm::GasPriceLessThanBasefee => PoolError::Consensus(Consensus::FeeCapTooLow),
+        Evm::BlobGasPriceGreaterThanMax { .. } => PoolError::Underpriced,
+        Evm::CallerGasLimitMoreThanBlock => PoolError::Consensus(Consensus::GasTooHigh),
+        Evm::TxGasLimitGreaterThanCap { gas_limit, cap } => {
+            PoolError::MaxTxGasLimitExceeded(*gas_limit, *cap)
+        }
+        Evm::CallGasCostMoreThanGasLimit { .. } | Evm::GasFloorMoreThanGasLimit { .. } => {
+            PoolError::IntrinsicGasTooLow
+        }
+        Evm::OverflowPaymentInTransaction => PoolError::Consensus(Consensus::GasUintOverflow),
+        Evm::RejectCallerWithCode => PoolError::Consensus(Consensus::SignerAccountHasBytecode),
+        Evm::InvalidChainId | Evm::MissingChainId => {
+            PoolError::Consensus(Consensus::ChainIdMismatch)
+        }
+        Evm::EmptyBlobs => PoolError::Eip4844(Eip4844PoolTransactionError::NoEip4844Blobs),
+        Evm::TooManyBlobs { max, have } => {
+            PoolError::Eip4844(Eip4844PoolTransactionError::TooManyEip4844Blobs {
+                have: *have as u64,
+                permitted: *max as u64,
+            })
+        }
+        Evm::Eip2930NotSupported | Evm::AccessListNotSupported => {
+            PoolError::Consensus(Consensus::Eip2930Disabled)
+        }
+        Evm::Eip1559NotSupported => PoolError::Consensus(Consensus::Eip1559Disabled),
+        Evm::Eip4844NotSupported |
+        Evm::MaxFeePerBlobGasNotSupported |
+        Evm::BlobVersionedHashesNotSupported => PoolError::Consensus(Consensus::Eip4844Disabled),
+        Evm::Eip7702NotSupported | Evm::AuthorizationListNotSupported => {
+            PoolError::Consensus(Consensus::Eip7702Disabled)
+        }
+        other => PoolError::Other(Box::new(LoadTxExecutionError(other.to_string()))),
+    };
+
+    Some(InvalidTxAction::MarkInvalid(reason))
+}
+
+/// Executes `transactions` against `db`, committing each result, and returns how much the
+/// balance of `coinbase` grew along with the committed state changes, in order. Fails on invalid
+/// transactions and on reverts `may_revert` rejects.
+fn simulate_transactions<'a, EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    evm_env: &EvmEnvFor<EvmConfig>,
+    db: DB,
+    coinbase: Address,
+    transactions: impl IntoIterator<Item = &'a Recovered<TransactionSigned>>,
+    may_revert: impl Fn(&B256) -> bool,
+) -> Result<(U256, Vec<EvmState>), String>
+where
+    EvmConfig: ConfigureEvm<Primitives = LoadPrimitives>,
+    DB: Database + DatabaseCommit,
+{
+    let mut evm = evm_config.evm_with_env(db, evm_env.clone());
+    let coinbase_balance = |evm: &mut reth_evm::EvmFor<EvmConfig, DB>| {
+        evm.db_mut()
+            .basic(coinbase)
+            .map(|account| account.map(|account| account.balance).unwrap_or_default())
+            .map_err(|err| err.to_string())
+    };
+
+    let balance_before = coinbase_balance(&mut evm)?;
+    let mut changes = Vec::new();
+    for tx in transactions {
+        let result = evm
+            .transact(evm_config.tx_env(tx.as_recovered_ref()))
+            .map_err(|err| format!("transaction {} is invalid: {err}", tx.tx_hash()))?;
+        if !result.result.is_success() && !may_revert(tx.tx_hash()) {
+            return Err(format!("transaction {} reverted", tx.tx_hash()));
+        }
+        evm.db_mut().commit(result.state.clone());
+        changes.push(result.state);
+    }
+    let balance_after = coinbase_balance(&mut evm)?;
+
+    Ok((balance_after.saturating_sub(balance_before), changes))
+}
+
+/// Simulates a bundle and returns the fee-recipient profit it generates and its state changes.
+fn simulate_bundle<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    evm_env: &EvmEnvFor<EvmConfig>,
+    db: DB,
+    coinbase: Address,
+    bundle: &LoadBundle,
+) -> Result<(U256, Vec<EvmState>), String>
+where
+    EvmConfig: ConfigureEvm<Primitives = LoadPrimitives>,
+    DB: Database + DatabaseCommit,
+{
+    simulate_transactions(
+        evm_config,
+        evm_env,
+        db,
+        coinbase,
+        bundle.transactions().iter().map(|tx| &tx.tx),
+        |hash| bundle.may_revert(hash),
+    )
+}
+
+/// Commits a simulated bundle to the block and returns the gas it used.
+///
+/// Fails if the block disagrees with the simulation; transactions before the failing one stay
+/// in the block.
+fn execute_bundle<B: BlockBuilder<Primitives = LoadPrimitives>>(
+    builder: &mut B,
+    bundle: &LoadBundle,
+    base_fee: u64,
+    total_fees: &mut U256,
+) -> Result<u64, String> {
+    let mut gas_used = 0;
+    for tx in bundle.transactions() {
+        let mut success = true;
+        let tx_gas_used = builder
+            .execute_transaction_with_result_closure(tx.tx.clone(), |result| {
+                success = result.is_success();
+            })
+            .map_err(|err| format!("transaction {} failed: {err}", tx.tx.tx_hash()))?;
+        if !success && !bundle.may_revert(tx.tx.tx_hash()) {
+            return Err(format!("transaction {} reverted", tx.tx.tx_hash()));
+        }
+
+        let miner_fee =
+            tx.tx.effective_tip_per_gas(base_fee).expect("fee is valid after successful execution");
+        *total_fees += U256::from(miner_fee) * U256::from(tx_gas_used);
+        gas_used += tx_gas_used;
+    }
+    Ok(gas_used)
 }
 
 fn compute_load_blob_cap(chain_spec: &LoadChainSpec, timestamp: u64) -> u64 {
@@ -432,4 +1211,67 @@ mod tests {
         let cap = compute_load_blob_cap(&spec, spec.inner.genesis().timestamp);
         assert_eq!(cap, super::LOAD_MAX_BLOB_COUNT);
     }
+
+    #[test]
+    fn nonce_too_low_skips_without_invalidating_descendants() {
+        let action =
+            classify_invalid_tx(Some(&InvalidTransaction::NonceTooLow { tx: 1, state: 2 }));
+        assert!(matches!(action, Some(InvalidTxAction::Skip)));
+    }
+
+    #[test]
+    fn insufficient_funds_maps_to_overdraft() {
+        let error = InvalidTransaction::LackOfFundForMaxFee {
+            fee: Box::new(alloy_primitives::U256::from(10)),
+            balance: Box::new(alloy_primitives::U256::from(1)),
+        };
+        let action = classify_invalid_tx(Some(&error));
+        assert!(matches!(
+            action,
+            Some(InvalidTxAction::MarkInvalid(InvalidPoolTransactionError::Overdraft { .. }))
+        ));
+    }
+
+    #[test]
+    fn blob_fee_cap_too_low_maps_to_underpriced() {
+        let error = InvalidTransaction::BlobGasPriceGreaterThanMax {
+            block_blob_gas_price: 2,
+            tx_max_fee_per_blob_gas: 1,
+        };
+        let action = classify_invalid_tx(Some(&error));
+        assert!(matches!(
+            action,
+            Some(InvalidTxAction::MarkInvalid(InvalidPoolTransactionError::Underpriced))
+        ));
+    }
+
+    #[test]
+    fn intrinsic_gas_failure_maps_to_intrinsic_gas_too_low() {
+        let error =
+            InvalidTransaction::CallGasCostMoreThanGasLimit { initial_gas: 21_000, gas_limit: 1 };
+        let action = classify_invalid_tx(Some(&error));
+        assert!(matches!(
+            action,
+            Some(InvalidTxAction::MarkInvalid(InvalidPoolTransactionError::IntrinsicGasTooLow))
+        ));
+    }
+
+    #[test]
+    fn too_many_blobs_reports_counts() {
+        let error = InvalidTransaction::TooManyBlobs { max: 32, have: 33 };
+        match classify_invalid_tx(Some(&error)) {
+            Some(InvalidTxAction::MarkInvalid(InvalidPoolTransactionError::Eip4844(
+                Eip4844PoolTransactionError::TooManyEip4844Blobs { have, permitted },
+            ))) => {
+                assert_eq!(have, 33);
+                assert_eq!(permitted, 32);
+            }
+            other => panic!("unexpected classification: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn non_evm_errors_are_left_to_the_caller() {
+        assert!(classify_invalid_tx(None).is_none());
+    }
 }
@@ -0,0 +1,216 @@
+//! Load capability negotiation over `engine_exchangeCapabilities`.
+//!
+//! Next to the Engine API methods it serves, the EL advertises `load.<key>.<value>` capabilities
+//! pinning the chain parameters Ultramarine has to agree on: the blob cap, the constant
+//! PREVRANDAO, the maximum block gas limit and the activation timestamp of every time-based
+//! hardfork. They are derived from the active chain spec. The list the consensus client sends is
+//! recorded on every exchange and checked against them: a client that leaves one out, or
+//! advertises another value under the same key, is incompatible. Capabilities with keys the EL
+//! does not require are ignored.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use jsonrpsee::types::ErrorObjectOwned;
+use reth_chainspec::{EthChainSpec, ForkCondition, Hardforks};
+use thiserror::Error;
+
+use crate::chainspec::{LoadChainParams, LoadChainSpec};
+
+/// Engine API error code for payload builds refused because of a capability mismatch.
+pub const LOAD_CAPABILITY_MISMATCH_CODE: i32 = -38100;
+
+/// Prefix shared by every Load capability.
+const LOAD_CAPABILITY_PREFIX: &str = "load.";
+
+/// Why the consensus client's capabilities are not accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LoadCapabilityError {
+    #[error("consensus client has not exchanged capabilities yet")]
+    NotExchanged,
+    #[error(
+        "consensus client capabilities do not match the chain spec: missing {missing:?}, \
+         conflicting {conflicting:?}"
+    )]
+    Incompatible { missing: Vec<String>, conflicting: Vec<String> },
+}
+
+impl From<LoadCapabilityError> for ErrorObjectOwned {
+    fn from(err: LoadCapabilityError) -> Self {
+        Self::owned(LOAD_CAPABILITY_MISMATCH_CODE, err.to_string(), None::<()>)
+    }
+}
+
+/// Load capabilities the consensus client must advertise with matching values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadCapabilities {
+    required: Vec<String>,
+}
+
+impl LoadCapabilities {
+    /// Derives the required capabilities from a chain spec.
+    pub fn from_chain_spec(chain_spec: &LoadChainSpec) -> Self {
+        let mut required = Vec::new();
+        if let Some(blob_params) = chain_spec.blob_params_at_timestamp(u64::MAX) {
+            required.push(format!("load.blobs.{}", blob_params.max_blob_count));
+        }
+        required.push("load.prev_randao.0x01".to_string());
+        required.push(format!("load.gas_limit.{}", chain_spec.max_gas_limit()));
+        for (fork, condition) in chain_spec.forks_iter() {
+            if let ForkCondition::Timestamp(timestamp) = condition {
+                required.push(format!("load.fork.{}.{timestamp}", fork.name().to_lowercase()));
+            }
+        }
+        Self { required }
+    }
+
+    /// Capability strings to advertise.
+    pub fn list(&self) -> &[String] {
+        &self.required
+    }
+
+    /// Checks what the consensus client advertised against the required capabilities.
+    pub fn check(&self, advertised: &[String]) -> Result<(), LoadCapabilityError> {
+        let mut missing = Vec::new();
+        let mut conflicting = Vec::new();
+        for required in &self.required {
+            let key = capability_key(required);
+            let mut same_key =
+                advertised.iter().filter(|cap| capability_key(cap) == key).peekable();
+            if same_key.peek().is_none() {
+                missing.push(required.clone());
+            }
+            conflicting.extend(same_key.filter(|cap| *cap != required).cloned());
+        }
+        if missing.is_empty() && conflicting.is_empty() {
+            Ok(())
+        } else {
+            Err(LoadCapabilityError::Incompatible { missing, conflicting })
+        }
+    }
+}
+
+/// `load.<key>` part of a Load capability, or `None` for other capabilities.
+fn capability_key(capability: &str) -> Option<&str> {
+    capability
+        .starts_with(LOAD_CAPABILITY_PREFIX)
+        .then(|| capability.rsplit_once('.').map(|(key, _)| key))
+        .flatten()
+}
+
+/// Outcome of the last capability exchange, shared by every Engine API handler.
+#[derive(Debug, Clone, Default)]
+pub struct LoadCapabilityNegotiation {
+    capabilities: Arc<LoadCapabilities>,
+    require: bool,
+    exchanged: Arc<RwLock<Option<LoadExchangedCapabilities>>>,
+}
+
+/// Capabilities the consensus client sent in its last exchange.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadExchangedCapabilities {
+    /// The client's list, as sent.
+    pub advertised: Vec<String>,
+    /// Whether it satisfies the required capabilities.
+    pub outcome: Result<(), LoadCapabilityError>,
+}
+
+impl LoadCapabilityNegotiation {
+    pub fn new(capabilities: LoadCapabilities) -> Self {
+        Self { capabilities: Arc::new(capabilities), ..Default::default() }
+    }
+
+    /// Refuse payload building until the consensus client is compatible.
+    pub const fn with_require(mut self, require: bool) -> Self {
+        self.require = require;
+        self
+    }
+
+    /// The required capabilities.
+    pub fn capabilities(&self) -> &LoadCapabilities {
+        &self.capabilities
+    }
+
+    /// Records an exchange and returns whether the consensus client is compatible.
+    pub fn record(&self, advertised: Vec<String>) -> Result<(), LoadCapabilityError> {
+        let outcome = self.capabilities.check(&advertised);
+        *self.exchanged.write().unwrap_or_else(PoisonError::into_inner) =
+            Some(LoadExchangedCapabilities { advertised, outcome: outcome.clone() });
+        outcome
+    }
+
+    /// The last exchange, if any.
+    pub fn exchanged(&self) -> Option<LoadExchangedCapabilities> {
+        self.exchanged.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Fails if payload building has to be refused.
+    ///
+    /// Always passes unless [`Self::with_require`] is set; then the consensus client must have
+    /// exchanged compatible capabilities.
+    pub fn ensure_can_build(&self) -> Result<(), LoadCapabilityError> {
+        if !self.require {
+            return Ok(());
+        }
+        self.exchanged
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map_or(Err(LoadCapabilityError::NotExchanged), |exchanged| exchanged.outcome.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(caps: &[&str]) -> Vec<String> {
+        caps.iter().map(|cap| cap.to_string()).collect()
+    }
+
+    #[test]
+    fn derives_capabilities_from_chain_spec() {
+        let capabilities = LoadCapabilities::from_chain_spec(&LoadChainSpec::default());
+        let list = capabilities.list();
+        assert!(list.contains(&"load.blobs.1024".to_string()));
+        assert!(list.contains(&"load.prev_randao.0x01".to_string()));
+        assert!(list.contains(&"load.gas_limit.2000000000".to_string()));
+        assert!(list.contains(&"load.fork.cancun.0".to_string()));
+        assert!(list.contains(&"load.fork.prague.0".to_string()));
+        assert!(capabilities.check(list).is_ok());
+    }
+
+    #[test]
+    fn reports_missing_and_conflicting_capabilities() {
+        let capabilities = LoadCapabilities {
+            required: caps(&["load.blobs.1024", "load.prev_randao.0x01", "load.fork.osaka.100"]),
+        };
+        assert_eq!(
+            capabilities.check(&caps(&[
+                "engine_newPayloadV4",
+                "load.blobs.512",
+                "load.fork.osaka.100",
+                "load.forced_transactions.v1",
+            ])),
+            Err(LoadCapabilityError::Incompatible {
+                missing: caps(&["load.prev_randao.0x01"]),
+                conflicting: caps(&["load.blobs.512"]),
+            })
+        );
+    }
+
+    #[test]
+    fn refuses_building_only_when_required() {
+        let capabilities = LoadCapabilities { required: caps(&["load.blobs.1024"]) };
+        let lenient = LoadCapabilityNegotiation::new(capabilities.clone());
+        assert!(lenient.record(caps(&["load.blobs.512"])).is_err());
+        assert!(lenient.ensure_can_build().is_ok());
+
+        let strict = LoadCapabilityNegotiation::new(capabilities).with_require(true);
+        assert_eq!(strict.ensure_can_build(), Err(LoadCapabilityError::NotExchanged));
+        assert!(strict.record(caps(&["load.blobs.512"])).is_err());
+        assert!(strict.ensure_can_build().is_err());
+        assert!(strict.record(caps(&["load.blobs.1024"])).is_ok());
+        assert!(strict.ensure_can_build().is_ok());
+        assert_eq!(strict.exchanged().unwrap().advertised, caps(&["load.blobs.1024"]));
+    }
+}
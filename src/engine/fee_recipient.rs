@@ -0,0 +1,102 @@
+//! Fee-recipient policy for Load payload attributes.
+//!
+//! Ultramarine picks `suggested_fee_recipient` for every block it asks us to build. A
+//! misconfigured CL could burn rewards to the zero address or redirect them elsewhere, so
+//! operators can pin the accepted recipients here. The policy is enforced at Engine API ingress
+//! (`LoadEngineValidator`) and again by the payload builder before a job starts.
+
+use std::{collections::HashSet, sync::Arc};
+
+use alloy_primitives::Address;
+use thiserror::Error;
+
+/// Fee-recipient rejection reasons.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FeeRecipientPolicyError {
+    #[error("suggested_fee_recipient is the zero address; block rewards would be burned")]
+    ZeroAddress,
+    #[error("suggested_fee_recipient {0} is not in the Load fee-recipient allowlist")]
+    NotAllowed(Address),
+}
+
+/// Accepted fee recipients for Load payload attributes.
+///
+/// The default policy accepts any address, matching upstream Ethereum behaviour.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadFeeRecipientPolicy {
+    allowlist: Option<Arc<HashSet<Address>>>,
+    deny_zero: bool,
+}
+
+impl LoadFeeRecipientPolicy {
+    /// Accept only the given recipients. An empty list leaves the allowlist disabled.
+    pub fn with_allowlist(mut self, allowlist: impl IntoIterator<Item = Address>) -> Self {
+        let allowlist: HashSet<_> = allowlist.into_iter().collect();
+        self.allowlist = (!allowlist.is_empty()).then(|| Arc::new(allowlist));
+        self
+    }
+
+    /// Reject the zero address even if it is allowlisted.
+    pub const fn with_deny_zero(mut self, deny_zero: bool) -> Self {
+        self.deny_zero = deny_zero;
+        self
+    }
+
+    /// Returns `true` if any recipient is accepted.
+    pub fn is_permissive(&self) -> bool {
+        self.allowlist.is_none() && !self.deny_zero
+    }
+
+    /// Checks a suggested fee recipient against the policy.
+    pub fn check(&self, fee_recipient: Address) -> Result<(), FeeRecipientPolicyError> {
+        if self.deny_zero && fee_recipient.is_zero() {
+            return Err(FeeRecipientPolicyError::ZeroAddress);
+        }
+
+        if let Some(allowlist) = &self.allowlist {
+            if !allowlist.contains(&fee_recipient) {
+                return Err(FeeRecipientPolicyError::NotAllowed(fee_recipient));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::address;
+
+    use super::*;
+
+    const VALIDATOR: Address = address!("0x00000000000000000000000000000000000000aa");
+
+    #[test]
+    fn default_policy_accepts_anything() {
+        let policy = LoadFeeRecipientPolicy::default();
+        assert!(policy.is_permissive());
+        assert!(policy.check(Address::ZERO).is_ok());
+        assert!(policy.check(VALIDATOR).is_ok());
+    }
+
+    #[test]
+    fn deny_zero_rejects_burn_address() {
+        let policy = LoadFeeRecipientPolicy::default().with_deny_zero(true);
+        assert_eq!(policy.check(Address::ZERO), Err(FeeRecipientPolicyError::ZeroAddress));
+        assert!(policy.check(VALIDATOR).is_ok());
+    }
+
+    #[test]
+    fn allowlist_rejects_unknown_recipients() {
+        let policy = LoadFeeRecipientPolicy::default().with_allowlist([VALIDATOR]);
+        assert!(policy.check(VALIDATOR).is_ok());
+        let other = Address::repeat_byte(0x11);
+        assert_eq!(policy.check(other), Err(FeeRecipientPolicyError::NotAllowed(other)));
+    }
+
+    #[test]
+    fn empty_allowlist_is_disabled() {
+        let policy = LoadFeeRecipientPolicy::default().with_allowlist([]);
+        assert!(policy.is_permissive());
+    }
+}
@@ -0,0 +1,368 @@
+//! Tendermint finality guard for forkchoice updates.
+//!
+//! Ultramarine finalizes a block in the slot it is proposed, so a forkchoice update must never
+//! move the finalized block backwards or onto a branch that leaves the finalized chain, and its
+//! head must build on that chain. Upstream only checks that a forkchoice state is consistent in
+//! itself; a buggy consensus client could still reorg history the network considers final. The
+//! guard compares every update with the block the node has already finalized and rejects
+//! violations before they reach the engine tree. Blocks the node does not know pass, since the
+//! engine answers `SYNCING` for them anyway.
+//!
+//! The provider only knows canonical blocks, while the engine tree also holds every valid block
+//! received through `engine_newPayload`. Those are recorded in [`LoadPayloadAncestry`] so a
+//! sibling of a finalized block is recognized as such instead of passing as unknown.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloy_consensus::BlockHeader;
+use alloy_eips::BlockNumHash;
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::ForkchoiceState;
+use jsonrpsee::types::{error::INTERNAL_ERROR_CODE, ErrorObjectOwned};
+use reth::providers::{BlockIdReader, HeaderProvider, ProviderError, ProviderResult};
+use thiserror::Error;
+
+/// Engine API error code for forkchoice updates that violate Load finality.
+pub const LOAD_FINALITY_VIOLATION_CODE: i32 = -38101;
+
+/// Forkchoice updates refused by the finality guard.
+#[derive(Debug, Error)]
+pub enum LoadFinalityError {
+    #[error(
+        "finalized block {finalized} (#{number}) is below the finalized block {previous} \
+         (#{previous_number})"
+    )]
+    FinalizedMovedBackward { finalized: B256, number: u64, previous: B256, previous_number: u64 },
+    #[error("finalized block hash is unset but block {previous} (#{previous_number}) is final")]
+    FinalizedUnset { previous: B256, previous_number: u64 },
+    #[error(
+        "{role} block {hash} (#{number}) is not on the chain of the finalized block {finalized} \
+         (#{finalized_number})"
+    )]
+    OffFinalizedChain {
+        role: &'static str,
+        hash: B256,
+        number: u64,
+        finalized: B256,
+        finalized_number: u64,
+    },
+    #[error("head {head}, safe {safe} and finalized {finalized} must be the same block")]
+    NotInstantFinality { head: B256, safe: B256, finalized: B256 },
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+impl LoadFinalityError {
+    /// Metric label of a violation, or `None` if the guard itself failed.
+    pub const fn kind(&self) -> Option<&'static str> {
+        match self {
+            Self::FinalizedMovedBackward { .. } | Self::FinalizedUnset { .. } => Some("backward"),
+            Self::OffFinalizedChain { .. } => Some("off_chain"),
+            Self::NotInstantFinality { .. } => Some("not_instant"),
+            Self::Provider(_) => None,
+        }
+    }
+}
+
+impl From<LoadFinalityError> for ErrorObjectOwned {
+    fn from(err: LoadFinalityError) -> Self {
+        let code =
+            if err.kind().is_some() { LOAD_FINALITY_VIOLATION_CODE } else { INTERNAL_ERROR_CODE };
+        Self::owned(code, err.to_string(), None::<()>)
+    }
+}
+
+/// Chain lookups the finality guard needs.
+pub trait LoadFinalityChain {
+    /// Number and parent hash of a known block.
+    fn block_parent(&self, hash: B256) -> ProviderResult<Option<(u64, B256)>>;
+
+    /// Hash of the canonical block at `number`.
+    fn canonical_hash(&self, number: u64) -> ProviderResult<Option<B256>>;
+
+    /// The block the node has finalized.
+    fn finalized(&self) -> ProviderResult<Option<BlockNumHash>>;
+}
+
+impl<P> LoadFinalityChain for P
+where
+    P: HeaderProvider + BlockIdReader,
+{
+    fn block_parent(&self, hash: B256) -> ProviderResult<Option<(u64, B256)>> {
+        Ok(self.header(hash)?.map(|header| (header.number(), header.parent_hash())))
+    }
+
+    fn canonical_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+        self.block_hash(number)
+    }
+
+    fn finalized(&self) -> ProviderResult<Option<BlockNumHash>> {
+        self.finalized_block_num_hash()
+    }
+}
+
+/// Number and parent of the blocks received through `engine_newPayload`, by hash.
+///
+/// Clones share the same records.
+#[derive(Debug, Clone, Default)]
+pub struct LoadPayloadAncestry(Arc<Mutex<HashMap<B256, (u64, B256)>>>);
+
+impl LoadPayloadAncestry {
+    /// Records a block the engine accepted.
+    pub fn record(&self, number: u64, hash: B256, parent: B256) {
+        self.lock().insert(hash, (number, parent));
+    }
+
+    /// Forgets blocks below the finalized height. Blocks at the finalized height are kept, so a
+    /// sibling of the finalized block is still recognized.
+    pub fn prune(&self, finalized: u64) {
+        self.lock().retain(|_, (number, _)| *number >= finalized);
+    }
+
+    /// Number of recorded blocks.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, hash: &B256) -> Option<(u64, B256)> {
+        self.lock().get(hash).copied()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<B256, (u64, B256)>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A chain that also resolves the blocks recorded from `engine_newPayload`.
+#[derive(Debug)]
+pub struct LoadPayloadChain<'a, C> {
+    chain: &'a C,
+    payloads: &'a LoadPayloadAncestry,
+}
+
+impl<'a, C> LoadPayloadChain<'a, C> {
+    pub const fn new(chain: &'a C, payloads: &'a LoadPayloadAncestry) -> Self {
+        Self { chain, payloads }
+    }
+}
+
+impl<C: LoadFinalityChain> LoadFinalityChain for LoadPayloadChain<'_, C> {
+    fn block_parent(&self, hash: B256) -> ProviderResult<Option<(u64, B256)>> {
+        match self.chain.block_parent(hash)? {
+            Some(block) => Ok(Some(block)),
+            None => Ok(self.payloads.get(&hash)),
+        }
+    }
+
+    fn canonical_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+        self.chain.canonical_hash(number)
+    }
+
+    fn finalized(&self) -> ProviderResult<Option<BlockNumHash>> {
+        self.chain.finalized()
+    }
+}
+
+/// Rejects forkchoice updates that would undo finalized history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadFinalityGuard {
+    instant_finality: bool,
+}
+
+impl LoadFinalityGuard {
+    /// Also require `head == safe == finalized`, as Ultramarine sends them.
+    pub const fn with_instant_finality(mut self, instant_finality: bool) -> Self {
+        self.instant_finality = instant_finality;
+        self
+    }
+
+    /// Checks a forkchoice state against the finalized block of `chain`.
+    pub fn check(
+        &self,
+        chain: &impl LoadFinalityChain,
+        state: &ForkchoiceState,
+    ) -> Result<(), LoadFinalityError> {
+        let ForkchoiceState { head_block_hash, safe_block_hash, finalized_block_hash } = *state;
+        if self.instant_finality &&
+            (head_block_hash != safe_block_hash || safe_block_hash != finalized_block_hash)
+        {
+            return Err(LoadFinalityError::NotInstantFinality {
+                head: head_block_hash,
+                safe: safe_block_hash,
+                finalized: finalized_block_hash,
+            });
+        }
+
+        let Some(previous) = chain.finalized()? else { return Ok(()) };
+        if finalized_block_hash.is_zero() {
+            return if previous.number == 0 {
+                Ok(())
+            } else {
+                Err(LoadFinalityError::FinalizedUnset {
+                    previous: previous.hash,
+                    previous_number: previous.number,
+                })
+            };
+        }
+        if let Some((number, _)) = chain.block_parent(finalized_block_hash)? {
+            if number < previous.number {
+                return Err(LoadFinalityError::FinalizedMovedBackward {
+                    finalized: finalized_block_hash,
+                    number,
+                    previous: previous.hash,
+                    previous_number: previous.number,
+                });
+            }
+        }
+        ensure_on_finalized_chain(chain, "finalized", finalized_block_hash, previous)?;
+        ensure_on_finalized_chain(chain, "head", head_block_hash, previous)
+    }
+}
+
+/// Walks back from `hash` to the canonical chain and fails unless it joins at or above
+/// `finalized`.
+fn ensure_on_finalized_chain(
+    chain: &impl LoadFinalityChain,
+    role: &'static str,
+    hash: B256,
+    finalized: BlockNumHash,
+) -> Result<(), LoadFinalityError> {
+    let mut cursor = hash;
+    let mut first = None;
+    while let Some((number, parent)) = chain.block_parent(cursor)? {
+        let number_of_hash = *first.get_or_insert(number);
+        if number >= finalized.number && chain.canonical_hash(number)? == Some(cursor) {
+            return Ok(());
+        }
+        if number <= finalized.number {
+            return Err(LoadFinalityError::OffFinalizedChain {
+                role,
+                hash,
+                number: number_of_hash,
+                finalized: finalized.hash,
+                finalized_number: finalized.number,
+            });
+        }
+        cursor = parent;
+    }
+    // Unknown ancestry: the engine tree decides.
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Canonical chain `0..=3` plus a fork `2'` on top of block 1.
+    struct TestChain {
+        blocks: HashMap<B256, (u64, B256)>,
+        canonical: Vec<B256>,
+        finalized: Option<BlockNumHash>,
+    }
+
+    fn hash(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    const FORK: u8 = 0xf2;
+
+    impl TestChain {
+        fn new(finalized: u64) -> Self {
+            let canonical: Vec<_> = (0..=3).map(|n| hash(n + 1)).collect();
+            let mut blocks: HashMap<_, _> = canonical
+                .iter()
+                .enumerate()
+                .map(|(n, h)| (*h, (n as u64, if n == 0 { B256::ZERO } else { canonical[n - 1] })))
+                .collect();
+            blocks.insert(hash(FORK), (2, canonical[1]));
+            let finalized = Some(BlockNumHash::new(finalized, canonical[finalized as usize]));
+            Self { blocks, canonical, finalized }
+        }
+    }
+
+    impl LoadFinalityChain for TestChain {
+        fn block_parent(&self, hash: B256) -> ProviderResult<Option<(u64, B256)>> {
+            Ok(self.blocks.get(&hash).copied())
+        }
+
+        fn canonical_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+            Ok(self.canonical.get(number as usize).copied())
+        }
+
+        fn finalized(&self) -> ProviderResult<Option<BlockNumHash>> {
+            Ok(self.finalized)
+        }
+    }
+
+    fn state(head: B256, safe: B256, finalized: B256) -> ForkchoiceState {
+        ForkchoiceState {
+            head_block_hash: head,
+            safe_block_hash: safe,
+            finalized_block_hash: finalized,
+        }
+    }
+
+    #[test]
+    fn accepts_forward_and_unknown_blocks() {
+        let chain = TestChain::new(1);
+        let guard = LoadFinalityGuard::default();
+        assert!(guard.check(&chain, &state(hash(2), hash(2), hash(2))).is_ok());
+        assert!(guard.check(&chain, &state(hash(4), hash(3), hash(2))).is_ok());
+        assert!(guard.check(&chain, &state(hash(FORK), hash(FORK), hash(FORK))).is_ok());
+        assert!(guard.check(&chain, &state(hash(0xaa), hash(0xaa), hash(0xaa))).is_ok());
+    }
+
+    #[test]
+    fn rejects_finalized_moving_backward_or_off_chain() {
+        let chain = TestChain::new(2);
+        let guard = LoadFinalityGuard::default();
+        let err = guard.check(&chain, &state(hash(4), hash(4), hash(2))).unwrap_err();
+        assert!(matches!(err, LoadFinalityError::FinalizedMovedBackward { number: 1, .. }));
+        let err = guard.check(&chain, &state(hash(FORK), hash(FORK), hash(FORK))).unwrap_err();
+        assert!(matches!(err, LoadFinalityError::OffFinalizedChain { role: "finalized", .. }));
+        let err = guard.check(&chain, &state(hash(FORK), hash(3), hash(3))).unwrap_err();
+        assert!(matches!(err, LoadFinalityError::OffFinalizedChain { role: "head", .. }));
+        let err = guard.check(&chain, &state(hash(4), hash(4), B256::ZERO)).unwrap_err();
+        assert!(matches!(err, LoadFinalityError::FinalizedUnset { previous_number: 2, .. }));
+        assert_eq!(err.kind(), Some("backward"));
+    }
+
+    #[test]
+    fn instant_finality_requires_a_single_block() {
+        let chain = TestChain::new(1);
+        let guard = LoadFinalityGuard::default().with_instant_finality(true);
+        assert!(guard.check(&chain, &state(hash(3), hash(3), hash(3))).is_ok());
+        let err = guard.check(&chain, &state(hash(3), hash(2), hash(2))).unwrap_err();
+        assert_eq!(err.kind(), Some("not_instant"));
+    }
+
+    #[test]
+    fn resolves_siblings_from_recorded_payloads() {
+        let chain = TestChain::new(2);
+        let payloads = LoadPayloadAncestry::default();
+        let guard = LoadFinalityGuard::default();
+        // A sibling of the finalized block the provider does not know.
+        let sibling = hash(0xf3);
+        let finalize_sibling = state(sibling, sibling, sibling);
+        assert!(guard.check(&LoadPayloadChain::new(&chain, &payloads), &finalize_sibling).is_ok());
+
+        payloads.record(2, sibling, hash(2));
+        let err =
+            guard.check(&LoadPayloadChain::new(&chain, &payloads), &finalize_sibling).unwrap_err();
+        assert!(matches!(err, LoadFinalityError::OffFinalizedChain { number: 2, .. }));
+
+        payloads.prune(2);
+        assert_eq!(payloads.len(), 1, "siblings at the finalized height are kept");
+        payloads.prune(3);
+        assert!(payloads.is_empty());
+    }
+}
@@ -1,7 +1,17 @@
 //! Load-specific payload builder wiring and Engine API glue.
 
+pub mod blob_import;
+pub mod budget;
 pub mod builder;
+pub mod capabilities;
+pub mod fee_recipient;
+pub mod finality;
+#[cfg(feature = "parallel-exec")]
+pub mod parallel;
 pub mod payload;
+pub mod payload_cache;
+pub mod prefetch;
+pub mod recorder;
 pub mod rpc;
 pub mod validator;
 pub use builder::{default_load_payload, LoadPayloadBuilder, LoadPayloadServiceBuilder};
@@ -0,0 +1,406 @@
+//! Optimistic parallel transaction execution (`parallel-exec` feature).
+//!
+//! Block-STM style: every transaction of a batch runs at once against a multi-version view of the
+//! state, where a read of a key returns the write of the closest preceding transaction in the
+//! batch (or the base state if there is none) and records which version it saw. After each wave
+//! the reads are validated in block order; a transaction whose reads no longer match the latest
+//! preceding writes is executed again in the next wave. The first invalid transaction always
+//! validates after its re-execution, since everything before it is final, so the loop terminates
+//! and the outcome is the one sequential execution would produce.
+//!
+//! The payload builder runs pool transactions through this with
+//! [`speculative::ParallelTransactions`]: they are pulled ahead in batches and executed here before
+//! the builder reaches them. For each transaction the builder then checks that every account and
+//! storage value the batch execution read still holds in its own state, and if so commits the
+//! batch result through its block executor instead of executing the transaction again. A
+//! transaction whose reads no longer hold (because of a system call, a forced transaction, a
+//! bundle, or an earlier transaction the builder left out) is executed by the builder as in a
+//! sequential build, so block contents always match sequential building.
+
+pub mod speculative;
+pub mod state;
+
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError, RwLock,
+    },
+};
+
+/// Default number of pool transactions executed per batch.
+pub const DEFAULT_PARALLEL_BATCH_SIZE: usize = 512;
+
+/// Number of independently locked shards in the multi-version memory.
+const MV_SHARDS: usize = 64;
+
+/// Parallel execution settings for the payload builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadParallelConfig {
+    /// Worker threads per batch.
+    pub threads: usize,
+    /// Pool transactions executed per batch.
+    pub batch_size: usize,
+}
+
+impl LoadParallelConfig {
+    pub const fn new(threads: usize) -> Self {
+        Self { threads, batch_size: DEFAULT_PARALLEL_BATCH_SIZE }
+    }
+
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl Default for LoadParallelConfig {
+    fn default() -> Self {
+        Self::new(std::thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+/// Worker threads shared by every payload build.
+///
+/// A batch runs on the building thread plus the extra workers it can take from here, so concurrent
+/// builds split the configured thread count between them instead of each spawning a full set.
+#[derive(Debug, Clone)]
+pub struct LoadParallelWorkers {
+    idle: Arc<AtomicUsize>,
+}
+
+impl LoadParallelWorkers {
+    /// Pool for `threads` workers in total, counting the building thread.
+    pub fn new(threads: usize) -> Self {
+        Self { idle: Arc::new(AtomicUsize::new(threads.saturating_sub(1))) }
+    }
+
+    /// Takes up to `wanted` extra workers; they go back to the pool when the permit is dropped.
+    pub fn acquire(&self, wanted: usize) -> LoadWorkerPermit {
+        let mut taken = 0;
+        let _ = self.idle.fetch_update(Ordering::AcqRel, Ordering::Acquire, |idle| {
+            taken = idle.min(wanted);
+            Some(idle - taken)
+        });
+        LoadWorkerPermit { idle: Arc::clone(&self.idle), workers: taken }
+    }
+}
+
+impl Default for LoadParallelWorkers {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Extra workers taken from [`LoadParallelWorkers`] for one batch.
+#[derive(Debug)]
+pub struct LoadWorkerPermit {
+    idle: Arc<AtomicUsize>,
+    workers: usize,
+}
+
+impl LoadWorkerPermit {
+    /// Threads the batch may run on, including the building thread.
+    pub const fn threads(&self) -> usize {
+        self.workers + 1
+    }
+}
+
+impl Drop for LoadWorkerPermit {
+    fn drop(&mut self) {
+        self.idle.fetch_add(self.workers, Ordering::AcqRel);
+    }
+}
+
+/// Read access to the state a transaction executes against.
+pub trait StateView<K, V> {
+    fn read(&mut self, key: &K) -> V;
+}
+
+/// Result of executing one transaction: the keys it wrote and a task-specific output.
+#[derive(Debug, Clone)]
+pub struct TaskExecution<K, V, O> {
+    pub writes: Vec<(K, V)>,
+    pub output: O,
+}
+
+/// A batch of transactions that can be executed in parallel.
+///
+/// `execute` must be deterministic in the values it reads: given the same reads it has to produce
+/// the same writes and output.
+pub trait ParallelTask: Sync {
+    type Key: Clone + Eq + Hash + Send + Sync;
+    type Value: Clone + Send + Sync;
+    type Output: Send;
+
+    /// Number of transactions in the batch.
+    fn transaction_count(&self) -> usize;
+
+    /// Value of `key` before the batch.
+    fn base(&self, key: &Self::Key) -> Self::Value;
+
+    /// Executes transaction `index`, reading all state through `view`.
+    fn execute(
+        &self,
+        index: usize,
+        view: &mut dyn StateView<Self::Key, Self::Value>,
+    ) -> TaskExecution<Self::Key, Self::Value, Self::Output>;
+}
+
+/// Work done to reach the sequential outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParallelStats {
+    /// Transaction executions, including re-executions.
+    pub executions: usize,
+    /// Execute/validate rounds.
+    pub waves: usize,
+}
+
+impl ParallelStats {
+    /// Executions beyond one per transaction.
+    pub const fn reexecutions(&self, transactions: usize) -> usize {
+        self.executions.saturating_sub(transactions)
+    }
+}
+
+/// Outputs and final state of an executed batch.
+#[derive(Debug)]
+pub struct ParallelOutcome<K, V, O> {
+    /// Per-transaction outputs, in batch order.
+    pub outputs: Vec<O>,
+    /// Final value of every key written by the batch.
+    pub writes: HashMap<K, V>,
+    pub stats: ParallelStats,
+}
+
+/// Which write a read observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadOrigin {
+    Base,
+    Transaction { index: usize, incarnation: u32 },
+}
+
+type VersionedValues<V> = BTreeMap<usize, (u32, V)>;
+
+/// Writes of every transaction in the batch, by key and writer index.
+struct MvMemory<K, V> {
+    shards: Vec<RwLock<HashMap<K, VersionedValues<V>>>>,
+    hasher: RandomState,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> MvMemory<K, V> {
+    fn new() -> Self {
+        Self {
+            shards: (0..MV_SHARDS).map(|_| RwLock::default()).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn shard(&self, key: &K) -> &RwLock<HashMap<K, VersionedValues<V>>> {
+        &self.shards[self.hasher.hash_one(key) as usize % MV_SHARDS]
+    }
+
+    /// Latest write to `key` by a transaction before `index`.
+    fn read(&self, key: &K, index: usize) -> Option<(ReadOrigin, V)> {
+        let shard = self.shard(key).read().unwrap_or_else(PoisonError::into_inner);
+        let (&writer, (incarnation, value)) = shard.get(key)?.range(..index).next_back()?;
+        Some((ReadOrigin::Transaction { index: writer, incarnation: *incarnation }, value.clone()))
+    }
+
+    fn origin(&self, key: &K, index: usize) -> ReadOrigin {
+        let shard = self.shard(key).read().unwrap_or_else(PoisonError::into_inner);
+        shard.get(key).and_then(|versions| versions.range(..index).next_back()).map_or(
+            ReadOrigin::Base,
+            |(&writer, (incarnation, _))| ReadOrigin::Transaction {
+                index: writer,
+                incarnation: *incarnation,
+            },
+        )
+    }
+
+    /// Replaces the writes of `index` with the ones from its latest incarnation.
+    fn record(&self, index: usize, incarnation: u32, previous: &[K], writes: &[(K, V)]) {
+        for key in previous {
+            let mut shard = self.shard(key).write().unwrap_or_else(PoisonError::into_inner);
+            if let Some(versions) = shard.get_mut(key) {
+                versions.remove(&index);
+            }
+        }
+        for (key, value) in writes {
+            let mut shard = self.shard(key).write().unwrap_or_else(PoisonError::into_inner);
+            shard.entry(key.clone()).or_default().insert(index, (incarnation, value.clone()));
+        }
+    }
+
+    /// Final value of every written key.
+    fn into_writes(self) -> HashMap<K, V> {
+        self.shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .filter_map(|(key, versions)| {
+                versions.into_values().next_back().map(|(_, value)| (key, value))
+            })
+            .collect()
+    }
+}
+
+/// Per-transaction bookkeeping across incarnations.
+struct TxSlot<K, O> {
+    incarnation: u32,
+    reads: Vec<(K, ReadOrigin)>,
+    written: Vec<K>,
+    output: Option<O>,
+}
+
+impl<K, O> Default for TxSlot<K, O> {
+    fn default() -> Self {
+        Self { incarnation: 0, reads: Vec::new(), written: Vec::new(), output: None }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+struct MvView<'a, T: ParallelTask> {
+    task: &'a T,
+    memory: &'a MvMemory<T::Key, T::Value>,
+    index: usize,
+    reads: Vec<(T::Key, ReadOrigin)>,
+}
+
+impl<T: ParallelTask> StateView<T::Key, T::Value> for MvView<'_, T> {
+    fn read(&mut self, key: &T::Key) -> T::Value {
+        let (origin, value) = self
+            .memory
+            .read(key, self.index)
+            .unwrap_or_else(|| (ReadOrigin::Base, self.task.base(key)));
+        self.reads.push((key.clone(), origin));
+        value
+    }
+}
+
+/// Executes the batch on `threads` workers and returns the sequential outcome.
+pub fn execute_parallel<T: ParallelTask>(
+    task: &T,
+    threads: usize,
+) -> ParallelOutcome<T::Key, T::Value, T::Output> {
+    let count = task.transaction_count();
+    let memory = MvMemory::new();
+    let slots: Vec<Mutex<TxSlot<T::Key, T::Output>>> =
+        (0..count).map(|_| Mutex::default()).collect();
+    let mut stats = ParallelStats::default();
+    let mut pending: Vec<usize> = (0..count).collect();
+    let mut final_prefix = 0;
+
+    while !pending.is_empty() {
+        stats.waves += 1;
+        stats.executions += pending.len();
+        run_wave(task, &memory, &slots, &pending, threads);
+
+        pending.clear();
+        for index in final_prefix..count {
+            let valid = lock(&slots[index])
+                .reads
+                .iter()
+                .all(|(key, origin)| memory.origin(key, index) == *origin);
+            if !valid {
+                pending.push(index);
+            } else if pending.is_empty() {
+                final_prefix = index + 1;
+            }
+        }
+    }
+
+    let outputs = slots
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .output
+                .expect("every transaction executed at least once")
+        })
+        .collect();
+    ParallelOutcome { outputs, writes: memory.into_writes(), stats }
+}
+
+fn run_wave<T: ParallelTask>(
+    task: &T,
+    memory: &MvMemory<T::Key, T::Value>,
+    slots: &[Mutex<TxSlot<T::Key, T::Output>>],
+    pending: &[usize],
+    threads: usize,
+) {
+    let next = AtomicUsize::new(0);
+    let work = || loop {
+        let Some(&index) = pending.get(next.fetch_add(1, Ordering::Relaxed)) else {
+            break;
+        };
+        let mut view = MvView { task, memory, index, reads: Vec::new() };
+        let execution = task.execute(index, &mut view);
+
+        let mut slot = lock(&slots[index]);
+        slot.incarnation += 1;
+        memory.record(index, slot.incarnation, &slot.written, &execution.writes);
+        slot.written = execution.writes.into_iter().map(|(key, _)| key).collect();
+        slot.reads = view.reads;
+        slot.output = Some(execution.output);
+    };
+
+    let workers = threads.clamp(1, pending.len());
+    if workers == 1 {
+        work();
+        return;
+    }
+    std::thread::scope(|scope| {
+        for _ in 1..workers {
+            scope.spawn(work);
+        }
+        work();
+    });
+}
+
+struct SequentialView<'a, T: ParallelTask> {
+    task: &'a T,
+    writes: &'a HashMap<T::Key, T::Value>,
+}
+
+impl<T: ParallelTask> StateView<T::Key, T::Value> for SequentialView<'_, T> {
+    fn read(&mut self, key: &T::Key) -> T::Value {
+        self.writes.get(key).cloned().unwrap_or_else(|| self.task.base(key))
+    }
+}
+
+/// Executes the batch one transaction at a time. Reference for [`execute_parallel`].
+pub fn execute_sequential<T: ParallelTask>(
+    task: &T,
+) -> ParallelOutcome<T::Key, T::Value, T::Output> {
+    let count = task.transaction_count();
+    let mut writes = HashMap::new();
+    let mut outputs = Vec::with_capacity(count);
+    for index in 0..count {
+        let execution = task.execute(index, &mut SequentialView { task, writes: &writes });
+        writes.extend(execution.writes);
+        outputs.push(execution.output);
+    }
+    ParallelOutcome { outputs, writes, stats: ParallelStats { executions: count, waves: count } }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workers_are_shared_between_builds() {
+        let workers = LoadParallelWorkers::new(4);
+        let first = workers.acquire(8);
+        assert_eq!(first.threads(), 4);
+        // A concurrent build runs on its own thread only until the first one is done.
+        assert_eq!(workers.acquire(8).threads(), 1);
+        drop(first);
+        assert_eq!(workers.acquire(2).threads(), 3);
+        assert_eq!(workers.acquire(8).threads(), 4);
+    }
+}
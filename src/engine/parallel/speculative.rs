@@ -0,0 +1,742 @@
+//! Parallel execution of the pool transactions the block builder is about to include.
+//!
+//! [`ParallelTransactions`] wraps the pool's best-transactions iterator. Whenever its buffer runs
+//! dry it pulls the next batch, executes it with [`execute_parallel`] on top of the writes of
+//! earlier batches, and then hands the transactions to the builder one by one in pool order. The
+//! results go to [`SpeculativeResults`], where the builder takes each one back when it reaches its
+//! transaction and commits it if it still applies. Invalidations and the blob cut-off reported by
+//! the builder are applied to the buffer exactly as the pool iterator would apply them, so the
+//! builder sees the same sequence as without the wrapper.
+//!
+//! Batches stop at the block gas limit: transactions whose gas limit does not fit what is left
+//! after earlier batches are passed through without being executed. Batch workers come from the
+//! [`LoadParallelWorkers`] shared by all builds.
+//!
+//! The speculative state is a close approximation of the builder's, not a copy: system calls,
+//! forced transactions and bundles are not replayed, and transactions the builder later skips
+//! still count. Every result is therefore checked against the builder's state before it is
+//! committed. Fee-recipient credits are left out of the read/write sets so every transaction does
+//! not conflict with every other one; the credit is re-applied to the builder's fee-recipient
+//! balance on commit, which is only sound when nothing else observed that balance. Results of
+//! transactions that run contract code, send from or to the fee recipient, or carry an
+//! authorization list are therefore never committed, and the builder executes them itself.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Instant,
+};
+
+use alloy_consensus::{transaction::Recovered, Transaction};
+use alloy_primitives::{Address, TxHash, TxKind, B256, KECCAK256_EMPTY, U256};
+use reth::revm::{
+    context_interface::result::ResultAndState,
+    state::{AccountInfo, Bytecode},
+    Database, DatabaseRef,
+};
+use reth_ethereum_primitives::TransactionSigned;
+use reth_evm::{ConfigureEvm, Evm, EvmEnvFor, HaltReasonFor};
+use reth_transaction_pool::{
+    error::InvalidPoolTransactionError, BestTransactions, PoolTransaction, ValidPoolTransaction,
+};
+
+use super::{
+    execute_parallel, state::SharedStateCache, LoadParallelConfig, LoadParallelWorkers,
+    ParallelTask, StateView, TaskExecution,
+};
+use crate::{metrics::LoadParallelMetrics, primitives::LoadPrimitives};
+
+/// State location read or written by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EvmStateKey {
+    Account(Address),
+    Storage(Address, U256),
+}
+
+/// Value stored at an [`EvmStateKey`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvmStateValue {
+    Account(Option<AccountInfo>),
+    Storage(U256),
+}
+
+impl EvmStateValue {
+    /// Whether an execution that read `self` would read the same through `other`. Account code is
+    /// compared by hash, since it may or may not be loaded alongside the account.
+    fn reads_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Account(Some(a)), Self::Account(Some(b))) => {
+                a.balance == b.balance && a.nonce == b.nonce && a.code_hash == b.code_hash
+            }
+            (Self::Account(None), Self::Account(None)) => true,
+            (Self::Storage(a), Self::Storage(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// A transaction executed by a batch, with what is needed to commit it without executing it
+/// again.
+#[derive(Debug, PartialEq)]
+pub struct SpeculativeResult<H> {
+    /// Execution result and state changes.
+    pub result: ResultAndState<H>,
+    /// Every account and storage value the execution read, fee recipient excluded.
+    reads: Vec<(EvmStateKey, EvmStateValue)>,
+    /// Amount credited to the fee recipient.
+    fee: U256,
+    /// Whether the fee credit is the only way the execution touched the fee recipient.
+    committable: bool,
+}
+
+impl<H> SpeculativeResult<H> {
+    /// Gas used by the transaction.
+    pub fn gas_used(&self) -> u64 {
+        self.result.result.gas_used()
+    }
+
+    /// Returns the result to commit on top of `db`, or `None` if the transaction has to be
+    /// executed again because `db` no longer holds what the execution read.
+    ///
+    /// The fee-recipient account is rebased onto its balance in `db`.
+    pub fn into_committable<DB: Database>(
+        self,
+        db: &mut DB,
+        beneficiary: Address,
+    ) -> Option<ResultAndState<H>> {
+        if !self.committable {
+            return None;
+        }
+        for (key, value) in &self.reads {
+            let current = match key {
+                EvmStateKey::Account(address) => EvmStateValue::Account(db.basic(*address).ok()?),
+                EvmStateKey::Storage(address, index) => {
+                    EvmStateValue::Storage(db.storage(*address, *index).ok()?)
+                }
+            };
+            if !current.reads_as(value) {
+                return None;
+            }
+        }
+        let mut result = self.result;
+        if let Some(account) = result.state.get_mut(&beneficiary) {
+            let current = db.basic(beneficiary).ok()?.unwrap_or_default();
+            account.info = AccountInfo { balance: current.balance + self.fee, ..current };
+        }
+        Some(result)
+    }
+}
+
+/// Results of the batches executed so far, shared between [`ParallelTransactions`] and the block
+/// builder.
+#[derive(Debug)]
+pub struct SpeculativeResults<H> {
+    results: Mutex<HashMap<TxHash, SpeculativeResult<H>>>,
+    beneficiary: Address,
+    metrics: LoadParallelMetrics,
+}
+
+impl<H> SpeculativeResults<H> {
+    pub fn new(beneficiary: Address) -> Self {
+        Self { results: Mutex::default(), beneficiary, metrics: LoadParallelMetrics::default() }
+    }
+
+    /// Takes the batch result of `hash` if it can be committed on top of `db`.
+    ///
+    /// `None` means the transaction was not executed by a batch or its result no longer applies;
+    /// either way the builder executes it itself.
+    pub fn take_committable<DB: Database>(
+        &self,
+        hash: &TxHash,
+        db: &mut DB,
+    ) -> Option<ResultAndState<H>> {
+        let speculative = self.lock().remove(hash)?;
+        let committable = speculative.into_committable(db, self.beneficiary);
+        self.metrics.record_commit(committable.is_some());
+        committable
+    }
+
+    fn insert(&self, results: impl IntoIterator<Item = (TxHash, SpeculativeResult<H>)>) {
+        self.lock().extend(results);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<TxHash, SpeculativeResult<H>>> {
+        self.results.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A batch of recovered transactions executed against the parent state plus earlier writes.
+pub struct EvmBatch<'a, EvmConfig: ConfigureEvm, DB> {
+    evm_config: &'a EvmConfig,
+    evm_env: &'a EvmEnvFor<EvmConfig>,
+    state: &'a SharedStateCache<DB>,
+    /// Writes of earlier batches.
+    overlay: &'a HashMap<EvmStateKey, EvmStateValue>,
+    beneficiary: Address,
+    transactions: &'a [Recovered<TransactionSigned>],
+}
+
+impl<'a, EvmConfig: ConfigureEvm, DB> EvmBatch<'a, EvmConfig, DB> {
+    pub const fn new(
+        evm_config: &'a EvmConfig,
+        evm_env: &'a EvmEnvFor<EvmConfig>,
+        state: &'a SharedStateCache<DB>,
+        overlay: &'a HashMap<EvmStateKey, EvmStateValue>,
+        beneficiary: Address,
+        transactions: &'a [Recovered<TransactionSigned>],
+    ) -> Self {
+        Self { evm_config, evm_env, state, overlay, beneficiary, transactions }
+    }
+}
+
+impl<EvmConfig: ConfigureEvm, DB> fmt::Debug for EvmBatch<'_, EvmConfig, DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvmBatch")
+            .field("transactions", &self.transactions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<EvmConfig, DB> EvmBatch<'_, EvmConfig, DB>
+where
+    EvmConfig: ConfigureEvm,
+    DB: DatabaseRef,
+{
+    /// Parent-state account. Database errors read as a missing account; the builder surfaces
+    /// them when it checks the result against its own state.
+    fn parent_account(&self, address: Address) -> Option<AccountInfo> {
+        self.state.basic_ref(address).ok().flatten()
+    }
+}
+
+impl<EvmConfig, DB> ParallelTask for EvmBatch<'_, EvmConfig, DB>
+where
+    EvmConfig: ConfigureEvm<Primitives = LoadPrimitives>,
+    DB: DatabaseRef + Sync,
+{
+    type Key = EvmStateKey;
+    type Value = EvmStateValue;
+    /// `None` if the transaction was invalid against the speculative state.
+    type Output = Option<SpeculativeResult<HaltReasonFor<EvmConfig>>>;
+
+    fn transaction_count(&self) -> usize {
+        self.transactions.len()
+    }
+
+    fn base(&self, key: &EvmStateKey) -> EvmStateValue {
+        if let Some(value) = self.overlay.get(key) {
+            return value.clone();
+        }
+        match key {
+            EvmStateKey::Account(address) => EvmStateValue::Account(self.parent_account(*address)),
+            EvmStateKey::Storage(address, index) => {
+                EvmStateValue::Storage(self.state.storage_ref(*address, *index).unwrap_or_default())
+            }
+        }
+    }
+
+    fn execute(
+        &self,
+        index: usize,
+        view: &mut dyn StateView<EvmStateKey, EvmStateValue>,
+    ) -> TaskExecution<EvmStateKey, EvmStateValue, Self::Output> {
+        let tx = &self.transactions[index];
+        let db = ViewDb { batch: self, view, reads: Vec::new() };
+        let mut evm = self.evm_config.evm_with_env(db, self.evm_env.clone());
+        let result = evm.transact(self.evm_config.tx_env(tx.as_recovered_ref()));
+        let reads = std::mem::take(&mut evm.db_mut().reads);
+        let Ok(result) = result else {
+            return TaskExecution { writes: Vec::new(), output: None };
+        };
+
+        let mut writes = Vec::new();
+        for (address, account) in &result.state {
+            if !account.is_touched() {
+                continue;
+            }
+            if *address != self.beneficiary {
+                let info = (!account.is_selfdestructed()).then(|| account.info.clone());
+                writes.push((EvmStateKey::Account(*address), EvmStateValue::Account(info)));
+            }
+            for (slot, value) in &account.storage {
+                if value.is_changed() {
+                    writes.push((
+                        EvmStateKey::Storage(*address, *slot),
+                        EvmStateValue::Storage(value.present_value()),
+                    ));
+                }
+            }
+        }
+
+        let parent_balance =
+            self.parent_account(self.beneficiary).map_or(U256::ZERO, |info| info.balance);
+        let fee = result
+            .state
+            .get(&self.beneficiary)
+            .map_or(U256::ZERO, |account| account.info.balance.saturating_sub(parent_balance));
+        // Code could read the fee-recipient balance, which the batch does not track.
+        let runs_code = match tx.kind() {
+            TxKind::Create => true,
+            TxKind::Call(to) => result
+                .state
+                .get(&to)
+                .is_none_or(|account| account.info.code_hash != KECCAK256_EMPTY),
+        };
+        let committable = !runs_code &&
+            tx.signer() != self.beneficiary &&
+            tx.to() != Some(self.beneficiary) &&
+            tx.authorization_list().is_none();
+
+        TaskExecution {
+            writes,
+            output: Some(SpeculativeResult { result, reads, fee, committable }),
+        }
+    }
+}
+
+/// EVM database reading through the multi-version view and recording what it read.
+struct ViewDb<'v, 'a, EvmConfig: ConfigureEvm, DB> {
+    batch: &'v EvmBatch<'a, EvmConfig, DB>,
+    view: &'v mut dyn StateView<EvmStateKey, EvmStateValue>,
+    reads: Vec<(EvmStateKey, EvmStateValue)>,
+}
+
+impl<EvmConfig, DB> ViewDb<'_, '_, EvmConfig, DB>
+where
+    EvmConfig: ConfigureEvm,
+    DB: DatabaseRef,
+{
+    fn read(&mut self, key: EvmStateKey) -> EvmStateValue {
+        let value = self.view.read(&key);
+        self.reads.push((key, value.clone()));
+        value
+    }
+}
+
+impl<EvmConfig, DB> Database for ViewDb<'_, '_, EvmConfig, DB>
+where
+    EvmConfig: ConfigureEvm,
+    DB: DatabaseRef,
+{
+    type Error = Infallible;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        // Every transaction credits the fee recipient; tracking that read would serialize the
+        // whole batch.
+        if address == self.batch.beneficiary {
+            return Ok(self.batch.parent_account(address));
+        }
+        let EvmStateValue::Account(info) = self.read(EvmStateKey::Account(address)) else {
+            unreachable!("account keys hold account values")
+        };
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self.batch.state.code_by_hash_ref(code_hash).unwrap_or_default())
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let EvmStateValue::Storage(value) = self.read(EvmStateKey::Storage(address, index)) else {
+            unreachable!("storage keys hold storage values")
+        };
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        Ok(self.batch.state.block_hash_ref(number).unwrap_or_default())
+    }
+}
+
+type PoolItem<T> = Arc<ValidPoolTransaction<T>>;
+
+/// Best-transactions iterator that executes batches in parallel before yielding them.
+pub struct ParallelTransactions<'a, EvmConfig: ConfigureEvm, DB, T: PoolTransaction> {
+    inner: Box<dyn BestTransactions<Item = PoolItem<T>> + 'a>,
+    evm_config: &'a EvmConfig,
+    evm_env: EvmEnvFor<EvmConfig>,
+    state: &'a SharedStateCache<DB>,
+    results: &'a SpeculativeResults<HaltReasonFor<EvmConfig>>,
+    config: LoadParallelConfig,
+    workers: &'a LoadParallelWorkers,
+    /// Block gas left for pool transactions when the wrapper was created.
+    gas_limit: u64,
+    /// Gas used by the batches executed so far.
+    gas_used: u64,
+    /// Final writes of every batch executed so far.
+    overlay: HashMap<EvmStateKey, EvmStateValue>,
+    buffered: VecDeque<PoolItem<T>>,
+    skip_blobs: bool,
+    metrics: LoadParallelMetrics,
+}
+
+impl<'a, EvmConfig, DB, T> ParallelTransactions<'a, EvmConfig, DB, T>
+where
+    EvmConfig: ConfigureEvm<Primitives = LoadPrimitives>,
+    DB: DatabaseRef + Sync,
+    T: PoolTransaction<Consensus = TransactionSigned>,
+{
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        inner: Box<dyn BestTransactions<Item = PoolItem<T>> + 'a>,
+        evm_config: &'a EvmConfig,
+        evm_env: EvmEnvFor<EvmConfig>,
+        state: &'a SharedStateCache<DB>,
+        results: &'a SpeculativeResults<HaltReasonFor<EvmConfig>>,
+        config: LoadParallelConfig,
+        workers: &'a LoadParallelWorkers,
+        gas_limit: u64,
+    ) -> Self {
+        Self {
+            inner,
+            evm_config,
+            evm_env,
+            state,
+            results,
+            config,
+            workers,
+            gas_limit,
+            gas_used: 0,
+            overlay: HashMap::new(),
+            buffered: VecDeque::new(),
+            skip_blobs: false,
+            metrics: LoadParallelMetrics::default(),
+        }
+    }
+
+    /// Pulls the next batch and executes the transactions that fit the remaining block gas.
+    fn fill(&mut self) {
+        let mut remaining = self.gas_limit.saturating_sub(self.gas_used);
+        let mut transactions = Vec::new();
+        while self.buffered.len() < self.config.batch_size {
+            let Some(tx) = self.inner.next() else { break };
+            // The builder cannot include what does not fit, so there is nothing to execute.
+            if tx.gas_limit() <= remaining {
+                remaining -= tx.gas_limit();
+                transactions.push(tx.to_consensus());
+            }
+            self.buffered.push_back(tx);
+        }
+        if transactions.is_empty() {
+            return;
+        }
+
+        let start = Instant::now();
+        let task = EvmBatch::new(
+            self.evm_config,
+            &self.evm_env,
+            self.state,
+            &self.overlay,
+            self.results.beneficiary,
+            &transactions,
+        );
+        let permit = self.workers.acquire(self.config.threads.saturating_sub(1));
+        let outcome = execute_parallel(&task, permit.threads());
+        drop(permit);
+        self.metrics.record_batch(transactions.len(), outcome.stats, start.elapsed());
+
+        self.gas_used +=
+            outcome.outputs.iter().flatten().map(SpeculativeResult::gas_used).sum::<u64>();
+        self.overlay.extend(outcome.writes);
+        self.results.insert(
+            transactions
+                .iter()
+                .zip(outcome.outputs)
+                .filter_map(|(tx, output)| output.map(|output| (*tx.tx_hash(), output))),
+        );
+    }
+}
+
+impl<EvmConfig, DB, T> fmt::Debug for ParallelTransactions<'_, EvmConfig, DB, T>
+where
+    EvmConfig: ConfigureEvm,
+    T: PoolTransaction,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelTransactions")
+            .field("config", &self.config)
+            .field("gas_used", &self.gas_used)
+            .field("buffered", &self.buffered.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<EvmConfig, DB, T> Iterator for ParallelTransactions<'_, EvmConfig, DB, T>
+where
+    EvmConfig: ConfigureEvm<Primitives = LoadPrimitives>,
+    DB: DatabaseRef + Sync,
+    T: PoolTransaction<Consensus = TransactionSigned>,
+{
+    type Item = PoolItem<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.buffered.is_empty() {
+                self.fill();
+            }
+            let tx = self.buffered.pop_front()?;
+            if self.skip_blobs && tx.is_eip4844() {
+                continue;
+            }
+            return Some(tx);
+        }
+    }
+}
+
+impl<EvmConfig, DB, T> BestTransactions for ParallelTransactions<'_, EvmConfig, DB, T>
+where
+    EvmConfig: ConfigureEvm<Primitives = LoadPrimitives>,
+    DB: DatabaseRef + Sync,
+    T: PoolTransaction<Consensus = TransactionSigned>,
+{
+    fn mark_invalid(&mut self, transaction: &Self::Item, kind: &InvalidPoolTransactionError) {
+        // The pool drops every later transaction of an invalid sender; do the same for the ones
+        // already pulled into the buffer.
+        let sender = transaction.sender();
+        self.buffered.retain(|tx| tx.sender() != sender);
+        self.inner.mark_invalid(transaction, kind);
+    }
+
+    fn no_updates(&mut self) {
+        self.inner.no_updates();
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.skip_blobs = skip_blobs;
+        self.inner.set_skip_blobs(skip_blobs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Instant};
+
+    use alloy_consensus::{Header, SignableTransaction, TxEip1559};
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::{Signature, TxKind};
+    use reth::revm::{
+        context_interface::result::ExecutionResult,
+        database_interface::WrapDatabaseRef,
+        db::{CacheDB, EmptyDB},
+        DatabaseCommit, State,
+    };
+    use reth_transaction_pool::{
+        identifier::{SenderId, TransactionId},
+        EthPooledTransaction, TransactionOrigin,
+    };
+
+    use super::*;
+    use crate::{
+        chainspec::LoadChainSpec,
+        engine::parallel::{execute_sequential, state::SharedStateCache},
+        evm::LoadEvmConfig,
+    };
+
+    const TRANSFER_GAS: u64 = 21_000;
+    const SENDERS: u64 = 48;
+    /// Room for fewer transfers than the pool holds, so the gas limit cuts the block short.
+    const BLOCK_GAS_LIMIT: u64 = 40 * TRANSFER_GAS;
+
+    type PoolTx = PoolItem<EthPooledTransaction>;
+
+    fn address(n: u64) -> Address {
+        Address::with_last_byte(n as u8 + 1)
+    }
+
+    /// The fee recipient is also the busiest recipient, so its transfers are executed again.
+    fn beneficiary() -> Address {
+        address(SENDERS)
+    }
+
+    fn evm() -> (LoadEvmConfig, EvmEnvFor<LoadEvmConfig>) {
+        let evm_config = LoadEvmConfig::new(Arc::new(LoadChainSpec::default()));
+        let header = Header {
+            beneficiary: beneficiary(),
+            gas_limit: BLOCK_GAS_LIMIT,
+            base_fee_per_gas: Some(1),
+            timestamp: 1,
+            ..Default::default()
+        };
+        let evm_env = evm_config.evm_env(&header).expect("evm env");
+        (evm_config, evm_env)
+    }
+
+    /// Funded senders, each with one or two transfers to a handful of shared recipients, plus a
+    /// transfer with a nonce gap that fails.
+    fn pool(chain_id: u64) -> (CacheDB<EmptyDB>, Vec<PoolTx>) {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let mut transactions = Vec::new();
+        let mut push = |sender: u64, nonce: u64, recipient: u64| {
+            let tx = TxEip1559 {
+                chain_id,
+                nonce,
+                gas_limit: TRANSFER_GAS,
+                max_fee_per_gas: 10,
+                max_priority_fee_per_gas: 1,
+                to: TxKind::Call(address(recipient)),
+                value: U256::from(1_000),
+                ..Default::default()
+            };
+            let signed: TransactionSigned = tx.into_signed(Signature::test_signature()).into();
+            let recovered = Recovered::new_unchecked(signed, address(sender));
+            let length = recovered.encode_2718_len();
+            transactions.push(Arc::new(ValidPoolTransaction {
+                transaction: EthPooledTransaction::new(recovered, length),
+                transaction_id: TransactionId::new(SenderId::from(sender), nonce),
+                propagate: false,
+                timestamp: Instant::now(),
+                origin: TransactionOrigin::Local,
+                authority_ids: None,
+            }));
+        };
+        for sender in 0..SENDERS {
+            push(sender, 0, SENDERS + sender % 4);
+            if sender % 3 == 0 {
+                // Sends on to another sender, whose balance the next transactions read.
+                push(sender, 1, (sender + 1) % SENDERS);
+            }
+        }
+        push(SENDERS - 1, 7, SENDERS);
+        for sender in 0..SENDERS {
+            db.insert_account_info(
+                address(sender),
+                AccountInfo { balance: U256::from(1_000_000_000u64), ..Default::default() },
+            );
+        }
+        (db, transactions)
+    }
+
+    /// Pool order iterator with the pool's invalidation rule.
+    struct Pending(VecDeque<PoolTx>);
+
+    impl Iterator for Pending {
+        type Item = PoolTx;
+
+        fn next(&mut self) -> Option<PoolTx> {
+            self.0.pop_front()
+        }
+    }
+
+    impl BestTransactions for Pending {
+        fn mark_invalid(&mut self, transaction: &PoolTx, _kind: &InvalidPoolTransactionError) {
+            let sender = transaction.sender();
+            self.0.retain(|tx| tx.sender() != sender);
+        }
+
+        fn no_updates(&mut self) {}
+
+        fn set_skip_blobs(&mut self, _skip_blobs: bool) {}
+    }
+
+    /// What a build produced: the included transactions with their results, and the final state
+    /// of every account involved.
+    type Block = (Vec<(B256, ExecutionResult)>, Vec<Option<AccountInfo>>);
+
+    /// Fills a block from `best` the way the payload builder does, committing the batch results
+    /// in `results` that still apply. Also returns how many were committed.
+    fn build<DB: Database>(
+        evm_config: &LoadEvmConfig,
+        evm_env: &EvmEnvFor<LoadEvmConfig>,
+        db: DB,
+        best: &mut dyn BestTransactions<Item = PoolTx>,
+        results: Option<&SpeculativeResults<HaltReasonFor<LoadEvmConfig>>>,
+    ) -> (Block, usize) {
+        let mut state = State::builder().with_database(db).build();
+        let mut included = Vec::new();
+        let mut committed = 0;
+        {
+            let mut evm = evm_config.evm_with_env(&mut state, evm_env.clone());
+            let mut cumulative_gas_used = 0;
+            while let Some(tx) = best.next() {
+                if cumulative_gas_used + tx.gas_limit() > BLOCK_GAS_LIMIT {
+                    let kind = InvalidPoolTransactionError::ExceedsGasLimit(
+                        tx.gas_limit(),
+                        BLOCK_GAS_LIMIT,
+                    );
+                    best.mark_invalid(&tx, &kind);
+                    continue;
+                }
+                if let Some(output) =
+                    results.and_then(|results| results.take_committable(tx.hash(), evm.db_mut()))
+                {
+                    evm.db_mut().commit(output.state);
+                    cumulative_gas_used += output.result.gas_used();
+                    included.push((*tx.hash(), output.result));
+                    committed += 1;
+                    continue;
+                }
+                let consensus = tx.to_consensus();
+                match evm.transact_commit(evm_config.tx_env(consensus.as_recovered_ref())) {
+                    Ok(result) => {
+                        cumulative_gas_used += result.gas_used();
+                        included.push((*tx.hash(), result));
+                    }
+                    Err(_) => best.mark_invalid(&tx, &InvalidPoolTransactionError::Underpriced),
+                }
+            }
+        }
+        let accounts = (0..SENDERS + 4).map(|n| state.basic(address(n)).ok().flatten()).collect();
+        ((included, accounts), committed)
+    }
+
+    #[test]
+    fn committed_results_match_sequential_building() {
+        let (evm_config, evm_env) = evm();
+        let (db, transactions) = pool(evm_env.cfg_env.chain_id);
+
+        let mut sequential = Pending(transactions.iter().cloned().collect());
+        let (expected, _) = build(&evm_config, &evm_env, db.clone(), &mut sequential, None);
+        assert!(expected.0.len() < transactions.len(), "gas limit cuts the block short");
+
+        for threads in [2, 4, 8] {
+            let shared = SharedStateCache::new(db.clone());
+            let workers = LoadParallelWorkers::new(threads);
+            let results = SpeculativeResults::new(beneficiary());
+            let mut parallel = ParallelTransactions::new(
+                Box::new(Pending(transactions.iter().cloned().collect())),
+                &evm_config,
+                evm_env.clone(),
+                &shared,
+                &results,
+                LoadParallelConfig::new(threads).with_batch_size(8),
+                &workers,
+                BLOCK_GAS_LIMIT,
+            );
+            let (block, committed) = build(
+                &evm_config,
+                &evm_env,
+                WrapDatabaseRef(&shared),
+                &mut parallel,
+                Some(&results),
+            );
+            assert_eq!(block, expected, "{threads} threads");
+            assert!(committed > 0, "{threads} threads: no batch result committed");
+            assert!(
+                committed < block.0.len(),
+                "{threads} threads: transfers to the fee recipient were committed"
+            );
+            assert!(parallel.gas_used <= BLOCK_GAS_LIMIT, "executed past the gas limit");
+        }
+    }
+
+    #[test]
+    fn evm_batches_match_sequential_execution() {
+        let (evm_config, evm_env) = evm();
+        let (db, transactions) = pool(evm_env.cfg_env.chain_id);
+        let transactions: Vec<_> = transactions.iter().map(|tx| tx.to_consensus()).collect();
+        let shared = SharedStateCache::new(db);
+        let overlay = HashMap::new();
+        let task =
+            EvmBatch::new(&evm_config, &evm_env, &shared, &overlay, beneficiary(), &transactions);
+
+        let sequential = execute_sequential(&task);
+        assert_eq!(sequential.outputs.iter().filter(|output| output.is_none()).count(), 1);
+        for threads in [1, 2, 4, 8] {
+            let parallel = execute_parallel(&task, threads);
+            assert_eq!(parallel.outputs, sequential.outputs, "{threads} threads");
+            assert_eq!(parallel.writes, sequential.writes, "{threads} threads");
+        }
+    }
+}
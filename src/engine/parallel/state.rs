@@ -0,0 +1,135 @@
+//! Read-through state cache shared by speculative workers and the block builder.
+
+use std::{
+    collections::HashMap,
+    sync::{PoisonError, RwLock},
+};
+
+use alloy_primitives::{Address, B256, U256};
+use reth::revm::{
+    state::{AccountInfo, Bytecode},
+    DatabaseRef,
+};
+
+/// Caches every read from `DB` so state loaded by one reader is in memory for all others.
+///
+/// Only reads of the parent state go through here; it never sees writes, so entries stay valid
+/// for the whole payload build.
+#[derive(Debug)]
+pub struct SharedStateCache<DB> {
+    db: DB,
+    accounts: RwLock<HashMap<Address, Option<AccountInfo>>>,
+    storage: RwLock<HashMap<(Address, U256), U256>>,
+    contracts: RwLock<HashMap<B256, Bytecode>>,
+    block_hashes: RwLock<HashMap<u64, B256>>,
+}
+
+impl<DB> SharedStateCache<DB> {
+    pub fn new(db: DB) -> Self {
+        Self {
+            db,
+            accounts: Default::default(),
+            storage: Default::default(),
+            contracts: Default::default(),
+            block_hashes: Default::default(),
+        }
+    }
+
+    /// Number of cached accounts and storage slots.
+    pub fn len(&self) -> usize {
+        self.accounts.read().unwrap_or_else(PoisonError::into_inner).len() +
+            self.storage.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn cached<K, V, E>(
+    cache: &RwLock<HashMap<K, V>>,
+    key: K,
+    load: impl FnOnce(&K) -> Result<V, E>,
+) -> Result<V, E>
+where
+    K: Eq + std::hash::Hash,
+    V: Clone,
+{
+    if let Some(value) = cache.read().unwrap_or_else(PoisonError::into_inner).get(&key) {
+        return Ok(value.clone());
+    }
+    let value = load(&key)?;
+    cache.write().unwrap_or_else(PoisonError::into_inner).insert(key, value.clone());
+    Ok(value)
+}
+
+impl<DB: DatabaseRef> DatabaseRef for SharedStateCache<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        cached(&self.accounts, address, |address| self.db.basic_ref(*address))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        cached(&self.contracts, code_hash, |code_hash| self.db.code_by_hash_ref(*code_hash))
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        cached(&self.storage, (address, index), |(address, index)| {
+            self.db.storage_ref(*address, *index)
+        })
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        cached(&self.block_hashes, number, |number| self.db.block_hash_ref(*number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use reth::revm::db::EmptyDB;
+
+    use super::*;
+
+    /// Counts reads reaching the underlying database.
+    #[derive(Default)]
+    struct CountingDb {
+        reads: AtomicUsize,
+        inner: EmptyDB,
+    }
+
+    impl DatabaseRef for CountingDb {
+        type Error = <EmptyDB as DatabaseRef>::Error;
+
+        fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            self.inner.basic_ref(address)
+        }
+
+        fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            self.inner.code_by_hash_ref(code_hash)
+        }
+
+        fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            self.inner.storage_ref(address, index)
+        }
+
+        fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+            self.inner.block_hash_ref(number)
+        }
+    }
+
+    #[test]
+    fn serves_repeated_reads_from_memory() {
+        let cache = SharedStateCache::new(CountingDb::default());
+        for _ in 0..3 {
+            cache.basic_ref(Address::ZERO).unwrap();
+            cache.storage_ref(Address::ZERO, U256::from(1)).unwrap();
+        }
+        assert_eq!(cache.db.reads.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.len(), 2);
+    }
+}
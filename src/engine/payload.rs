@@ -2,33 +2,57 @@
 //!
 //! Enforces Load invariants on top of standard Ethereum payloads:
 //! - `prev_randao` must be the constant `0x01`.
-//! - Only EIP-4844 sidecars accepted (EIP-7594 rejected).
+//! - Sidecars must match the envelope's fork: EIP-4844 for V3/V4, EIP-7594 (cell proofs) for V5.
 //! - Blob count capped at 1024.
+//! - Optional CL-provided `extraData` capped at 32 bytes.
+//! - Optional CL-forced transactions must decode, recover and carry no blobs.
+//! - Optional CL-pinned `gasLimit` must stay within protocol and chain-spec bounds.
 
 use std::{fmt, sync::Arc};
 
-use alloy_eips::{eip4895::Withdrawals, eip7685::RequestsOrHash};
-use alloy_primitives::{Bytes, B256};
+use alloy_consensus::{
+    constants::MAXIMUM_EXTRA_DATA_SIZE,
+    transaction::{Recovered, SignerRecoverable},
+};
+use alloy_eips::{
+    eip2718::{Decodable2718, Encodable2718, WithEncoded},
+    eip4844::BlobTransactionSidecarVariant,
+    eip4895::Withdrawals,
+    eip7685::RequestsOrHash,
+    Typed2718,
+};
+use alloy_primitives::{keccak256, Address, Bytes, Keccak256, B256};
 use alloy_rpc_types_engine::{
-    BlobsBundleV1, CancunPayloadFields, ExecutionData, ExecutionPayload,
+    BlobsBundleV1, BlobsBundleV2, CancunPayloadFields, ExecutionData, ExecutionPayload,
     ExecutionPayloadEnvelopeV2, ExecutionPayloadEnvelopeV3, ExecutionPayloadEnvelopeV4,
     ExecutionPayloadEnvelopeV5, ExecutionPayloadInputV2, ExecutionPayloadSidecar,
-    ExecutionPayloadV1, ExecutionPayloadV3, PayloadAttributes as EthPayloadAttributes, PayloadId,
-    PraguePayloadFields,
+    ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3,
+    PayloadAttributes as EthPayloadAttributes, PayloadId, PraguePayloadFields,
 };
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_ethereum_engine_primitives::{
-    BlobSidecars, BuiltPayloadConversionError, EthBuiltPayload, EthPayloadBuilderAttributes,
+    BuiltPayloadConversionError, EthBuiltPayload, EthPayloadBuilderAttributes,
 };
+use reth_ethereum_primitives::TransactionSigned;
 use reth_evm::{ConfigureEngineEvm, EvmEnvFor, ExecutableTxIterator, ExecutionCtxFor};
 use reth_payload_primitives::{
     BuiltPayload, ExecutionPayload as ExecutionPayloadTrait, PayloadAttributesBuilder,
     PayloadBuilderAttributes,
 };
-use reth_primitives_traits::{NodePrimitives, SealedBlock, SealedHeader};
+use reth_primitives_traits::{
+    constants::{GAS_LIMIT_BOUND_DIVISOR, MINIMUM_GAS_LIMIT},
+    NodePrimitives, SealedBlock, SealedHeader,
+};
 use thiserror::Error;
 
-use crate::{chainspec::LOAD_MAX_BLOB_COUNT, LOAD_PREVRANDAO};
+use crate::{
+    chainspec::LOAD_MAX_BLOB_COUNT,
+    engine::{
+        budget::SidecarReservation,
+        fee_recipient::{FeeRecipientPolicyError, LoadFeeRecipientPolicy},
+    },
+    LOAD_PREVRANDAO,
+};
 
 /// Validate `prev_randao` for Load (must be constant 0x01).
 pub fn validate_prev_randao(prev_randao: B256) -> Result<(), String> {
@@ -46,13 +70,117 @@ pub enum LoadPayloadAttributesError {
     InvalidPrevRandao(String),
     #[error("invalid timestamp for Load: {0}")]
     InvalidTimestamp(String),
+    #[error("invalid extraData for Load: {0}")]
+    InvalidExtraData(String),
+    #[error("invalid fee recipient for Load: {0}")]
+    InvalidFeeRecipient(#[from] FeeRecipientPolicyError),
+    #[error("invalid forced transaction for Load: {0}")]
+    InvalidForcedTransaction(String),
+    #[error("invalid gasLimit for Load: {0}")]
+    InvalidGasLimit(String),
+}
+
+/// Validate that `extraData` fits in a block header (at most 32 bytes).
+pub fn validate_extra_data(extra_data: &Bytes) -> Result<(), LoadPayloadAttributesError> {
+    if extra_data.len() > MAXIMUM_EXTRA_DATA_SIZE {
+        return Err(LoadPayloadAttributesError::InvalidExtraData(format!(
+            "{} bytes exceeds the {MAXIMUM_EXTRA_DATA_SIZE}-byte limit",
+            extra_data.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a CL-requested block gas limit against the protocol minimum and the chain-spec max.
+pub fn validate_gas_limit(
+    gas_limit: u64,
+    max_gas_limit: u64,
+) -> Result<(), LoadPayloadAttributesError> {
+    if gas_limit < MINIMUM_GAS_LIMIT {
+        return Err(LoadPayloadAttributesError::InvalidGasLimit(format!(
+            "{gas_limit} is below the protocol minimum {MINIMUM_GAS_LIMIT}"
+        )));
+    }
+    if gas_limit > max_gas_limit {
+        return Err(LoadPayloadAttributesError::InvalidGasLimit(format!(
+            "{gas_limit} exceeds the chain maximum {max_gas_limit}"
+        )));
+    }
+    Ok(())
+}
+
+/// Validate that a CL-requested gas limit is reachable from the parent in one block.
+///
+/// Consensus rejects headers whose gas limit moves by `parent / 1024` or more, so an exact
+/// target outside that window cannot be honoured.
+pub fn validate_gas_limit_against_parent(
+    gas_limit: u64,
+    parent_gas_limit: u64,
+) -> Result<(), LoadPayloadAttributesError> {
+    let max_delta = parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR;
+    if gas_limit.abs_diff(parent_gas_limit) >= max_delta {
+        return Err(LoadPayloadAttributesError::InvalidGasLimit(format!(
+            "{gas_limit} is not within {max_delta} of the parent gas limit {parent_gas_limit}"
+        )));
+    }
+    Ok(())
+}
+
+/// CL-forced transaction, recovered and kept alongside its EIP-2718 encoding.
+pub type LoadForcedTransaction = WithEncoded<Recovered<TransactionSigned>>;
+
+/// Decodes and recovers CL-forced transactions.
+///
+/// Blob transactions are rejected: attributes carry no sidecars, so the built payload could not
+/// serve their blobs to the CL.
+pub fn decode_forced_transactions(
+    transactions: &[Bytes],
+) -> Result<Vec<LoadForcedTransaction>, LoadPayloadAttributesError> {
+    transactions
+        .iter()
+        .enumerate()
+        .map(|(index, encoded)| {
+            let tx = TransactionSigned::decode_2718_exact(encoded).map_err(|err| {
+                LoadPayloadAttributesError::InvalidForcedTransaction(format!(
+                    "transaction {index}: {err}"
+                ))
+            })?;
+            if tx.is_eip4844() {
+                return Err(LoadPayloadAttributesError::InvalidForcedTransaction(format!(
+                    "transaction {index}: blob transactions cannot be forced"
+                )));
+            }
+            let tx = tx.try_into_recovered().map_err(|_| {
+                LoadPayloadAttributesError::InvalidForcedTransaction(format!(
+                    "transaction {index}: invalid signature"
+                ))
+            })?;
+            Ok(WithEncoded::new(encoded.clone(), tx))
+        })
+        .collect()
 }
 
 /// Load RPC payload attributes (wrapper over Ethereum attributes).
+///
+/// Load extensions are optional so a CL that only speaks the Ethereum schema keeps working.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct LoadPayloadAttributes {
     #[serde(flatten)]
     pub inner: EthPayloadAttributes,
+    /// `extraData` for the built block; overrides the node-configured value.
+    #[serde(default, rename = "extraData", skip_serializing_if = "Option::is_none")]
+    pub extra_data: Option<Bytes>,
+    /// EIP-2718 encoded transactions executed in order before any pool transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<Vec<Bytes>>,
+    /// Exact gas limit for the built block; overrides the node-configured target.
+    #[serde(
+        default,
+        rename = "gasLimit",
+        with = "alloy_serde::quantity::opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_limit: Option<u64>,
 }
 
 impl LoadPayloadAttributes {
@@ -63,6 +191,28 @@ impl LoadPayloadAttributes {
     pub fn timestamp(&self) -> u64 {
         self.inner.timestamp
     }
+
+    pub fn suggested_fee_recipient(&self) -> alloy_primitives::Address {
+        self.inner.suggested_fee_recipient
+    }
+
+    pub fn extra_data(&self) -> Option<&Bytes> {
+        self.extra_data.as_ref()
+    }
+
+    pub fn transactions(&self) -> Option<&[Bytes]> {
+        self.transactions.as_deref()
+    }
+
+    pub fn gas_limit(&self) -> Option<u64> {
+        self.gas_limit
+    }
+}
+
+impl From<EthPayloadAttributes> for LoadPayloadAttributes {
+    fn from(inner: EthPayloadAttributes) -> Self {
+        Self { inner, extra_data: None, transactions: None, gas_limit: None }
+    }
 }
 
 impl reth::api::PayloadAttributes for LoadPayloadAttributes {
@@ -83,11 +233,62 @@ impl reth::api::PayloadAttributes for LoadPayloadAttributes {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LoadPayloadBuilderAttributes {
     inner: EthPayloadBuilderAttributes,
+    /// Payload ID derived from the Ethereum fields only.
+    upstream_id: PayloadId,
+    extra_data: Option<Bytes>,
+    transactions: Vec<LoadForcedTransaction>,
+    gas_limit: Option<u64>,
 }
 
 impl LoadPayloadBuilderAttributes {
     pub const fn new(inner: EthPayloadBuilderAttributes) -> Self {
-        Self { inner }
+        let upstream_id = inner.id;
+        Self { inner, upstream_id, extra_data: None, transactions: Vec::new(), gas_limit: None }
+    }
+
+    /// Sets the CL-provided `extraData` and re-derives the payload ID to cover it.
+    pub fn with_extra_data(mut self, extra_data: Option<Bytes>) -> Self {
+        self.extra_data = extra_data;
+        self.refresh_payload_id();
+        self
+    }
+
+    /// Sets the CL-forced transactions and re-derives the payload ID to cover them.
+    pub fn with_transactions(mut self, transactions: Vec<LoadForcedTransaction>) -> Self {
+        self.transactions = transactions;
+        self.refresh_payload_id();
+        self
+    }
+
+    /// Sets the CL-pinned gas limit and re-derives the payload ID to cover it.
+    pub fn with_gas_limit(mut self, gas_limit: Option<u64>) -> Self {
+        self.gas_limit = gas_limit;
+        self.refresh_payload_id();
+        self
+    }
+
+    /// CL-provided `extraData`, if any.
+    pub fn extra_data(&self) -> Option<&Bytes> {
+        self.extra_data.as_ref()
+    }
+
+    /// CL-pinned block gas limit, if any.
+    pub const fn gas_limit(&self) -> Option<u64> {
+        self.gas_limit
+    }
+
+    /// CL-forced transactions, in execution order.
+    pub fn transactions(&self) -> &[LoadForcedTransaction] {
+        &self.transactions
+    }
+
+    fn refresh_payload_id(&mut self) {
+        self.inner.id = load_payload_id(
+            self.upstream_id,
+            self.extra_data.as_ref(),
+            &self.transactions,
+            self.gas_limit,
+        );
     }
 
     pub fn into_inner(self) -> EthPayloadBuilderAttributes {
@@ -95,6 +296,43 @@ impl LoadPayloadBuilderAttributes {
     }
 }
 
+/// Derives the payload ID for Load attributes.
+///
+/// The upstream ID only covers the Ethereum attribute fields. Load extensions are hashed on top
+/// of it so FCUs that differ only in Load fields start distinct jobs, while attributes without
+/// extensions keep the upstream ID. Each extension is tagged and length-prefixed so no two
+/// combinations hash the same input.
+fn load_payload_id(
+    upstream: PayloadId,
+    extra_data: Option<&Bytes>,
+    transactions: &[LoadForcedTransaction],
+    gas_limit: Option<u64>,
+) -> PayloadId {
+    if extra_data.is_none() && transactions.is_empty() && gas_limit.is_none() {
+        return upstream;
+    }
+
+    let mut hasher = Keccak256::new();
+    hasher.update(upstream.0);
+    if let Some(extra_data) = extra_data {
+        hasher.update([0x01, extra_data.len() as u8]);
+        hasher.update(extra_data);
+    }
+    if !transactions.is_empty() {
+        hasher.update([0x02]);
+        hasher.update((transactions.len() as u64).to_be_bytes());
+        for tx in transactions {
+            hasher.update(keccak256(tx.encoded_bytes()));
+        }
+    }
+    if let Some(gas_limit) = gas_limit {
+        hasher.update([0x03]);
+        hasher.update(gas_limit.to_be_bytes());
+    }
+    let out = hasher.finalize();
+    PayloadId::new(out[..8].try_into().expect("keccak output is 32 bytes"))
+}
+
 impl reth_payload_primitives::PayloadBuilderAttributes for LoadPayloadBuilderAttributes {
     type RpcPayloadAttributes = LoadPayloadAttributes;
     type Error = LoadPayloadAttributesError;
@@ -104,14 +342,22 @@ impl reth_payload_primitives::PayloadBuilderAttributes for LoadPayloadBuilderAtt
         rpc_payload_attributes: LoadPayloadAttributes,
         version: u8,
     ) -> Result<Self, Self::Error> {
-        let inner =
-            EthPayloadBuilderAttributes::try_new(parent, rpc_payload_attributes.inner, version)
-                .expect("EthPayloadBuilderAttributes::try_new is infallible");
+        let LoadPayloadAttributes { inner, extra_data, transactions, gas_limit } =
+            rpc_payload_attributes;
+        let inner = EthPayloadBuilderAttributes::try_new(parent, inner, version)
+            .expect("EthPayloadBuilderAttributes::try_new is infallible");
 
         validate_prev_randao(inner.prev_randao())
             .map_err(LoadPayloadAttributesError::InvalidPrevRandao)?;
+        if let Some(extra_data) = &extra_data {
+            validate_extra_data(extra_data)?;
+        }
+        let transactions = decode_forced_transactions(transactions.as_deref().unwrap_or_default())?;
 
-        Ok(Self::new(inner))
+        Ok(Self::new(inner)
+            .with_extra_data(extra_data)
+            .with_transactions(transactions)
+            .with_gas_limit(gas_limit))
     }
 
     fn payload_id(&self) -> PayloadId {
@@ -143,13 +389,15 @@ impl reth_payload_primitives::PayloadBuilderAttributes for LoadPayloadBuilderAtt
     }
 }
 
-/// Validate Load payload attributes against parent header fields.
+/// Validate Load payload attributes against parent header fields and the fee-recipient policy.
 pub fn validate_payload_attributes(
     parent_timestamp: u64,
     attrs: &LoadPayloadBuilderAttributes,
+    fee_recipient_policy: &LoadFeeRecipientPolicy,
 ) -> Result<(), LoadPayloadAttributesError> {
     validate_prev_randao(attrs.prev_randao())
         .map_err(LoadPayloadAttributesError::InvalidPrevRandao)?;
+    fee_recipient_policy.check(attrs.suggested_fee_recipient())?;
 
     if attrs.timestamp() <= parent_timestamp {
         return Err(LoadPayloadAttributesError::InvalidTimestamp(format!(
@@ -162,67 +410,255 @@ pub fn validate_payload_attributes(
     Ok(())
 }
 
-/// Local payload attributes builder for Load (debug/self-builders).
-pub struct LoadLocalPayloadAttributesBuilder(
-    pub reth_engine_local::LocalPayloadAttributesBuilder<crate::chainspec::LoadChainSpec>,
-);
+/// Local payload attributes builder for Load (debug/self-builders and `--load.dev`).
+///
+/// Unlike the upstream builder, attributes are deterministic for a given parent: the timestamp is
+/// the parent's plus the block time, the parent beacon block root is the parent hash and the fee
+/// recipient is configurable.
+pub struct LoadLocalPayloadAttributesBuilder {
+    inner: reth_engine_local::LocalPayloadAttributesBuilder<crate::chainspec::LoadChainSpec>,
+    fee_recipient: Address,
+    /// Seconds between a parent and its child.
+    block_time: u64,
+}
+
+impl LoadLocalPayloadAttributesBuilder {
+    pub fn new(chain_spec: Arc<crate::chainspec::LoadChainSpec>) -> Self {
+        Self {
+            inner: reth_engine_local::LocalPayloadAttributesBuilder::new(chain_spec),
+            fee_recipient: Address::ZERO,
+            block_time: 1,
+        }
+    }
+
+    /// Spaces block timestamps by `block_time`, rounded up to whole seconds (at least one).
+    pub const fn with_block_time(mut self, block_time: std::time::Duration) -> Self {
+        let secs = block_time.as_secs() + (block_time.subsec_nanos() > 0) as u64;
+        self.block_time = if secs == 0 { 1 } else { secs };
+        self
+    }
+
+    /// Address credited with the fees of locally built blocks.
+    pub const fn with_fee_recipient(mut self, fee_recipient: Address) -> Self {
+        self.fee_recipient = fee_recipient;
+        self
+    }
+
+    pub const fn fee_recipient(&self) -> Address {
+        self.fee_recipient
+    }
+}
 
 impl fmt::Debug for LoadLocalPayloadAttributesBuilder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("LoadLocalPayloadAttributesBuilder")
-            .field(&self.0.chain_spec.chain())
+        f.debug_struct("LoadLocalPayloadAttributesBuilder")
+            .field("chain", &self.inner.chain_spec.chain())
+            .field("fee_recipient", &self.fee_recipient)
+            .field("block_time", &self.block_time)
             .finish()
     }
 }
 
 impl Clone for LoadLocalPayloadAttributesBuilder {
     fn clone(&self) -> Self {
-        Self(reth_engine_local::LocalPayloadAttributesBuilder::new(self.0.chain_spec.clone()))
+        Self {
+            inner: reth_engine_local::LocalPayloadAttributesBuilder::new(
+                self.inner.chain_spec.clone(),
+            ),
+            fee_recipient: self.fee_recipient,
+            block_time: self.block_time,
+        }
     }
 }
 
 impl PayloadAttributesBuilder<LoadPayloadAttributes> for LoadLocalPayloadAttributesBuilder {
     fn build(&self, parent: &SealedHeader) -> LoadPayloadAttributes {
-        let timestamp = parent.timestamp + 1;
-        LoadPayloadAttributes {
-            inner: EthPayloadAttributes {
-                timestamp,
-                prev_randao: B256::from(crate::LOAD_PREVRANDAO),
-                suggested_fee_recipient: alloy_primitives::Address::ZERO,
-                withdrawals: self
-                    .0
-                    .chain_spec
-                    .is_shanghai_active_at_timestamp(timestamp)
-                    .then(Default::default),
-                parent_beacon_block_root: self
-                    .0
-                    .chain_spec
-                    .is_cancun_active_at_timestamp(timestamp)
-                    .then(B256::random),
-            },
-        }
+        let timestamp = parent.timestamp + self.block_time;
+        let chain_spec = &self.inner.chain_spec;
+        LoadPayloadAttributes::from(EthPayloadAttributes {
+            timestamp,
+            prev_randao: B256::from(crate::LOAD_PREVRANDAO),
+            suggested_fee_recipient: self.fee_recipient,
+            withdrawals: chain_spec
+                .is_shanghai_active_at_timestamp(timestamp)
+                .then(Default::default),
+            parent_beacon_block_root: chain_spec
+                .is_cancun_active_at_timestamp(timestamp)
+                .then(|| parent.hash()),
+        })
     }
 }
 
+/// Blob sidecars of a built payload, shared with the pool's blob store entries.
+pub type LoadBlobSidecars = Arc<[Arc<BlobTransactionSidecarVariant>]>;
+
 /// Load-specific built payload wrapper.
+///
+/// Sidecars are kept as the `Arc`s handed out by the blob store rather than in
+/// `EthBuiltPayload`'s owned `BlobSidecars`, and the block is the `Arc` inside `inner`, so cloning
+/// a payload (job best payload, resolve, getPayload) never copies blob data. The only copy is the
+/// one into the Engine API envelope.
 #[derive(Debug, Clone)]
 pub struct LoadBuiltPayload {
+    /// Block, fees and requests. Its own sidecar list is left empty; see [`Self::sidecars`].
     pub inner: EthBuiltPayload,
+    sidecars: LoadBlobSidecars,
+    /// Sidecar memory reserved for this payload; released once the last clone is dropped.
+    sidecar_reservation: Option<Arc<SidecarReservation>>,
 }
 
 impl LoadBuiltPayload {
-    pub const fn new(inner: EthBuiltPayload) -> Self {
-        Self { inner }
+    pub fn new(inner: EthBuiltPayload) -> Self {
+        Self { inner, sidecars: Arc::from([]), sidecar_reservation: None }
     }
 
-    fn guard_sidecars(&self, max_blobs: usize) -> Result<(), BuiltPayloadConversionError> {
-        match self.inner.sidecars() {
-            BlobSidecars::Eip7594(_) => Err(BuiltPayloadConversionError::UnexpectedEip7594Sidecars),
-            BlobSidecars::Eip4844(sidecars) if sidecars.len() > max_blobs => {
-                Err(BuiltPayloadConversionError::UnexpectedEip4844Sidecars)
-            }
-            _ => Ok(()),
+    /// Payload ID of the job that built this payload.
+    pub fn id(&self) -> PayloadId {
+        self.inner.id()
+    }
+
+    /// Attaches the blob sidecars of the block's blob transactions, in block order.
+    pub fn with_sidecars(mut self, sidecars: impl Into<LoadBlobSidecars>) -> Self {
+        self.sidecars = sidecars.into();
+        self
+    }
+
+    /// Blob sidecars of the block's blob transactions, in block order.
+    pub fn sidecars(&self) -> &[Arc<BlobTransactionSidecarVariant>] {
+        &self.sidecars
+    }
+
+    /// Versioned hashes of every blob carried by the payload, in block order.
+    pub fn versioned_hashes(&self) -> Vec<B256> {
+        self.sidecars
+            .iter()
+            .flat_map(|sidecar| match sidecar.as_ref() {
+                BlobTransactionSidecarVariant::Eip4844(sidecar) => {
+                    sidecar.versioned_hashes().collect::<Vec<_>>()
+                }
+                BlobTransactionSidecarVariant::Eip7594(sidecar) => {
+                    sidecar.versioned_hashes().collect::<Vec<_>>()
+                }
+            })
+            .collect()
+    }
+
+    /// Ties a sidecar budget reservation to the lifetime of this payload.
+    pub fn with_sidecar_reservation(mut self, reservation: SidecarReservation) -> Self {
+        self.sidecar_reservation = (reservation.bytes() > 0).then(|| Arc::new(reservation));
+        self
+    }
+
+    /// Checks that every sidecar has the variant the envelope carries (cell proofs from Osaka on)
+    /// and that the payload stays within the Load blob cap.
+    fn guard_sidecars(
+        &self,
+        max_blobs: usize,
+        cell_proofs: bool,
+    ) -> Result<(), BuiltPayloadConversionError> {
+        if let Some(sidecar) =
+            self.sidecars.iter().find(|sidecar| sidecar.is_eip7594() != cell_proofs)
+        {
+            return Err(if sidecar.is_eip7594() {
+                BuiltPayloadConversionError::UnexpectedEip7594Sidecars
+            } else {
+                BuiltPayloadConversionError::UnexpectedEip4844Sidecars
+            });
+        }
+        if self.sidecars.len() > max_blobs {
+            return Err(if cell_proofs {
+                BuiltPayloadConversionError::UnexpectedEip7594Sidecars
+            } else {
+                BuiltPayloadConversionError::UnexpectedEip4844Sidecars
+            });
+        }
+        Ok(())
+    }
+
+    /// Copies the EIP-4844 sidecars into a `BlobsBundleV1`, allocating each vector once.
+    fn blobs_bundle_v1(&self) -> BlobsBundleV1 {
+        let blob_count = self
+            .sidecars
+            .iter()
+            .filter_map(|sidecar| sidecar.as_eip4844())
+            .map(|sidecar| sidecar.blobs.len())
+            .sum();
+        let mut bundle = BlobsBundleV1 {
+            commitments: Vec::with_capacity(blob_count),
+            proofs: Vec::with_capacity(blob_count),
+            blobs: Vec::with_capacity(blob_count),
+        };
+        for sidecar in self.sidecars.iter().filter_map(|sidecar| sidecar.as_eip4844()) {
+            bundle.commitments.extend_from_slice(&sidecar.commitments);
+            bundle.proofs.extend_from_slice(&sidecar.proofs);
+            bundle.blobs.extend_from_slice(&sidecar.blobs);
         }
+        bundle
+    }
+
+    /// Copies the EIP-7594 sidecars into a `BlobsBundleV2`, allocating each vector once.
+    ///
+    /// Cell proofs are flattened blob by blob, `CELLS_PER_EXT_BLOB` per blob.
+    fn blobs_bundle_v2(&self) -> BlobsBundleV2 {
+        let (blob_count, proof_count) = self
+            .sidecars
+            .iter()
+            .filter_map(|sidecar| sidecar.as_eip7594())
+            .fold((0, 0), |(blobs, proofs), sidecar| {
+                (blobs + sidecar.blobs.len(), proofs + sidecar.cell_proofs.len())
+            });
+        let mut bundle = BlobsBundleV2 {
+            commitments: Vec::with_capacity(blob_count),
+            proofs: Vec::with_capacity(proof_count),
+            blobs: Vec::with_capacity(blob_count),
+        };
+        for sidecar in self.sidecars.iter().filter_map(|sidecar| sidecar.as_eip7594()) {
+            bundle.commitments.extend_from_slice(&sidecar.commitments);
+            bundle.proofs.extend_from_slice(&sidecar.cell_proofs);
+            bundle.blobs.extend_from_slice(&sidecar.blobs);
+        }
+        bundle
+    }
+}
+
+/// Builds the V3 execution payload of the sealed block from its header and body by reference.
+///
+/// Transactions are encoded straight from the shared block and only `extraData` and the
+/// withdrawals are copied, so the block itself is never cloned; the blob sidecars stay shared
+/// until they are copied into the response's blobs bundle.
+fn execution_payload_v3(
+    block: &SealedBlock<reth_ethereum_primitives::Block>,
+) -> ExecutionPayloadV3 {
+    let header = block.header();
+    let body = block.body();
+    ExecutionPayloadV3 {
+        payload_inner: ExecutionPayloadV2 {
+            payload_inner: ExecutionPayloadV1 {
+                parent_hash: header.parent_hash,
+                fee_recipient: header.beneficiary,
+                state_root: header.state_root,
+                receipts_root: header.receipts_root,
+                logs_bloom: header.logs_bloom,
+                prev_randao: header.mix_hash,
+                block_number: header.number,
+                gas_limit: header.gas_limit,
+                gas_used: header.gas_used,
+                timestamp: header.timestamp,
+                extra_data: header.extra_data.clone(),
+                base_fee_per_gas: alloy_primitives::U256::from(
+                    header.base_fee_per_gas.unwrap_or_default(),
+                ),
+                block_hash: block.hash(),
+                transactions: body.transactions.iter().map(|tx| tx.encoded_2718().into()).collect(),
+            },
+            withdrawals: body
+                .withdrawals
+                .as_ref()
+                .map(|withdrawals| withdrawals.to_vec())
+                .unwrap_or_default(),
+        },
+        blob_gas_used: header.blob_gas_used.unwrap_or_default(),
+        excess_blob_gas: header.excess_blob_gas.unwrap_or_default(),
     }
 }
 
@@ -258,21 +694,13 @@ impl TryFrom<LoadBuiltPayload> for ExecutionPayloadEnvelopeV3 {
     type Error = BuiltPayloadConversionError;
 
     fn try_from(value: LoadBuiltPayload) -> Result<Self, Self::Error> {
-        value.guard_sidecars(LOAD_MAX_BLOB_COUNT as usize)?;
-        let blobs_bundle = match value.inner.sidecars() {
-            BlobSidecars::Empty => BlobsBundleV1::empty(),
-            BlobSidecars::Eip4844(sidecars) => BlobsBundleV1::from(sidecars.clone()),
-            BlobSidecars::Eip7594(_) => unreachable!(),
-        };
+        value.guard_sidecars(LOAD_MAX_BLOB_COUNT as usize, false)?;
 
         Ok(ExecutionPayloadEnvelopeV3 {
-            execution_payload: ExecutionPayloadV3::from_block_unchecked(
-                value.block().hash(),
-                &value.block().clone().into_block(),
-            ),
+            execution_payload: execution_payload_v3(value.block()),
             block_value: value.fees(),
             should_override_builder: false,
-            blobs_bundle,
+            blobs_bundle: value.blobs_bundle_v1(),
         })
     }
 }
@@ -281,8 +709,11 @@ impl TryFrom<LoadBuiltPayload> for ExecutionPayloadEnvelopeV4 {
     type Error = BuiltPayloadConversionError;
 
     fn try_from(value: LoadBuiltPayload) -> Result<Self, Self::Error> {
-        value.guard_sidecars(LOAD_MAX_BLOB_COUNT as usize)?;
-        ExecutionPayloadEnvelopeV4::try_from(value.inner)
+        let execution_requests = value.requests().unwrap_or_default();
+        Ok(ExecutionPayloadEnvelopeV4 {
+            envelope_inner: ExecutionPayloadEnvelopeV3::try_from(value)?,
+            execution_requests,
+        })
     }
 }
 
@@ -290,8 +721,15 @@ impl TryFrom<LoadBuiltPayload> for ExecutionPayloadEnvelopeV5 {
     type Error = BuiltPayloadConversionError;
 
     fn try_from(value: LoadBuiltPayload) -> Result<Self, Self::Error> {
-        value.guard_sidecars(LOAD_MAX_BLOB_COUNT as usize)?;
-        ExecutionPayloadEnvelopeV5::try_from(value.inner)
+        value.guard_sidecars(LOAD_MAX_BLOB_COUNT as usize, true)?;
+
+        Ok(ExecutionPayloadEnvelopeV5 {
+            execution_payload: execution_payload_v3(value.block()),
+            block_value: value.fees(),
+            should_override_builder: false,
+            blobs_bundle: value.blobs_bundle_v2(),
+            execution_requests: value.requests().unwrap_or_default(),
+        })
     }
 }
 
@@ -487,11 +925,22 @@ where
 
 #[cfg(test)]
 mod tests {
+    use alloy_consensus::{SignableTransaction, TxEip1559, TxEip4844};
+    use alloy_eips::eip2718::Encodable2718;
     use alloy_rpc_types_engine::PayloadAttributes;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
     use reth_payload_primitives::PayloadBuilderAttributes;
 
     use super::*;
 
+    fn signed_eip1559(nonce: u64) -> Bytes {
+        let signer = PrivateKeySigner::random();
+        let tx = TxEip1559 { chain_id: 16_383, nonce, gas_limit: 21_000, ..Default::default() };
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).expect("signing succeeds");
+        TransactionSigned::from(tx.into_signed(signature)).encoded_2718().into()
+    }
+
     #[test]
     fn prev_randao_guard() {
         assert!(validate_prev_randao(B256::from(LOAD_PREVRANDAO)).is_ok());
@@ -500,14 +949,101 @@ mod tests {
 
     #[test]
     fn reject_eip7594_sidecars() {
-        let payload = empty_load_payload(PayloadId::default())
-            .inner
-            .with_sidecars(BlobSidecars::Eip7594(Vec::new()));
-        let err =
-            LoadBuiltPayload::new(payload).try_into() as Result<ExecutionPayloadEnvelopeV3, _>;
+        let sidecar = BlobTransactionSidecarVariant::Eip7594(Default::default());
+        let payload =
+            empty_load_payload(PayloadId::default()).with_sidecars(vec![Arc::new(sidecar)]);
+        let err = payload.try_into() as Result<ExecutionPayloadEnvelopeV3, _>;
+        assert!(matches!(err.unwrap_err(), BuiltPayloadConversionError::UnexpectedEip7594Sidecars));
+    }
+
+    #[test]
+    fn v5_rejects_eip4844_sidecars() {
+        use alloy_eips::eip4844::BlobTransactionSidecar;
+        let sidecar = BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::default());
+        let payload =
+            empty_load_payload(PayloadId::default()).with_sidecars(vec![Arc::new(sidecar)]);
+        let err = payload.try_into() as Result<ExecutionPayloadEnvelopeV5, _>;
+        assert!(matches!(err.unwrap_err(), BuiltPayloadConversionError::UnexpectedEip4844Sidecars));
+    }
+
+    #[test]
+    fn v5_flattens_cell_proofs() {
+        use alloy_eips::{
+            eip4844::{Blob, Bytes48},
+            eip7594::{BlobTransactionSidecarEip7594, CELLS_PER_EXT_BLOB},
+        };
+        let sidecar = |blobs: usize| {
+            Arc::new(BlobTransactionSidecarVariant::Eip7594(BlobTransactionSidecarEip7594::new(
+                vec![Blob::default(); blobs],
+                vec![Bytes48::default(); blobs],
+                vec![Bytes48::default(); blobs * CELLS_PER_EXT_BLOB],
+            )))
+        };
+        let payload =
+            empty_load_payload(PayloadId::default()).with_sidecars(vec![sidecar(2), sidecar(1)]);
+
+        let env: ExecutionPayloadEnvelopeV5 = payload.clone().try_into().unwrap();
+        assert_eq!(env.blobs_bundle.blobs.len(), 3);
+        assert_eq!(env.blobs_bundle.commitments.len(), 3);
+        assert_eq!(env.blobs_bundle.proofs.len(), 3 * CELLS_PER_EXT_BLOB);
+
+        let err = payload.try_into() as Result<ExecutionPayloadEnvelopeV3, _>;
         assert!(matches!(err.unwrap_err(), BuiltPayloadConversionError::UnexpectedEip7594Sidecars));
     }
 
+    #[test]
+    fn execution_payload_matches_cloned_block_conversion() {
+        use alloy_consensus::{BlockBody, Header};
+        use alloy_eips::eip4895::Withdrawal;
+        use alloy_primitives::Bloom;
+
+        let transactions = (0..3)
+            .map(|nonce| {
+                let tx =
+                    TxEip1559 { chain_id: 16_383, nonce, gas_limit: 21_000, ..Default::default() };
+                let signature = PrivateKeySigner::random()
+                    .sign_hash_sync(&tx.signature_hash())
+                    .expect("signing succeeds");
+                TransactionSigned::from(tx.into_signed(signature))
+            })
+            .collect();
+        let block = reth_ethereum_primitives::Block {
+            header: Header {
+                parent_hash: B256::repeat_byte(1),
+                beneficiary: Address::repeat_byte(2),
+                state_root: B256::repeat_byte(3),
+                receipts_root: B256::repeat_byte(4),
+                logs_bloom: Bloom::repeat_byte(5),
+                mix_hash: B256::from(LOAD_PREVRANDAO),
+                number: 6,
+                gas_limit: 7_000_000,
+                gas_used: 63_000,
+                timestamp: 8,
+                extra_data: Bytes::from_static(b"load"),
+                base_fee_per_gas: Some(9),
+                blob_gas_used: Some(10),
+                excess_blob_gas: Some(11),
+                ..Default::default()
+            },
+            body: BlockBody {
+                transactions,
+                ommers: Vec::new(),
+                withdrawals: Some(Withdrawals::new(vec![Withdrawal {
+                    index: 12,
+                    validator_index: 13,
+                    address: Address::repeat_byte(14),
+                    amount: 15,
+                }])),
+            },
+        };
+        let block = SealedBlock::seal_slow(block);
+
+        assert_eq!(
+            execution_payload_v3(&block),
+            ExecutionPayloadV3::from_block_unchecked(block.hash(), &block.clone_block())
+        );
+    }
+
     #[test]
     fn accept_empty_sidecars() {
         let payload = empty_load_payload(PayloadId::default());
@@ -518,12 +1054,15 @@ mod tests {
     #[test]
     fn cap_blob_count() {
         use alloy_eips::eip4844::BlobTransactionSidecar;
-        let sidecars = vec![BlobTransactionSidecar::default(); (LOAD_MAX_BLOB_COUNT as usize) + 1];
-        let payload = empty_load_payload(PayloadId::default())
-            .inner
-            .with_sidecars(BlobSidecars::Eip4844(sidecars));
-        let err =
-            LoadBuiltPayload::new(payload).try_into() as Result<ExecutionPayloadEnvelopeV3, _>;
+        let sidecar =
+            Arc::new(BlobTransactionSidecarVariant::Eip4844(BlobTransactionSidecar::default()));
+        let payload = empty_load_payload(PayloadId::default()).with_sidecars(vec![
+            sidecar;
+            (LOAD_MAX_BLOB_COUNT
+                as usize) +
+                1
+        ]);
+        let err = payload.try_into() as Result<ExecutionPayloadEnvelopeV3, _>;
         assert!(matches!(err.unwrap_err(), BuiltPayloadConversionError::UnexpectedEip4844Sidecars));
     }
 
@@ -539,7 +1078,7 @@ mod tests {
 
         let err = LoadPayloadBuilderAttributes::try_new(
             B256::ZERO,
-            LoadPayloadAttributes { inner: rpc_attrs },
+            LoadPayloadAttributes::from(rpc_attrs),
             3,
         )
         .unwrap_err();
@@ -558,13 +1097,215 @@ mod tests {
 
         let attrs = LoadPayloadBuilderAttributes::try_new(
             B256::ZERO,
-            LoadPayloadAttributes { inner: rpc_attrs },
+            LoadPayloadAttributes::from(rpc_attrs),
             3,
         )
         .expect("valid attrs");
         assert_eq!(attrs.prev_randao().as_slice(), LOAD_PREVRANDAO);
     }
 
+    #[test]
+    fn local_attributes_are_deterministic_per_parent() {
+        let builder = LoadLocalPayloadAttributesBuilder::new(Arc::new(Default::default()))
+            .with_fee_recipient(alloy_primitives::Address::with_last_byte(7));
+        let parent = SealedHeader::seal_slow(alloy_consensus::Header {
+            timestamp: 1_700_000_000,
+            ..Default::default()
+        });
+
+        let attrs = builder.build(&parent);
+        assert_eq!(attrs.timestamp(), parent.timestamp + 1);
+        assert_eq!(attrs.suggested_fee_recipient(), alloy_primitives::Address::with_last_byte(7));
+        assert_eq!(attrs.inner.parent_beacon_block_root, Some(parent.hash()));
+        assert_eq!(builder.clone().build(&parent), attrs);
+
+        let slow = builder.with_block_time(std::time::Duration::from_millis(2_500));
+        assert_eq!(slow.build(&parent).timestamp(), parent.timestamp + 3);
+        assert_eq!(slow.clone().build(&parent), slow.build(&parent));
+    }
+
+    #[test]
+    fn builder_attributes_reject_oversized_extra_data() {
+        let rpc_attrs = PayloadAttributes {
+            timestamp: 1,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: alloy_primitives::Address::ZERO,
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+        let attrs = LoadPayloadAttributes {
+            extra_data: Some(Bytes::from(vec![0u8; MAXIMUM_EXTRA_DATA_SIZE + 1])),
+            ..LoadPayloadAttributes::from(rpc_attrs)
+        };
+
+        let err = LoadPayloadBuilderAttributes::try_new(B256::ZERO, attrs, 3).unwrap_err();
+        assert!(matches!(err, LoadPayloadAttributesError::InvalidExtraData(_)));
+    }
+
+    #[test]
+    fn extra_data_changes_payload_id() {
+        let rpc_attrs = PayloadAttributes {
+            timestamp: 1,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: alloy_primitives::Address::ZERO,
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+        let plain = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes::from(rpc_attrs.clone()),
+            3,
+        )
+        .expect("valid attrs");
+        let tagged = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes {
+                extra_data: Some(Bytes::from_static(b"validator-1")),
+                ..LoadPayloadAttributes::from(rpc_attrs)
+            },
+            3,
+        )
+        .expect("valid attrs");
+
+        assert_eq!(plain.payload_id(), plain.clone().into_inner().payload_id());
+        assert_ne!(plain.payload_id(), tagged.payload_id());
+        assert_eq!(tagged.extra_data().map(|data| data.as_ref()), Some(&b"validator-1"[..]));
+    }
+
+    #[test]
+    fn forced_transactions_decode_and_change_payload_id() {
+        let rpc_attrs = PayloadAttributes {
+            timestamp: 1,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: alloy_primitives::Address::ZERO,
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+        let forced = vec![signed_eip1559(0), signed_eip1559(1)];
+        let plain = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes::from(rpc_attrs.clone()),
+            3,
+        )
+        .expect("valid attrs");
+        let with_txs = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes {
+                transactions: Some(forced.clone()),
+                ..LoadPayloadAttributes::from(rpc_attrs.clone())
+            },
+            3,
+        )
+        .expect("valid attrs");
+        let reordered = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes {
+                transactions: Some(forced.iter().rev().cloned().collect()),
+                ..LoadPayloadAttributes::from(rpc_attrs)
+            },
+            3,
+        )
+        .expect("valid attrs");
+
+        assert_eq!(with_txs.transactions().len(), 2);
+        assert_eq!(with_txs.transactions()[0].encoded_bytes(), &forced[0]);
+        assert_ne!(plain.payload_id(), with_txs.payload_id());
+        assert_ne!(with_txs.payload_id(), reordered.payload_id());
+    }
+
+    #[test]
+    fn gas_limit_changes_payload_id_and_round_trips_json() {
+        let rpc_attrs = PayloadAttributes {
+            timestamp: 1,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: alloy_primitives::Address::ZERO,
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+        let pinned = LoadPayloadAttributes {
+            gas_limit: Some(1_000_000_000),
+            ..LoadPayloadAttributes::from(rpc_attrs.clone())
+        };
+
+        let json = serde_json::to_value(&pinned).expect("serializes");
+        assert_eq!(json["gasLimit"], "0x3b9aca00");
+        assert_eq!(serde_json::from_value::<LoadPayloadAttributes>(json).unwrap(), pinned);
+
+        let plain = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes::from(rpc_attrs),
+            3,
+        )
+        .expect("valid attrs");
+        let pinned = LoadPayloadBuilderAttributes::try_new(B256::ZERO, pinned, 3).expect("valid");
+        assert_eq!(pinned.gas_limit(), Some(1_000_000_000));
+        assert_ne!(plain.payload_id(), pinned.payload_id());
+    }
+
+    #[test]
+    fn gas_limit_bounds() {
+        assert!(validate_gas_limit(MINIMUM_GAS_LIMIT, 2_000_000_000).is_ok());
+        assert!(validate_gas_limit(MINIMUM_GAS_LIMIT - 1, 2_000_000_000).is_err());
+        assert!(validate_gas_limit(2_000_000_001, 2_000_000_000).is_err());
+
+        let parent = 2_048_000;
+        assert!(validate_gas_limit_against_parent(parent, parent).is_ok());
+        assert!(validate_gas_limit_against_parent(parent + 1_999, parent).is_ok());
+        assert!(validate_gas_limit_against_parent(parent + 2_000, parent).is_err());
+        assert!(validate_gas_limit_against_parent(parent - 2_000, parent).is_err());
+    }
+
+    #[test]
+    fn forced_transactions_reject_garbage_and_blobs() {
+        assert!(matches!(
+            decode_forced_transactions(&[Bytes::from_static(&[0x02, 0xde, 0xad])]),
+            Err(LoadPayloadAttributesError::InvalidForcedTransaction(_))
+        ));
+
+        let signer = PrivateKeySigner::random();
+        let tx = TxEip4844 {
+            chain_id: 16_383,
+            gas_limit: 21_000,
+            blob_versioned_hashes: vec![B256::repeat_byte(0x01)],
+            ..Default::default()
+        };
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).expect("signing succeeds");
+        let blob_tx: Bytes =
+            TransactionSigned::from(tx.into_signed(signature)).encoded_2718().into();
+        let err = decode_forced_transactions(&[blob_tx]).unwrap_err();
+        assert!(err.to_string().contains("blob"), "{err}");
+    }
+
+    #[test]
+    fn payload_attributes_extra_data_is_optional_in_json() {
+        let json = r#"{
+            "timestamp": "0x1",
+            "prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "suggestedFeeRecipient": "0x0000000000000000000000000000000000000000"
+        }"#;
+        let attrs: LoadPayloadAttributes = serde_json::from_str(json).expect("valid json");
+        assert!(attrs.extra_data().is_none());
+
+        let json = r#"{
+            "timestamp": "0x1",
+            "prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "suggestedFeeRecipient": "0x0000000000000000000000000000000000000000",
+            "extraData": "0x6c6f6164"
+        }"#;
+        let attrs: LoadPayloadAttributes = serde_json::from_str(json).expect("valid json");
+        assert_eq!(attrs.extra_data().map(|data| data.as_ref()), Some(&b"load"[..]));
+        assert!(attrs.transactions().is_none());
+
+        let json = r#"{
+            "timestamp": "0x1",
+            "prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "suggestedFeeRecipient": "0x0000000000000000000000000000000000000000",
+            "transactions": ["0x02"]
+        }"#;
+        let attrs: LoadPayloadAttributes = serde_json::from_str(json).expect("valid json");
+        assert_eq!(attrs.transactions(), Some(&[Bytes::from_static(&[0x02])][..]));
+    }
+
     #[test]
     fn payload_attributes_reject_non_increasing_timestamp() {
         let rpc_attrs = PayloadAttributes {
@@ -577,11 +1318,12 @@ mod tests {
 
         let attrs = LoadPayloadBuilderAttributes::try_new(
             B256::ZERO,
-            LoadPayloadAttributes { inner: rpc_attrs },
+            LoadPayloadAttributes::from(rpc_attrs),
             3,
         )
         .expect("valid attrs");
-        let err = validate_payload_attributes(1, &attrs).unwrap_err();
+        let err =
+            validate_payload_attributes(1, &attrs, &LoadFeeRecipientPolicy::default()).unwrap_err();
         assert!(matches!(err, LoadPayloadAttributesError::InvalidTimestamp(_)));
     }
 
@@ -597,10 +1339,34 @@ mod tests {
 
         let attrs = LoadPayloadBuilderAttributes::try_new(
             B256::ZERO,
-            LoadPayloadAttributes { inner: rpc_attrs },
+            LoadPayloadAttributes::from(rpc_attrs),
+            3,
+        )
+        .expect("valid attrs");
+        assert!(validate_payload_attributes(1, &attrs, &LoadFeeRecipientPolicy::default()).is_ok());
+    }
+
+    #[test]
+    fn payload_attributes_enforce_fee_recipient_policy() {
+        let rpc_attrs = PayloadAttributes {
+            timestamp: 2,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: alloy_primitives::Address::ZERO,
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+
+        let attrs = LoadPayloadBuilderAttributes::try_new(
+            B256::ZERO,
+            LoadPayloadAttributes::from(rpc_attrs),
             3,
         )
         .expect("valid attrs");
-        assert!(validate_payload_attributes(1, &attrs).is_ok());
+        let policy = LoadFeeRecipientPolicy::default().with_deny_zero(true);
+        let err = validate_payload_attributes(1, &attrs, &policy).unwrap_err();
+        assert!(matches!(
+            err,
+            LoadPayloadAttributesError::InvalidFeeRecipient(FeeRecipientPolicyError::ZeroAddress)
+        ));
     }
 }
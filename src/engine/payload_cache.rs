@@ -0,0 +1,327 @@
+//! Pre-serialized `engine_getPayloadV3/V4` responses.
+//!
+//! A full 1024-blob envelope is ~170 MB of hex JSON. When a payload job resolves, its envelope is
+//! encoded once on the blocking pool and cached per payload ID and version; `getPayload` calls are
+//! answered with the cached bytes. A call that finds no entry (the payload event was lagged, or
+//! the call raced the resolution) encodes it itself, and calls for an ID that is still being
+//! encoded wait for that encode instead of starting their own. Entries are evicted oldest first
+//! once the cache holds more than its entry or byte limit.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloy_rpc_types_engine::PayloadId;
+use serde::{Serialize, Serializer};
+use serde_json::value::RawValue;
+use tokio::sync::OnceCell;
+
+use crate::metrics::LoadPayloadCacheMetrics;
+
+/// Default number of cached responses (one per payload ID and version).
+pub const DEFAULT_PAYLOAD_CACHE_ENTRIES: usize = 8;
+/// Default byte limit for cached responses (about three full 1024-blob envelopes).
+pub const DEFAULT_PAYLOAD_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// `getPayload` versions served from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadPayloadVersion {
+    V3,
+    V4,
+}
+
+impl LoadPayloadVersion {
+    /// Engine API method name for this version.
+    pub const fn method(self) -> &'static str {
+        match self {
+            Self::V3 => "engine_getPayloadV3",
+            Self::V4 => "engine_getPayloadV4",
+        }
+    }
+}
+
+/// An encoded envelope, written verbatim into the RPC response.
+#[derive(Debug, Clone)]
+pub struct LoadPayloadJson(Arc<RawValue>);
+
+impl LoadPayloadJson {
+    /// Serializes `value` to JSON.
+    pub fn encode<T: Serialize>(value: &T) -> serde_json::Result<Self> {
+        serde_json::value::to_raw_value(value).map(|raw| Self(Arc::from(raw)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.get()
+    }
+
+    /// Size of the encoded JSON in bytes.
+    pub fn len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_str().is_empty()
+    }
+}
+
+impl Serialize for LoadPayloadJson {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+type CacheKey = (PayloadId, LoadPayloadVersion);
+type Slot = Arc<OnceCell<LoadPayloadJson>>;
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    slots: HashMap<CacheKey, Slot>,
+    /// Insertion order, oldest first.
+    order: VecDeque<CacheKey>,
+    bytes: u64,
+}
+
+impl CacheInner {
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(slot) = self.slots.remove(key) {
+            self.order.retain(|queued| queued != key);
+            if let Some(json) = slot.get() {
+                self.bytes -= json.len() as u64;
+            }
+        }
+    }
+}
+
+/// Bounded cache of encoded `getPayload` responses, keyed by payload ID and version.
+///
+/// Cloning is cheap; every clone shares the same entries.
+#[derive(Debug, Clone)]
+pub struct LoadPayloadResponseCache {
+    inner: Arc<Mutex<CacheInner>>,
+    max_entries: usize,
+    max_bytes: u64,
+    metrics: LoadPayloadCacheMetrics,
+}
+
+impl LoadPayloadResponseCache {
+    pub fn new(max_entries: usize, max_bytes: u64) -> Self {
+        Self {
+            inner: Default::default(),
+            max_entries,
+            max_bytes,
+            metrics: LoadPayloadCacheMetrics::default(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the slot for `key`, creating it if missing. The flag is `true` for a new slot.
+    fn slot(&self, key: CacheKey) -> (Slot, bool) {
+        let mut inner = self.lock();
+        let inner = &mut *inner;
+        let mut created = false;
+        let slot = inner
+            .slots
+            .entry(key)
+            .or_insert_with(|| {
+                created = true;
+                inner.order.push_back(key);
+                Slot::default()
+            })
+            .clone();
+        (slot, created)
+    }
+
+    /// Returns the cached response for `id`, or runs `encode` to produce and cache it.
+    ///
+    /// Failed encodes are not cached, so the next call retries.
+    pub async fn get_or_encode<F, Fut, E>(
+        &self,
+        id: PayloadId,
+        version: LoadPayloadVersion,
+        encode: F,
+    ) -> Result<LoadPayloadJson, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<LoadPayloadJson, E>>,
+    {
+        let key = (id, version);
+        let (slot, _) = self.slot(key);
+        if let Some(json) = slot.get() {
+            self.metrics.record_hit();
+            return Ok(json.clone());
+        }
+        self.fill(key, slot, encode).await
+    }
+
+    /// Encodes and caches the response for `id` unless an entry for it already exists. Called when
+    /// a payload job resolves, ahead of the consensus client's `getPayload`.
+    pub async fn prefill<F, Fut, E>(
+        &self,
+        id: PayloadId,
+        version: LoadPayloadVersion,
+        encode: F,
+    ) -> Result<(), E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<LoadPayloadJson, E>>,
+    {
+        let key = (id, version);
+        let (slot, created) = self.slot(key);
+        if !created {
+            return Ok(());
+        }
+        self.fill(key, slot, encode).await.map(drop)
+    }
+
+    /// Initializes `slot`, or waits for the encode already running, and accounts for the result.
+    async fn fill<F, Fut, E>(
+        &self,
+        key: CacheKey,
+        slot: Slot,
+        encode: F,
+    ) -> Result<LoadPayloadJson, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<LoadPayloadJson, E>>,
+    {
+        let mut encoded = false;
+        let result = slot
+            .get_or_try_init(|| {
+                encoded = true;
+                encode()
+            })
+            .await
+            .cloned();
+
+        let mut inner = self.lock();
+        let current = inner.slots.get(&key).is_some_and(|cached| Arc::ptr_eq(cached, &slot));
+        match &result {
+            Ok(json) if encoded => {
+                self.metrics.record_miss();
+                if current {
+                    inner.bytes += json.len() as u64;
+                    self.evict(&mut inner, &key);
+                }
+            }
+            Ok(_) => self.metrics.record_hit(),
+            Err(_) if current && slot.get().is_none() => inner.remove(&key),
+            Err(_) => {}
+        }
+        self.metrics.set_usage(inner.slots.len(), inner.bytes);
+        result
+    }
+
+    /// Number of cached responses, including ones still being encoded.
+    pub fn len(&self) -> usize {
+        self.lock().slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes held by cached responses.
+    pub fn bytes(&self) -> u64 {
+        self.lock().bytes
+    }
+
+    /// Drops the oldest entries other than `keep` until the cache is within its limits.
+    fn evict(&self, inner: &mut CacheInner, keep: &CacheKey) {
+        while inner.slots.len() > self.max_entries || inner.bytes > self.max_bytes {
+            let Some(oldest) = inner.order.iter().find(|key| *key != keep).copied() else {
+                break;
+            };
+            inner.remove(&oldest);
+            self.metrics.record_eviction();
+        }
+    }
+}
+
+impl Default for LoadPayloadResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAYLOAD_CACHE_ENTRIES, DEFAULT_PAYLOAD_CACHE_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    fn id(n: u8) -> PayloadId {
+        PayloadId::new([n; 8])
+    }
+
+    async fn encode(cache: &LoadPayloadResponseCache, n: u8, body: &str) -> (String, bool) {
+        let mut called = false;
+        let json = cache
+            .get_or_encode(id(n), LoadPayloadVersion::V3, || {
+                called = true;
+                async { Ok::<_, Infallible>(LoadPayloadJson::encode(&body).unwrap()) }
+            })
+            .await
+            .unwrap();
+        (json.as_str().to_string(), called)
+    }
+
+    #[tokio::test]
+    async fn encodes_once_per_payload() {
+        let cache = LoadPayloadResponseCache::default();
+        assert_eq!(encode(&cache, 1, "a").await, ("\"a\"".to_string(), true));
+        assert_eq!(encode(&cache, 1, "b").await, ("\"a\"".to_string(), false));
+        assert_eq!(cache.bytes(), 3);
+    }
+
+    #[tokio::test]
+    async fn prefilled_responses_are_served() {
+        let cache = LoadPayloadResponseCache::default();
+        cache
+            .prefill(id(1), LoadPayloadVersion::V3, || async {
+                Ok::<_, Infallible>(LoadPayloadJson::encode(&"a").unwrap())
+            })
+            .await
+            .unwrap();
+        assert_eq!(encode(&cache, 1, "b").await, ("\"a\"".to_string(), false));
+
+        // An entry created by `getPayload` is not encoded again.
+        encode(&cache, 2, "c").await;
+        cache
+            .prefill(id(2), LoadPayloadVersion::V3, || async {
+                Err::<LoadPayloadJson, _>("encoded twice")
+            })
+            .await
+            .unwrap();
+        assert_eq!(cache.bytes(), 6);
+    }
+
+    #[tokio::test]
+    async fn failed_encodes_are_not_cached() {
+        let cache = LoadPayloadResponseCache::default();
+        let err = cache
+            .get_or_encode(id(1), LoadPayloadVersion::V4, || async { Err("unknown payload") })
+            .await;
+        assert!(err.is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn evicts_oldest_over_limits() {
+        let cache = LoadPayloadResponseCache::new(2, 8);
+        encode(&cache, 1, "a").await;
+        encode(&cache, 2, "b").await;
+        encode(&cache, 3, "c").await;
+        assert_eq!(cache.len(), 2);
+        assert!(encode(&cache, 1, "a").await.1, "oldest entry evicted by count");
+
+        // A response over the byte limit is still served, evicting everything older.
+        encode(&cache, 4, "0123456789").await;
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.bytes(), 12);
+    }
+}
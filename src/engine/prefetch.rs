@@ -0,0 +1,654 @@
+//! Background state prefetching for pending pool transactions.
+//!
+//! Payload jobs only inherit `CachedReads` from earlier builds of the same job, so the first build
+//! after a forkchoice update reads most of its state cold. [`LoadStatePrefetcher`] keeps a
+//! `CachedReads` for the current head warm instead: a background task executes the top pending
+//! pool transactions against the head state (results are discarded, the reads are kept) whenever
+//! the head or the pending set changes. When a new block becomes canonical the cached entries are
+//! carried over by applying that block's state changes, so the cache is valid for the new head
+//! right away. The first build of every job whose parent is the cached head reads through it in
+//! place ([`LoadPrefetchedDb`]) instead of copying it.
+//!
+//! The cache holds at most [`DEFAULT_PREFETCH_MAX_ENTRIES`] accounts and storage slots; the
+//! accounts no prefetch pass has touched for the longest are evicted first.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use alloy_consensus::{transaction::Recovered, BlockHeader, Header};
+use alloy_primitives::{Address, B256, U256};
+use reth::{
+    providers::{CanonStateNotification, CanonStateSubscriptions, StateProviderFactory},
+    revm::{
+        cached::{CachedAccount, CachedReads, CachedReadsDbMut},
+        database::StateProviderDatabase,
+        db::BundleAccount,
+        state::{AccountInfo, Bytecode},
+        Database, DatabaseCommit, DatabaseRef, State,
+    },
+    transaction_pool::{PoolTransaction, TransactionPool},
+};
+use reth_ethereum_primitives::TransactionSigned;
+use reth_evm::{ConfigureEvm, Evm, NextBlockEnvAttributes};
+use reth_primitives_traits::SealedHeader;
+use tokio::{sync::broadcast::error::RecvError, time::interval};
+use tracing::{debug, warn};
+
+use crate::{metrics::LoadPrefetchMetrics, primitives::LoadPrimitives};
+
+/// Default number of pending transactions executed per prefetch pass.
+pub const DEFAULT_PREFETCH_TRANSACTIONS: usize = 1024;
+/// Default bound on cached accounts plus storage slots.
+pub const DEFAULT_PREFETCH_MAX_ENTRIES: usize = 1 << 19;
+/// How often the pending set is checked for changes between blocks.
+pub const PREFETCH_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug)]
+struct PrefetchedState {
+    head: B256,
+    reads: Arc<CachedReads>,
+    /// Prefetch pass that last touched each cached account.
+    last_used: HashMap<Address, u64>,
+    passes: u64,
+}
+
+impl PrefetchedState {
+    fn new(head: B256) -> Self {
+        Self { head, reads: Default::default(), last_used: HashMap::new(), passes: 0 }
+    }
+
+    /// Cached accounts plus storage slots.
+    fn entries(&self) -> usize {
+        self.reads.accounts.values().map(|account| 1 + account.storage.len()).sum()
+    }
+
+    /// Adds what a prefetch pass read, then evicts the least recently used accounts until the
+    /// cache holds at most `max_entries`.
+    fn merge(&mut self, touched: CachedReads, max_entries: usize) {
+        self.passes += 1;
+        let reads = Arc::make_mut(&mut self.reads);
+        for (address, account) in touched.accounts {
+            self.last_used.insert(address, self.passes);
+            match reads.accounts.get_mut(&address) {
+                Some(cached) => cached.storage.extend(account.storage),
+                None => {
+                    reads.accounts.insert(address, account);
+                }
+            }
+        }
+        reads.contracts.extend(touched.contracts);
+        reads.block_hashes.extend(touched.block_hashes);
+
+        let mut entries = self.entries();
+        if entries <= max_entries {
+            return;
+        }
+        let mut by_age: Vec<_> =
+            self.last_used.iter().map(|(address, pass)| (*pass, *address)).collect();
+        by_age.sort_unstable();
+        let reads = Arc::make_mut(&mut self.reads);
+        for (_, address) in by_age {
+            if entries <= max_entries {
+                break;
+            }
+            self.last_used.remove(&address);
+            if let Some(account) = reads.accounts.remove(&address) {
+                entries -= 1 + account.storage.len();
+            }
+        }
+        // Keep bytecode only for the accounts that are left.
+        let code: HashSet<B256> = reads
+            .accounts
+            .values()
+            .filter_map(|account| account.info.as_ref().map(|info| info.code_hash))
+            .collect();
+        reads.contracts.retain(|code_hash, _| code.contains(code_hash));
+    }
+}
+
+/// Warm `CachedReads` for the current head, shared between the prefetch task and payload jobs.
+///
+/// Cloning is cheap; every clone shares the same cache. A prefetcher created with
+/// `max_transactions == 0` is disabled and never seeds a build.
+#[derive(Debug, Clone)]
+pub struct LoadStatePrefetcher {
+    state: Arc<Mutex<Option<PrefetchedState>>>,
+    max_transactions: usize,
+    max_entries: usize,
+    metrics: LoadPrefetchMetrics,
+}
+
+impl LoadStatePrefetcher {
+    pub fn new(max_transactions: usize) -> Self {
+        Self {
+            state: Default::default(),
+            max_transactions,
+            max_entries: DEFAULT_PREFETCH_MAX_ENTRIES,
+            metrics: LoadPrefetchMetrics::default(),
+        }
+    }
+
+    /// Bounds the cache to `max_entries` accounts plus storage slots.
+    pub const fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// A prefetcher that never caches anything.
+    pub fn disabled() -> Self {
+        Self::new(0)
+    }
+
+    pub const fn max_transactions(&self) -> usize {
+        self.max_transactions
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.max_transactions > 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<PrefetchedState>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Head the cached state belongs to.
+    pub fn head(&self) -> Option<B256> {
+        self.lock().as_ref().map(|state| state.head)
+    }
+
+    fn snapshot(&self, head: B256) -> Option<Arc<CachedReads>> {
+        self.lock().as_ref().filter(|state| state.head == head).map(|state| state.reads.clone())
+    }
+
+    /// Prefetched state for a job's first build on `parent`, to be read through
+    /// [`LoadPrefetchedDb`]. The cache is shared, not copied.
+    pub fn seed(&self, parent: B256) -> Option<Arc<CachedReads>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let reads = self.snapshot(parent);
+        self.metrics.record_seed(reads.is_some());
+        reads
+    }
+
+    /// Records the cache hit rate of a seeded build.
+    pub fn record_reads(&self, tally: &LoadReadTally) {
+        self.metrics.record_reads(tally.hits(), tally.misses());
+    }
+
+    /// Moves the cache from `parent` to `tip` by applying the state changes of the blocks in
+    /// between to the entries it already holds. A cache for any other head is dropped.
+    pub fn on_canonical_chain<'a>(
+        &self,
+        parent: B256,
+        tip: B256,
+        changes: impl IntoIterator<Item = (Address, &'a BundleAccount)>,
+    ) {
+        let mut state = self.lock();
+        let Some(mut current) = state.take().filter(|state| state.head == parent) else {
+            return;
+        };
+
+        let cached = Arc::make_mut(&mut current.reads);
+        for (address, account) in changes {
+            // Anything not cached is read from the new head's state when it is needed.
+            let Some(entry) = cached.accounts.get_mut(&address) else {
+                continue;
+            };
+            entry.info = account.info.clone();
+            if account.info.is_none() || account.was_destroyed() {
+                entry.storage.clear();
+            }
+            for (slot, value) in &account.storage {
+                if let Some(cached_value) = entry.storage.get_mut(slot) {
+                    *cached_value = value.present_value;
+                }
+            }
+        }
+        current.head = tip;
+        *state = Some(current);
+    }
+
+    /// Executes `transactions` on top of `head` and keeps every read they make.
+    ///
+    /// Transactions are committed one after another so later ones see the nonces and balances of
+    /// earlier ones; failed transactions still leave what they read in the cache.
+    pub fn prefetch<EvmConfig, DB>(
+        &self,
+        evm_config: &EvmConfig,
+        head: &SealedHeader<Header>,
+        db: DB,
+        transactions: &[Recovered<TransactionSigned>],
+    ) -> eyre::Result<()>
+    where
+        EvmConfig:
+            ConfigureEvm<Primitives = LoadPrimitives, NextBlockEnvCtx = NextBlockEnvAttributes>,
+        DB: DatabaseRef,
+    {
+        let start = Instant::now();
+        let evm_env = evm_config.next_evm_env(
+            head.header(),
+            &NextBlockEnvAttributes {
+                timestamp: head.timestamp() + 1,
+                suggested_fee_recipient: Address::ZERO,
+                prev_randao: B256::ZERO,
+                gas_limit: head.gas_limit(),
+                parent_beacon_block_root: Some(B256::ZERO),
+                withdrawals: None,
+                extra_data: Default::default(),
+            },
+        )?;
+
+        // Everything the pass reads lands in `touched`, whether it came from the cache or the
+        // database, and is merged back afterwards.
+        let mut touched = CachedReads::default();
+        {
+            let tally = LoadReadTally::default();
+            let db = LoadPrefetchedDb::new(self.snapshot(head.hash()), db, &tally);
+            let mut state = State::builder().with_database(touched.as_db_mut(db)).build();
+            let mut evm = evm_config.evm_with_env(&mut state, evm_env);
+            for tx in transactions.iter().take(self.max_transactions) {
+                if let Ok(result) = evm.transact(evm_config.tx_env(tx.as_recovered_ref())) {
+                    evm.db_mut().commit(result.state);
+                }
+            }
+        }
+
+        let mut state = self.lock();
+        // Only add to a cache for the same head, or fill an empty one.
+        let current = state.get_or_insert_with(|| PrefetchedState::new(head.hash()));
+        if current.head != head.hash() {
+            return Ok(());
+        }
+        current.merge(touched, self.max_entries);
+
+        let storage_slots =
+            current.reads.accounts.values().map(|account| account.storage.len()).sum();
+        self.metrics.record_pass(
+            transactions.len().min(self.max_transactions),
+            current.reads.accounts.len(),
+            storage_slots,
+            start.elapsed(),
+        );
+        Ok(())
+    }
+
+    /// Keeps the cache warm until the canonical state stream closes.
+    pub async fn run<Client, Pool, EvmConfig>(
+        self,
+        client: Client,
+        pool: Pool,
+        evm_config: EvmConfig,
+    ) where
+        Client: StateProviderFactory
+            + CanonStateSubscriptions<Primitives = LoadPrimitives>
+            + Clone
+            + 'static,
+        Pool: TransactionPool<Transaction: PoolTransaction<Consensus = TransactionSigned>>,
+        EvmConfig: ConfigureEvm<Primitives = LoadPrimitives, NextBlockEnvCtx = NextBlockEnvAttributes>
+            + Clone
+            + 'static,
+    {
+        let mut canon_state = client.subscribe_to_canonical_state();
+        let mut ticker = interval(PREFETCH_INTERVAL);
+        let mut head: Option<SealedHeader<Header>> = None;
+        // Head and pending transactions of the last pass; an identical pass is skipped.
+        let mut last_pass: Option<(B256, Vec<B256>)> = None;
+
+        loop {
+            tokio::select! {
+                notification = canon_state.recv() => match notification {
+                    Ok(notification) => head = Some(self.on_canonical_state(&notification)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            target: "load_reth::prefetch",
+                            skipped,
+                            "prefetcher lagged behind canonical state"
+                        );
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = ticker.tick() => {}
+            }
+            let Some(head) = head.clone() else { continue };
+
+            let transactions: Vec<_> = pool
+                .best_transactions()
+                .take(self.max_transactions)
+                .map(|tx| tx.to_consensus())
+                .collect();
+            let pass = (head.hash(), transactions.iter().map(|tx| *tx.tx_hash()).collect());
+            if transactions.is_empty() || last_pass.as_ref() == Some(&pass) {
+                continue;
+            }
+            last_pass = Some(pass);
+
+            let prefetcher = self.clone();
+            let client = client.clone();
+            let evm_config = evm_config.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let state = client.state_by_block_hash(head.hash())?;
+                prefetcher.prefetch(
+                    &evm_config,
+                    &head,
+                    StateProviderDatabase::new(state),
+                    &transactions,
+                )
+            })
+            .await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => debug!(target: "load_reth::prefetch", %err, "prefetch pass failed"),
+                Err(err) => warn!(target: "load_reth::prefetch", %err, "prefetch task panicked"),
+            }
+        }
+    }
+
+    /// Carries the cache over to the new tip and returns its header.
+    fn on_canonical_state(
+        &self,
+        notification: &CanonStateNotification<LoadPrimitives>,
+    ) -> SealedHeader<Header> {
+        let committed = notification.committed();
+        let tip = committed.tip();
+        if notification.reverted().is_some() {
+            // Reorg: the reverted blocks' writes are not in the bundle; start over.
+            *self.lock() = None;
+        } else {
+            self.on_canonical_chain(
+                committed.first().parent_hash(),
+                tip.hash(),
+                committed.execution_outcome().bundle_accounts_iter(),
+            );
+        }
+        tip.clone_sealed_header()
+    }
+}
+
+impl Default for LoadStatePrefetcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_PREFETCH_TRANSACTIONS)
+    }
+}
+
+/// Counts account and storage reads served from `CachedReads` versus loaded from the database.
+#[derive(Debug, Default)]
+pub struct LoadReadTally {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl LoadReadTally {
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+/// [`CachedReadsDbMut`] that records reads served by the job's own cache in a [`LoadReadTally`].
+/// Reads it passes on are recorded by the [`LoadPrefetchedDb`] underneath.
+#[derive(Debug)]
+pub struct TallyingCachedReads<'a, DB> {
+    inner: CachedReadsDbMut<'a, DB>,
+    tally: &'a LoadReadTally,
+}
+
+impl<'a, DB> TallyingCachedReads<'a, DB> {
+    pub const fn new(inner: CachedReadsDbMut<'a, DB>, tally: &'a LoadReadTally) -> Self {
+        Self { inner, tally }
+    }
+}
+
+impl<DB: DatabaseRef> Database for TallyingCachedReads<'_, DB> {
+    type Error = <DB as DatabaseRef>::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if self.inner.cached.accounts.contains_key(&address) {
+            self.tally.record(true);
+        }
+        self.inner.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.inner.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let hit = self
+            .inner
+            .cached
+            .accounts
+            .get(&address)
+            .is_some_and(|account| account.storage.contains_key(&index));
+        if hit {
+            self.tally.record(true);
+        }
+        self.inner.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.inner.block_hash(number)
+    }
+}
+
+/// Database that serves reads from prefetched state before falling back to `DB`, recording which
+/// reads it served in a [`LoadReadTally`].
+#[derive(Debug)]
+pub struct LoadPrefetchedDb<'a, DB> {
+    prefetched: Option<Arc<CachedReads>>,
+    db: DB,
+    tally: &'a LoadReadTally,
+}
+
+impl<'a, DB> LoadPrefetchedDb<'a, DB> {
+    pub const fn new(
+        prefetched: Option<Arc<CachedReads>>,
+        db: DB,
+        tally: &'a LoadReadTally,
+    ) -> Self {
+        Self { prefetched, db, tally }
+    }
+
+    fn account(&self, address: &Address) -> Option<&CachedAccount> {
+        self.prefetched.as_ref()?.accounts.get(address)
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for LoadPrefetchedDb<'_, DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let cached = self.account(&address).map(|account| account.info.clone());
+        self.tally.record(cached.is_some());
+        match cached {
+            Some(info) => Ok(info),
+            None => self.db.basic_ref(address),
+        }
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        match self.prefetched.as_ref().and_then(|reads| reads.contracts.get(&code_hash)) {
+            Some(code) => Ok(code.clone()),
+            None => self.db.code_by_hash_ref(code_hash),
+        }
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let cached = self.account(&address).and_then(|account| account.storage.get(&index));
+        self.tally.record(cached.is_some());
+        match cached {
+            Some(value) => Ok(*value),
+            None => self.db.storage_ref(address, index),
+        }
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        match self.prefetched.as_ref().and_then(|reads| reads.block_hashes.get(&number)) {
+            Some(hash) => Ok(*hash),
+            None => self.db.block_hash_ref(number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reth::revm::db::{AccountStatus, EmptyDB, StorageSlot};
+
+    use super::*;
+
+    fn info(balance: u64) -> AccountInfo {
+        AccountInfo { balance: U256::from(balance), ..Default::default() }
+    }
+
+    fn warm(prefetcher: &LoadStatePrefetcher, head: B256, reads: CachedReads) {
+        let mut state = PrefetchedState::new(head);
+        state.merge(reads, prefetcher.max_entries);
+        *prefetcher.lock() = Some(state);
+    }
+
+    #[test]
+    fn seeds_only_builds_on_the_cached_head() {
+        let prefetcher = LoadStatePrefetcher::default();
+        let mut reads = CachedReads::default();
+        reads.insert_account(Address::with_last_byte(1), info(1), Default::default());
+        warm(&prefetcher, B256::with_last_byte(1), reads);
+
+        assert!(prefetcher.seed(B256::with_last_byte(2)).is_none());
+        let seeded = prefetcher.seed(B256::with_last_byte(1)).expect("cached head");
+        assert!(seeded.accounts.contains_key(&Address::with_last_byte(1)));
+        // Jobs share the cache instead of copying it.
+        let again = prefetcher.seed(B256::with_last_byte(1)).expect("cached head");
+        assert!(Arc::ptr_eq(&seeded, &again));
+
+        let disabled = LoadStatePrefetcher::disabled();
+        warm(&disabled, B256::with_last_byte(1), CachedReads::default());
+        assert!(disabled.seed(B256::with_last_byte(1)).is_none());
+    }
+
+    #[test]
+    fn carries_cache_over_canonical_blocks() {
+        let prefetcher = LoadStatePrefetcher::default();
+        let (changed, untouched, destroyed, uncached) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+            Address::with_last_byte(4),
+        );
+        let mut reads = CachedReads::default();
+        let storage = [(U256::from(1), U256::from(10)), (U256::from(2), U256::from(20))];
+        reads.insert_account(changed, info(1), storage.into_iter().collect());
+        reads.insert_account(untouched, info(2), Default::default());
+        reads.insert_account(destroyed, info(3), storage.into_iter().collect());
+        warm(&prefetcher, B256::with_last_byte(1), reads);
+
+        let mut slots = reth::revm::primitives::HashMap::default();
+        slots.insert(U256::from(1), StorageSlot::new_changed(U256::from(10), U256::from(11)));
+        slots.insert(U256::from(3), StorageSlot::new_changed(U256::ZERO, U256::from(30)));
+        let changes = [
+            (
+                changed,
+                BundleAccount::new(Some(info(1)), Some(info(5)), slots, AccountStatus::Changed),
+            ),
+            (
+                destroyed,
+                BundleAccount::new(
+                    Some(info(3)),
+                    None,
+                    Default::default(),
+                    AccountStatus::Destroyed,
+                ),
+            ),
+            (
+                uncached,
+                BundleAccount::new(None, Some(info(4)), Default::default(), AccountStatus::Changed),
+            ),
+        ];
+        prefetcher.on_canonical_chain(
+            B256::with_last_byte(1),
+            B256::with_last_byte(2),
+            changes.iter().map(|(address, account)| (*address, account)),
+        );
+
+        let reads = prefetcher.snapshot(B256::with_last_byte(2)).expect("moved to the new head");
+        let account = &reads.accounts[&changed];
+        assert_eq!(account.info.as_ref().map(|info| info.balance), Some(U256::from(5)));
+        assert_eq!(account.storage[&U256::from(1)], U256::from(11));
+        assert_eq!(account.storage[&U256::from(2)], U256::from(20));
+        // Only entries the cache already held are updated.
+        assert!(!account.storage.contains_key(&U256::from(3)));
+        assert!(!reads.accounts.contains_key(&uncached));
+        assert!(reads.accounts[&untouched].info.is_some());
+        assert!(reads.accounts[&destroyed].info.is_none());
+        assert!(reads.accounts[&destroyed].storage.is_empty());
+
+        // A block that does not extend the cached head drops the cache.
+        prefetcher.on_canonical_chain(B256::with_last_byte(9), B256::with_last_byte(3), []);
+        assert_eq!(prefetcher.head(), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_prefetched_accounts() {
+        let (a, b, c) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let pass = |address: Address, slots: u64| {
+            let mut reads = CachedReads::default();
+            reads.insert_account(
+                address,
+                info(1),
+                (0..slots).map(|slot| (U256::from(slot), U256::from(slot))).collect(),
+            );
+            reads
+        };
+        let mut state = PrefetchedState::new(B256::ZERO);
+        state.merge(pass(a, 1), 4);
+        state.merge(pass(b, 0), 4);
+        state.merge(pass(a, 0), 4);
+        assert_eq!(state.entries(), 3);
+
+        // `b` was touched less recently than `a`.
+        state.merge(pass(c, 1), 4);
+        assert_eq!(state.entries(), 4);
+        assert!(!state.reads.accounts.contains_key(&b));
+        assert!(state.reads.accounts.contains_key(&a));
+        assert!(state.reads.accounts.contains_key(&c));
+    }
+
+    #[test]
+    fn tallies_cache_hits() {
+        let mut prefetched = CachedReads::default();
+        prefetched.insert_account(
+            Address::with_last_byte(1),
+            info(1),
+            [(U256::from(1), U256::from(1))].into_iter().collect(),
+        );
+        let tally = LoadReadTally::default();
+        let mut job_reads = CachedReads::default();
+        let layer = LoadPrefetchedDb::new(Some(Arc::new(prefetched)), EmptyDB::default(), &tally);
+        let mut db = TallyingCachedReads::new(job_reads.as_db_mut(layer), &tally);
+
+        db.basic(Address::with_last_byte(1)).unwrap();
+        db.storage(Address::with_last_byte(1), U256::from(1)).unwrap();
+        db.storage(Address::with_last_byte(1), U256::from(2)).unwrap();
+        db.basic(Address::with_last_byte(2)).unwrap();
+        assert_eq!((tally.hits(), tally.misses()), (2, 2));
+
+        // Reads now served by the job's own cache count as hits too.
+        db.basic(Address::with_last_byte(2)).unwrap();
+        assert_eq!((tally.hits(), tally.misses()), (3, 2));
+    }
+}
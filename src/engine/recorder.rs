@@ -0,0 +1,555 @@
+//! Opt-in recorder of Engine API traffic (`--load.engine-recorder`).
+//!
+//! CL/EL desyncs such as the persistence race behind `persistence_threshold=0` only show up under
+//! real consensus traffic. The recorder appends every `engine_forkchoiceUpdated*`,
+//! `engine_newPayload*`, `engine_getPayload*` and `engine_getBlobs*` call to a rotating log, one
+//! JSON line per call with its parameters, result or error, start time, duration and payload ID,
+//! so `load-reth engine replay` can feed the same sequence into a fresh node.
+//!
+//! Blob data is not recorded: `getPayload` results carry a blob count in place of `blobsBundle`
+//! and `getBlobs` results only say which blobs were served. Lines are written by a dedicated
+//! thread, which also serializes `newPayload` blocks and cached `getPayload` responses so the
+//! Engine API only pays for cheap copies; when it falls behind, calls are dropped and counted
+//! rather than stalling the Engine API, and the gap shows in the sequence numbers.
+
+use std::{
+    fmt,
+    fs::{self, File},
+    future::Future,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Arc,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use alloy_rpc_types_engine::PayloadId;
+use eyre::WrapErr;
+use jsonrpsee::core::RpcResult;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{engine::payload_cache::LoadPayloadJson, metrics::LoadEngineRecorderMetrics};
+
+/// Size at which the recorder starts a new file, by default.
+pub const DEFAULT_ENGINE_RECORDER_MAX_FILE_BYTES: u64 = 256 * 1024 * 1024;
+/// Files kept by the recorder, by default; the oldest is deleted on rotation.
+pub const DEFAULT_ENGINE_RECORDER_MAX_FILES: usize = 16;
+
+/// Calls buffered for the writer thread before new ones are dropped.
+const QUEUE_CAPACITY: usize = 4096;
+const FILE_PREFIX: &str = "engine-";
+const FILE_SUFFIX: &str = ".jsonl";
+
+/// Recorder settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadEngineRecorderConfig {
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+    /// Defaults to `engine-recordings` in the data directory.
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for LoadEngineRecorderConfig {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: DEFAULT_ENGINE_RECORDER_MAX_FILE_BYTES,
+            max_files: DEFAULT_ENGINE_RECORDER_MAX_FILES,
+            dir: None,
+        }
+    }
+}
+
+impl LoadEngineRecorderConfig {
+    pub const fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    pub const fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    pub fn with_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.dir = dir;
+        self
+    }
+}
+
+/// One recorded Engine API call, as written to the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadEngineRecord {
+    /// Order in which calls arrived; lines are written in completion order.
+    pub seq: u64,
+    /// Arrival time in milliseconds since the Unix epoch.
+    pub started_at_ms: u64,
+    pub duration_us: u64,
+    pub method: String,
+    pub params: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<LoadRecordedError>,
+    /// Payload ID returned by `forkchoiceUpdated` or requested by `getPayload`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_id: Option<PayloadId>,
+}
+
+/// JSON-RPC error returned by a recorded call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadRecordedError {
+    pub code: i32,
+    pub message: String,
+}
+
+/// Parameters of a call, turned into JSON on the writer thread where that is expensive.
+enum RecordedParams {
+    Json(Value),
+    Deferred(Box<dyn FnOnce() -> Value + Send>),
+}
+
+impl RecordedParams {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Json(value) => value,
+            Self::Deferred(serialize) => serialize(),
+        }
+    }
+}
+
+impl fmt::Debug for RecordedParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(value) => f.debug_tuple("Json").field(value).finish(),
+            Self::Deferred(_) => f.write_str("Deferred"),
+        }
+    }
+}
+
+/// Outcome of a call, turned into JSON on the writer thread where that is expensive.
+#[derive(Debug)]
+enum RecordedOutcome {
+    Result(Value),
+    Payload(LoadPayloadJson),
+    Error(LoadRecordedError),
+}
+
+#[derive(Debug)]
+struct PendingRecord {
+    record: LoadEngineRecord,
+    params: RecordedParams,
+    outcome: RecordedOutcome,
+}
+
+/// Handle to the recorder; a no-op unless opened.
+#[derive(Debug, Clone, Default)]
+pub struct LoadEngineRecorder {
+    inner: Option<Arc<RecorderInner>>,
+}
+
+#[derive(Debug)]
+struct RecorderInner {
+    seq: AtomicU64,
+    sender: SyncSender<PendingRecord>,
+    metrics: LoadEngineRecorderMetrics,
+}
+
+impl LoadEngineRecorder {
+    /// A recorder that records nothing.
+    pub const fn disabled() -> Self {
+        Self { inner: None }
+    }
+
+    /// Starts a new file in `dir` and spawns the writer thread.
+    pub fn open(
+        dir: impl Into<PathBuf>,
+        max_file_bytes: u64,
+        max_files: usize,
+    ) -> eyre::Result<Self> {
+        let mut writer = RecordWriter::open(dir.into(), max_file_bytes, max_files.max(1))?;
+        let metrics = LoadEngineRecorderMetrics::default();
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let writer_metrics = metrics.clone();
+        std::thread::Builder::new()
+            .name("load-engine-recorder".into())
+            .spawn(move || writer.run(receiver, &writer_metrics))
+            .wrap_err("failed to spawn the engine recorder thread")?;
+        Ok(Self {
+            inner: Some(Arc::new(RecorderInner { seq: AtomicU64::new(0), sender, metrics })),
+        })
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Starts recording a call. `params` is only evaluated when the recorder is enabled.
+    pub fn begin(&self, method: &'static str, params: impl FnOnce() -> Value) -> LoadEngineCall {
+        self.start(method, || RecordedParams::Json(params()))
+    }
+
+    /// Starts recording a call whose parameters are expensive to serialize, such as a
+    /// `newPayload` block. `params` only runs when the recorder is enabled and should return
+    /// owned copies, e.g. a tuple, which serializes as the parameter array; they are turned into
+    /// JSON on the writer thread.
+    pub fn begin_deferred<P: Serialize + Send + 'static>(
+        &self,
+        method: &'static str,
+        params: impl FnOnce() -> P,
+    ) -> LoadEngineCall {
+        self.start(method, || {
+            let params = params();
+            RecordedParams::Deferred(Box::new(move || {
+                serde_json::to_value(params).unwrap_or(Value::Null)
+            }))
+        })
+    }
+
+    fn start(
+        &self,
+        method: &'static str,
+        params: impl FnOnce() -> RecordedParams,
+    ) -> LoadEngineCall {
+        let call = self.inner.as_ref().map(|inner| ActiveCall {
+            inner: inner.clone(),
+            seq: inner.seq.fetch_add(1, Ordering::Relaxed),
+            method,
+            params: params(),
+            started_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+            started: Instant::now(),
+        });
+        LoadEngineCall { call }
+    }
+}
+
+/// A call in flight; [`LoadEngineCall::record`] awaits it and queues the record.
+#[derive(Debug)]
+pub struct LoadEngineCall {
+    call: Option<ActiveCall>,
+}
+
+#[derive(Debug)]
+struct ActiveCall {
+    inner: Arc<RecorderInner>,
+    seq: u64,
+    method: &'static str,
+    params: RecordedParams,
+    started_at_ms: u64,
+    started: Instant,
+}
+
+impl LoadEngineCall {
+    /// Awaits the call and records its result as JSON.
+    pub async fn record<T: Serialize>(
+        self,
+        call: impl Future<Output = RpcResult<T>>,
+    ) -> RpcResult<T> {
+        self.record_with(call, |result| serde_json::to_value(result).unwrap_or(Value::Null)).await
+    }
+
+    /// Awaits the call and records `summarize` of its result.
+    pub async fn record_with<T>(
+        self,
+        call: impl Future<Output = RpcResult<T>>,
+        summarize: impl FnOnce(&T) -> Value,
+    ) -> RpcResult<T> {
+        let result = call.await;
+        if self.call.is_some() {
+            let outcome = match &result {
+                Ok(value) => RecordedOutcome::Result(summarize(value)),
+                Err(err) => RecordedOutcome::Error(recorded_error(err)),
+            };
+            self.finish(outcome);
+        }
+        result
+    }
+
+    /// Awaits a pre-serialized `getPayload` response; it is summarized on the writer thread.
+    pub async fn record_payload(
+        self,
+        call: impl Future<Output = RpcResult<LoadPayloadJson>>,
+    ) -> RpcResult<LoadPayloadJson> {
+        let result = call.await;
+        if self.call.is_some() {
+            let outcome = match &result {
+                Ok(json) => RecordedOutcome::Payload(json.clone()),
+                Err(err) => RecordedOutcome::Error(recorded_error(err)),
+            };
+            self.finish(outcome);
+        }
+        result
+    }
+
+    fn finish(self, outcome: RecordedOutcome) {
+        let Some(call) = self.call else { return };
+        let record = LoadEngineRecord {
+            seq: call.seq,
+            started_at_ms: call.started_at_ms,
+            duration_us: call.started.elapsed().as_micros() as u64,
+            method: call.method.to_string(),
+            params: Value::Null,
+            result: None,
+            error: None,
+            payload_id: None,
+        };
+        let pending = PendingRecord { record, params: call.params, outcome };
+        if call.inner.sender.try_send(pending).is_err() {
+            call.inner.metrics.record_dropped();
+        }
+    }
+}
+
+fn recorded_error(err: &jsonrpsee::types::ErrorObjectOwned) -> LoadRecordedError {
+    LoadRecordedError { code: err.code(), message: err.message().to_string() }
+}
+
+/// Marks which entries of a `getBlobs` response were served.
+pub fn blobs_served<T>(entries: &[Option<T>]) -> Value {
+    Value::Array(entries.iter().map(|entry| Value::Bool(entry.is_some())).collect())
+}
+
+/// Fills in the parameters and outcome of a record, summarizing blob data.
+fn complete(pending: PendingRecord) -> LoadEngineRecord {
+    let PendingRecord { mut record, params, outcome } = pending;
+    record.params = params.into_value();
+    match outcome {
+        RecordedOutcome::Result(value) => record.result = Some(value),
+        RecordedOutcome::Payload(json) => {
+            record.result = Some(serde_json::from_str(json.as_str()).unwrap_or(Value::Null));
+        }
+        RecordedOutcome::Error(err) => record.error = Some(err),
+    }
+    if record.method.starts_with("engine_getPayload") {
+        if let Some(Value::Object(envelope)) = &mut record.result {
+            if let Some(bundle) = envelope.remove("blobsBundle") {
+                let blobs = bundle.get("blobs").and_then(Value::as_array).map_or(0, Vec::len);
+                envelope.insert("blobCount".into(), blobs.into());
+            }
+        }
+        record.payload_id =
+            record.params.get(0).and_then(|id| serde_json::from_value(id.clone()).ok());
+    } else if record.method.starts_with("engine_forkchoiceUpdated") {
+        record.payload_id = record
+            .result
+            .as_ref()
+            .and_then(|result| result.get("payloadId"))
+            .and_then(|id| serde_json::from_value(id.clone()).ok());
+    }
+    record
+}
+
+/// Appends records to `engine-<index>.jsonl` files, rotating by size.
+#[derive(Debug)]
+struct RecordWriter {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    max_files: usize,
+    index: u64,
+    file: BufWriter<File>,
+    written: u64,
+}
+
+impl RecordWriter {
+    fn open(dir: PathBuf, max_file_bytes: u64, max_files: usize) -> eyre::Result<Self> {
+        fs::create_dir_all(&dir).wrap_err_with(|| format!("creating {}", dir.display()))?;
+        let index = recording_files(&dir)?.last().map_or(0, |(index, _)| index + 1);
+        let file = create_file(&dir, index)?;
+        let mut writer = Self { dir, max_file_bytes, max_files, index, file, written: 0 };
+        writer.remove_old_files()?;
+        Ok(writer)
+    }
+
+    fn run(&mut self, receiver: Receiver<PendingRecord>, metrics: &LoadEngineRecorderMetrics) {
+        while let Ok(pending) = receiver.recv() {
+            // Flush once the queue is drained, so a crash loses at most the calls in flight.
+            for pending in std::iter::once(pending).chain(receiver.try_iter()) {
+                match self.write(&complete(pending)) {
+                    Ok(()) => metrics.record_written(),
+                    Err(err) => {
+                        metrics.record_write_error();
+                        warn!(target: "load_reth::engine_recorder", %err, "Failed to record call");
+                    }
+                }
+            }
+            if let Err(err) = self.file.flush() {
+                metrics.record_write_error();
+                warn!(target: "load_reth::engine_recorder", %err, "Failed to flush recording");
+            }
+        }
+    }
+
+    fn write(&mut self, record: &LoadEngineRecord) -> eyre::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        if self.written > 0 && self.written + line.len() as u64 > self.max_file_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(&line)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> eyre::Result<()> {
+        self.file.flush()?;
+        self.index += 1;
+        self.file = create_file(&self.dir, self.index)?;
+        self.written = 0;
+        self.remove_old_files()
+    }
+
+    fn remove_old_files(&self) -> eyre::Result<()> {
+        let files = recording_files(&self.dir)?;
+        for (_, path) in files.iter().take(files.len().saturating_sub(self.max_files)) {
+            fs::remove_file(path).wrap_err_with(|| format!("removing {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+fn create_file(dir: &Path, index: u64) -> eyre::Result<BufWriter<File>> {
+    let path = dir.join(format!("{FILE_PREFIX}{index:010}{FILE_SUFFIX}"));
+    let file = File::create(&path).wrap_err_with(|| format!("creating {}", path.display()))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Recording files in `dir`, oldest first.
+pub fn recording_files(dir: &Path) -> eyre::Result<Vec<(u64, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).wrap_err_with(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        let index = path.file_name().and_then(|name| name.to_str()).and_then(|name| {
+            name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?.parse().ok()
+        });
+        if let Some(index) = index {
+            files.push((index, path));
+        }
+    }
+    files.sort_unstable();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn read_records(dir: &Path) -> Vec<LoadEngineRecord> {
+        recording_files(dir)
+            .unwrap()
+            .into_iter()
+            .flat_map(|(_, path)| std::io::BufReader::new(File::open(path).unwrap()).lines())
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect()
+    }
+
+    fn pending(seq: u64, method: &str, params: Value, outcome: RecordedOutcome) -> PendingRecord {
+        PendingRecord {
+            record: LoadEngineRecord {
+                seq,
+                started_at_ms: 0,
+                duration_us: 0,
+                method: method.to_string(),
+                params: Value::Null,
+                result: None,
+                error: None,
+                payload_id: None,
+            },
+            params: RecordedParams::Json(params),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn summarizes_blobs_and_extracts_payload_ids() {
+        let payload_id = PayloadId::new([1; 8]);
+        let fcu = complete(pending(
+            0,
+            "engine_forkchoiceUpdatedV3",
+            json!([{}, {}]),
+            RecordedOutcome::Result(json!({ "payloadStatus": {}, "payloadId": payload_id })),
+        ));
+        assert_eq!(fcu.payload_id, Some(payload_id));
+
+        let json = LoadPayloadJson::encode(&json!({
+            "executionPayload": { "blockHash": "0x01" },
+            "blobsBundle": { "commitments": [], "proofs": [], "blobs": ["0x00", "0x00"] },
+        }))
+        .unwrap();
+        let get_payload = complete(pending(
+            1,
+            "engine_getPayloadV4",
+            json!([payload_id]),
+            RecordedOutcome::Payload(json),
+        ));
+        assert_eq!(get_payload.payload_id, Some(payload_id));
+        let result = get_payload.result.unwrap();
+        assert!(result.get("blobsBundle").is_none());
+        assert_eq!(result["blobCount"], 2);
+
+        assert_eq!(blobs_served(&[Some(()), None]), json!([true, false]));
+    }
+
+    #[test]
+    fn rotates_and_keeps_the_newest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = RecordWriter::open(dir.path().to_path_buf(), 1, 2).unwrap();
+        for seq in 0..4 {
+            let record = complete(pending(
+                seq,
+                "engine_newPayloadV3",
+                json!([]),
+                RecordedOutcome::Error(LoadRecordedError { code: -1, message: "x".into() }),
+            ));
+            writer.write(&record).unwrap();
+        }
+        writer.file.flush().unwrap();
+
+        let files = recording_files(dir.path()).unwrap();
+        assert_eq!(files.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![2, 3]);
+        let records = read_records(dir.path());
+        assert_eq!(records.iter().map(|record| record.seq).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(records[0].error.as_ref().unwrap().code, -1);
+
+        // A restart continues after the newest file.
+        drop(writer);
+        let writer = RecordWriter::open(dir.path().to_path_buf(), 1, 2).unwrap();
+        assert_eq!(writer.index, 4);
+    }
+
+    #[test]
+    fn deferred_params_serialize_as_an_array() {
+        let payload_id = PayloadId::new([2; 8]);
+        let mut deferred = pending(
+            0,
+            "engine_newPayloadV3",
+            Value::Null,
+            RecordedOutcome::Result(json!({ "status": "VALID" })),
+        );
+        deferred.params = RecordedParams::Deferred(Box::new(move || {
+            serde_json::to_value((payload_id, vec![1u64], "0x01")).unwrap()
+        }));
+        assert_eq!(complete(deferred).params, json!([payload_id, [1], "0x01"]));
+    }
+
+    #[tokio::test]
+    async fn disabled_recorder_skips_params() {
+        let recorder = LoadEngineRecorder::default();
+        assert!(!recorder.is_enabled());
+        let call = recorder.begin("engine_newPayloadV3", || panic!("params evaluated"));
+        assert_eq!(call.record(async { RpcResult::Ok(1) }).await.unwrap(), 1);
+        let call = recorder
+            .begin_deferred("engine_newPayloadV3", || -> u64 { panic!("params evaluated") });
+        assert_eq!(call.record(async { RpcResult::Ok(1) }).await.unwrap(), 1);
+    }
+}
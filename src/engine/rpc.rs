@@ -3,65 +3,169 @@
 //! Wraps the upstream `EngineApi` to:
 //! - reuse Load payload types/builder (already configured in the node),
 //! - lift blob request limits to `LOAD_MAX_BLOB_COUNT`,
+//! - serve `engine_getPayloadV3/V4` from a cache of pre-serialized responses,
+//! - serve EIP-7594 cell-proof sidecars through `engine_getBlobsV2/V3` once Osaka is active,
+//! - archive the sidecars of `newPayload` blocks before finalization drops them from the pool,
+//! - fall back to the blob archive (`--load.blob-archive`) for blobs no longer in the pool,
+//! - accept block sidecars from the consensus layer through `engine_loadImportBlobsV1`,
+//! - record Engine API traffic for `load-reth engine replay` (`--load.engine-recorder`),
+//! - negotiate chain-spec derived `load.*` capabilities with the consensus client,
+//! - reject forkchoice updates that would undo finalized history,
 //! - keep a hook surface for future fork/attribute guards.
 
-use std::{
-    fmt,
-    sync::Arc,
-    time::{Instant, SystemTime, UNIX_EPOCH},
-};
+use std::{fmt, sync::Arc, time::Instant};
 
-use alloy_eips::{eip4844::BlobAndProofV2, eip7685::RequestsOrHash};
+use alloy_consensus::{constants::EIP4844_TX_TYPE_ID, BlockHeader};
+use alloy_eips::{
+    eip2718::Decodable2718,
+    eip4844::{env_settings::EnvKzgSettings, BlobAndProofV2},
+    eip7685::RequestsOrHash,
+};
 use alloy_primitives::B256;
 use alloy_rpc_types_engine::{
-    BlobAndProofV1, ExecutionPayloadBodiesV1, ExecutionPayloadInputV2, ExecutionPayloadV1,
-    ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, PayloadId, PayloadStatus,
+    BlobAndProofV1, BlobsBundleV1, ExecutionPayloadBodiesV1, ExecutionPayloadEnvelopeV3,
+    ExecutionPayloadEnvelopeV4, ExecutionPayloadInputV2, ExecutionPayloadV1, ExecutionPayloadV3,
+    ForkchoiceState, ForkchoiceUpdated, PayloadId, PayloadStatus,
 };
 use async_trait::async_trait;
-use jsonrpsee::{core::RpcResult, RpcModule};
+use eyre::OptionExt;
+use jsonrpsee::{
+    core::RpcResult,
+    types::{error::INTERNAL_ERROR_CODE, ErrorObjectOwned},
+    RpcModule,
+};
 use reth::{
     api::NodeTypes,
     payload::PayloadStore,
-    providers::{BlockReader, HeaderProvider, StateProviderFactory},
+    providers::{
+        BlockIdReader, BlockReader, CanonStateSubscriptions, HeaderProvider, ProviderError,
+        StateProviderFactory,
+    },
     rpc::api::IntoEngineApiRpcModule,
 };
 use reth_chainspec::EthereumHardforks;
 use reth_engine_primitives::{EngineApiValidator, EngineTypes};
+use reth_ethereum_engine_primitives::BuiltPayloadConversionError;
 use reth_network::NetworkInfo;
 use reth_node_api::{AddOnsContext, FullNodeComponents};
 use reth_node_builder::rpc::PayloadValidatorBuilder;
 use reth_node_core::version::{version_metadata, CLIENT_CODE};
-use reth_payload_primitives::{EngineObjectValidationError, PayloadTypes};
+use reth_payload_builder::{Events, PayloadEvents};
+use reth_payload_primitives::{BuiltPayload, EngineObjectValidationError, PayloadTypes};
 use reth_rpc_api::EngineApiServer;
 use reth_rpc_engine_api::{EngineApi, EngineApiError, EngineCapabilities};
 use reth_transaction_pool::TransactionPool;
-use tracing::trace;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, trace, warn};
 
 use crate::{
-    chainspec::{LoadChainSpec, LOAD_MAX_BLOB_COUNT},
-    engine::payload::{LoadExecutionData, LoadPayloadAttributes},
+    archive::{LoadBlobArchive, LoadBlobArchiveConfig, LoadBlobArchiveSlot},
+    chainspec::{LoadChainSpec, LOAD_MAX_BLOB_COUNT, LOAD_SLOT_SECS},
+    engine::{
+        blob_import::{
+            block_versioned_hashes, verify_bundle, LoadImportBlobsError, LoadImportBlobsStatus,
+            LOAD_IMPORT_BLOBS_V1,
+        },
+        capabilities::{LoadCapabilities, LoadCapabilityError, LoadCapabilityNegotiation},
+        finality::{LoadFinalityError, LoadFinalityGuard, LoadPayloadAncestry, LoadPayloadChain},
+        payload::{
+            validate_gas_limit_against_parent, LoadBuiltPayload, LoadExecutionData,
+            LoadPayloadAttributes,
+        },
+        payload_cache::{LoadPayloadJson, LoadPayloadResponseCache, LoadPayloadVersion},
+        recorder::{blobs_served, LoadEngineRecorder, LoadEngineRecorderConfig},
+    },
     metrics::LoadEngineRpcMetrics,
+    pool::LoadKzgSettingsSlot,
+    primitives::{LoadBlock, LoadPrimitives, LoadTransactionSigned},
     version::{load_client_version_entry, load_client_version_string},
 };
 
-const LOAD_CAP_BLOBS: &str = "load.blobs.1024";
-const LOAD_CAP_PREVRANDAO: &str = "load.prev_randao.0x01";
+const LOAD_CAP_FORCED_TRANSACTIONS: &str = "load.forced_transactions.v1";
+
+/// Engine API "Invalid payload attributes" error code, returned after a forkchoice update whose
+/// attributes pin an unreachable gas limit.
+pub const INVALID_PAYLOAD_ATTRIBUTES_CODE: i32 = -38003;
 
 /// Engine API builder specialized for Load.
 #[derive(Debug, Clone)]
 pub struct LoadEngineApiBuilder<PVB> {
     payload_validator_builder: PVB,
+    payload_cache: LoadPayloadResponseCache,
+    blob_archive: Option<LoadBlobArchiveConfig>,
+    blob_archive_slot: LoadBlobArchiveSlot,
+    kzg_settings: LoadKzgSettingsSlot,
+    engine_recorder: Option<LoadEngineRecorderConfig>,
+    require_capabilities: bool,
+    finality_guard: LoadFinalityGuard,
 }
 
 impl<PVB> LoadEngineApiBuilder<PVB> {
-    pub const fn new(payload_validator_builder: PVB) -> Self {
-        Self { payload_validator_builder }
+    pub fn new(payload_validator_builder: PVB) -> Self {
+        Self {
+            payload_validator_builder,
+            payload_cache: LoadPayloadResponseCache::default(),
+            blob_archive: None,
+            blob_archive_slot: LoadBlobArchiveSlot::default(),
+            kzg_settings: LoadKzgSettingsSlot::default(),
+            engine_recorder: None,
+            require_capabilities: false,
+            finality_guard: LoadFinalityGuard::default(),
+        }
+    }
+
+    /// Sets the cache used for pre-serialized `getPayload` responses.
+    pub fn with_payload_cache(mut self, payload_cache: LoadPayloadResponseCache) -> Self {
+        self.payload_cache = payload_cache;
+        self
+    }
+
+    /// Archives the sidecars of canonical blocks and serves `getBlobs` misses from the archive.
+    /// The opened archive is published to `slot` for other readers.
+    pub fn with_blob_archive(
+        mut self,
+        blob_archive: Option<LoadBlobArchiveConfig>,
+        slot: LoadBlobArchiveSlot,
+    ) -> Self {
+        self.blob_archive = blob_archive;
+        self.blob_archive_slot = slot;
+        self
+    }
+
+    /// Verifies `engine_loadImportBlobsV1` bundles with the KZG settings the pool publishes to
+    /// `kzg_settings`.
+    pub fn with_kzg_settings(mut self, kzg_settings: LoadKzgSettingsSlot) -> Self {
+        self.kzg_settings = kzg_settings;
+        self
+    }
+
+    /// Records Engine API traffic to a rotating log for `load-reth engine replay`.
+    pub fn with_engine_recorder(
+        mut self,
+        engine_recorder: Option<LoadEngineRecorderConfig>,
+    ) -> Self {
+        self.engine_recorder = engine_recorder;
+        self
+    }
+
+    /// Refuses payload building while the consensus client lacks the `load.*` capabilities
+    /// derived from the chain spec.
+    pub const fn with_required_capabilities(mut self, require_capabilities: bool) -> Self {
+        self.require_capabilities = require_capabilities;
+        self
+    }
+
+    /// Sets the guard applied to every forkchoice update.
+    pub const fn with_finality_guard(mut self, finality_guard: LoadFinalityGuard) -> Self {
+        self.finality_guard = finality_guard;
+        self
     }
 }
 
 impl<PVB: Default> Default for LoadEngineApiBuilder<PVB> {
     fn default() -> Self {
-        Self { payload_validator_builder: PVB::default() }
+        Self::new(PVB::default())
     }
 }
 
@@ -70,9 +174,11 @@ where
     N: FullNodeComponents<
         Types: NodeTypes<
             ChainSpec = LoadChainSpec,
+            Primitives = LoadPrimitives,
             Payload: PayloadTypes<
                 ExecutionData = LoadExecutionData,
                 PayloadAttributes = crate::engine::payload::LoadPayloadAttributes,
+                BuiltPayload = LoadBuiltPayload,
             > + EngineTypes,
         >,
     >,
@@ -92,9 +198,13 @@ where
             commit: version_metadata().vergen_git_sha.to_string(),
         };
 
+        let load_capabilities = LoadCapabilities::from_chain_spec(&ctx.config.chain);
         let mut capabilities = EngineCapabilities::default();
-        capabilities.add_capability(LOAD_CAP_BLOBS);
-        capabilities.add_capability(LOAD_CAP_PREVRANDAO);
+        for capability in load_capabilities.list() {
+            capabilities.add_capability(capability.clone());
+        }
+        capabilities.add_capability(LOAD_CAP_FORCED_TRANSACTIONS);
+        capabilities.add_capability(LOAD_IMPORT_BLOBS_V1);
 
         let inner = EngineApi::new(
             ctx.node.provider().clone(),
@@ -110,12 +220,82 @@ where
             ctx.node.network().clone(),
         );
 
+        let payload_events = ctx.node.payload_builder_handle().subscribe().await?;
+        ctx.node.task_executor().spawn(Box::pin(encode_resolved_payloads(
+            payload_events,
+            self.payload_cache.clone(),
+            ctx.config.chain.clone(),
+        )));
+
         let engine_metrics = Arc::new(LoadEngineRpcMetrics::new());
         let network = ctx.node.network().clone();
         let is_syncing = Arc::new(move || network.is_syncing());
 
+        let blob_archive = match self.blob_archive {
+            Some(config) => {
+                let dir = config
+                    .dir
+                    .unwrap_or_else(|| ctx.config.datadir().data_dir().join("blob-archive"));
+                let archive = LoadBlobArchive::open(dir, config.retention_blocks)?;
+                info!(
+                    target: "load_reth::blob_archive",
+                    dir = %archive.dir().display(),
+                    retention_blocks = config.retention_blocks,
+                    "Blob archive enabled"
+                );
+                let notifications = ctx.node.provider().subscribe_to_canonical_state();
+                ctx.node.task_executor().spawn(Box::pin(archive.clone().run(
+                    ctx.node.provider().clone(),
+                    notifications,
+                    ctx.node.pool().clone(),
+                )));
+                self.blob_archive_slot.set(archive.clone());
+                Some(archive)
+            }
+            None => None,
+        };
+
+        let recorder = match self.engine_recorder {
+            Some(config) => {
+                let dir = config
+                    .dir
+                    .unwrap_or_else(|| ctx.config.datadir().data_dir().join("engine-recordings"));
+                let recorder =
+                    LoadEngineRecorder::open(&dir, config.max_file_bytes, config.max_files)?;
+                info!(
+                    target: "load_reth::engine_recorder",
+                    dir = %dir.display(),
+                    max_file_bytes = config.max_file_bytes,
+                    max_files = config.max_files,
+                    "Engine API recorder enabled"
+                );
+                recorder
+            }
+            None => LoadEngineRecorder::disabled(),
+        };
+        let negotiation = LoadCapabilityNegotiation::new(load_capabilities)
+            .with_require(self.require_capabilities);
+        // The pool is built before the add-ons, so its settings are published by now.
+        let kzg_settings = self
+            .kzg_settings
+            .get()
+            .cloned()
+            .ok_or_eyre("transaction pool did not publish its KZG settings")?;
+
         // Wrap with Load-specific behaviour.
-        Ok(LoadEngineApi::new(inner, ctx.node.pool().clone(), engine_metrics, is_syncing))
+        Ok(LoadEngineApi::new(
+            inner,
+            ctx.node.provider().clone(),
+            ctx.node.pool().clone(),
+            engine_metrics,
+            is_syncing,
+            self.payload_cache,
+            blob_archive,
+        )
+        .with_recorder(recorder)
+        .with_capabilities(negotiation)
+        .with_finality_guard(self.finality_guard)
+        .with_kzg_settings(kzg_settings))
     }
 }
 
@@ -123,9 +303,18 @@ where
 #[derive(Clone)]
 pub struct LoadEngineApi<Provider, PayloadT: PayloadTypes, Pool, Validator> {
     inner: EngineApi<Provider, PayloadT, Pool, Validator, LoadChainSpec>,
+    provider: Provider,
     pool: Pool,
     metrics: Arc<LoadEngineRpcMetrics>,
     is_syncing: Arc<dyn Fn() -> bool + Send + Sync>,
+    payload_cache: LoadPayloadResponseCache,
+    blob_archive: Option<LoadBlobArchive>,
+    recorder: LoadEngineRecorder,
+    capabilities: LoadCapabilityNegotiation,
+    finality_guard: LoadFinalityGuard,
+    /// Blocks received through `newPayload`, for the finality guard.
+    payload_ancestry: LoadPayloadAncestry,
+    kzg_settings: EnvKzgSettings,
 }
 
 impl<Provider, PayloadT: PayloadTypes, Pool, Validator> fmt::Debug
@@ -139,16 +328,393 @@ impl<Provider, PayloadT: PayloadTypes, Pool, Validator> fmt::Debug
 impl<Provider, PayloadT: PayloadTypes, Pool, Validator>
     LoadEngineApi<Provider, PayloadT, Pool, Validator>
 {
-    pub const fn new(
+    pub fn new(
         inner: EngineApi<Provider, PayloadT, Pool, Validator, LoadChainSpec>,
+        provider: Provider,
         pool: Pool,
         metrics: Arc<LoadEngineRpcMetrics>,
         is_syncing: Arc<dyn Fn() -> bool + Send + Sync>,
+        payload_cache: LoadPayloadResponseCache,
+        blob_archive: Option<LoadBlobArchive>,
     ) -> Self {
-        Self { inner, pool, metrics, is_syncing }
+        Self {
+            inner,
+            provider,
+            pool,
+            metrics,
+            is_syncing,
+            payload_cache,
+            blob_archive,
+            recorder: LoadEngineRecorder::disabled(),
+            capabilities: LoadCapabilityNegotiation::default(),
+            finality_guard: LoadFinalityGuard::default(),
+            payload_ancestry: LoadPayloadAncestry::default(),
+            kzg_settings: EnvKzgSettings::Default,
+        }
+    }
+
+    /// Records the calls this API serves.
+    pub fn with_recorder(mut self, recorder: LoadEngineRecorder) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    /// Negotiates `load.*` capabilities with the consensus client.
+    pub fn with_capabilities(mut self, capabilities: LoadCapabilityNegotiation) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Rejects forkchoice updates that would undo finalized history.
+    pub const fn with_finality_guard(mut self, finality_guard: LoadFinalityGuard) -> Self {
+        self.finality_guard = finality_guard;
+        self
+    }
+
+    /// Verifies imported blob bundles with `kzg_settings`, normally the pool validator's.
+    pub fn with_kzg_settings(mut self, kzg_settings: EnvKzgSettings) -> Self {
+        self.kzg_settings = kzg_settings;
+        self
+    }
+
+    /// Records a `newPayload` block for the finality guard unless the engine found it invalid.
+    fn record_payload(&self, block: PayloadBlock, status: &PayloadStatus) {
+        if !status.status.is_invalid() {
+            let (number, hash, parent) = block;
+            self.payload_ancestry.record(number, hash, parent);
+        }
+    }
+
+    /// Returns why payload building is refused after the last capability exchange, or `None` if
+    /// building is allowed.
+    ///
+    /// A refused forkchoice update is still applied without its attributes before the error is
+    /// returned, so the node follows the chain while it refuses to build on it.
+    fn capability_build_refusal(&self) -> Option<LoadCapabilityError> {
+        self.capabilities
+            .ensure_can_build()
+            .inspect_err(|err| {
+                self.metrics.record_capability_refused_build();
+                warn!(target: "rpc::engine", %err, "Refusing payload build");
+            })
+            .err()
+    }
+}
+
+impl<Provider, PayloadT: PayloadTypes, Pool, Validator>
+    LoadEngineApi<Provider, PayloadT, Pool, Validator>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Archives the sidecars of a `newPayload` block unless it was invalid.
+    ///
+    /// Runs before the consensus client's forkchoice update, which on Load finalizes the block
+    /// and lets the pool drop its sidecars.
+    async fn archive_payload_sidecars(
+        &self,
+        payload: PayloadBlobTransactions,
+        status: &PayloadStatus,
+    ) {
+        let Some(archive) = &self.blob_archive else { return };
+        if status.status.is_invalid() {
+            return;
+        }
+        let (number, block_hash, transactions) = payload;
+        if let Err(err) =
+            archive.archive_payload(&self.pool, number, block_hash, &transactions).await
+        {
+            warn!(
+                target: "load_reth::blob_archive",
+                number,
+                %block_hash,
+                %err,
+                "Failed to archive payload sidecars"
+            );
+        }
+    }
+}
+
+impl<Provider, PayloadT: PayloadTypes, Pool, Validator>
+    LoadEngineApi<Provider, PayloadT, Pool, Validator>
+where
+    Provider: HeaderProvider + BlockIdReader,
+{
+    /// Applies the finality guard to a forkchoice state, resolving blocks the provider does not
+    /// know yet from the recorded `newPayload` blocks.
+    fn ensure_finality(&self, state: &ForkchoiceState) -> Result<(), LoadFinalityError> {
+        if let Some(finalized) = self.provider.finalized_block_num_hash()? {
+            self.payload_ancestry.prune(finalized.number);
+        }
+        let chain = LoadPayloadChain::new(&self.provider, &self.payload_ancestry);
+        self.finality_guard.check(&chain, state).inspect_err(|err| {
+            if let Some(kind) = err.kind() {
+                self.metrics.record_finality_violation(kind);
+                warn!(target: "rpc::engine", %err, "Rejecting forkchoice update");
+            }
+        })
+    }
+
+    /// Returns why a build on `head` is refused if the attributes pin a gas limit the head block
+    /// cannot reach in one step.
+    ///
+    /// Checked here rather than in the validator, which does not see the parent; without it the
+    /// forkchoice update would hand out a payload ID whose every build fails. Like a capability
+    /// refusal, the forkchoice update is still applied without its attributes.
+    fn gas_limit_build_refusal(
+        &self,
+        head: B256,
+        attributes: &LoadPayloadAttributes,
+    ) -> Result<Option<ErrorObjectOwned>, EngineApiError> {
+        let Some(gas_limit) = attributes.gas_limit() else {
+            return Ok(None);
+        };
+        // An unknown head is answered with `SYNCING` and starts no build.
+        let Some(parent) =
+            self.provider.header(head).map_err(|err| EngineApiError::Internal(Box::new(err)))?
+        else {
+            return Ok(None);
+        };
+        Ok(validate_gas_limit_against_parent(gas_limit, parent.gas_limit()).err().map(|err| {
+            let err = EngineObjectValidationError::InvalidParams(err.into());
+            warn!(target: "rpc::engine", %err, "Refusing payload build");
+            ErrorObjectOwned::owned(INVALID_PAYLOAD_ATTRIBUTES_CODE, err.to_string(), None::<()>)
+        }))
+    }
+}
+
+impl<Provider, EngineT, Pool, Validator> LoadEngineApi<Provider, EngineT, Pool, Validator>
+where
+    Provider: HeaderProvider + BlockReader + StateProviderFactory + 'static,
+    EngineT: EngineTypes<ExecutionData = LoadExecutionData>
+        + PayloadTypes<PayloadAttributes = LoadPayloadAttributes>,
+    Pool: TransactionPool + Clone + 'static,
+    Validator: EngineApiValidator<EngineT>,
+{
+    /// Fails with `UnsupportedFork` unless Osaka is active for the block built on the canonical
+    /// head, i.e. at the head timestamp plus [`LOAD_SLOT_SECS`].
+    ///
+    /// Gated on the chain rather than the local clock, so a node that is catching up or has a
+    /// skewed clock agrees with the blocks it serves. The CL fetches blobs for the block it is
+    /// about to propose, so the last pre-Osaka head already serves cell-proof sidecars.
+    fn ensure_osaka_for_next_block(&self) -> Result<(), EngineApiError> {
+        let internal = |err: ProviderError| EngineApiError::Internal(Box::new(err));
+        let number = self.provider.best_block_number().map_err(internal)?;
+        let head = self
+            .provider
+            .header_by_number(number)
+            .map_err(internal)?
+            .ok_or_else(|| internal(ProviderError::HeaderNotFound(number.into())))?;
+        let next_timestamp = head.timestamp().saturating_add(LOAD_SLOT_SECS);
+        if self.inner.chain_spec().is_osaka_active_at_timestamp(next_timestamp) {
+            Ok(())
+        } else {
+            Err(EngineApiError::EngineObjectValidationError(
+                EngineObjectValidationError::UnsupportedFork,
+            ))
+        }
+    }
+
+    /// Fills the entries the pool could not serve from the blob archive.
+    ///
+    /// Runs on the blocking pool since every miss is a disk read.
+    async fn fill_from_archive<T: Send + 'static>(
+        &self,
+        versioned_hashes: &[B256],
+        mut entries: Vec<Option<T>>,
+        lookup: fn(&LoadBlobArchive, &B256) -> Option<T>,
+    ) -> Result<Vec<Option<T>>, EngineApiError> {
+        let Some(archive) = self.blob_archive.clone() else { return Ok(entries) };
+        if entries.iter().all(Option::is_some) {
+            return Ok(entries);
+        }
+        let versioned_hashes = versioned_hashes.to_vec();
+        tokio::task::spawn_blocking(move || {
+            for (entry, versioned_hash) in entries.iter_mut().zip(&versioned_hashes) {
+                if entry.is_none() {
+                    *entry = lookup(&archive, versioned_hash);
+                }
+            }
+            entries
+        })
+        .await
+        .map_err(|err| EngineApiError::Internal(Box::new(err)))
+    }
+
+    /// Serves `getPayloadV3/V4` from the response cache, resolving and encoding on a miss.
+    async fn get_payload_json(
+        &self,
+        payload_id: PayloadId,
+        version: LoadPayloadVersion,
+    ) -> RpcResult<LoadPayloadJson> {
+        let call = self.recorder.begin(version.method(), || json!([payload_id]));
+        call.record_payload(async {
+            let start = Instant::now();
+            let json = self
+                .payload_cache
+                .get_or_encode(payload_id, version, || async move {
+                    match version {
+                        LoadPayloadVersion::V3 => {
+                            encode_envelope(self.inner.get_payload_v3_metered(payload_id).await?)
+                                .await
+                        }
+                        LoadPayloadVersion::V4 => {
+                            encode_envelope(self.inner.get_payload_v4_metered(payload_id).await?)
+                                .await
+                        }
+                    }
+                })
+                .await?;
+            self.metrics.record_get_payload(start.elapsed());
+            Ok(json)
+        })
+        .await
+    }
+}
+
+impl<Provider, EngineT, Pool, Validator> LoadEngineApi<Provider, EngineT, Pool, Validator>
+where
+    Provider: HeaderProvider + BlockReader<Block = LoadBlock> + StateProviderFactory + 'static,
+    EngineT: EngineTypes<ExecutionData = LoadExecutionData>
+        + PayloadTypes<PayloadAttributes = LoadPayloadAttributes>,
+    Pool: TransactionPool + Clone + 'static,
+    Validator: EngineApiValidator<EngineT>,
+{
+    /// Serves `engine_loadImportBlobsV1`: verifies the sidecars the consensus layer pushes for a
+    /// canonical block and adds them to the blob archive.
+    async fn import_blobs(
+        &self,
+        block_hash: B256,
+        bundle: BlobsBundleV1,
+    ) -> Result<LoadImportBlobsStatus, LoadImportBlobsError> {
+        let start = Instant::now();
+        let result = self.verify_and_archive(block_hash, bundle).await;
+        match &result {
+            Ok(imported) => self.metrics.record_import_blobs(*imported, start.elapsed()),
+            Err(err) => {
+                self.metrics.record_import_blobs_rejected();
+                debug!(target: "rpc::engine", %block_hash, %err, "Rejected blob import");
+            }
+        }
+        Ok(LoadImportBlobsStatus { imported: result? as u64 })
+    }
+
+    async fn verify_and_archive(
+        &self,
+        block_hash: B256,
+        bundle: BlobsBundleV1,
+    ) -> Result<usize, LoadImportBlobsError> {
+        let archive = self.blob_archive.clone().ok_or(LoadImportBlobsError::ArchiveDisabled)?;
+        let block = self
+            .provider
+            .block_by_hash(block_hash)?
+            .ok_or(LoadImportBlobsError::UnknownBlock(block_hash))?;
+        let number = block.header.number;
+        let osaka = self.inner.chain_spec().is_osaka_active_at_timestamp(block.header.timestamp);
+        let versioned_hashes = block_versioned_hashes(&block);
+        let kzg_settings = self.kzg_settings.clone();
+
+        // KZG verification of up to 1024 blobs and their disk writes.
+        tokio::task::spawn_blocking(move || {
+            let sidecar =
+                verify_bundle(block_hash, &versioned_hashes, bundle, osaka, &kzg_settings)?;
+            if versioned_hashes.is_empty() {
+                return Ok(0);
+            }
+            archive
+                .archive_block(number, block_hash, &[Arc::new(sidecar)])
+                .map_err(LoadImportBlobsError::Archive)
+        })
+        .await?
     }
 }
 
+/// Encodes the `getPayloadV3/V4` response of every payload as its job resolves, so the consensus
+/// client's `getPayload` is answered from the cache. Osaka payloads go out through
+/// `getPayloadV5`, which is not cached.
+async fn encode_resolved_payloads<T>(
+    mut events: PayloadEvents<T>,
+    cache: LoadPayloadResponseCache,
+    chain_spec: Arc<LoadChainSpec>,
+) where
+    T: PayloadTypes<BuiltPayload = LoadBuiltPayload>,
+{
+    let conversion = |err: BuiltPayloadConversionError| {
+        ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+    };
+    loop {
+        let payload = match events.receiver.recv().await {
+            Ok(Events::BuiltPayload(payload)) => payload,
+            Ok(Events::Attributes(_)) => continue,
+            // The getPayload call for a skipped payload encodes it itself.
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return,
+        };
+        let id = payload.id();
+        let timestamp = payload.block().timestamp();
+        let result = if chain_spec.is_osaka_active_at_timestamp(timestamp) {
+            continue;
+        } else if chain_spec.is_prague_active_at_timestamp(timestamp) {
+            cache
+                .prefill(id, LoadPayloadVersion::V4, || async move {
+                    encode_envelope(
+                        ExecutionPayloadEnvelopeV4::try_from(payload).map_err(conversion)?,
+                    )
+                    .await
+                })
+                .await
+        } else if chain_spec.is_cancun_active_at_timestamp(timestamp) {
+            cache
+                .prefill(id, LoadPayloadVersion::V3, || async move {
+                    encode_envelope(
+                        ExecutionPayloadEnvelopeV3::try_from(payload).map_err(conversion)?,
+                    )
+                    .await
+                })
+                .await
+        } else {
+            continue;
+        };
+        if let Err(err) = result {
+            debug!(target: "rpc::engine", %id, %err, "Failed to encode resolved payload");
+        }
+    }
+}
+
+/// Encodes an envelope on the blocking pool; a full blob envelope takes far too long for the
+/// RPC runtime.
+async fn encode_envelope<T>(envelope: T) -> Result<LoadPayloadJson, ErrorObjectOwned>
+where
+    T: serde::Serialize + Send + 'static,
+{
+    let internal = |err: String| ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err, None::<()>);
+    tokio::task::spawn_blocking(move || LoadPayloadJson::encode(&envelope))
+        .await
+        .map_err(|err| internal(err.to_string()))?
+        .map_err(|err| internal(err.to_string()))
+}
+
+/// Number, hash and parent hash of a `newPayload` block.
+type PayloadBlock = (u64, B256, B256);
+
+fn payload_block(payload: &ExecutionPayloadV1) -> PayloadBlock {
+    (payload.block_number, payload.block_hash, payload.parent_hash)
+}
+
+/// Number, hash and decoded blob transactions of a `newPayload` block.
+type PayloadBlobTransactions = (u64, B256, Vec<LoadTransactionSigned>);
+
+/// Decodes the blob transactions of a payload for archiving; other transactions are skipped.
+fn payload_blob_transactions(payload: &ExecutionPayloadV3) -> PayloadBlobTransactions {
+    let block = &payload.payload_inner.payload_inner;
+    let transactions = block
+        .transactions
+        .iter()
+        .filter(|raw| raw.first() == Some(&EIP4844_TX_TYPE_ID))
+        .filter_map(|raw| LoadTransactionSigned::decode_2718(&mut raw.as_ref()).ok())
+        .collect();
+    (block.block_number, block.block_hash, transactions)
+}
+
 fn validate_blob_request(versioned_hashes: &[B256]) -> Result<(), EngineApiError> {
     if versioned_hashes.len() > LOAD_MAX_BLOB_COUNT as usize {
         return Err(EngineApiError::BlobRequestTooLarge { len: versioned_hashes.len() });
@@ -176,26 +742,38 @@ fn payload_v3_prev_randao(payload: &ExecutionPayloadV3) -> B256 {
 impl<Provider, EngineT, Pool, Validator> EngineApiServer<EngineT>
     for LoadEngineApi<Provider, EngineT, Pool, Validator>
 where
-    Provider: HeaderProvider + BlockReader + StateProviderFactory + 'static,
+    Provider: HeaderProvider + BlockReader + BlockIdReader + StateProviderFactory + 'static,
     EngineT: EngineTypes<ExecutionData = LoadExecutionData>
         + PayloadTypes<PayloadAttributes = LoadPayloadAttributes>,
     Pool: TransactionPool + Clone + 'static,
     Validator: EngineApiValidator<EngineT>,
 {
     async fn new_payload_v1(&self, payload: ExecutionPayloadV1) -> RpcResult<PayloadStatus> {
-        let payload = LoadExecutionData::from(payload);
-        let start = Instant::now();
-        let result = self.inner.new_payload_v1_metered(payload).await?;
-        self.metrics.record_new_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin_deferred("engine_newPayloadV1", || (payload.clone(),));
+        call.record(async {
+            let block = payload_block(&payload);
+            let payload = LoadExecutionData::from(payload);
+            let start = Instant::now();
+            let result = self.inner.new_payload_v1_metered(payload).await?;
+            self.metrics.record_new_payload(start.elapsed());
+            self.record_payload(block, &result);
+            Ok(result)
+        })
+        .await
     }
 
     async fn new_payload_v2(&self, payload: ExecutionPayloadInputV2) -> RpcResult<PayloadStatus> {
-        let payload = LoadExecutionData::from(payload);
-        let start = Instant::now();
-        let result = self.inner.new_payload_v2_metered(payload).await?;
-        self.metrics.record_new_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin_deferred("engine_newPayloadV2", || (payload.clone(),));
+        call.record(async {
+            let block = payload_block(&payload.execution_payload);
+            let payload = LoadExecutionData::from(payload);
+            let start = Instant::now();
+            let result = self.inner.new_payload_v2_metered(payload).await?;
+            self.metrics.record_new_payload(start.elapsed());
+            self.record_payload(block, &result);
+            Ok(result)
+        })
+        .await
     }
 
     async fn new_payload_v3(
@@ -204,18 +782,31 @@ where
         versioned_hashes: Vec<B256>,
         parent_beacon_block_root: B256,
     ) -> RpcResult<PayloadStatus> {
-        if let Err(err) = validate_blob_request(&versioned_hashes) {
-            return Err(err.into());
-        }
-        let prev_randao = payload_v3_prev_randao(&payload);
-        if let Err(err) = ensure_load_prev_randao(&prev_randao) {
-            return Err(err.into());
-        }
-        let payload = LoadExecutionData::v3(payload, versioned_hashes, parent_beacon_block_root);
-        let start = Instant::now();
-        let result = self.inner.new_payload_v3_metered(payload).await?;
-        self.metrics.record_new_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin_deferred("engine_newPayloadV3", || {
+            (payload.clone(), versioned_hashes.clone(), parent_beacon_block_root)
+        });
+        call.record(async {
+            if let Err(err) = validate_blob_request(&versioned_hashes) {
+                return Err(err.into());
+            }
+            let prev_randao = payload_v3_prev_randao(&payload);
+            if let Err(err) = ensure_load_prev_randao(&prev_randao) {
+                return Err(err.into());
+            }
+            let block = payload_block(&payload.payload_inner.payload_inner);
+            let blob_txs = self.blob_archive.is_some().then(|| payload_blob_transactions(&payload));
+            let payload =
+                LoadExecutionData::v3(payload, versioned_hashes, parent_beacon_block_root);
+            let start = Instant::now();
+            let result = self.inner.new_payload_v3_metered(payload).await?;
+            self.metrics.record_new_payload(start.elapsed());
+            self.record_payload(block, &result);
+            if let Some(blob_txs) = blob_txs {
+                self.archive_payload_sidecars(blob_txs, &result).await;
+            }
+            Ok(result)
+        })
+        .await
     }
 
     async fn new_payload_v4(
@@ -225,23 +816,40 @@ where
         parent_beacon_block_root: B256,
         execution_requests: RequestsOrHash,
     ) -> RpcResult<PayloadStatus> {
-        if let Err(err) = validate_blob_request(&versioned_hashes) {
-            return Err(err.into());
-        }
-        let prev_randao = payload_v3_prev_randao(&payload);
-        if let Err(err) = ensure_load_prev_randao(&prev_randao) {
-            return Err(err.into());
-        }
-        let payload = LoadExecutionData::v4(
-            payload,
-            versioned_hashes,
-            parent_beacon_block_root,
-            execution_requests,
-        );
-        let start = Instant::now();
-        let result = self.inner.new_payload_v4_metered(payload).await?;
-        self.metrics.record_new_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin_deferred("engine_newPayloadV4", || {
+            (
+                payload.clone(),
+                versioned_hashes.clone(),
+                parent_beacon_block_root,
+                execution_requests.clone(),
+            )
+        });
+        call.record(async {
+            if let Err(err) = validate_blob_request(&versioned_hashes) {
+                return Err(err.into());
+            }
+            let prev_randao = payload_v3_prev_randao(&payload);
+            if let Err(err) = ensure_load_prev_randao(&prev_randao) {
+                return Err(err.into());
+            }
+            let block = payload_block(&payload.payload_inner.payload_inner);
+            let blob_txs = self.blob_archive.is_some().then(|| payload_blob_transactions(&payload));
+            let payload = LoadExecutionData::v4(
+                payload,
+                versioned_hashes,
+                parent_beacon_block_root,
+                execution_requests,
+            );
+            let start = Instant::now();
+            let result = self.inner.new_payload_v4_metered(payload).await?;
+            self.metrics.record_new_payload(start.elapsed());
+            self.record_payload(block, &result);
+            if let Some(blob_txs) = blob_txs {
+                self.archive_payload_sidecars(blob_txs, &result).await;
+            }
+            Ok(result)
+        })
+        .await
     }
 
     async fn fork_choice_updated_v1(
@@ -249,13 +857,30 @@ where
         fork_choice_state: ForkchoiceState,
         payload_attributes: Option<<EngineT as PayloadTypes>::PayloadAttributes>,
     ) -> RpcResult<ForkchoiceUpdated> {
-        let start = Instant::now();
-        let result = self
-            .inner
-            .fork_choice_updated_v1_metered(fork_choice_state, payload_attributes)
-            .await?;
-        self.metrics.record_forkchoice(start.elapsed());
-        Ok(result)
+        let call = self
+            .recorder
+            .begin("engine_forkchoiceUpdatedV1", || json!([fork_choice_state, payload_attributes]));
+        call.record(async {
+            self.ensure_finality(&fork_choice_state)?;
+            let mut refusal = None;
+            if let Some(attrs) = &payload_attributes {
+                refusal = self
+                    .gas_limit_build_refusal(fork_choice_state.head_block_hash, attrs)?
+                    .or_else(|| self.capability_build_refusal().map(Into::into));
+            }
+            let payload_attributes = payload_attributes.filter(|_| refusal.is_none());
+            let start = Instant::now();
+            let result = self
+                .inner
+                .fork_choice_updated_v1_metered(fork_choice_state, payload_attributes)
+                .await?;
+            self.metrics.record_forkchoice(start.elapsed());
+            match refusal {
+                Some(err) => Err(err),
+                None => Ok(result),
+            }
+        })
+        .await
     }
 
     async fn fork_choice_updated_v2(
@@ -263,13 +888,30 @@ where
         fork_choice_state: ForkchoiceState,
         payload_attributes: Option<<EngineT as PayloadTypes>::PayloadAttributes>,
     ) -> RpcResult<ForkchoiceUpdated> {
-        let start = Instant::now();
-        let result = self
-            .inner
-            .fork_choice_updated_v2_metered(fork_choice_state, payload_attributes)
-            .await?;
-        self.metrics.record_forkchoice(start.elapsed());
-        Ok(result)
+        let call = self
+            .recorder
+            .begin("engine_forkchoiceUpdatedV2", || json!([fork_choice_state, payload_attributes]));
+        call.record(async {
+            self.ensure_finality(&fork_choice_state)?;
+            let mut refusal = None;
+            if let Some(attrs) = &payload_attributes {
+                refusal = self
+                    .gas_limit_build_refusal(fork_choice_state.head_block_hash, attrs)?
+                    .or_else(|| self.capability_build_refusal().map(Into::into));
+            }
+            let payload_attributes = payload_attributes.filter(|_| refusal.is_none());
+            let start = Instant::now();
+            let result = self
+                .inner
+                .fork_choice_updated_v2_metered(fork_choice_state, payload_attributes)
+                .await?;
+            self.metrics.record_forkchoice(start.elapsed());
+            match refusal {
+                Some(err) => Err(err),
+                None => Ok(result),
+            }
+        })
+        .await
     }
 
     async fn fork_choice_updated_v3(
@@ -277,70 +919,107 @@ where
         fork_choice_state: ForkchoiceState,
         payload_attributes: Option<<EngineT as PayloadTypes>::PayloadAttributes>,
     ) -> RpcResult<ForkchoiceUpdated> {
-        if let Some(attrs) = &payload_attributes {
-            let prev_randao = attrs.prev_randao();
-            if let Err(err) = ensure_load_prev_randao(&prev_randao) {
-                return Err(err.into());
+        let call = self
+            .recorder
+            .begin("engine_forkchoiceUpdatedV3", || json!([fork_choice_state, payload_attributes]));
+        call.record(async {
+            self.ensure_finality(&fork_choice_state)?;
+            let mut refusal = None;
+            if let Some(attrs) = &payload_attributes {
+                let prev_randao = attrs.prev_randao();
+                if let Err(err) = ensure_load_prev_randao(&prev_randao) {
+                    return Err(err.into());
+                }
+                refusal = self
+                    .gas_limit_build_refusal(fork_choice_state.head_block_hash, attrs)?
+                    .or_else(|| self.capability_build_refusal().map(Into::into));
             }
-        }
 
-        let start = Instant::now();
-        let result = self
-            .inner
-            .fork_choice_updated_v3_metered(fork_choice_state, payload_attributes)
-            .await?;
-        self.metrics.record_forkchoice(start.elapsed());
-        Ok(result)
+            let payload_attributes = payload_attributes.filter(|_| refusal.is_none());
+            let start = Instant::now();
+            let result = self
+                .inner
+                .fork_choice_updated_v3_metered(fork_choice_state, payload_attributes)
+                .await?;
+            self.metrics.record_forkchoice(start.elapsed());
+            match refusal {
+                Some(err) => Err(err),
+                None => Ok(result),
+            }
+        })
+        .await
     }
 
     async fn get_payload_v1(
         &self,
         payload_id: PayloadId,
     ) -> RpcResult<EngineT::ExecutionPayloadEnvelopeV1> {
-        let start = Instant::now();
-        let result = self.inner.get_payload_v1_metered(payload_id).await?;
-        self.metrics.record_get_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin("engine_getPayloadV1", || json!([payload_id]));
+        call.record(async {
+            let start = Instant::now();
+            let result = self.inner.get_payload_v1_metered(payload_id).await?;
+            self.metrics.record_get_payload(start.elapsed());
+            Ok(result)
+        })
+        .await
     }
 
     async fn get_payload_v2(
         &self,
         payload_id: PayloadId,
     ) -> RpcResult<EngineT::ExecutionPayloadEnvelopeV2> {
-        let start = Instant::now();
-        let result = self.inner.get_payload_v2_metered(payload_id).await?;
-        self.metrics.record_get_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin("engine_getPayloadV2", || json!([payload_id]));
+        call.record(async {
+            let start = Instant::now();
+            let result = self.inner.get_payload_v2_metered(payload_id).await?;
+            self.metrics.record_get_payload(start.elapsed());
+            Ok(result)
+        })
+        .await
     }
 
+    // `into_rpc_module` routes `engine_getPayloadV3/V4` to `get_payload_json`; these typed
+    // handlers are kept for the trait and uncached callers.
     async fn get_payload_v3(
         &self,
         payload_id: PayloadId,
     ) -> RpcResult<EngineT::ExecutionPayloadEnvelopeV3> {
-        let start = Instant::now();
-        let result = self.inner.get_payload_v3_metered(payload_id).await?;
-        self.metrics.record_get_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin("engine_getPayloadV3", || json!([payload_id]));
+        call.record(async {
+            let start = Instant::now();
+            let result = self.inner.get_payload_v3_metered(payload_id).await?;
+            self.metrics.record_get_payload(start.elapsed());
+            Ok(result)
+        })
+        .await
     }
 
     async fn get_payload_v4(
         &self,
         payload_id: PayloadId,
     ) -> RpcResult<EngineT::ExecutionPayloadEnvelopeV4> {
-        let start = Instant::now();
-        let result = self.inner.get_payload_v4_metered(payload_id).await?;
-        self.metrics.record_get_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin("engine_getPayloadV4", || json!([payload_id]));
+        call.record(async {
+            let start = Instant::now();
+            let result = self.inner.get_payload_v4_metered(payload_id).await?;
+            self.metrics.record_get_payload(start.elapsed());
+            Ok(result)
+        })
+        .await
     }
 
     async fn get_payload_v5(
         &self,
         payload_id: PayloadId,
     ) -> RpcResult<EngineT::ExecutionPayloadEnvelopeV5> {
-        let start = Instant::now();
-        let result = self.inner.get_payload_v5_metered(payload_id).await?;
-        self.metrics.record_get_payload(start.elapsed());
-        Ok(result)
+        let call = self.recorder.begin("engine_getPayloadV5", || json!([payload_id]));
+        call.record(async {
+            let start = Instant::now();
+            let result = self.inner.get_payload_v5_metered(payload_id).await?;
+            self.metrics.record_get_payload(start.elapsed());
+            Ok(result)
+        })
+        .await
     }
 
     async fn get_payload_bodies_by_hash_v1(
@@ -368,14 +1047,24 @@ where
     }
 
     async fn exchange_capabilities(&self, capabilities: Vec<String>) -> RpcResult<Vec<String>> {
+        match self.capabilities.record(capabilities) {
+            Ok(()) => {
+                self.metrics.record_capability_exchange(true);
+                debug!(target: "rpc::engine", "Consensus client capabilities match the chain spec");
+            }
+            Err(err) => {
+                self.metrics.record_capability_exchange(false);
+                error!(
+                    target: "rpc::engine",
+                    %err,
+                    required = ?self.capabilities.capabilities().list(),
+                    "Consensus client is not compatible with this Load chain spec"
+                );
+            }
+        }
+        // The Load capabilities were added when the inner API was built.
         let mut caps = self.inner.capabilities().clone();
-        caps.add_capability(LOAD_CAP_BLOBS);
-        caps.add_capability(LOAD_CAP_PREVRANDAO);
         caps.add_capability(load_client_version_string().to_string());
-        // Echo peer capabilities plus ours.
-        for cap in capabilities {
-            caps.add_capability(cap);
-        }
         Ok(caps.list())
     }
 
@@ -384,48 +1073,74 @@ where
         versioned_hashes: Vec<B256>,
     ) -> RpcResult<Vec<Option<BlobAndProofV1>>> {
         trace!(target: "rpc::engine", "Serving engine_getBlobsV1 (Load)");
-        if let Err(err) = validate_blob_request(&versioned_hashes) {
-            return Err(err.into());
-        }
+        let call = self.recorder.begin("engine_getBlobsV1", || json!([versioned_hashes]));
+        call.record_with(
+            async {
+                if let Err(err) = validate_blob_request(&versioned_hashes) {
+                    return Err(err.into());
+                }
 
-        let blobs = match self.pool.get_blobs_for_versioned_hashes_v1(&versioned_hashes) {
-            Ok(blobs) => blobs,
-            Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
-        };
-        let hits = blobs.iter().filter(|entry| entry.is_some()).count() as u64;
-        let misses = blobs.len() as u64 - hits;
-        self.metrics.record_get_blobs(hits, misses);
-        Ok(blobs)
+                let blobs = match self.pool.get_blobs_for_versioned_hashes_v1(&versioned_hashes) {
+                    Ok(blobs) => blobs,
+                    Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
+                };
+                let blobs = self
+                    .fill_from_archive(&versioned_hashes, blobs, LoadBlobArchive::blob_v1)
+                    .await?;
+                let hits = blobs.iter().filter(|entry| entry.is_some()).count() as u64;
+                let misses = blobs.len() as u64 - hits;
+                self.metrics.record_get_blobs(hits, misses);
+                Ok(blobs)
+            },
+            |blobs| blobs_served(blobs),
+        )
+        .await
     }
 
+    /// Post-Osaka (at the canonical head) only; the pool stores cell-proof sidecars from Osaka on,
+    /// so every hash a pending or recently included blob transaction carries resolves here.
     async fn get_blobs_v2(
         &self,
         versioned_hashes: Vec<B256>,
     ) -> RpcResult<Option<Vec<BlobAndProofV2>>> {
         trace!(target: "rpc::engine", "Serving engine_getBlobsV2 (Load)");
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
-        if !self.inner.chain_spec().is_osaka_active_at_timestamp(now) {
-            return Err(EngineApiError::EngineObjectValidationError(
-                EngineObjectValidationError::UnsupportedFork,
-            )
-            .into());
-        }
-        if let Err(err) = validate_blob_request(&versioned_hashes) {
-            return Err(err.into());
-        }
+        let call = self.recorder.begin("engine_getBlobsV2", || json!([versioned_hashes]));
+        call.record_with(
+            async {
+                self.ensure_osaka_for_next_block()?;
+                if let Err(err) = validate_blob_request(&versioned_hashes) {
+                    return Err(err.into());
+                }
 
-        let blobs = match self.pool.get_blobs_for_versioned_hashes_v2(&versioned_hashes) {
-            Ok(blobs) => blobs,
-            Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
-        };
-        if let Some(entries) = &blobs {
-            let hits = entries.len() as u64;
-            let misses = versioned_hashes.len() as u64 - hits;
-            self.metrics.record_get_blobs(hits, misses);
-        } else {
-            self.metrics.record_get_blobs(0, versioned_hashes.len() as u64);
-        }
-        Ok(blobs)
+                let blobs = if self.blob_archive.is_some() {
+                    // All or nothing, like the pool lookup, but with archive hits counting.
+                    let entries =
+                        match self.pool.get_blobs_for_versioned_hashes_v3(&versioned_hashes) {
+                            Ok(entries) => entries,
+                            Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
+                        };
+                    self.fill_from_archive(&versioned_hashes, entries, LoadBlobArchive::blob_v2)
+                        .await?
+                        .into_iter()
+                        .collect()
+                } else {
+                    match self.pool.get_blobs_for_versioned_hashes_v2(&versioned_hashes) {
+                        Ok(blobs) => blobs,
+                        Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
+                    }
+                };
+                if let Some(entries) = &blobs {
+                    let hits = entries.len() as u64;
+                    let misses = versioned_hashes.len() as u64 - hits;
+                    self.metrics.record_get_blobs(hits, misses);
+                } else {
+                    self.metrics.record_get_blobs(0, versioned_hashes.len() as u64);
+                }
+                Ok(blobs)
+            },
+            |blobs| json!(blobs.is_some()),
+        )
+        .await
     }
 
     async fn get_blobs_v3(
@@ -433,39 +1148,65 @@ where
         versioned_hashes: Vec<B256>,
     ) -> RpcResult<Option<Vec<Option<BlobAndProofV2>>>> {
         trace!(target: "rpc::engine", "Serving engine_getBlobsV3 (Load)");
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
-        if !self.inner.chain_spec().is_osaka_active_at_timestamp(now) {
-            return Err(EngineApiError::EngineObjectValidationError(
-                EngineObjectValidationError::UnsupportedFork,
-            )
-            .into());
-        }
-        if let Err(err) = validate_blob_request(&versioned_hashes) {
-            return Err(err.into());
-        }
-        if (self.is_syncing)() {
-            return Ok(None);
-        }
+        let call = self.recorder.begin("engine_getBlobsV3", || json!([versioned_hashes]));
+        call.record_with(
+            async {
+                self.ensure_osaka_for_next_block()?;
+                if let Err(err) = validate_blob_request(&versioned_hashes) {
+                    return Err(err.into());
+                }
+                if (self.is_syncing)() {
+                    return Ok(None);
+                }
 
-        let blobs = match self.pool.get_blobs_for_versioned_hashes_v3(&versioned_hashes) {
-            Ok(blobs) => blobs,
-            Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
-        };
-        let hits = blobs.iter().filter(|entry| entry.is_some()).count() as u64;
-        let misses = blobs.len() as u64 - hits;
-        self.metrics.record_get_blobs(hits, misses);
-        Ok(Some(blobs))
+                let blobs = match self.pool.get_blobs_for_versioned_hashes_v3(&versioned_hashes) {
+                    Ok(blobs) => blobs,
+                    Err(err) => return Err(EngineApiError::Internal(Box::new(err)).into()),
+                };
+                let blobs = self
+                    .fill_from_archive(&versioned_hashes, blobs, LoadBlobArchive::blob_v2)
+                    .await?;
+                let hits = blobs.iter().filter(|entry| entry.is_some()).count() as u64;
+                let misses = blobs.len() as u64 - hits;
+                self.metrics.record_get_blobs(hits, misses);
+                Ok(Some(blobs))
+            },
+            |blobs| blobs.as_deref().map_or(Value::Null, blobs_served),
+        )
+        .await
     }
 }
 
-impl<Provider, PayloadT, Pool, Validator> IntoEngineApiRpcModule
-    for LoadEngineApi<Provider, PayloadT, Pool, Validator>
+impl<Provider, EngineT, Pool, Validator> IntoEngineApiRpcModule
+    for LoadEngineApi<Provider, EngineT, Pool, Validator>
 where
-    PayloadT: PayloadTypes + EngineTypes,
-    Self: EngineApiServer<PayloadT>,
+    Provider: HeaderProvider + BlockReader<Block = LoadBlock> + StateProviderFactory + 'static,
+    EngineT: EngineTypes<ExecutionData = LoadExecutionData>
+        + PayloadTypes<PayloadAttributes = LoadPayloadAttributes>,
+    Pool: TransactionPool + Clone + 'static,
+    Validator: EngineApiValidator<EngineT>,
+    Self: EngineApiServer<EngineT>,
 {
     fn into_rpc_module(self) -> RpcModule<()> {
-        self.into_rpc().remove_context()
+        let mut module = self.into_rpc();
+        for version in [LoadPayloadVersion::V3, LoadPayloadVersion::V4] {
+            module.remove_method(version.method());
+            module
+                .register_async_method(version.method(), move |params, api, _| async move {
+                    let payload_id = params.one::<PayloadId>()?;
+                    api.get_payload_json(payload_id, version).await
+                })
+                .expect("getPayload handler was just removed");
+        }
+        module
+            .register_async_method(LOAD_IMPORT_BLOBS_V1, |params, api, _| async move {
+                let mut params = params.sequence();
+                let block_hash = params.next::<B256>()?;
+                let bundle = params.next::<BlobsBundleV1>()?;
+                Ok::<_, ErrorObjectOwned>(api.import_blobs(block_hash, bundle).await?)
+            })
+            .expect("engine_loadImportBlobsV1 is not an upstream method");
+        module.remove_context()
     }
 }
 
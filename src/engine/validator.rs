@@ -2,13 +2,22 @@
 //!
 //! Wraps the upstream Ethereum validator and enforces Load invariants on payload attributes:
 //! - `prev_randao` must equal `LOAD_PREVRANDAO`.
+//! - CL-provided `extraData` must fit in a header (32 bytes); any compliant value is accepted.
+//! - `suggested_fee_recipient` must satisfy the operator's fee-recipient policy.
+//! - A CL-pinned `gasLimit` must lie between the protocol minimum and the chain-spec max.
+//! - CL-forced transactions must fit in a block. They are decoded and sender-recovered only once,
+//!   when the payload job's attributes are built.
+//! - Imported blocks must fit the Load max RLP block size at every fork, not only after Osaka.
 //! - Fork-specific field validation remains delegated to the upstream validator.
 
 use std::sync::Arc;
 
+use alloy_primitives::Bytes;
+use alloy_rlp::Encodable;
 use eyre::eyre;
 #[cfg(test)]
 use reth::chainspec::{EthereumHardfork, ForkCondition};
+use reth::consensus::ConsensusError;
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_engine_primitives::{EngineApiValidator, EngineTypes, PayloadValidator};
 use reth_ethereum_payload_builder::EthereumExecutionPayloadValidator;
@@ -24,8 +33,14 @@ use reth_primitives_traits::SealedBlock;
 #[cfg(test)]
 use crate::chainspec::LOAD_EXECUTION_GAS_LIMIT;
 use crate::{
-    chainspec::LOAD_MAX_BLOB_COUNT,
-    engine::payload::{LoadExecutionData, LoadPayloadAttributes},
+    chainspec::{LoadChainParams, LOAD_MAX_BLOB_COUNT},
+    engine::{
+        builder::LOAD_BLOCK_SIZE_MARGIN,
+        fee_recipient::LoadFeeRecipientPolicy,
+        payload::{
+            validate_extra_data, validate_gas_limit, LoadExecutionData, LoadPayloadAttributes,
+        },
+    },
     LOAD_PREVRANDAO,
 };
 
@@ -33,17 +48,28 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct LoadEngineValidator<ChainSpec = reth_chainspec::ChainSpec> {
     inner: EthereumExecutionPayloadValidator<ChainSpec>,
+    fee_recipient_policy: LoadFeeRecipientPolicy,
 }
 
 /// Builder for the Load engine validator (used by node add-ons).
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct LoadEngineValidatorBuilder;
+pub struct LoadEngineValidatorBuilder {
+    fee_recipient_policy: LoadFeeRecipientPolicy,
+}
+
+impl LoadEngineValidatorBuilder {
+    /// Sets the fee-recipient policy enforced on payload attributes.
+    pub fn with_fee_recipient_policy(mut self, policy: LoadFeeRecipientPolicy) -> Self {
+        self.fee_recipient_policy = policy;
+        self
+    }
+}
 
 impl<Node, Types> PayloadValidatorBuilder<Node> for LoadEngineValidatorBuilder
 where
     Types: NodeTypes<
-        ChainSpec: EthChainSpec + EthereumHardforks + Clone + 'static,
+        ChainSpec: EthChainSpec + EthereumHardforks + LoadChainParams + Clone + 'static,
         Payload: EngineTypes<ExecutionData = LoadExecutionData> + PayloadTypes,
     >,
     Node: FullNodeComponents<Types = Types>,
@@ -51,13 +77,23 @@ where
     type Validator = LoadEngineValidator<Types::ChainSpec>;
 
     async fn build(self, ctx: &AddOnsContext<'_, Node>) -> eyre::Result<Self::Validator> {
-        Ok(LoadEngineValidator::new(ctx.config.chain.clone()))
+        Ok(LoadEngineValidator::new(ctx.config.chain.clone())
+            .with_fee_recipient_policy(self.fee_recipient_policy))
     }
 }
 
-impl<ChainSpec: EthereumHardforks> LoadEngineValidator<ChainSpec> {
-    pub const fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { inner: EthereumExecutionPayloadValidator::new(chain_spec) }
+impl<ChainSpec: EthereumHardforks + LoadChainParams> LoadEngineValidator<ChainSpec> {
+    pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
+        Self {
+            inner: EthereumExecutionPayloadValidator::new(chain_spec),
+            fee_recipient_policy: LoadFeeRecipientPolicy::default(),
+        }
+    }
+
+    /// Sets the fee-recipient policy enforced on payload attributes.
+    pub fn with_fee_recipient_policy(mut self, policy: LoadFeeRecipientPolicy) -> Self {
+        self.fee_recipient_policy = policy;
+        self
     }
 
     #[inline]
@@ -103,11 +139,56 @@ impl<ChainSpec: EthereumHardforks> LoadEngineValidator<ChainSpec> {
 
         Ok(())
     }
+
+    fn ensure_load_attributes_invariants(
+        &self,
+        attributes: &LoadPayloadAttributes,
+    ) -> Result<(), EngineObjectValidationError> {
+        if attributes.prev_randao().as_slice() != LOAD_PREVRANDAO {
+            return Err(EngineObjectValidationError::InvalidParams(
+                eyre!("prev_randao must be constant 0x01 for Load").into(),
+            ));
+        }
+
+        if let Some(extra_data) = attributes.extra_data() {
+            validate_extra_data(extra_data)
+                .map_err(|err| EngineObjectValidationError::InvalidParams(err.into()))?;
+        }
+
+        self.fee_recipient_policy
+            .check(attributes.suggested_fee_recipient())
+            .map_err(|err| EngineObjectValidationError::InvalidParams(err.into()))?;
+
+        if let Some(gas_limit) = attributes.gas_limit() {
+            validate_gas_limit(gas_limit, self.chain_spec().max_gas_limit())
+                .map_err(|err| EngineObjectValidationError::InvalidParams(err.into()))?;
+        }
+
+        if let Some(transactions) = attributes.transactions() {
+            // Leave the same room for withdrawals and the header as the builder does, so a list
+            // accepted here is not skipped by every build.
+            let encoded_len: usize = transactions.iter().map(Bytes::len).sum::<usize>() +
+                attributes.withdrawals().map_or(0, Encodable::length) +
+                LOAD_BLOCK_SIZE_MARGIN;
+            let max_rlp_block_size =
+                self.chain_spec().max_rlp_block_size_at_timestamp(attributes.timestamp());
+            if encoded_len > max_rlp_block_size {
+                return Err(EngineObjectValidationError::InvalidParams(
+                    eyre!(
+                        "forced transactions need {encoded_len} block bytes (block limit {max_rlp_block_size})"
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<ChainSpec, Types> PayloadValidator<Types> for LoadEngineValidator<ChainSpec>
 where
-    ChainSpec: EthChainSpec + EthereumHardforks + 'static,
+    ChainSpec: EthChainSpec + EthereumHardforks + LoadChainParams + 'static,
     Types: PayloadTypes<ExecutionData = LoadExecutionData>,
 {
     type Block = Block;
@@ -117,13 +198,28 @@ where
         payload: LoadExecutionData,
     ) -> Result<SealedBlock<Self::Block>, NewPayloadError> {
         self.ensure_load_payload_invariants(&payload).map_err(NewPayloadError::other)?;
-        self.inner.ensure_well_formed_payload(payload.into()).map_err(Into::into)
+        let block = self.inner.ensure_well_formed_payload(payload.into())?;
+
+        // Blob sidecars are not part of the block body, so only the block RLP counts here. Chains
+        // that predate the limit may delay it for imports with `loadMaxRlpBlockSizeTime`.
+        let timestamp = block.header().timestamp;
+        let max_rlp_length = self.chain_spec().max_rlp_block_size_at_timestamp(timestamp);
+        if self.chain_spec().is_max_rlp_block_size_enforced_at_timestamp(timestamp) &&
+            block.rlp_length() > max_rlp_length
+        {
+            return Err(NewPayloadError::other(ConsensusError::BlockTooLarge {
+                rlp_length: block.rlp_length(),
+                max_rlp_length,
+            }));
+        }
+
+        Ok(block)
     }
 }
 
 impl<ChainSpec, Types> EngineApiValidator<Types> for LoadEngineValidator<ChainSpec>
 where
-    ChainSpec: EthChainSpec + EthereumHardforks + 'static,
+    ChainSpec: EthChainSpec + EthereumHardforks + LoadChainParams + 'static,
     Types:
         PayloadTypes<PayloadAttributes = LoadPayloadAttributes, ExecutionData = LoadExecutionData>,
 {
@@ -138,13 +234,9 @@ where
             self.ensure_load_payload_invariants(payload)?;
         }
 
-        // Enforce prev_randao invariant on attributes.
+        // Enforce prev_randao / extraData invariants on attributes.
         if let PayloadOrAttributes::PayloadAttributes(attrs) = &payload_or_attrs {
-            if attrs.prev_randao().as_slice() != LOAD_PREVRANDAO {
-                return Err(EngineObjectValidationError::InvalidParams(
-                    eyre!("prev_randao must be constant 0x01 for Load").into(),
-                ));
-            }
+            self.ensure_load_attributes_invariants(attrs)?;
         }
 
         reth_payload_primitives::validate_version_specific_fields(
@@ -159,11 +251,7 @@ where
         version: EngineApiMessageVersion,
         attributes: &LoadPayloadAttributes,
     ) -> Result<(), EngineObjectValidationError> {
-        if attributes.prev_randao().as_slice() != LOAD_PREVRANDAO {
-            return Err(EngineObjectValidationError::InvalidParams(
-                eyre!("prev_randao must be constant 0x01 for Load").into(),
-            ));
-        }
+        self.ensure_load_attributes_invariants(attributes)?;
 
         reth_payload_primitives::validate_version_specific_fields(
             self.chain_spec(),
@@ -179,8 +267,11 @@ where
 mod tests {
     use std::sync::Arc;
 
-    use alloy_eips::eip7685::{Requests, RequestsOrHash};
-    use alloy_primitives::{Address, Bloom, Bytes, U256};
+    use alloy_eips::{
+        eip4895::Withdrawal,
+        eip7685::{Requests, RequestsOrHash},
+    };
+    use alloy_primitives::{Address, Bloom, U256};
     use alloy_rpc_types_engine::{
         CancunPayloadFields, ExecutionPayload, ExecutionPayloadSidecar, ExecutionPayloadV1,
         ExecutionPayloadV2, ExecutionPayloadV3,
@@ -376,6 +467,131 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn attributes_accept_compliant_extra_data_and_reject_oversized() {
+        let rpc_attrs = alloy_rpc_types_engine::PayloadAttributes {
+            timestamp: 1,
+            prev_randao: alloy_primitives::B256::from(crate::LOAD_PREVRANDAO),
+            suggested_fee_recipient: Address::ZERO,
+            withdrawals: Some(Vec::new()),
+            parent_beacon_block_root: Some(alloy_primitives::B256::ZERO),
+        };
+        let validator = LoadEngineValidator::new(Arc::new(LoadChainSpec::default()));
+
+        let compliant = LoadPayloadAttributes {
+            extra_data: Some(Bytes::from(vec![0xab; 32])),
+            ..LoadPayloadAttributes::from(rpc_attrs.clone())
+        };
+        let result = <LoadEngineValidator<LoadChainSpec> as EngineApiValidator<
+            LoadEngineTypes,
+        >>::ensure_well_formed_attributes(
+            &validator, EngineApiMessageVersion::V3, &compliant
+        );
+        assert!(result.is_ok(), "32-byte extraData must be accepted: {result:?}");
+
+        let oversized = LoadPayloadAttributes {
+            extra_data: Some(Bytes::from(vec![0xab; 33])),
+            ..LoadPayloadAttributes::from(rpc_attrs)
+        };
+        let result = <LoadEngineValidator<LoadChainSpec> as EngineApiValidator<
+            LoadEngineTypes,
+        >>::ensure_well_formed_attributes(
+            &validator, EngineApiMessageVersion::V3, &oversized
+        );
+        assert!(matches!(result, Err(EngineObjectValidationError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn attributes_enforce_fee_recipient_policy() {
+        let allowed = Address::repeat_byte(0xaa);
+        let rpc_attrs = |suggested_fee_recipient| alloy_rpc_types_engine::PayloadAttributes {
+            timestamp: 1,
+            prev_randao: alloy_primitives::B256::from(crate::LOAD_PREVRANDAO),
+            suggested_fee_recipient,
+            withdrawals: Some(Vec::new()),
+            parent_beacon_block_root: Some(alloy_primitives::B256::ZERO),
+        };
+        let validator = LoadEngineValidator::new(Arc::new(LoadChainSpec::default()))
+            .with_fee_recipient_policy(
+                LoadFeeRecipientPolicy::default().with_allowlist([allowed]).with_deny_zero(true),
+            );
+
+        for (recipient, ok) in
+            [(allowed, true), (Address::ZERO, false), (Address::repeat_byte(1), false)]
+        {
+            let attrs = LoadPayloadAttributes::from(rpc_attrs(recipient));
+            let result = <LoadEngineValidator<LoadChainSpec> as EngineApiValidator<
+                LoadEngineTypes,
+            >>::ensure_well_formed_attributes(
+                &validator, EngineApiMessageVersion::V3, &attrs
+            );
+            assert_eq!(result.is_ok(), ok, "recipient {recipient}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn attributes_gas_limit_bounded_by_chain_spec() {
+        let rpc_attrs = alloy_rpc_types_engine::PayloadAttributes {
+            timestamp: 1,
+            prev_randao: alloy_primitives::B256::from(crate::LOAD_PREVRANDAO),
+            suggested_fee_recipient: Address::ZERO,
+            withdrawals: Some(Vec::new()),
+            parent_beacon_block_root: Some(alloy_primitives::B256::ZERO),
+        };
+        let validator = LoadEngineValidator::new(Arc::new(LoadChainSpec::default()));
+
+        for (gas_limit, ok) in [
+            (LOAD_EXECUTION_GAS_LIMIT, true),
+            (1_000, false),
+            (LOAD_EXECUTION_GAS_LIMIT + 1, false),
+        ] {
+            let attrs = LoadPayloadAttributes {
+                gas_limit: Some(gas_limit),
+                ..LoadPayloadAttributes::from(rpc_attrs.clone())
+            };
+            let result = <LoadEngineValidator<LoadChainSpec> as EngineApiValidator<
+                LoadEngineTypes,
+            >>::ensure_well_formed_attributes(
+                &validator, EngineApiMessageVersion::V3, &attrs
+            );
+            assert_eq!(result.is_ok(), ok, "gas limit {gas_limit}: {result:?}");
+        }
+    }
+
+    #[test]
+    fn attributes_forced_transactions_leave_room_for_the_header() {
+        let mut genesis = alloy_genesis::Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis.config.extra_fields.insert(
+            crate::chainspec::LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY.to_string(),
+            serde_json::json!(4096),
+        );
+        let validator =
+            LoadEngineValidator::new(Arc::new(LoadChainSpec::from_genesis(genesis).unwrap()));
+        let rpc_attrs = alloy_rpc_types_engine::PayloadAttributes {
+            timestamp: 1,
+            prev_randao: alloy_primitives::B256::from(crate::LOAD_PREVRANDAO),
+            suggested_fee_recipient: Address::ZERO,
+            withdrawals: Some(Vec::new()),
+            parent_beacon_block_root: Some(alloy_primitives::B256::ZERO),
+        };
+        // Everything but the header margin and the (empty) withdrawals list.
+        let room = 4096 - LOAD_BLOCK_SIZE_MARGIN - Vec::<Withdrawal>::new().length();
+
+        for (size, ok) in [(room, true), (room + 1, false), (4096, false)] {
+            let attrs = LoadPayloadAttributes {
+                transactions: Some(vec![Bytes::from(vec![0u8; size])]),
+                ..LoadPayloadAttributes::from(rpc_attrs.clone())
+            };
+            let result = <LoadEngineValidator<LoadChainSpec> as EngineApiValidator<
+                LoadEngineTypes,
+            >>::ensure_well_formed_attributes(
+                &validator, EngineApiMessageVersion::V3, &attrs
+            );
+            assert_eq!(result.is_ok(), ok, "forced bytes {size}: {result:?}");
+        }
+    }
+
     #[test]
     fn convert_payload_rejects_wrong_prev_randao() {
         let payload_v1 = ExecutionPayloadV1 {
@@ -414,6 +630,70 @@ mod tests {
         assert!(err.to_string().contains("prev_randao"));
     }
 
+    #[test]
+    fn convert_payload_enforces_load_max_block_size() {
+        let block = Block {
+            header: alloy_consensus::Header {
+                number: 1,
+                gas_limit: LOAD_EXECUTION_GAS_LIMIT,
+                timestamp: 1,
+                mix_hash: alloy_primitives::B256::from(crate::LOAD_PREVRANDAO),
+                base_fee_per_gas: Some(0),
+                withdrawals_root: Some(alloy_consensus::EMPTY_ROOT_HASH),
+                blob_gas_used: Some(0),
+                excess_blob_gas: Some(0),
+                parent_beacon_block_root: Some(alloy_primitives::B256::ZERO),
+                requests_hash: Some(Requests::default().requests_hash()),
+                ..Default::default()
+            },
+            body: alloy_consensus::BlockBody {
+                transactions: Vec::new(),
+                ommers: Vec::new(),
+                withdrawals: Some(Default::default()),
+            },
+        };
+        let payload_for = |block: &Block| {
+            let (payload, sidecar) = ExecutionPayload::from_block_slow(block);
+            LoadExecutionData::new(payload, sidecar)
+        };
+
+        let validator = LoadEngineValidator::new(Arc::new(LoadChainSpec::default()));
+        let result = <LoadEngineValidator<LoadChainSpec> as PayloadValidator<
+            LoadEngineTypes,
+        >>::convert_payload_to_block(&validator, payload_for(&block));
+        assert!(result.is_ok(), "empty block fits the default limit: {result:?}");
+
+        let validator_with = |enforced_from: Option<u64>| {
+            let mut genesis = alloy_genesis::Genesis::default();
+            genesis.config.cancun_time = Some(0);
+            genesis.config.extra_fields.insert(
+                crate::chainspec::LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY.to_string(),
+                serde_json::json!(256),
+            );
+            if let Some(time) = enforced_from {
+                genesis.config.extra_fields.insert(
+                    crate::chainspec::LOAD_MAX_RLP_BLOCK_SIZE_TIME_GENESIS_KEY.to_string(),
+                    serde_json::json!(time),
+                );
+            }
+            LoadEngineValidator::new(Arc::new(LoadChainSpec::from_genesis(genesis).unwrap()))
+        };
+        let convert = |validator: &LoadEngineValidator<LoadChainSpec>| {
+            PayloadValidator::<LoadEngineTypes>::convert_payload_to_block(
+                validator,
+                payload_for(&block),
+            )
+        };
+
+        let err = convert(&validator_with(None))
+            .expect_err("pre-Osaka block above the Load limit must be rejected");
+        assert!(err.to_string().contains("256"), "{err}");
+        assert!(convert(&validator_with(Some(1))).is_err());
+
+        // A delayed activation leaves earlier imports alone.
+        assert!(convert(&validator_with(Some(2))).is_ok());
+    }
+
     #[test]
     fn convert_payload_rejects_too_many_blobs() {
         let payload_v1 = ExecutionPayloadV1 {
@@ -38,7 +38,11 @@
 //! ```
 
 // Core modules
+pub mod archive;
+pub mod args;
+pub mod bundle;
 pub mod chainspec;
+pub mod cli;
 pub mod consensus;
 pub mod engine;
 pub mod evm;
@@ -50,6 +54,7 @@ pub mod rpc;
 pub mod transaction;
 pub mod version;
 
+pub use args::LoadArgs;
 pub use chainspec::{LoadChainSpec, LoadChainSpecParser};
 pub use engine::{
     payload::{
@@ -3,9 +3,14 @@
 use std::sync::Arc;
 
 use clap::Parser;
-use load_reth::{chainspec::LoadChainSpecParser, node::LoadNode, LoadChainSpec, LoadEvmConfig};
+use eyre::WrapErr;
+use load_reth::{
+    chainspec::LoadChainSpecParser,
+    cli::{is_load_subcommand, LoadCli},
+    node::{dev::LoadDevMiner, LoadNode},
+    LoadArgs, LoadChainSpec, LoadEvmConfig,
+};
 use reth::CliRunner;
-use reth_cli_commands::node::NoArgs;
 use reth_cli_util::sigsegv_handler;
 use reth_ethereum_cli::Cli;
 use reth_ethereum_consensus::EthBeaconConsensus;
@@ -26,6 +31,15 @@ fn main() {
         unsafe { std::env::set_var("RUST_BACKTRACE", "1") };
     }
 
+    // Load-only tooling (e.g. `payload rebuild`) never launches a node.
+    if is_load_subcommand(std::env::args()) {
+        if let Err(err) = LoadCli::parse().run() {
+            eprintln!("Error: {err:?}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Load Network requires immediate block persistence for 1-slot finality.
     // Default persistence_threshold=2 causes race condition where finalized
     // blocks may still be in memory on restart, leading to CL/EL desync.
@@ -39,17 +53,40 @@ fn main() {
         (LoadEvmConfig::new(spec.clone()), Arc::new(EthBeaconConsensus::new(spec)))
     };
 
-    if let Err(err) = Cli::<LoadChainSpecParser, NoArgs>::parse()
+    if let Err(err) = Cli::<LoadChainSpecParser, LoadArgs>::parse()
         .with_runner_and_components::<LoadNode>(
             CliRunner::try_default_runtime().expect("Failed to create default runtime"),
             cli_components_builder,
-            async move |builder, _| {
+            async move |builder, load_args| {
                 info!(target: "load_reth::cli", "🚀 Launching Load Network execution client");
                 info!(target: "load_reth::cli", "Version: {}", env!("CARGO_PKG_VERSION"));
 
-                let NodeHandle { node: _node, node_exit_future } =
-                    builder.node(LoadNode::default()).launch().await?;
+                let dev = load_args.dev_config()?;
+                if dev.is_some() && builder.config().dev.dev {
+                    eyre::bail!("--load.dev replaces --dev; pass only one of them");
+                }
+
+                let NodeHandle { node, node_exit_future } =
+                    builder.node(LoadNode::new(load_args)).launch().await?;
 
+                let Some(dev) = dev else {
+                    return node_exit_future.await;
+                };
+                let miner = LoadDevMiner::new(
+                    node.provider.clone(),
+                    node.pool.clone(),
+                    node.add_ons_handle.beacon_engine_handle.clone(),
+                    node.payload_builder_handle.clone(),
+                    node.chain_spec(),
+                    dev,
+                );
+                // The dev miner is the only block source, so a failure takes the node down with
+                // it instead of leaving it running without producing blocks.
+                tokio::pin!(node_exit_future);
+                tokio::select! {
+                    result = &mut node_exit_future => return result,
+                    result = miner.run() => result.wrap_err("dev block production stopped")?,
+                }
                 node_exit_future.await
             },
         )
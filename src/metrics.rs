@@ -2,6 +2,7 @@
 
 use std::time::Duration;
 
+use alloy_primitives::U256;
 use reth_metrics::metrics::{self, Counter, Gauge, Histogram};
 
 /// Engine RPC latency + getBlobs counters.
@@ -13,6 +14,12 @@ pub struct LoadEngineRpcMetrics {
     get_blobs_requests: Counter,
     get_blobs_hits: Counter,
     get_blobs_misses: Counter,
+    imported_blobs: Counter,
+    import_blobs_rejected: Counter,
+    import_blobs_latency: Histogram,
+    capability_mismatches: Counter,
+    capabilities_compatible: Gauge,
+    capability_refused_builds: Counter,
 }
 
 impl LoadEngineRpcMetrics {
@@ -33,6 +40,21 @@ impl LoadEngineRpcMetrics {
             get_blobs_requests: metrics::counter!("load_reth_engine_get_blobs_requests_total"),
             get_blobs_hits: metrics::counter!("load_reth_engine_get_blobs_hits_total"),
             get_blobs_misses: metrics::counter!("load_reth_engine_get_blobs_misses_total"),
+            imported_blobs: metrics::counter!("load_reth_engine_imported_blobs_total"),
+            import_blobs_rejected: metrics::counter!(
+                "load_reth_engine_import_blobs_rejected_total"
+            ),
+            import_blobs_latency: metrics::histogram!(
+                "load_reth_engine_import_blobs_duration_seconds",
+                "stage" => "loadImportBlobsV1"
+            ),
+            capability_mismatches: metrics::counter!(
+                "load_reth_engine_capability_mismatches_total"
+            ),
+            capabilities_compatible: metrics::gauge!("load_reth_engine_capabilities_compatible"),
+            capability_refused_builds: metrics::counter!(
+                "load_reth_engine_capability_refused_builds_total"
+            ),
         }
     }
 
@@ -53,6 +75,31 @@ impl LoadEngineRpcMetrics {
         self.get_blobs_hits.increment(hits);
         self.get_blobs_misses.increment(misses);
     }
+
+    pub fn record_import_blobs(&self, imported: usize, duration: Duration) {
+        self.imported_blobs.increment(imported as u64);
+        self.import_blobs_latency.record(duration.as_secs_f64());
+    }
+
+    pub fn record_import_blobs_rejected(&self) {
+        self.import_blobs_rejected.increment(1);
+    }
+
+    pub fn record_capability_exchange(&self, compatible: bool) {
+        self.capabilities_compatible.set(if compatible { 1.0 } else { 0.0 });
+        if !compatible {
+            self.capability_mismatches.increment(1);
+        }
+    }
+
+    pub fn record_capability_refused_build(&self) {
+        self.capability_refused_builds.increment(1);
+    }
+
+    pub fn record_finality_violation(&self, kind: &'static str) {
+        metrics::counter!("load_reth_engine_finality_violations_total", "kind" => kind)
+            .increment(1);
+    }
 }
 
 impl Default for LoadEngineRpcMetrics {
@@ -89,3 +136,633 @@ impl Default for LoadBlobCacheMetrics {
         Self::new()
     }
 }
+
+/// Reason a pool transaction was left out of a payload under construction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPayloadSkipReason {
+    /// Transaction gas limit does not fit in the remaining block gas.
+    GasLimit,
+    /// Transaction would push the block over the RLP size limit.
+    BlockSize,
+    /// Transaction blobs would exceed the per-block blob cap.
+    BlobCap,
+    /// Blob sidecar missing from the pool or in the wrong format for the fork.
+    BlobSidecar,
+    /// The global sidecar memory budget for payload jobs is exhausted.
+    SidecarBudget,
+    /// Transaction nonce already used; skipped without touching descendants.
+    NonceTooLow,
+    /// EVM rejected the transaction; it and its descendants were invalidated.
+    InvalidTransaction,
+    /// CL-forced transaction did not fit or failed to execute and was left out.
+    ForcedTransaction,
+}
+
+impl LoadPayloadSkipReason {
+    /// Label used for the `reason` dimension of the skip counter.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::GasLimit => "gas_limit",
+            Self::BlockSize => "block_size",
+            Self::BlobCap => "blob_cap",
+            Self::BlobSidecar => "blob_sidecar",
+            Self::SidecarBudget => "sidecar_budget",
+            Self::NonceTooLow => "nonce_too_low",
+            Self::InvalidTransaction => "invalid_transaction",
+            Self::ForcedTransaction => "forced_transaction",
+        }
+    }
+}
+
+/// Payload builder output, outcome and phase-timing metrics.
+#[derive(Debug, Clone)]
+pub struct LoadPayloadBuilderMetrics {
+    gas_used: Histogram,
+    gas_limit: Gauge,
+    blob_count: Histogram,
+    blob_cap: Gauge,
+    tx_count: Histogram,
+    total_fees: Histogram,
+    outcome_better: Counter,
+    outcome_aborted: Counter,
+    outcome_cancelled: Counter,
+    outcome_throttled: Counter,
+    pre_execution_latency: Histogram,
+    tx_loop_latency: Histogram,
+    finish_latency: Histogram,
+    skipped_gas_limit: Counter,
+    skipped_block_size: Counter,
+    skipped_blob_cap: Counter,
+    skipped_blob_sidecar: Counter,
+    skipped_sidecar_budget: Counter,
+    skipped_nonce_too_low: Counter,
+    skipped_invalid_transaction: Counter,
+    skipped_forced_transaction: Counter,
+}
+
+impl LoadPayloadBuilderMetrics {
+    pub fn new() -> Self {
+        let skipped = |reason: LoadPayloadSkipReason| {
+            metrics::counter!(
+                "load_reth_payload_skipped_transactions_total",
+                "reason" => reason.as_str()
+            )
+        };
+        let outcome = |outcome: &'static str| metrics::counter!("load_reth_payload_build_outcomes_total", "outcome" => outcome);
+        let phase = |phase: &'static str| metrics::histogram!("load_reth_payload_build_phase_duration_seconds", "phase" => phase);
+
+        Self {
+            gas_used: metrics::histogram!("load_reth_payload_gas_used"),
+            gas_limit: metrics::gauge!("load_reth_payload_gas_limit"),
+            blob_count: metrics::histogram!("load_reth_payload_blob_count"),
+            blob_cap: metrics::gauge!("load_reth_payload_blob_cap"),
+            tx_count: metrics::histogram!("load_reth_payload_transactions"),
+            total_fees: metrics::histogram!("load_reth_payload_fees_wei"),
+            outcome_better: outcome("better"),
+            outcome_aborted: outcome("aborted"),
+            outcome_cancelled: outcome("cancelled"),
+            outcome_throttled: outcome("throttled"),
+            pre_execution_latency: phase("pre_execution"),
+            tx_loop_latency: phase("tx_loop"),
+            finish_latency: phase("finish"),
+            skipped_gas_limit: skipped(LoadPayloadSkipReason::GasLimit),
+            skipped_block_size: skipped(LoadPayloadSkipReason::BlockSize),
+            skipped_blob_cap: skipped(LoadPayloadSkipReason::BlobCap),
+            skipped_blob_sidecar: skipped(LoadPayloadSkipReason::BlobSidecar),
+            skipped_sidecar_budget: skipped(LoadPayloadSkipReason::SidecarBudget),
+            skipped_nonce_too_low: skipped(LoadPayloadSkipReason::NonceTooLow),
+            skipped_invalid_transaction: skipped(LoadPayloadSkipReason::InvalidTransaction),
+            skipped_forced_transaction: skipped(LoadPayloadSkipReason::ForcedTransaction),
+        }
+    }
+
+    /// Records what a build attempt packed into the block, whatever its outcome.
+    pub fn record_contents(
+        &self,
+        gas_used: u64,
+        gas_limit: u64,
+        blob_count: u64,
+        blob_cap: u64,
+        tx_count: usize,
+        total_fees: U256,
+    ) {
+        self.gas_used.record(gas_used as f64);
+        self.gas_limit.set(gas_limit as f64);
+        self.blob_count.record(blob_count as f64);
+        self.blob_cap.set(blob_cap as f64);
+        self.tx_count.record(tx_count as f64);
+        self.total_fees.record(total_fees.saturating_to::<u128>() as f64);
+    }
+
+    pub fn record_better(&self) {
+        self.outcome_better.increment(1);
+    }
+
+    pub fn record_aborted(&self) {
+        self.outcome_aborted.increment(1);
+    }
+
+    pub fn record_cancelled(&self) {
+        self.outcome_cancelled.increment(1);
+    }
+
+    pub fn record_throttled(&self) {
+        self.outcome_throttled.increment(1);
+    }
+
+    pub fn record_pre_execution(&self, duration: Duration) {
+        self.pre_execution_latency.record(duration.as_secs_f64());
+    }
+
+    pub fn record_tx_loop(&self, duration: Duration) {
+        self.tx_loop_latency.record(duration.as_secs_f64());
+    }
+
+    pub fn record_finish(&self, duration: Duration) {
+        self.finish_latency.record(duration.as_secs_f64());
+    }
+
+    pub fn record_skip(&self, reason: LoadPayloadSkipReason) {
+        let counter = match reason {
+            LoadPayloadSkipReason::GasLimit => &self.skipped_gas_limit,
+            LoadPayloadSkipReason::BlockSize => &self.skipped_block_size,
+            LoadPayloadSkipReason::BlobCap => &self.skipped_blob_cap,
+            LoadPayloadSkipReason::BlobSidecar => &self.skipped_blob_sidecar,
+            LoadPayloadSkipReason::SidecarBudget => &self.skipped_sidecar_budget,
+            LoadPayloadSkipReason::NonceTooLow => &self.skipped_nonce_too_low,
+            LoadPayloadSkipReason::InvalidTransaction => &self.skipped_invalid_transaction,
+            LoadPayloadSkipReason::ForcedTransaction => &self.skipped_forced_transaction,
+        };
+        counter.increment(1);
+    }
+}
+
+impl Default for LoadPayloadBuilderMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sidecar memory budget gauges for in-flight payload jobs.
+#[derive(Debug, Clone)]
+pub struct LoadSidecarBudgetMetrics {
+    capacity: Gauge,
+    reserved: Gauge,
+    exhausted: Counter,
+}
+
+impl LoadSidecarBudgetMetrics {
+    pub fn new() -> Self {
+        Self {
+            capacity: metrics::gauge!("load_reth_payload_sidecar_budget_bytes"),
+            reserved: metrics::gauge!("load_reth_payload_sidecar_reserved_bytes"),
+            exhausted: metrics::counter!("load_reth_payload_sidecar_budget_exhausted_total"),
+        }
+    }
+
+    pub fn set_capacity(&self, bytes: u64) {
+        self.capacity.set(bytes as f64);
+    }
+
+    pub fn set_reserved(&self, bytes: u64) {
+        self.reserved.set(bytes as f64);
+    }
+
+    pub fn record_exhausted(&self) {
+        self.exhausted.increment(1);
+    }
+}
+
+impl Default for LoadSidecarBudgetMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parallel pool-transaction execution counters (`parallel-exec` feature).
+#[cfg(feature = "parallel-exec")]
+#[derive(Debug, Clone)]
+pub struct LoadParallelMetrics {
+    transactions: Counter,
+    reexecutions: Counter,
+    waves: Histogram,
+    batch_duration: Histogram,
+    committed: Counter,
+    fallbacks: Counter,
+}
+
+#[cfg(feature = "parallel-exec")]
+impl LoadParallelMetrics {
+    pub fn new() -> Self {
+        Self {
+            transactions: metrics::counter!("load_reth_payload_parallel_transactions_total"),
+            reexecutions: metrics::counter!("load_reth_payload_parallel_reexecutions_total"),
+            waves: metrics::histogram!("load_reth_payload_parallel_waves"),
+            batch_duration: metrics::histogram!(
+                "load_reth_payload_parallel_batch_duration_seconds"
+            ),
+            committed: metrics::counter!("load_reth_payload_parallel_committed_total"),
+            fallbacks: metrics::counter!("load_reth_payload_parallel_fallbacks_total"),
+        }
+    }
+
+    pub fn record_batch(
+        &self,
+        transactions: usize,
+        stats: crate::engine::parallel::ParallelStats,
+        duration: Duration,
+    ) {
+        self.transactions.increment(transactions as u64);
+        self.reexecutions.increment(stats.reexecutions(transactions) as u64);
+        self.waves.record(stats.waves as f64);
+        self.batch_duration.record(duration.as_secs_f64());
+    }
+
+    /// Records whether the builder committed a batch result or executed the transaction again.
+    pub fn record_commit(&self, committed: bool) {
+        if committed {
+            self.committed.increment(1);
+        } else {
+            self.fallbacks.increment(1);
+        }
+    }
+}
+
+#[cfg(feature = "parallel-exec")]
+impl Default for LoadParallelMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pending-transaction state prefetcher counters and cache size.
+#[derive(Debug, Clone)]
+pub struct LoadPrefetchMetrics {
+    transactions: Counter,
+    duration: Histogram,
+    accounts: Gauge,
+    storage_slots: Gauge,
+    seeded_builds: Counter,
+    unseeded_builds: Counter,
+    hits: Counter,
+    misses: Counter,
+}
+
+impl LoadPrefetchMetrics {
+    pub fn new() -> Self {
+        let builds = |outcome: &'static str| metrics::counter!("load_reth_payload_prefetch_builds_total", "outcome" => outcome);
+        Self {
+            transactions: metrics::counter!("load_reth_payload_prefetch_transactions_total"),
+            duration: metrics::histogram!("load_reth_payload_prefetch_duration_seconds"),
+            accounts: metrics::gauge!("load_reth_payload_prefetch_accounts"),
+            storage_slots: metrics::gauge!("load_reth_payload_prefetch_storage_slots"),
+            seeded_builds: builds("seeded"),
+            unseeded_builds: builds("unseeded"),
+            hits: metrics::counter!("load_reth_payload_prefetch_hits_total"),
+            misses: metrics::counter!("load_reth_payload_prefetch_misses_total"),
+        }
+    }
+
+    /// Records one prefetch pass and the resulting cache size.
+    pub fn record_pass(
+        &self,
+        transactions: usize,
+        accounts: usize,
+        storage_slots: usize,
+        duration: Duration,
+    ) {
+        self.transactions.increment(transactions as u64);
+        self.duration.record(duration.as_secs_f64());
+        self.accounts.set(accounts as f64);
+        self.storage_slots.set(storage_slots as f64);
+    }
+
+    /// Records whether a job's first build started from prefetched state.
+    pub fn record_seed(&self, seeded: bool) {
+        if seeded {
+            self.seeded_builds.increment(1);
+        } else {
+            self.unseeded_builds.increment(1);
+        }
+    }
+
+    /// Records how many of a seeded build's account and storage reads were already cached.
+    pub fn record_reads(&self, hits: u64, misses: u64) {
+        self.hits.increment(hits);
+        self.misses.increment(misses);
+    }
+}
+
+impl Default for LoadPrefetchMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pre-serialized `getPayload` response cache counters and occupancy gauges.
+#[derive(Debug, Clone)]
+pub struct LoadPayloadCacheMetrics {
+    hits: Counter,
+    misses: Counter,
+    evictions: Counter,
+    entries: Gauge,
+    bytes: Gauge,
+}
+
+impl LoadPayloadCacheMetrics {
+    pub fn new() -> Self {
+        Self {
+            hits: metrics::counter!("load_reth_engine_payload_cache_hits_total"),
+            misses: metrics::counter!("load_reth_engine_payload_cache_misses_total"),
+            evictions: metrics::counter!("load_reth_engine_payload_cache_evictions_total"),
+            entries: metrics::gauge!("load_reth_engine_payload_cache_entries"),
+            bytes: metrics::gauge!("load_reth_engine_payload_cache_bytes"),
+        }
+    }
+
+    pub fn record_hit(&self) {
+        self.hits.increment(1);
+    }
+
+    pub fn record_miss(&self) {
+        self.misses.increment(1);
+    }
+
+    pub fn record_eviction(&self) {
+        self.evictions.increment(1);
+    }
+
+    pub fn set_usage(&self, entries: usize, bytes: u64) {
+        self.entries.set(entries as f64);
+        self.bytes.set(bytes as f64);
+    }
+}
+
+impl Default for LoadPayloadCacheMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bundle pool outcome counters and occupancy gauge.
+#[derive(Debug, Clone)]
+pub struct LoadBundleMetrics {
+    received: Counter,
+    built: Counter,
+    rejected: Counter,
+    dropped: Counter,
+    landed: Counter,
+    expired: Counter,
+    pending: Gauge,
+    pending_bytes: Gauge,
+}
+
+impl LoadBundleMetrics {
+    pub fn new() -> Self {
+        let outcome = |outcome: &'static str| metrics::counter!("load_reth_bundles_total", "outcome" => outcome);
+
+        Self {
+            received: outcome("received"),
+            built: outcome("built"),
+            rejected: outcome("rejected"),
+            dropped: outcome("dropped"),
+            landed: outcome("landed"),
+            expired: outcome("expired"),
+            pending: metrics::gauge!("load_reth_bundle_pool_pending"),
+            pending_bytes: metrics::gauge!("load_reth_bundle_pool_pending_bytes"),
+        }
+    }
+
+    pub fn record_received(&self, pending: usize, pending_bytes: usize) {
+        self.received.increment(1);
+        self.set_pending(pending, pending_bytes);
+    }
+
+    pub fn record_built(&self) {
+        self.built.increment(1);
+    }
+
+    pub fn record_rejected(&self) {
+        self.rejected.increment(1);
+    }
+
+    pub fn record_dropped(&self, pending: usize, pending_bytes: usize) {
+        self.dropped.increment(1);
+        self.set_pending(pending, pending_bytes);
+    }
+
+    pub fn record_landed(&self) {
+        self.landed.increment(1);
+    }
+
+    pub fn record_expired(&self) {
+        self.expired.increment(1);
+    }
+
+    pub fn set_pending(&self, pending: usize, pending_bytes: usize) {
+        self.pending.set(pending as f64);
+        self.pending_bytes.set(pending_bytes as f64);
+    }
+}
+
+impl Default for LoadBundleMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Osaka sidecar conversion progress.
+#[derive(Debug, Clone)]
+pub struct LoadSidecarConversionMetrics {
+    pending: Gauge,
+    precomputed: Counter,
+    converted: Counter,
+    failures: Counter,
+    batch_duration: Histogram,
+}
+
+impl LoadSidecarConversionMetrics {
+    pub fn new() -> Self {
+        Self {
+            pending: metrics::gauge!("load_reth_pool_sidecar_conversion_pending_blobs"),
+            precomputed: metrics::counter!(
+                "load_reth_pool_sidecar_conversion_precomputed_blobs_total"
+            ),
+            converted: metrics::counter!("load_reth_pool_sidecar_conversion_converted_blobs_total"),
+            failures: metrics::counter!("load_reth_pool_sidecar_conversion_failures_total"),
+            batch_duration: metrics::histogram!(
+                "load_reth_pool_sidecar_conversion_batch_duration_seconds"
+            ),
+        }
+    }
+
+    /// Sets how many pooled blobs still lack cell proofs (before Osaka) or an EIP-7594 sidecar
+    /// (after).
+    pub fn set_pending(&self, blobs: usize) {
+        self.pending.set(blobs as f64);
+    }
+
+    pub fn record_precomputed(&self, blobs: usize, duration: Duration) {
+        self.precomputed.increment(blobs as u64);
+        self.batch_duration.record(duration.as_secs_f64());
+    }
+
+    pub fn record_converted(&self, blobs: usize, duration: Duration) {
+        self.converted.increment(blobs as u64);
+        self.batch_duration.record(duration.as_secs_f64());
+    }
+
+    pub fn record_failures(&self, transactions: usize) {
+        self.failures.increment(transactions as u64);
+    }
+}
+
+impl Default for LoadSidecarConversionMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Size and activity of the on-disk blob archive.
+#[derive(Debug, Clone)]
+pub struct LoadBlobArchiveMetrics {
+    blocks: Gauge,
+    blobs: Gauge,
+    bytes: Gauge,
+    archived: Counter,
+    missing: Counter,
+    pruned: Counter,
+    backfilled: Counter,
+    hits: Counter,
+    write_duration: Histogram,
+}
+
+impl LoadBlobArchiveMetrics {
+    pub fn new() -> Self {
+        Self {
+            blocks: metrics::gauge!("load_reth_blob_archive_blocks"),
+            blobs: metrics::gauge!("load_reth_blob_archive_blobs"),
+            bytes: metrics::gauge!("load_reth_blob_archive_bytes"),
+            archived: metrics::counter!("load_reth_blob_archive_archived_blobs_total"),
+            missing: metrics::counter!("load_reth_blob_archive_missing_blobs_total"),
+            pruned: metrics::counter!("load_reth_blob_archive_pruned_blocks_total"),
+            backfilled: metrics::counter!("load_reth_blob_archive_backfilled_blocks_total"),
+            hits: metrics::counter!("load_reth_blob_archive_hits_total"),
+            write_duration: metrics::histogram!("load_reth_blob_archive_write_duration_seconds"),
+        }
+    }
+
+    pub fn set_usage(&self, blocks: usize, blobs: usize, bytes: u64) {
+        self.blocks.set(blocks as f64);
+        self.blobs.set(blobs as f64);
+        self.bytes.set(bytes as f64);
+    }
+
+    pub fn record_archived(&self, blobs: usize, duration: Duration) {
+        self.archived.increment(blobs as u64);
+        self.write_duration.record(duration.as_secs_f64());
+    }
+
+    /// Counts blobs that had already left the pool when their block was archived.
+    pub fn record_missing(&self, blobs: usize) {
+        self.missing.increment(blobs as u64);
+    }
+
+    pub fn record_pruned(&self, blocks: usize) {
+        self.pruned.increment(blocks as u64);
+    }
+
+    /// Counts canonical blocks revisited after the archive task fell behind.
+    pub fn record_backfilled(&self, blocks: usize) {
+        self.backfilled.increment(blocks as u64);
+    }
+
+    pub fn record_hit(&self) {
+        self.hits.increment(1);
+    }
+}
+
+impl Default for LoadBlobArchiveMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Progress of the versioned-hash blob index.
+#[derive(Debug, Clone)]
+pub struct LoadBlobIndexMetrics {
+    checkpoint: Gauge,
+    stalled: Gauge,
+    indexed: Counter,
+    pruned: Counter,
+}
+
+impl LoadBlobIndexMetrics {
+    pub fn new() -> Self {
+        Self {
+            checkpoint: metrics::gauge!("load_reth_blob_index_checkpoint_block"),
+            stalled: metrics::gauge!("load_reth_blob_index_stalled"),
+            indexed: metrics::counter!("load_reth_blob_index_indexed_blobs_total"),
+            pruned: metrics::counter!("load_reth_blob_index_pruned_blocks_total"),
+        }
+    }
+
+    pub fn set_checkpoint(&self, block_number: u64) {
+        self.checkpoint.set(block_number as f64);
+    }
+
+    /// Marks the index as behind the head because its backfill keeps failing.
+    pub fn set_stalled(&self, stalled: bool) {
+        self.stalled.set(if stalled { 1.0 } else { 0.0 });
+    }
+
+    pub fn record_indexed(&self, blobs: usize) {
+        self.indexed.increment(blobs as u64);
+    }
+
+    pub fn record_pruned(&self, blocks: usize) {
+        self.pruned.increment(blocks as u64);
+    }
+}
+
+impl Default for LoadBlobIndexMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Engine API recorder throughput.
+#[derive(Debug, Clone)]
+pub struct LoadEngineRecorderMetrics {
+    recorded: Counter,
+    dropped: Counter,
+    write_errors: Counter,
+}
+
+impl LoadEngineRecorderMetrics {
+    pub fn new() -> Self {
+        Self {
+            recorded: metrics::counter!("load_reth_engine_recorder_calls_total"),
+            dropped: metrics::counter!("load_reth_engine_recorder_dropped_total"),
+            write_errors: metrics::counter!("load_reth_engine_recorder_write_errors_total"),
+        }
+    }
+
+    pub fn record_written(&self) {
+        self.recorded.increment(1);
+    }
+
+    pub fn record_dropped(&self) {
+        self.dropped.increment(1);
+    }
+
+    pub fn record_write_error(&self) {
+        self.write_errors.increment(1);
+    }
+}
+
+impl Default for LoadEngineRecorderMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
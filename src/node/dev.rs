@@ -0,0 +1,181 @@
+//! Embedded consensus loop for standalone devnets (`--load.dev`).
+//!
+//! Stands in for Ultramarine: builds a block on a fixed interval or as soon as transactions are
+//! pending, imports it through the Engine API and finalizes it in the same forkchoice update
+//! (head = safe = finalized), mirroring Load's single-slot finality. Everything goes through the
+//! node's consensus engine handle, so blocks take the same path as CL-driven ones.
+
+use std::{sync::Arc, time::Duration};
+
+use alloy_primitives::{Address, B256};
+use alloy_rpc_types_engine::ForkchoiceState;
+use eyre::OptionExt;
+use reth::providers::BlockReaderIdExt;
+use reth_engine_primitives::ConsensusEngineHandle;
+use reth_ethereum_primitives::Block;
+use reth_payload_builder::PayloadBuilderHandle;
+use reth_payload_primitives::{
+    BuiltPayload, EngineApiMessageVersion, PayloadAttributesBuilder, PayloadKind, PayloadTypes,
+};
+use reth_primitives_traits::{SealedBlock, SealedHeader};
+use reth_transaction_pool::TransactionPool;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, info};
+
+use crate::{
+    chainspec::LoadChainSpec,
+    engine::payload::{LoadEngineTypes, LoadLocalPayloadAttributesBuilder},
+};
+
+/// Block production settings for `--load.dev`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadDevConfig {
+    /// Interval between blocks. `None` builds a block whenever transactions are pending.
+    pub block_time: Option<Duration>,
+    /// Address credited with block fees.
+    pub fee_recipient: Address,
+}
+
+impl LoadDevConfig {
+    pub const fn with_block_time(mut self, block_time: Option<Duration>) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    pub const fn with_fee_recipient(mut self, fee_recipient: Address) -> Self {
+        self.fee_recipient = fee_recipient;
+        self
+    }
+}
+
+/// Forkchoice state that makes `hash` head, safe and finalized at once.
+const fn finalized(hash: B256) -> ForkchoiceState {
+    ForkchoiceState { head_block_hash: hash, safe_block_hash: hash, finalized_block_hash: hash }
+}
+
+/// Drives block production for a standalone node.
+#[derive(Debug)]
+pub struct LoadDevMiner<Provider, Pool> {
+    provider: Provider,
+    pool: Pool,
+    engine: ConsensusEngineHandle<LoadEngineTypes>,
+    payload_builder: PayloadBuilderHandle<LoadEngineTypes>,
+    attributes: LoadLocalPayloadAttributesBuilder,
+    config: LoadDevConfig,
+}
+
+impl<Provider, Pool> LoadDevMiner<Provider, Pool>
+where
+    Provider: BlockReaderIdExt<Header = alloy_consensus::Header>,
+    Pool: TransactionPool,
+{
+    pub fn new(
+        provider: Provider,
+        pool: Pool,
+        engine: ConsensusEngineHandle<LoadEngineTypes>,
+        payload_builder: PayloadBuilderHandle<LoadEngineTypes>,
+        chain_spec: Arc<LoadChainSpec>,
+        config: LoadDevConfig,
+    ) -> Self {
+        let mut attributes = LoadLocalPayloadAttributesBuilder::new(chain_spec)
+            .with_fee_recipient(config.fee_recipient);
+        if let Some(block_time) = config.block_time {
+            attributes = attributes.with_block_time(block_time);
+        }
+        Self { provider, pool, engine, payload_builder, attributes, config }
+    }
+
+    /// Produces blocks until the pool shuts down or the engine rejects a block.
+    pub async fn run(self) -> eyre::Result<()> {
+        let mut head = self.provider.latest_header()?.ok_or_eyre("no canonical head")?;
+        // Finalize the starting head so the chain is final from the first block on.
+        self.update_forkchoice(head.hash()).await?;
+        info!(
+            target: "load_reth::dev",
+            head = %head.hash(),
+            block_time = ?self.config.block_time,
+            fee_recipient = %self.config.fee_recipient,
+            "Dev block production started"
+        );
+
+        match self.config.block_time {
+            Some(block_time) => {
+                let mut ticker = interval(block_time);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    ticker.tick().await;
+                    head = self.advance(&head).await?.clone_sealed_header();
+                }
+            }
+            None => {
+                let mut pending = self.pool.pending_transactions_listener();
+                let mut backlog = self.pool.pool_size().pending > 0;
+                loop {
+                    // Wait for new transactions unless the last block left some behind.
+                    if !backlog && pending.recv().await.is_none() {
+                        return Ok(());
+                    }
+                    // The next block takes everything pending; these need no block of their own.
+                    while pending.try_recv().is_ok() {}
+
+                    let block = self.advance(&head).await?;
+                    // A block that included nothing will not do better on a retry.
+                    backlog =
+                        !block.body().transactions.is_empty() && self.pool.pool_size().pending > 0;
+                    head = block.clone_sealed_header();
+                }
+            }
+        }
+    }
+
+    /// Builds, imports and finalizes one block on top of `parent`.
+    async fn advance(&self, parent: &SealedHeader) -> eyre::Result<SealedBlock<Block>> {
+        let attributes = self.attributes.build(parent);
+        let updated = self
+            .engine
+            .fork_choice_updated(
+                finalized(parent.hash()),
+                Some(attributes),
+                EngineApiMessageVersion::default(),
+            )
+            .await?;
+        if !updated.payload_status.status.is_valid() {
+            eyre::bail!("forkchoice update rejected: {:?}", updated.payload_status);
+        }
+        let payload_id = updated.payload_id.ok_or_eyre("no payload job started")?;
+
+        let payload = self
+            .payload_builder
+            .resolve_kind(payload_id, PayloadKind::WaitForPending)
+            .await
+            .ok_or_eyre("payload job vanished")??;
+        let block = payload.block().clone();
+
+        let status =
+            self.engine.new_payload(LoadEngineTypes::block_to_payload(block.clone())).await?;
+        if !status.status.is_valid() {
+            eyre::bail!("built block {} rejected: {:?}", block.hash(), status);
+        }
+        self.update_forkchoice(block.hash()).await?;
+
+        debug!(
+            target: "load_reth::dev",
+            number = block.number,
+            hash = %block.hash(),
+            transactions = block.body().transactions.len(),
+            "Dev block finalized"
+        );
+        Ok(block)
+    }
+
+    async fn update_forkchoice(&self, hash: B256) -> eyre::Result<()> {
+        let updated = self
+            .engine
+            .fork_choice_updated(finalized(hash), None, EngineApiMessageVersion::default())
+            .await?;
+        if !updated.payload_status.status.is_valid() {
+            eyre::bail!("forkchoice update to {hash} rejected: {:?}", updated.payload_status);
+        }
+        Ok(())
+    }
+}
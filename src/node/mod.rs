@@ -9,14 +9,15 @@
 //! still leaning on the upstream network/RPC layers until bespoke behaviour is
 //! required.
 
+pub mod dev;
+
 use std::sync::Arc;
 
 use alloy_eips::eip4844::BYTES_PER_BLOB;
 use reth::{
     api::{BlockTy, NodeTypes, TxTy},
-    providers::EthStorage,
+    providers::{CanonStateSubscriptions, EthStorage, PruneCheckpointReader},
 };
-use reth_engine_local::LocalPayloadAttributesBuilder;
 use reth_network::{primitives::BasicNetworkPrimitives, NetworkHandle, NetworkManager, PeersInfo};
 use reth_node_api::{FullNodeComponents, PrimitivesTy};
 use reth_node_builder::{
@@ -31,6 +32,9 @@ use reth_transaction_pool::{PoolPooledTx, PoolTransaction, TransactionPool};
 use tracing::info;
 
 use crate::{
+    archive::{index::LoadBlobIndex, LoadBlobArchiveSlot},
+    args::LoadArgs,
+    bundle::LoadBundlePool,
     chainspec::LoadChainSpec,
     consensus::LoadConsensusBuilder,
     engine::{
@@ -40,9 +44,13 @@ use crate::{
         LoadPayloadServiceBuilder,
     },
     evm::{LoadEvmConfig, LoadExecutorBuilder},
-    pool::LoadPoolBuilder,
+    pool::{LoadKzgSettingsSlot, LoadPoolBuilder},
     primitives::LoadPrimitives,
-    rpc::backpressure::LoadRpcBackpressureLayer,
+    rpc::{
+        backpressure::LoadRpcBackpressureLayer,
+        blobs::{LoadBlobsApiServer, LoadBlobsRpc},
+        bundle::{LoadBundleApiServer, LoadBundleRpc},
+    },
     version::load_client_version_string,
 };
 
@@ -56,7 +64,28 @@ const LOAD_POOLED_TX_SOFT_LIMIT_BYTES: usize =
 /// touching application code.
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct LoadNode;
+pub struct LoadNode {
+    /// Operator-provided Load arguments (`--load.*`).
+    pub args: LoadArgs,
+    /// Bundles submitted via `eth_sendBundle`, shared by the RPC handler and payload builder.
+    pub bundle_pool: LoadBundlePool,
+    /// Blob archive opened by the Engine API, read by `eth_getBlobSidecars`.
+    pub blob_archive: LoadBlobArchiveSlot,
+    /// KZG settings of the pool's validator, reused to verify `engine_loadImportBlobsV1` bundles.
+    pub kzg_settings: LoadKzgSettingsSlot,
+}
+
+impl LoadNode {
+    /// Creates a Load node configured from the `--load.*` CLI arguments.
+    pub fn new(args: LoadArgs) -> Self {
+        Self {
+            args,
+            bundle_pool: LoadBundlePool::default(),
+            blob_archive: LoadBlobArchiveSlot::default(),
+            kzg_settings: LoadKzgSettingsSlot::default(),
+        }
+    }
+}
 
 impl NodeTypes for LoadNode {
     type Primitives = LoadPrimitives;
@@ -144,6 +173,7 @@ where
         LoadConsensusBuilder,
     > as NodeComponentsBuilder<N>>::Components: NodeComponents<N, Evm = LoadEvmConfig>,
     LoadConsensusBuilder: reth_node_builder::components::ConsensusBuilder<N>,
+    N::Provider: PruneCheckpointReader,
 {
     type ComponentsBuilder = ComponentsBuilder<
         N,
@@ -169,9 +199,12 @@ where
         // now but can be replaced once Load needs bespoke gossip.
         ComponentsBuilder::default()
             .node_types::<N>()
-            .pool(LoadPoolBuilder)
+            .pool(LoadPoolBuilder::default().with_kzg_settings(self.kzg_settings.clone()))
             .executor(LoadExecutorBuilder)
-            .payload(BasicPayloadServiceBuilder::new(LoadPayloadServiceBuilder::default()))
+            .payload(BasicPayloadServiceBuilder::new(
+                LoadPayloadServiceBuilder::new(self.args.clone())
+                    .with_bundle_pool(self.bundle_pool.clone()),
+            ))
             .network(LoadNetworkBuilder)
             .consensus(LoadConsensusBuilder)
     }
@@ -180,13 +213,46 @@ where
         // Engine validators + RPC API talking to Ultramarine.  We keep the
         // Ethereum ETH API for user-facing RPC compatibility, and layer the
         // Load engine service on top.
+        let validator_builder = LoadEngineValidatorBuilder::default()
+            .with_fee_recipient_policy(self.args.fee_recipient_policy());
+        let bundle_pool = self.bundle_pool.clone();
+        let blob_archive = self.blob_archive.clone();
+        let blob_index = self.args.blob_index;
         RpcAddOns::new(
             EthereumEthApiBuilder::default(),
-            LoadEngineValidatorBuilder::default(),
-            LoadEngineApiBuilder::<LoadEngineValidatorBuilder>::default(),
-            BasicEngineValidatorBuilder::new(LoadEngineValidatorBuilder::default()),
+            validator_builder.clone(),
+            LoadEngineApiBuilder::new(validator_builder.clone())
+                .with_payload_cache(self.args.payload_response_cache())
+                .with_blob_archive(self.args.blob_archive_config(), self.blob_archive.clone())
+                .with_kzg_settings(self.kzg_settings.clone())
+                .with_engine_recorder(self.args.engine_recorder_config())
+                .with_required_capabilities(self.args.require_capabilities)
+                .with_finality_guard(self.args.finality_guard()),
+            BasicEngineValidatorBuilder::new(validator_builder),
             LoadRpcBackpressureLayer::from_env(),
         )
+        .extend_rpc_modules(move |ctx| {
+            ctx.modules.merge_configured(LoadBundleRpc::new(bundle_pool).into_rpc())?;
+            let index = if blob_index {
+                let index =
+                    LoadBlobIndex::open(ctx.config.datadir().data_dir().join("blob-index"))?;
+                let notifications = ctx.node().provider().subscribe_to_canonical_state();
+                ctx.node().task_executor().spawn(Box::pin(
+                    index.clone().run(ctx.node().provider().clone(), notifications),
+                ));
+                Some(index)
+            } else {
+                None
+            };
+            let blobs = LoadBlobsRpc::new(
+                ctx.node().provider().clone(),
+                ctx.node().pool().clone(),
+                blob_archive,
+            )
+            .with_index(index);
+            ctx.modules.merge_configured(blobs.into_rpc())?;
+            Ok(())
+        })
     }
 }
 
@@ -213,8 +279,6 @@ where
     fn local_payload_attributes_builder(
         chain_spec: &Self::ChainSpec,
     ) -> impl PayloadAttributesBuilder<<Self::Payload as PayloadTypes>::PayloadAttributes> {
-        LoadLocalPayloadAttributesBuilder(LocalPayloadAttributesBuilder::new(Arc::new(
-            chain_spec.clone(),
-        )))
+        LoadLocalPayloadAttributesBuilder::new(Arc::new(chain_spec.clone()))
     }
 }
@@ -2,11 +2,18 @@
 //!
 //! Blob cache sizing is derived from the chain spec (target blob count) unless
 //! overridden via CLI/config. With Load target=512, the auto size yields
-//! `512 * 32 * 2 = 32_768` blobs (~4.3 GB).
+//! `512 * 32 * 2 = 32_768` blobs (~4.3 GB). Osaka keeps the Load blob params, so the cache does
+//! not shrink when EIP-7594 cell-proof sidecars take over. Pooled EIP-4844 sidecars are rewritten
+//! to EIP-7594 at Osaka activation; see [`osaka`].
 
-use std::time::{Duration, SystemTime};
+pub mod osaka;
 
-use alloy_eips::{eip7840::BlobParams, merge::EPOCH_SLOTS};
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use alloy_eips::{eip4844::env_settings::EnvKzgSettings, eip7840::BlobParams, merge::EPOCH_SLOTS};
 use reth::{api::NodeTypes, providers::CanonStateSubscriptions};
 use reth_chainspec::EthChainSpec;
 use reth_ethereum_primitives::TransactionSigned;
@@ -22,14 +29,41 @@ use reth_transaction_pool::{
 use tokio::time::interval;
 use tracing::{debug, info};
 
+use self::osaka::LoadSidecarConverter;
 use crate::{
-    chainspec::{LoadChainSpec, LOAD_MAX_BLOBS_PER_TX, LOAD_TARGET_BLOB_COUNT},
+    chainspec::{LoadChainParams, LoadChainSpec, LOAD_MAX_BLOBS_PER_TX, LOAD_TARGET_BLOB_COUNT},
     metrics::LoadBlobCacheMetrics,
 };
 
+/// KZG settings the pool validates blob sidecars with, published once the pool is built so
+/// other components verify blobs against the same trusted setup.
+#[derive(Debug, Clone, Default)]
+pub struct LoadKzgSettingsSlot(Arc<OnceLock<EnvKzgSettings>>);
+
+impl LoadKzgSettingsSlot {
+    /// Publishes the pool's settings. Later calls are ignored.
+    pub fn set(&self, kzg_settings: EnvKzgSettings) {
+        let _ = self.0.set(kzg_settings);
+    }
+
+    pub fn get(&self) -> Option<&EnvKzgSettings> {
+        self.0.get()
+    }
+}
+
 /// Load Network transaction pool builder.
-#[derive(Debug, Default, Clone, Copy)]
-pub struct LoadPoolBuilder;
+#[derive(Debug, Default, Clone)]
+pub struct LoadPoolBuilder {
+    kzg_settings: LoadKzgSettingsSlot,
+}
+
+impl LoadPoolBuilder {
+    /// Publishes the validator's KZG settings to `kzg_settings` when the pool is built.
+    pub fn with_kzg_settings(mut self, kzg_settings: LoadKzgSettingsSlot) -> Self {
+        self.kzg_settings = kzg_settings;
+        self
+    }
+}
 
 impl<Types, Node> PoolBuilder<Node> for LoadPoolBuilder
 where
@@ -45,10 +79,11 @@ where
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let mut pool_config = ctx.pool_config();
 
-        let current_timestamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+        // Follow the chain head rather than the local clock, which may be skewed or far ahead of a
+        // node that is still syncing.
         let blob_params = ctx
             .chain_spec()
-            .blob_params_at_timestamp(current_timestamp)
+            .blob_params_at_timestamp(ctx.head().timestamp)
             .unwrap_or_else(BlobParams::cancun);
 
         // Derive blob cache size from the target blob count (2 epochs worth) unless overridden.
@@ -93,11 +128,24 @@ where
             Some(blob_cache_size),
         )?;
 
+        // A single transaction can never be larger than the block that would carry it.
+        let max_rlp_block_size =
+            ctx.chain_spec().max_rlp_block_size_at_timestamp(ctx.head().timestamp);
+        let max_tx_input_bytes = ctx.config().txpool.max_tx_input_bytes.min(max_rlp_block_size);
+        if max_tx_input_bytes < ctx.config().txpool.max_tx_input_bytes {
+            info!(
+                target: "load_reth::pool",
+                max_tx_input_bytes,
+                max_rlp_block_size,
+                "Clamped max transaction input size to the Load block size limit"
+            );
+        }
+
         let validator = TransactionValidationTaskExecutor::eth_builder(ctx.provider().clone())
             .with_head_timestamp(ctx.head().timestamp)
             .set_eip4844(true)
             .kzg_settings(ctx.kzg_settings()?)
-            .with_max_tx_input_bytes(ctx.config().txpool.max_tx_input_bytes)
+            .with_max_tx_input_bytes(max_tx_input_bytes)
             .with_local_transactions_config(pool_config.local_transactions_config.clone())
             .set_tx_fee_cap(ctx.config().rpc.rpc_tx_fee_cap)
             .with_max_tx_gas_limit(ctx.config().txpool.max_tx_gas_limit)
@@ -114,9 +162,22 @@ where
             });
         }
 
+        let kzg_settings = validator.validator().kzg_settings().clone();
+        self.kzg_settings.set(kzg_settings.clone());
         let transaction_pool = TxPoolBuilder::new(ctx)
             .with_validator(validator)
-            .build_and_spawn_maintenance_task(blob_store, pool_config)?;
+            .build_and_spawn_maintenance_task(blob_store.clone(), pool_config)?;
+
+        if let Some(converter) = LoadSidecarConverter::new(
+            transaction_pool.clone(),
+            blob_store,
+            ctx.chain_spec().as_ref(),
+            ctx.head().timestamp,
+            kzg_settings,
+        ) {
+            let notifications = ctx.provider().subscribe_to_canonical_state();
+            ctx.task_executor().spawn(Box::pin(converter.run(notifications)));
+        }
 
         let metrics = LoadBlobCacheMetrics::new();
         let pool_clone = transaction_pool.clone();
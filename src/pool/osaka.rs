@@ -0,0 +1,600 @@
+//! Converts pooled EIP-4844 blob sidecars to EIP-7594 across the Osaka boundary.
+//!
+//! From Osaka on the builder only includes cell-proof sidecars, so a pool full of EIP-4844
+//! sidecars would lose every pending blob transaction at activation. Computing 128 cell proofs per
+//! blob for a Load-sized pool takes minutes, so the work is split in two:
+//!
+//! - Once the next block is within [`LOAD_OSAKA_CONVERSION_LEAD_SECS`] of activation, cell proofs
+//!   are precomputed on a few blocking threads and kept in memory (6 KiB per blob). The stored
+//!   sidecars stay EIP-4844 so pre-Osaka blocks can still include them.
+//! - As soon as the block built on the head (head plus [`LOAD_SLOT_SECS`]) is an Osaka block, each
+//!   sidecar is rewritten to EIP-7594 in the blob store using the precomputed proofs, which only
+//!   costs a store write. Waiting for an Osaka head instead would leave the first Osaka block
+//!   without blobs. Transactions stay in the pool; only those whose sidecar cannot be converted are
+//!   dropped, and sidecars of transactions that left the pool meanwhile are not written back.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+use alloy_consensus::BlockHeader;
+use alloy_eips::{
+    eip4844::{env_settings::EnvKzgSettings, BlobTransactionSidecarVariant, Bytes48},
+    eip7594::{BlobTransactionSidecarEip7594, CELLS_PER_EXT_BLOB},
+    Typed2718,
+};
+use alloy_primitives::TxHash;
+use reth::{chainspec::EthereumHardfork, providers::CanonStateNotifications};
+use reth_chainspec::Hardforks;
+use reth_primitives_traits::NodePrimitives;
+use reth_transaction_pool::{BlobStore, TransactionPool};
+use tokio::{
+    sync::broadcast::error::{RecvError, TryRecvError},
+    task::JoinSet,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    chainspec::{LoadChainSpec, LOAD_SLOT_SECS},
+    metrics::LoadSidecarConversionMetrics,
+};
+
+/// How long before Osaka activation cell proofs start being precomputed.
+pub const LOAD_OSAKA_CONVERSION_LEAD_SECS: u64 = 30 * 60;
+
+/// Transactions converted per blocking task.
+const CONVERSION_BATCH_TXS: usize = 8;
+
+/// Cell proofs of one transaction's blobs, flattened blob by blob.
+type CellProofs = Arc<[Bytes48]>;
+
+/// Pool maintenance task rewriting EIP-4844 sidecars to EIP-7594 around Osaka activation.
+#[derive(Debug)]
+pub struct LoadSidecarConverter<Pool, Store> {
+    pool: Pool,
+    blob_store: Store,
+    osaka_timestamp: u64,
+    /// Timestamp of the latest canonical head seen.
+    head_timestamp: u64,
+    lead_secs: u64,
+    kzg_settings: EnvKzgSettings,
+    /// Blocking tasks allowed in flight, kept well below the core count so payload building keeps
+    /// its threads.
+    workers: usize,
+    cell_proofs: HashMap<TxHash, CellProofs>,
+    metrics: LoadSidecarConversionMetrics,
+}
+
+impl<Pool, Store> LoadSidecarConverter<Pool, Store>
+where
+    Pool: TransactionPool + 'static,
+    Store: BlobStore + Clone,
+{
+    /// Returns `None` unless Osaka is scheduled at a timestamp after `head_timestamp`.
+    pub fn new(
+        pool: Pool,
+        blob_store: Store,
+        chain_spec: &LoadChainSpec,
+        head_timestamp: u64,
+        kzg_settings: EnvKzgSettings,
+    ) -> Option<Self> {
+        let osaka_timestamp = chain_spec.fork(EthereumHardfork::Osaka).as_timestamp()?;
+        if osaka_timestamp <= head_timestamp {
+            return None;
+        }
+        let workers = std::thread::available_parallelism().map_or(1, |n| (n.get() / 4).max(1));
+        Some(Self {
+            pool,
+            blob_store,
+            osaka_timestamp,
+            head_timestamp,
+            lead_secs: LOAD_OSAKA_CONVERSION_LEAD_SECS,
+            kzg_settings,
+            workers,
+            cell_proofs: HashMap::new(),
+            metrics: LoadSidecarConversionMetrics::new(),
+        })
+    }
+
+    pub const fn with_lead_secs(mut self, lead_secs: u64) -> Self {
+        self.lead_secs = lead_secs;
+        self
+    }
+
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Follows the canonical head until every pooled sidecar has been converted after Osaka.
+    pub async fn run<N: NodePrimitives>(mut self, mut notifications: CanonStateNotifications<N>) {
+        info!(
+            target: "load_reth::pool",
+            osaka_timestamp = self.osaka_timestamp,
+            lead_secs = self.lead_secs,
+            workers = self.workers,
+            "Scheduled EIP-7594 sidecar conversion"
+        );
+        loop {
+            if self.in_lead_window() {
+                if !self.next_block_is_osaka() {
+                    self.precompute(&mut notifications).await;
+                }
+                // The head before Osaka may have arrived while precomputing.
+                if self.next_block_is_osaka() && self.convert().await == 0 {
+                    info!(target: "load_reth::pool", "EIP-7594 sidecar conversion complete");
+                    return;
+                }
+            }
+            self.head_timestamp = match notifications.recv().await {
+                Ok(notification) => notification.tip().header().timestamp(),
+                // Only the latest head matters.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+        }
+    }
+
+    /// Computes cell proofs for pooled EIP-4844 sidecars that have none yet.
+    ///
+    /// Stops early once the next block is an Osaka block so the rewrite is not held up.
+    async fn precompute<N: NodePrimitives>(
+        &mut self,
+        notifications: &mut CanonStateNotifications<N>,
+    ) {
+        let legacy = self.legacy_sidecars();
+        let pooled: HashSet<_> = legacy.iter().map(|(hash, _)| *hash).collect();
+        self.cell_proofs.retain(|hash, _| pooled.contains(hash));
+        let todo: Vec<_> =
+            legacy.into_iter().filter(|(hash, _)| !self.cell_proofs.contains_key(hash)).collect();
+        self.metrics.set_pending(blob_count(&todo));
+        if todo.is_empty() {
+            return;
+        }
+
+        let mut tasks = JoinSet::new();
+        let mut batches = todo.chunks(CONVERSION_BATCH_TXS).map(<[_]>::to_vec);
+        loop {
+            self.follow_head(notifications);
+            while tasks.len() < self.workers && !self.next_block_is_osaka() {
+                let Some(batch) = batches.next() else { break };
+                let kzg_settings = self.kzg_settings.clone();
+                tasks.spawn_blocking(move || {
+                    let started = Instant::now();
+                    let proofs = batch
+                        .into_iter()
+                        .map(|(hash, sidecar)| (hash, compute_cell_proofs(&sidecar, &kzg_settings)))
+                        .collect::<Vec<_>>();
+                    (proofs, started.elapsed())
+                });
+            }
+            let Some(joined) = tasks.join_next().await else { break };
+            let Ok((proofs, elapsed)) = joined else { continue };
+            let mut blobs = 0;
+            for (hash, result) in proofs {
+                match result {
+                    Ok(proofs) => {
+                        blobs += proofs.len() / CELLS_PER_EXT_BLOB;
+                        self.cell_proofs.insert(hash, proofs);
+                    }
+                    Err(err) => {
+                        self.metrics.record_failures(1);
+                        warn!(
+                            target: "load_reth::pool",
+                            %hash,
+                            %err,
+                            "Cell proof computation failed"
+                        );
+                    }
+                }
+            }
+            self.metrics.record_precomputed(blobs, elapsed);
+        }
+        debug!(
+            target: "load_reth::pool",
+            transactions = self.cell_proofs.len(),
+            "Precomputed cell proofs ahead of Osaka"
+        );
+    }
+
+    /// Rewrites pooled EIP-4844 sidecars as EIP-7594 and returns how many transactions carried
+    /// one. The task keeps running until a pass finds none, which also catches stragglers admitted
+    /// before the pool noticed the fork.
+    async fn convert(&mut self) -> usize {
+        let legacy = self.legacy_sidecars();
+        self.metrics.set_pending(blob_count(&legacy));
+        if legacy.is_empty() {
+            self.cell_proofs.clear();
+            return 0;
+        }
+
+        let found = legacy.len();
+        let work: Vec<_> = legacy
+            .into_iter()
+            .map(|(hash, sidecar)| {
+                let proofs = self.cell_proofs.remove(&hash);
+                (hash, sidecar, proofs)
+            })
+            .collect();
+        let mut tasks = JoinSet::new();
+        let mut batches = work.chunks(CONVERSION_BATCH_TXS).map(<[_]>::to_vec);
+        let mut failed = Vec::new();
+        loop {
+            while tasks.len() < self.workers {
+                let Some(batch) = batches.next() else { break };
+                let store = self.blob_store.clone();
+                let pool = self.pool.clone();
+                let kzg_settings = self.kzg_settings.clone();
+                tasks.spawn_blocking(move || {
+                    let started = Instant::now();
+                    let pooled = |hash: &TxHash| pool.contains(hash);
+                    let outcome = convert_batch(&store, batch, &kzg_settings, pooled);
+                    (outcome, started.elapsed())
+                });
+            }
+            let Some(joined) = tasks.join_next().await else { break };
+            let Ok(((converted, batch_failed), elapsed)) = joined else { continue };
+            self.metrics.record_converted(converted, elapsed);
+            self.metrics.record_failures(batch_failed.len());
+            failed.extend(batch_failed);
+        }
+        if !failed.is_empty() {
+            // An EIP-4844 sidecar can no longer be included; drop its transaction like the
+            // builder would.
+            warn!(
+                target: "load_reth::pool",
+                transactions = failed.len(),
+                "Dropping blob transactions whose sidecar could not be converted"
+            );
+            self.pool.remove_transactions(failed);
+        }
+        found
+    }
+
+    /// Earliest timestamp of the block built on the current head.
+    const fn next_timestamp(&self) -> u64 {
+        self.head_timestamp.saturating_add(LOAD_SLOT_SECS)
+    }
+
+    /// Whether the block built on the current head is within the lead window of Osaka.
+    const fn in_lead_window(&self) -> bool {
+        self.next_timestamp().saturating_add(self.lead_secs) >= self.osaka_timestamp
+    }
+
+    /// Whether the block built on the current head must carry EIP-7594 sidecars.
+    const fn next_block_is_osaka(&self) -> bool {
+        self.next_timestamp() >= self.osaka_timestamp
+    }
+
+    /// Catches up with canonical heads announced while a pass is running.
+    fn follow_head<N: NodePrimitives>(&mut self, notifications: &mut CanonStateNotifications<N>) {
+        loop {
+            match notifications.try_recv() {
+                Ok(notification) => self.head_timestamp = notification.tip().header().timestamp(),
+                Err(TryRecvError::Lagged(_)) => {}
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// EIP-4844 sidecars of every pooled blob transaction.
+    fn legacy_sidecars(&self) -> Vec<(TxHash, Arc<BlobTransactionSidecarVariant>)> {
+        let all = self.pool.all_transactions();
+        let hashes = all
+            .pending
+            .iter()
+            .chain(&all.queued)
+            .filter(|tx| tx.transaction.is_eip4844())
+            .map(|tx| *tx.hash())
+            .collect();
+        match self.pool.get_all_blobs(hashes) {
+            Ok(sidecars) => {
+                sidecars.into_iter().filter(|(_, sidecar)| sidecar.is_eip4844()).collect()
+            }
+            Err(err) => {
+                warn!(target: "load_reth::pool", %err, "Failed to read pooled blob sidecars");
+                Vec::new()
+            }
+        }
+    }
+}
+
+fn blob_count(sidecars: &[(TxHash, Arc<BlobTransactionSidecarVariant>)]) -> usize {
+    sidecars
+        .iter()
+        .filter_map(|(_, sidecar)| sidecar.as_eip4844())
+        .map(|sidecar| sidecar.blobs.len())
+        .sum()
+}
+
+/// Computes the cell proofs of an EIP-4844 sidecar's blobs.
+fn compute_cell_proofs(
+    sidecar: &BlobTransactionSidecarVariant,
+    kzg_settings: &EnvKzgSettings,
+) -> eyre::Result<CellProofs> {
+    let sidecar = sidecar.as_eip4844().ok_or_else(|| eyre::eyre!("sidecar is not EIP-4844"))?;
+    let converted = sidecar.clone().try_into_7594(kzg_settings.get())?;
+    Ok(converted.cell_proofs.into())
+}
+
+/// Rewrites a batch of sidecars in the blob store, computing cell proofs that were not
+/// precomputed. Returns how many blobs were converted and the transactions that failed.
+///
+/// The batch was read from the pool earlier, so a transaction may have been included or evicted
+/// since, and the pool deleted its sidecar. Such sidecars are skipped instead of being written
+/// back; a removal racing the write is caught by checking again afterwards.
+fn convert_batch<Store: BlobStore>(
+    store: &Store,
+    batch: Vec<(TxHash, Arc<BlobTransactionSidecarVariant>, Option<CellProofs>)>,
+    kzg_settings: &EnvKzgSettings,
+    pooled: impl Fn(&TxHash) -> bool,
+) -> (usize, Vec<TxHash>) {
+    let (mut converted, mut failed) = (0, Vec::new());
+    for (hash, sidecar, proofs) in batch {
+        let Some(legacy) = sidecar.as_eip4844() else { continue };
+        if !pooled(&hash) {
+            continue;
+        }
+        let proofs = match proofs.map_or_else(|| compute_cell_proofs(&sidecar, kzg_settings), Ok) {
+            Ok(proofs) => proofs,
+            Err(err) => {
+                warn!(target: "load_reth::pool", %hash, %err, "Cell proof computation failed");
+                failed.push(hash);
+                continue;
+            }
+        };
+        let rewritten = BlobTransactionSidecarEip7594::new(
+            legacy.blobs.clone(),
+            legacy.commitments.clone(),
+            proofs.to_vec(),
+        );
+        match store.insert(hash, BlobTransactionSidecarVariant::Eip7594(rewritten)) {
+            Ok(()) if !pooled(&hash) => {
+                if let Err(err) = store.delete(hash) {
+                    warn!(target: "load_reth::pool", %hash, %err, "Failed to drop stale sidecar");
+                }
+            }
+            Ok(()) => converted += legacy.blobs.len(),
+            Err(err) => {
+                warn!(target: "load_reth::pool", %hash, %err, "Failed to store converted sidecar");
+                failed.push(hash);
+            }
+        }
+    }
+    (converted, failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy_consensus::{Header, SidecarBuilder, Signed, SimpleCoder, TxEip4844};
+    use alloy_eips::{
+        eip2718::Encodable2718,
+        eip4844::{Blob, BlobTransactionSidecar},
+    };
+    use alloy_genesis::Genesis;
+    use alloy_primitives::{Address, Signature};
+    use reth::providers::{CanonStateNotification, Chain, ExecutionOutcome};
+    use reth_primitives_traits::{Recovered, RecoveredBlock, SealedBlock};
+    use reth_transaction_pool::{
+        blobstore::InMemoryBlobStore, noop::MockTransactionValidator, CoinbaseTipOrdering,
+        EthPooledTransaction, Pool, PoolConfig,
+    };
+    use tokio::sync::broadcast;
+
+    use super::*;
+    use crate::primitives::{LoadBlock, LoadPrimitives, LoadTransactionSigned};
+
+    const OSAKA: u64 = 10_000;
+    const LEAD_SECS: u64 = 100;
+
+    type TestPool = Pool<
+        MockTransactionValidator<EthPooledTransaction>,
+        CoinbaseTipOrdering<EthPooledTransaction>,
+        InMemoryBlobStore,
+    >;
+
+    fn converter(
+        head_timestamp: u64,
+    ) -> (LoadSidecarConverter<TestPool, InMemoryBlobStore>, TestPool, InMemoryBlobStore) {
+        let mut genesis = Genesis::default();
+        genesis.config.cancun_time = Some(0);
+        genesis.config.osaka_time = Some(OSAKA);
+        let chain_spec = LoadChainSpec::from_genesis(genesis).expect("valid genesis");
+        let store = InMemoryBlobStore::default();
+        let pool = Pool::new(
+            MockTransactionValidator::default(),
+            CoinbaseTipOrdering::default(),
+            store.clone(),
+            PoolConfig::default(),
+        );
+        let converter = LoadSidecarConverter::new(
+            pool.clone(),
+            store.clone(),
+            &chain_spec,
+            head_timestamp,
+            EnvKzgSettings::Default,
+        )
+        .expect("Osaka scheduled")
+        .with_lead_secs(LEAD_SECS)
+        .with_workers(1);
+        (converter, pool, store)
+    }
+
+    fn sidecar(byte: u8) -> BlobTransactionSidecar {
+        SidecarBuilder::<SimpleCoder>::from_slice(&[byte; 1_000]).build().expect("valid sidecar")
+    }
+
+    /// A blob that is not a canonical field element encoding has no cell proofs.
+    fn unconvertible_sidecar() -> BlobTransactionSidecar {
+        BlobTransactionSidecar::new(
+            vec![Blob::repeat_byte(0xff)],
+            vec![Bytes48::ZERO],
+            vec![Bytes48::ZERO],
+        )
+    }
+
+    /// Pools a blob transaction from its own sender, with its EIP-4844 sidecar in the blob store.
+    async fn add_blob_tx(
+        pool: &TestPool,
+        store: &InMemoryBlobStore,
+        sender: u8,
+        sidecar: BlobTransactionSidecar,
+    ) -> TxHash {
+        let tx = TxEip4844 {
+            gas_limit: 21_000,
+            max_fee_per_gas: 1_000_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            max_fee_per_blob_gas: 1_000_000_000_000,
+            blob_versioned_hashes: sidecar.versioned_hashes().collect(),
+            ..Default::default()
+        };
+        let tx =
+            LoadTransactionSigned::Eip4844(Signed::new_unhashed(tx, Signature::test_signature()));
+        let hash = *tx.tx_hash();
+        let encoded_length = tx.encode_2718_len();
+        let tx = Recovered::new_unchecked(tx, Address::with_last_byte(sender));
+        store.insert(hash, BlobTransactionSidecarVariant::Eip4844(sidecar)).unwrap();
+        pool.add_external_transaction(EthPooledTransaction::new(tx, encoded_length))
+            .await
+            .expect("pooled");
+        hash
+    }
+
+    /// Canonical notification for an empty block at `timestamp`.
+    fn head(timestamp: u64) -> CanonStateNotification<LoadPrimitives> {
+        let block = SealedBlock::seal_slow(LoadBlock {
+            header: Header { number: timestamp, timestamp, ..Default::default() },
+            body: Default::default(),
+        });
+        let chain = Chain::new(
+            [RecoveredBlock::new_sealed(block, Vec::new())],
+            ExecutionOutcome::default(),
+            Default::default(),
+        );
+        CanonStateNotification::Commit { new: Arc::new(chain) }
+    }
+
+    fn stored(store: &InMemoryBlobStore, hash: TxHash) -> Arc<BlobTransactionSidecarVariant> {
+        store.get(hash).unwrap().expect("sidecar stored")
+    }
+
+    #[test]
+    fn schedules_around_the_lead_window() {
+        let (mut converter, ..) = converter(0);
+        converter.head_timestamp = OSAKA - LEAD_SECS - LOAD_SLOT_SECS - 1;
+        assert!(!converter.in_lead_window());
+        converter.head_timestamp += 1;
+        assert!(converter.in_lead_window());
+        assert!(!converter.next_block_is_osaka());
+        converter.head_timestamp = OSAKA - LOAD_SLOT_SECS - 1;
+        assert!(!converter.next_block_is_osaka());
+        converter.head_timestamp += 1;
+        assert!(converter.next_block_is_osaka());
+    }
+
+    #[tokio::test]
+    async fn precompute_stops_once_the_next_block_is_osaka() {
+        let (mut converter, pool, store) = converter(OSAKA - LEAD_SECS);
+        let (heads, mut notifications) = broadcast::channel(16);
+        let first = add_blob_tx(&pool, &store, 1, sidecar(1)).await;
+        let unconvertible = add_blob_tx(&pool, &store, 2, unconvertible_sidecar()).await;
+        converter.precompute(&mut notifications).await;
+        assert!(converter.cell_proofs.contains_key(&first));
+        assert!(!converter.cell_proofs.contains_key(&unconvertible));
+        assert!(stored(&store, first).is_eip4844(), "pre-Osaka blocks still include it");
+
+        // The head before Osaka is announced before the next pass spawns a batch.
+        let second = add_blob_tx(&pool, &store, 3, sidecar(3)).await;
+        heads.send(head(OSAKA - LOAD_SLOT_SECS)).unwrap();
+        converter.precompute(&mut notifications).await;
+        assert!(converter.next_block_is_osaka());
+        assert!(!converter.cell_proofs.contains_key(&second));
+        assert!(converter.cell_proofs.contains_key(&first));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn run_converts_pooled_sidecars_across_osaka() {
+        let (converter, pool, store) = converter(0);
+        let convertible = add_blob_tx(&pool, &store, 1, sidecar(1)).await;
+        let versioned_hashes: Vec<_> = sidecar(1).versioned_hashes().collect();
+        let unconvertible = add_blob_tx(&pool, &store, 2, unconvertible_sidecar()).await;
+        let (heads, notifications) = broadcast::channel(16);
+        let task = tokio::spawn(converter.run(notifications));
+
+        // Before the lead window and inside it, sidecars stay EIP-4844 and nothing is dropped.
+        heads.send(head(1)).unwrap();
+        heads.send(head(OSAKA - LEAD_SECS)).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(stored(&store, convertible).is_eip4844());
+        assert!(pool.contains(&unconvertible));
+
+        // The block built on this head is the first Osaka block.
+        heads.send(head(OSAKA - LOAD_SLOT_SECS)).unwrap();
+        for _ in 0..200 {
+            if stored(&store, convertible).is_eip7594() && !pool.contains(&unconvertible) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let converted = stored(&store, convertible);
+        let converted = converted.as_eip7594().expect("rewritten as EIP-7594");
+        converted
+            .validate(&versioned_hashes, EnvKzgSettings::Default.get())
+            .expect("proofs verify");
+        assert!(pool.contains(&convertible));
+        assert!(!pool.contains(&unconvertible), "unconvertible transaction dropped");
+
+        // The next head finds nothing left to convert and the task ends.
+        heads.send(head(OSAKA)).unwrap();
+        tokio::time::timeout(Duration::from_secs(10), task)
+            .await
+            .expect("conversion task finished")
+            .unwrap();
+    }
+
+    #[test]
+    fn converts_stored_sidecar_with_precomputed_proofs() {
+        let kzg_settings = EnvKzgSettings::Default;
+        let legacy = SidecarBuilder::<SimpleCoder>::from_slice(&vec![0x42; 200_000])
+            .build()
+            .expect("valid sidecar");
+        let versioned_hashes: Vec<_> = legacy.versioned_hashes().collect();
+        let sidecar = Arc::new(BlobTransactionSidecarVariant::Eip4844(legacy));
+
+        let store = InMemoryBlobStore::default();
+        let hash = TxHash::with_last_byte(1);
+        store.insert(hash, (*sidecar).clone()).unwrap();
+
+        let proofs = compute_cell_proofs(&sidecar, &kzg_settings).unwrap();
+        let (converted, failed) =
+            convert_batch(&store, vec![(hash, sidecar, Some(proofs))], &kzg_settings, |_| true);
+        assert_eq!(converted, versioned_hashes.len());
+        assert!(failed.is_empty());
+
+        let stored = store.get(hash).unwrap().expect("sidecar kept");
+        let stored = stored.as_eip7594().expect("rewritten as EIP-7594");
+        stored.validate(&versioned_hashes, kzg_settings.get()).expect("cell proofs verify");
+    }
+
+    #[test]
+    fn skips_sidecars_of_transactions_that_left_the_pool() {
+        let kzg_settings = EnvKzgSettings::Default;
+        let legacy = SidecarBuilder::<SimpleCoder>::from_slice(&[0x42; 1_000])
+            .build()
+            .expect("valid sidecar");
+        let sidecar = Arc::new(BlobTransactionSidecarVariant::Eip4844(legacy));
+
+        // Included in a block and deleted from the store after the batch was read.
+        let store = InMemoryBlobStore::default();
+        let hash = TxHash::with_last_byte(1);
+        let (converted, failed) =
+            convert_batch(&store, vec![(hash, sidecar, None)], &kzg_settings, |_| false);
+        assert_eq!(converted, 0);
+        assert!(failed.is_empty());
+        assert!(store.get(hash).unwrap().is_none(), "stale sidecar must not be written back");
+    }
+}
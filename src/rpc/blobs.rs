@@ -0,0 +1,317 @@
+//! `eth_getBlobSidecars` and `load_getBlobInclusion` RPC endpoints.
+//!
+//! Load has no beacon chain, so rollups and indexers cannot ask a beacon API for the blobs of a
+//! block. This endpoint serves them from the pool's blob store while it still holds them and from
+//! the blob archive (`--load.blob-archive`) afterwards. Every blob is checked against the
+//! versioned hashes of the block's transactions before it is returned.
+//!
+//! A full Load block carries 1024 blobs, about 270 MB of JSON, which is more than the server's
+//! response limit. A call therefore returns at most [`LOAD_MAX_BLOB_SIDECARS_PER_RESPONSE`] blobs;
+//! larger blocks are fetched in parts by passing the wanted blob `indices`.
+//!
+//! `load_getBlobInclusion` answers the reverse question from the blob index (`--load.blob-index`):
+//! which block and transaction carried a versioned hash.
+
+use std::collections::{HashMap, HashSet};
+
+use alloy_consensus::Transaction;
+use alloy_eips::{
+    eip4844::{Blob, Bytes48},
+    BlockId,
+};
+use alloy_primitives::{TxHash, B256, U64};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::{
+        error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+        ErrorObjectOwned,
+    },
+};
+use reth::providers::{BlockReaderIdExt, ProviderError};
+use reth_db::DatabaseError;
+use reth_transaction_pool::{BlobStoreError, TransactionPool};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    archive::{
+        index::{LoadBlobInclusion, LoadBlobIndex},
+        LoadArchivedBlob, LoadBlobArchiveSlot,
+    },
+    primitives::LoadBlock,
+};
+
+/// JSON-RPC "resource not found" (EIP-1474), returned when a blob has left the pool and archive,
+/// and by `engine_loadImportBlobsV1` for unknown blocks and a disabled archive.
+pub(crate) const RESOURCE_NOT_FOUND_CODE: i32 = -32001;
+
+/// Most blobs returned by one `eth_getBlobSidecars` call.
+///
+/// A blob with 128 cell proofs serializes to about 275 KB of JSON, so 512 of them stay below the
+/// default 160 MB response limit.
+pub const LOAD_MAX_BLOB_SIDECARS_PER_RESPONSE: usize = 512;
+
+/// Blob sidecar API.
+#[rpc(server)]
+pub trait LoadBlobsApi {
+    /// Returns the blobs of a block with their commitments and proofs, or `null` if the block is
+    /// unknown. `indices` selects blobs by position in the block; without it every blob is
+    /// returned. Indices past the block's blobs are ignored.
+    #[method(name = "eth_getBlobSidecars")]
+    async fn get_blob_sidecars(
+        &self,
+        block_id: BlockId,
+        indices: Option<Vec<U64>>,
+    ) -> RpcResult<Option<LoadBlockBlobSidecars>>;
+
+    /// Returns the block and transaction that included a blob, or `null` if it is not indexed.
+    #[method(name = "load_getBlobInclusion")]
+    async fn get_blob_inclusion(
+        &self,
+        versioned_hash: B256,
+    ) -> RpcResult<Option<LoadBlobInclusion>>;
+}
+
+/// `eth_getBlobSidecars` response body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadBlockBlobSidecars {
+    #[serde(with = "alloy_serde::quantity")]
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub sidecars: Vec<LoadBlobSidecar>,
+}
+
+/// One blob of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadBlobSidecar {
+    /// Position of the blob among all blobs of the block.
+    #[serde(with = "alloy_serde::quantity")]
+    pub index: u64,
+    pub transaction_hash: TxHash,
+    pub versioned_hash: B256,
+    pub blob: Box<Blob>,
+    pub kzg_commitment: Bytes48,
+    /// The blob proof (EIP-4844) or its cell proofs (EIP-7594).
+    pub kzg_proofs: Vec<Bytes48>,
+}
+
+/// Failures serving the blob endpoints.
+#[derive(Debug, Error)]
+pub enum LoadBlobsRpcError {
+    #[error("blob {versioned_hash} of block {block_number} is no longer available")]
+    Unavailable { block_number: u64, versioned_hash: B256 },
+    #[error("stored sidecar of transaction {tx_hash} does not match its versioned hashes")]
+    Mismatch { tx_hash: TxHash },
+    #[error(
+        "{requested} blobs requested but at most {LOAD_MAX_BLOB_SIDECARS_PER_RESPONSE} are \
+         returned per call; select them with `indices`"
+    )]
+    TooManyBlobs { requested: usize },
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    #[error(transparent)]
+    BlobStore(#[from] BlobStoreError),
+    #[error("blob index is disabled; start the node with --load.blob-index")]
+    IndexDisabled,
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl From<LoadBlobsRpcError> for ErrorObjectOwned {
+    fn from(err: LoadBlobsRpcError) -> Self {
+        let code = match &err {
+            LoadBlobsRpcError::Unavailable { .. } | LoadBlobsRpcError::IndexDisabled => {
+                RESOURCE_NOT_FOUND_CODE
+            }
+            LoadBlobsRpcError::TooManyBlobs { .. } => INVALID_PARAMS_CODE,
+            _ => INTERNAL_ERROR_CODE,
+        };
+        Self::owned(code, err.to_string(), None::<()>)
+    }
+}
+
+/// Blob RPC handler backed by the chain, the pool's blob store, the blob archive and the blob
+/// index.
+#[derive(Debug, Clone)]
+pub struct LoadBlobsRpc<Provider, Pool> {
+    provider: Provider,
+    pool: Pool,
+    archive: LoadBlobArchiveSlot,
+    index: Option<LoadBlobIndex>,
+}
+
+impl<Provider, Pool> LoadBlobsRpc<Provider, Pool> {
+    pub const fn new(provider: Provider, pool: Pool, archive: LoadBlobArchiveSlot) -> Self {
+        Self { provider, pool, archive, index: None }
+    }
+
+    /// Serves `load_getBlobInclusion` from `index`.
+    pub fn with_index(mut self, index: Option<LoadBlobIndex>) -> Self {
+        self.index = index;
+        self
+    }
+}
+
+impl<Provider, Pool> LoadBlobsRpc<Provider, Pool>
+where
+    Provider: BlockReaderIdExt<Block = LoadBlock>,
+    Pool: TransactionPool,
+{
+    /// Collects the selected blobs of a block in transaction order.
+    fn blob_sidecars(
+        &self,
+        block_id: BlockId,
+        indices: Option<HashSet<u64>>,
+    ) -> Result<Option<LoadBlockBlobSidecars>, LoadBlobsRpcError> {
+        let Some(block_hash) = self.provider.block_hash_for_id(block_id)? else { return Ok(None) };
+        let Some(block) = self.provider.block_by_hash(block_hash)? else { return Ok(None) };
+        let block_number = block.header.number;
+
+        let blob_txs: Vec<_> = block
+            .body
+            .transactions
+            .iter()
+            .filter_map(|tx| Some((*tx.tx_hash(), tx.blob_versioned_hashes()?)))
+            .filter(|(_, versioned_hashes)| !versioned_hashes.is_empty())
+            .collect();
+        let selected = select_blobs(
+            blob_txs.iter().map(|(_, versioned_hashes)| versioned_hashes.len()),
+            indices.as_ref(),
+        )?;
+        let mut pooled: HashMap<_, _> = if selected.is_empty() {
+            HashMap::new()
+        } else {
+            let tx_hashes = selected.iter().map(|(tx, ..)| blob_txs[*tx].0).collect();
+            self.pool.get_all_blobs(tx_hashes)?.into_iter().collect()
+        };
+
+        let mut sidecars = Vec::new();
+        for (tx, first, picked) in selected {
+            let (tx_hash, versioned_hashes) = blob_txs[tx];
+            let blobs: Vec<_> = match pooled.remove(&tx_hash) {
+                Some(sidecar) => {
+                    let blobs = LoadArchivedBlob::from_sidecar(&sidecar)
+                        .map_err(|_| LoadBlobsRpcError::Mismatch { tx_hash })?;
+                    if blobs.len() != versioned_hashes.len() {
+                        return Err(LoadBlobsRpcError::Mismatch { tx_hash });
+                    }
+                    let mut blobs: Vec<_> = blobs.into_iter().map(Some).collect();
+                    picked.iter().map(|&idx| blobs[idx].take().expect("picked once")).collect()
+                }
+                None => picked
+                    .iter()
+                    .map(|&idx| {
+                        let versioned_hash = versioned_hashes[idx];
+                        self.archive
+                            .get()
+                            .and_then(|archive| archive.blob(&versioned_hash))
+                            .ok_or(LoadBlobsRpcError::Unavailable { block_number, versioned_hash })
+                    })
+                    .collect::<Result<_, _>>()?,
+            };
+            for (blob, idx) in blobs.into_iter().zip(picked) {
+                let versioned_hash = versioned_hashes[idx];
+                if blob.versioned_hash() != versioned_hash {
+                    return Err(LoadBlobsRpcError::Mismatch { tx_hash });
+                }
+                sidecars.push(LoadBlobSidecar {
+                    index: first + idx as u64,
+                    transaction_hash: tx_hash,
+                    versioned_hash,
+                    blob: blob.blob,
+                    kzg_commitment: blob.commitment,
+                    kzg_proofs: blob.proofs,
+                });
+            }
+        }
+
+        Ok(Some(LoadBlockBlobSidecars { block_number, block_hash, sidecars }))
+    }
+}
+
+/// Picks the requested blobs of a block from the blob counts of its blob transactions.
+///
+/// Returns, for every transaction with a selected blob, its position, the block index of its
+/// first blob and the selected blob positions within the transaction.
+fn select_blobs(
+    blob_counts: impl IntoIterator<Item = usize>,
+    indices: Option<&HashSet<u64>>,
+) -> Result<Vec<(usize, u64, Vec<usize>)>, LoadBlobsRpcError> {
+    let mut selected = Vec::new();
+    let (mut first, mut requested) = (0u64, 0);
+    for (tx, count) in blob_counts.into_iter().enumerate() {
+        let picked: Vec<_> = (0..count)
+            .filter(|idx| indices.is_none_or(|indices| indices.contains(&(first + *idx as u64))))
+            .collect();
+        requested += picked.len();
+        if !picked.is_empty() {
+            selected.push((tx, first, picked));
+        }
+        first += count as u64;
+    }
+    if requested > LOAD_MAX_BLOB_SIDECARS_PER_RESPONSE {
+        return Err(LoadBlobsRpcError::TooManyBlobs { requested });
+    }
+    Ok(selected)
+}
+
+#[async_trait]
+impl<Provider, Pool> LoadBlobsApiServer for LoadBlobsRpc<Provider, Pool>
+where
+    Provider: BlockReaderIdExt<Block = LoadBlock> + Clone + 'static,
+    Pool: TransactionPool + Clone + 'static,
+{
+    async fn get_blob_sidecars(
+        &self,
+        block_id: BlockId,
+        indices: Option<Vec<U64>>,
+    ) -> RpcResult<Option<LoadBlockBlobSidecars>> {
+        let indices =
+            indices.map(|indices| indices.into_iter().map(|idx| idx.to::<u64>()).collect());
+        // Up to 512 blobs read from disk and hashed.
+        let this = self.clone();
+        let sidecars = tokio::task::spawn_blocking(move || this.blob_sidecars(block_id, indices))
+            .await
+            .map_err(|err| {
+                ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+            })??;
+        Ok(sidecars)
+    }
+
+    async fn get_blob_inclusion(
+        &self,
+        versioned_hash: B256,
+    ) -> RpcResult<Option<LoadBlobInclusion>> {
+        let index = self.index.as_ref().ok_or(LoadBlobsRpcError::IndexDisabled)?;
+        Ok(index.inclusion(versioned_hash).map_err(LoadBlobsRpcError::from)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_requested_blobs_across_transactions() {
+        let indices = HashSet::from([1, 2, 5, 9]);
+        let selected = select_blobs([2, 3, 1], Some(&indices)).unwrap();
+        assert_eq!(selected, vec![(0, 0, vec![1]), (1, 2, vec![0])]);
+
+        let all = select_blobs([2, 1], None).unwrap();
+        assert_eq!(all, vec![(0, 0, vec![0, 1]), (1, 2, vec![0])]);
+    }
+
+    #[test]
+    fn caps_blobs_per_response() {
+        let full_block = [32; 32];
+        let err = select_blobs(full_block, None).unwrap_err();
+        assert!(matches!(err, LoadBlobsRpcError::TooManyBlobs { requested: 1024 }));
+
+        let first_half: HashSet<_> = (0..LOAD_MAX_BLOB_SIDECARS_PER_RESPONSE as u64).collect();
+        let selected = select_blobs(full_block, Some(&first_half)).unwrap();
+        assert_eq!(selected.len(), 16);
+    }
+}
@@ -0,0 +1,67 @@
+//! `eth_sendBundle` and `load_getBundleStatus` RPC endpoints.
+//!
+//! Submissions are decoded and KZG-checked off the async runtime, then handed to the shared
+//! [`LoadBundlePool`] that the payload builder drains for each block.
+
+use alloy_primitives::B256;
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::{
+        error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+        ErrorObjectOwned,
+    },
+};
+
+use crate::bundle::{
+    LoadBundle, LoadBundleError, LoadBundlePool, LoadBundleStatus, LoadSendBundle,
+    LoadSendBundleResponse,
+};
+
+/// Bundle submission API.
+#[rpc(server)]
+pub trait LoadBundleApi {
+    /// Submits a bundle for atomic inclusion in its target block.
+    #[method(name = "eth_sendBundle")]
+    async fn send_bundle(&self, bundle: LoadSendBundle) -> RpcResult<LoadSendBundleResponse>;
+
+    /// Returns the lifecycle status of a submitted bundle, or `null` if it is unknown.
+    #[method(name = "load_getBundleStatus")]
+    async fn get_bundle_status(&self, bundle_hash: B256) -> RpcResult<Option<LoadBundleStatus>>;
+}
+
+/// Bundle RPC handler backed by the node's bundle pool.
+#[derive(Debug, Clone)]
+pub struct LoadBundleRpc {
+    pool: LoadBundlePool,
+}
+
+impl LoadBundleRpc {
+    pub const fn new(pool: LoadBundlePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LoadBundleApiServer for LoadBundleRpc {
+    async fn send_bundle(&self, bundle: LoadSendBundle) -> RpcResult<LoadSendBundleResponse> {
+        // Signature recovery and KZG proof checks are CPU-bound.
+        let bundle = tokio::task::spawn_blocking(move || LoadBundle::try_from_request(bundle))
+            .await
+            .map_err(|err| {
+                ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+            })?
+            .map_err(invalid_bundle)?;
+
+        let bundle_hash = self.pool.insert(bundle).map_err(invalid_bundle)?;
+        Ok(LoadSendBundleResponse { bundle_hash })
+    }
+
+    async fn get_bundle_status(&self, bundle_hash: B256) -> RpcResult<Option<LoadBundleStatus>> {
+        Ok(self.pool.status(&bundle_hash))
+    }
+}
+
+fn invalid_bundle(err: LoadBundleError) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(INVALID_PARAMS_CODE, err.to_string(), None::<()>)
+}
@@ -4,6 +4,8 @@
 //! validator/API builders, mirroring the upstream `RpcAddOns` pattern.
 
 pub mod backpressure;
+pub mod blobs;
+pub mod bundle;
 
 use reth_node_api::FullNodeComponents;
 use reth_node_builder::rpc::{
@@ -0,0 +1,415 @@
+//! Ensures `--load.blob-archive` keeps the sidecars of canonical blocks and that
+//! engine_getBlobsV1 and eth_getBlobSidecars serve them after the pool's blob store dropped them,
+//! that `--load.blob-index` resolves versioned hashes to their inclusion, and that
+//! engine_loadImportBlobsV1 fills the archive with sidecars the node never saw.
+//! Uses a high-stack thread due to blob size (131 KB each).
+
+mod common;
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use alloy_consensus::transaction::PooledTransaction;
+use alloy_eips::{
+    eip2718::Decodable2718,
+    eip4844::{BlobAndProofV1, BlobTransactionSidecarVariant, Bytes48},
+    BlockId,
+};
+use alloy_primitives::{Bytes, B256, U64};
+use alloy_rpc_types_engine::BlobsBundleV1;
+use alloy_signer::Signer;
+use common::{blob_tx_with_nonce, funded_genesis, load_payload_attributes, test_wallet};
+use jsonrpsee::{
+    core::client::{ClientT, Error as RpcError},
+    rpc_params,
+};
+use load_reth::{
+    archive::index::LoadBlobInclusion,
+    chainspec::LoadChainSpec,
+    engine::blob_import::{LoadImportBlobsStatus, LOAD_IMPORT_BLOBS_V1},
+    node::LoadNode,
+    rpc::blobs::LoadBlockBlobSidecars,
+    LoadArgs,
+};
+use reth_chainspec::EthChainSpec;
+use reth_e2e_test_utils::node::NodeTestContext;
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_payload_primitives::BuiltPayload;
+use reth_tasks::TaskManager;
+use reth_transaction_pool::{BlobStore, TransactionPool};
+
+/// Waits until the archive has written a block manifest.
+async fn wait_for_archived_block(dir: &Path) -> eyre::Result<()> {
+    for _ in 0..100 {
+        if std::fs::read_dir(dir.join("blocks"))?
+            .filter_map(Result::ok)
+            .any(|entry| !entry.file_name().to_string_lossy().ends_with(".tmp"))
+        {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    eyre::bail!("block was not archived")
+}
+
+/// Builds the bundle a consensus layer would push for a signed blob transaction.
+fn bundle_of(raw_tx: &Bytes) -> eyre::Result<BlobsBundleV1> {
+    let PooledTransaction::Eip4844(signed) = PooledTransaction::decode_2718(&mut raw_tx.as_ref())?
+    else {
+        eyre::bail!("expected a blob transaction");
+    };
+    let BlobTransactionSidecarVariant::Eip4844(sidecar) = signed.into_parts().0.sidecar else {
+        eyre::bail!("expected an EIP-4844 sidecar");
+    };
+    Ok(BlobsBundleV1 {
+        commitments: sidecar.commitments,
+        proofs: sidecar.proofs,
+        blobs: sidecar.blobs,
+    })
+}
+
+#[test]
+fn blobs_fall_back_to_archive() {
+    std::thread::Builder::new()
+        .name("blob_archive".into())
+        .stack_size(32 * 1024 * 1024)
+        .spawn(|| {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .thread_stack_size(8 * 1024 * 1024)
+                .build()
+                .expect("tokio runtime");
+            match rt.block_on(async move {
+                let tasks = TaskManager::current();
+                let exec = tasks.executor();
+                let mut wallet = test_wallet();
+                let genesis = funded_genesis(&[wallet.address()]);
+                let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+                let chain_id = chain_spec.chain().id();
+                wallet = wallet.with_chain_id(Some(chain_id));
+
+                let archive_dir = tempfile::tempdir()?;
+                let args = LoadArgs {
+                    blob_archive: true,
+                    blob_archive_dir: Some(archive_dir.path().to_path_buf()),
+                    blob_index: true,
+                    ..Default::default()
+                };
+
+                let node_config = NodeConfig::new(chain_spec.clone())
+                    .with_unused_ports()
+                    .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+                let node_handle = NodeBuilder::new(node_config)
+                    .testing_node(exec)
+                    .node(LoadNode::new(args))
+                    .launch()
+                    .await?;
+
+                let mut node =
+                    NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+                let tx = blob_tx_with_nonce(chain_id, wallet.clone(), 0, 2).await?;
+                node.rpc.inject_tx(tx).await?;
+
+                let payload = node.new_payload().await?;
+                let new_head = payload.block().hash();
+                let parent = payload.block().header().parent_hash;
+                node.submit_payload(payload.clone()).await?;
+                node.update_forkchoice(parent, new_head).await?;
+
+                let versioned_hashes = payload.versioned_hashes();
+                assert_eq!(versioned_hashes.len(), 2);
+                wait_for_archived_block(archive_dir.path()).await?;
+
+                // Served from the pool's blob store while it holds the sidecars.
+                let http_client =
+                    node.inner.rpc_server_handle().http_client().expect("http rpc enabled");
+                let from_pool: Option<LoadBlockBlobSidecars> = ClientT::request(
+                    &http_client,
+                    "eth_getBlobSidecars",
+                    rpc_params![BlockId::number(1)],
+                )
+                .await?;
+                let from_pool = from_pool.expect("block 1 exists");
+                assert_eq!(from_pool.block_hash, new_head);
+                assert_eq!(
+                    from_pool.sidecars.iter().map(|s| s.versioned_hash).collect::<Vec<_>>(),
+                    versioned_hashes
+                );
+
+                // `indices` selects blobs by their position in the block.
+                let second: Option<LoadBlockBlobSidecars> = ClientT::request(
+                    &http_client,
+                    "eth_getBlobSidecars",
+                    rpc_params![BlockId::number(1), vec![U64::from(1), U64::from(7)]],
+                )
+                .await?;
+                assert_eq!(second.expect("block 1 exists").sidecars, from_pool.sidecars[1..]);
+
+                // Drop the sidecars from the pool, as finalization would.
+                let tx_hashes: Vec<_> =
+                    payload.block().body().transactions.iter().map(|tx| *tx.tx_hash()).collect();
+                let blob_store = node.inner.pool.blob_store();
+                blob_store.delete_all(tx_hashes)?;
+                blob_store.cleanup();
+                let pooled =
+                    node.inner.pool.get_blobs_for_versioned_hashes_v1(&versioned_hashes)?;
+                assert!(pooled.iter().all(Option::is_none), "pool still holds the sidecars");
+
+                let engine_client = node.inner.engine_http_client();
+                let blobs: Vec<Option<BlobAndProofV1>> = ClientT::request(
+                    &engine_client,
+                    "engine_getBlobsV1",
+                    rpc_params![versioned_hashes.clone()],
+                )
+                .await?;
+                assert_eq!(blobs.len(), versioned_hashes.len());
+                assert!(blobs.iter().all(Option::is_some), "archive should serve every blob");
+
+                // eth_getBlobSidecars falls back to the archive with identical contents.
+                let from_archive: Option<LoadBlockBlobSidecars> = ClientT::request(
+                    &http_client,
+                    "eth_getBlobSidecars",
+                    rpc_params![BlockId::hash(new_head)],
+                )
+                .await?;
+                assert_eq!(from_archive, Some(from_pool.clone()));
+                let blob_tx_hash = from_pool.sidecars[1].transaction_hash;
+
+                let unknown: Option<LoadBlockBlobSidecars> = ClientT::request(
+                    &http_client,
+                    "eth_getBlobSidecars",
+                    rpc_params![BlockId::number(100)],
+                )
+                .await?;
+                assert!(unknown.is_none(), "unknown block maps to null");
+
+                // The blob index catches up with the canonical block in the background.
+                let mut inclusion = None;
+                for _ in 0..100 {
+                    inclusion = ClientT::request::<Option<LoadBlobInclusion>, _>(
+                        &http_client,
+                        "load_getBlobInclusion",
+                        rpc_params![versioned_hashes[1]],
+                    )
+                    .await?;
+                    if inclusion.is_some() {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+                let inclusion = inclusion.expect("blob indexed");
+                assert_eq!(inclusion.block_number, 1);
+                assert_eq!(inclusion.block_hash, new_head);
+                assert_eq!(inclusion.transaction_hash, blob_tx_hash);
+                assert_eq!(inclusion.transaction_index, 0);
+                assert_eq!(inclusion.blob_index, 1);
+
+                Ok::<_, eyre::Report>(())
+            }) {
+                Ok(()) => {}
+                Err(err) => panic!("blob archive test failed: {err:?}"),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn consensus_layer_imports_blobs() {
+    std::thread::Builder::new()
+        .name("blob_import".into())
+        .stack_size(32 * 1024 * 1024)
+        .spawn(|| {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .thread_stack_size(8 * 1024 * 1024)
+                .build()
+                .expect("tokio runtime");
+            match rt.block_on(async move {
+                let tasks = TaskManager::current();
+                let exec = tasks.executor();
+                let mut wallet = test_wallet();
+                let genesis = funded_genesis(&[wallet.address()]);
+                let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+                let chain_id = chain_spec.chain().id();
+                wallet = wallet.with_chain_id(Some(chain_id));
+
+                let archive_dir = tempfile::tempdir()?;
+                let args = LoadArgs {
+                    blob_archive: true,
+                    blob_archive_dir: Some(archive_dir.path().to_path_buf()),
+                    ..Default::default()
+                };
+
+                let node_config = NodeConfig::new(chain_spec.clone())
+                    .with_unused_ports()
+                    .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+                let node_handle = NodeBuilder::new(node_config)
+                    .testing_node(exec)
+                    .node(LoadNode::new(args))
+                    .launch()
+                    .await?;
+
+                let mut node =
+                    NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+                let raw_tx = blob_tx_with_nonce(chain_id, wallet.clone(), 0, 2).await?;
+                node.rpc.inject_tx(raw_tx.clone()).await?;
+                let payload = node.new_payload().await?;
+                let new_head = payload.block().hash();
+                let parent = payload.block().header().parent_hash;
+
+                // The sidecars leave the pool before the block is archived, like on a validator
+                // whose pool never received them.
+                let tx_hashes: Vec<_> =
+                    payload.block().body().transactions.iter().map(|tx| *tx.tx_hash()).collect();
+                let blob_store = node.inner.pool.blob_store();
+                blob_store.delete_all(tx_hashes)?;
+                blob_store.cleanup();
+
+                node.submit_payload(payload.clone()).await?;
+                node.update_forkchoice(parent, new_head).await?;
+
+                let versioned_hashes = payload.versioned_hashes();
+                let engine_client = node.inner.engine_http_client();
+                let blobs: Vec<Option<BlobAndProofV1>> = ClientT::request(
+                    &engine_client,
+                    "engine_getBlobsV1",
+                    rpc_params![versioned_hashes.clone()],
+                )
+                .await?;
+                assert!(blobs.iter().all(Option::is_none), "nothing to serve before the import");
+
+                let bundle = bundle_of(&raw_tx)?;
+                let mut forged = bundle.clone();
+                forged.proofs[1] = Bytes48::repeat_byte(1);
+                let err = ClientT::request::<LoadImportBlobsStatus, _>(
+                    &engine_client,
+                    LOAD_IMPORT_BLOBS_V1,
+                    rpc_params![new_head, forged],
+                )
+                .await
+                .expect_err("forged proof is rejected");
+                let RpcError::Call(obj) = err else { panic!("unexpected error variant: {err:?}") };
+                assert_eq!(obj.code(), -32602, "invalid params");
+
+                let err = ClientT::request::<LoadImportBlobsStatus, _>(
+                    &engine_client,
+                    LOAD_IMPORT_BLOBS_V1,
+                    rpc_params![B256::repeat_byte(0xaa), bundle.clone()],
+                )
+                .await
+                .expect_err("unknown block is rejected");
+                let RpcError::Call(obj) = err else { panic!("unexpected error variant: {err:?}") };
+                assert_eq!(obj.code(), -32001, "resource not found");
+
+                let status: LoadImportBlobsStatus = ClientT::request(
+                    &engine_client,
+                    LOAD_IMPORT_BLOBS_V1,
+                    rpc_params![new_head, bundle],
+                )
+                .await?;
+                assert_eq!(status.imported, 2);
+
+                let blobs: Vec<Option<BlobAndProofV1>> = ClientT::request(
+                    &engine_client,
+                    "engine_getBlobsV1",
+                    rpc_params![versioned_hashes],
+                )
+                .await?;
+                assert!(blobs.iter().all(Option::is_some), "imported blobs are served");
+
+                Ok::<_, eyre::Report>(())
+            }) {
+                Ok(()) => {}
+                Err(err) => panic!("blob import test failed: {err:?}"),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn payload_sidecars_survive_finalization() {
+    std::thread::Builder::new()
+        .name("blob_archive_payload".into())
+        .stack_size(32 * 1024 * 1024)
+        .spawn(|| {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .thread_stack_size(8 * 1024 * 1024)
+                .build()
+                .expect("tokio runtime");
+            match rt.block_on(async move {
+                let tasks = TaskManager::current();
+                let exec = tasks.executor();
+                let mut wallet = test_wallet();
+                let genesis = funded_genesis(&[wallet.address()]);
+                let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+                let chain_id = chain_spec.chain().id();
+                wallet = wallet.with_chain_id(Some(chain_id));
+
+                let archive_dir = tempfile::tempdir()?;
+                let args = LoadArgs {
+                    blob_archive: true,
+                    blob_archive_dir: Some(archive_dir.path().to_path_buf()),
+                    ..Default::default()
+                };
+
+                let node_config = NodeConfig::new(chain_spec.clone())
+                    .with_unused_ports()
+                    .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+                let node_handle = NodeBuilder::new(node_config)
+                    .testing_node(exec)
+                    .node(LoadNode::new(args))
+                    .launch()
+                    .await?;
+
+                let mut node =
+                    NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+                let tx = blob_tx_with_nonce(chain_id, wallet.clone(), 0, 2).await?;
+                node.rpc.inject_tx(tx).await?;
+                let payload = node.new_payload().await?;
+                let new_head = payload.block().hash();
+                let parent = payload.block().header().parent_hash;
+                node.submit_payload(payload.clone()).await?;
+
+                // The sidecars leave the pool before the canonical notification reaches the
+                // archive, as when finalization outruns it; newPayload already took them.
+                let tx_hashes: Vec<_> =
+                    payload.block().body().transactions.iter().map(|tx| *tx.tx_hash()).collect();
+                let blob_store = node.inner.pool.blob_store();
+                blob_store.delete_all(tx_hashes)?;
+                blob_store.cleanup();
+
+                node.update_forkchoice(parent, new_head).await?;
+                wait_for_archived_block(archive_dir.path()).await?;
+
+                let versioned_hashes = payload.versioned_hashes();
+                let engine_client = node.inner.engine_http_client();
+                let blobs: Vec<Option<BlobAndProofV1>> = ClientT::request(
+                    &engine_client,
+                    "engine_getBlobsV1",
+                    rpc_params![versioned_hashes.clone()],
+                )
+                .await?;
+                assert_eq!(blobs.len(), versioned_hashes.len());
+                assert!(blobs.iter().all(Option::is_some), "archive should serve every blob");
+
+                Ok::<_, eyre::Report>(())
+            }) {
+                Ok(()) => {}
+                Err(err) => panic!("payload archive test failed: {err:?}"),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
@@ -54,13 +54,8 @@ async fn payload_includes_blob_sidecars(
     let tx_count = payload.block().body().transactions().count();
     assert_eq!(tx_count, txs, "all transactions should be included");
 
-    let (sidecar_count, total_blobs) = match payload.inner.sidecars() {
-        reth_ethereum_engine_primitives::BlobSidecars::Eip4844(sidecars) => {
-            let blobs = sidecars.iter().map(|s| s.blobs.len()).sum();
-            (sidecars.len(), blobs)
-        }
-        _ => (0, 0),
-    };
+    let sidecar_count = payload.sidecars().len();
+    let total_blobs = payload.versioned_hashes().len();
     assert_eq!(sidecar_count, txs, "one sidecar per tx expected");
     assert!(
         total_blobs >= expected_blobs && total_blobs <= LOAD_MAX_BLOB_COUNT as usize,
@@ -111,12 +106,7 @@ async fn payload_handles_near_full_blob_cap() -> eyre::Result<()> {
     }
 
     let payload = node.new_payload().await?;
-    let total_blobs = match payload.inner.sidecars() {
-        reth_ethereum_engine_primitives::BlobSidecars::Eip4844(sidecars) => {
-            sidecars.iter().map(|s| s.blobs.len()).sum()
-        }
-        _ => 0,
-    };
+    let total_blobs = payload.versioned_hashes().len();
 
     assert_eq!(total_blobs, LOAD_MAX_BLOB_COUNT as usize, "cap should apply (got {total_blobs})");
 
@@ -164,12 +154,7 @@ async fn payload_truncates_blobs_over_cap() -> eyre::Result<()> {
     }
 
     let payload = node.new_payload().await?;
-    let total_blobs = match payload.inner.sidecars() {
-        reth_ethereum_engine_primitives::BlobSidecars::Eip4844(sidecars) => {
-            sidecars.iter().map(|s| s.blobs.len()).sum()
-        }
-        _ => 0,
-    };
+    let total_blobs = payload.versioned_hashes().len();
 
     assert_eq!(
         total_blobs, LOAD_MAX_BLOB_COUNT as usize,
@@ -1,31 +1,46 @@
-//! Ensures engine_getBlobsV1/V2 return stored sidecars for committed blob transactions.
+//! Ensures engine_getBlobsV1/V2/V3 return stored sidecars for committed blob transactions and that
+//! getPayloadV5 emits EIP-7594 cell proofs once Osaka is active.
 //! Uses a high-stack thread due to blob size (131 KB each).
 
 mod common;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use alloy_eips::eip4844::{BlobAndProofV1, BlobAndProofV2};
-use alloy_primitives::B256;
+use alloy_eips::{
+    eip4844::{kzg_to_versioned_hash, BlobAndProofV1, BlobAndProofV2},
+    eip7594::CELLS_PER_EXT_BLOB,
+};
+use alloy_primitives::{Address, B256};
+use alloy_rpc_types_engine::{
+    ExecutionPayloadEnvelopeV5, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes,
+};
 use alloy_signer::Signer;
-use common::{blob_tx_with_nonce, funded_genesis, load_payload_attributes, test_wallet};
+use common::{
+    blob_tx_7594_with_nonce, blob_tx_with_nonce, funded_genesis, load_payload_attributes,
+    test_wallet, transfer_tx_with_nonce,
+};
 use jsonrpsee::{
     core::client::{ClientT, Error as RpcError},
     rpc_params,
 };
 use load_reth::{
     chainspec::{LoadChainSpec, LOAD_MAX_BLOB_COUNT},
+    engine::payload::LoadPayloadAttributes,
     node::LoadNode,
+    LOAD_PREVRANDAO,
+};
+use reth::{
+    chainspec::{EthereumHardfork, ForkCondition},
+    providers::BlockReaderIdExt,
 };
-use reth::chainspec::{EthereumHardfork, ForkCondition};
 use reth_chainspec::EthChainSpec;
 use reth_e2e_test_utils::node::NodeTestContext;
-use reth_ethereum_engine_primitives::BlobSidecars;
 use reth_network::{NetworkSyncUpdater, SyncState};
 use reth_node_builder::NodeBuilder;
 use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
 use reth_payload_primitives::BuiltPayload;
 use reth_tasks::TaskManager;
+use tokio::time::timeout;
 
 #[test]
 fn engine_get_blobs_returns_available_sidecars() {
@@ -77,12 +92,7 @@ fn engine_get_blobs_returns_available_sidecars() {
                 node.update_forkchoice(parent, new_head).await?;
 
                 // Collect the versioned hashes from the built payload's sidecars.
-                let versioned_hashes: Vec<B256> = match payload.inner.sidecars() {
-                    BlobSidecars::Eip4844(sidecars) => {
-                        sidecars.iter().flat_map(|sidecar| sidecar.versioned_hashes()).collect()
-                    }
-                    _ => Vec::new(),
-                };
+                let versioned_hashes = payload.versioned_hashes();
                 assert!(
                     !versioned_hashes.is_empty(),
                     "expected blob transactions to produce versioned hashes"
@@ -103,6 +113,7 @@ fn engine_get_blobs_returns_available_sidecars() {
                     "all requested blobs should be present in V1 response"
                 );
 
+                // Osaka is not scheduled, so V2 must be gated even for committed blob hashes.
                 let blobs_v2: Result<Option<Vec<BlobAndProofV2>>, _> = ClientT::request(
                     &engine_client,
                     "engine_getBlobsV2",
@@ -148,6 +159,141 @@ fn engine_get_blobs_returns_available_sidecars() {
         .unwrap();
 }
 
+#[test]
+fn engine_serves_eip7594_sidecars_after_osaka() {
+    // Same high-stack setup as above: cell-proof sidecars carry the full 131 KB blobs.
+    std::thread::Builder::new()
+        .name("blob_retrieval_osaka".into())
+        .stack_size(32 * 1024 * 1024)
+        .spawn(|| {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .thread_stack_size(8 * 1024 * 1024)
+                .build()
+                .expect("tokio runtime");
+            match rt.block_on(async move {
+                let tasks = TaskManager::current();
+                let exec = tasks.executor();
+                let mut wallet = test_wallet();
+                let genesis = funded_genesis(&[wallet.address()]);
+                let mut spec = LoadChainSpec::from_genesis(genesis)?;
+                spec.inner.hardforks.insert(EthereumHardfork::Osaka, ForkCondition::Timestamp(0));
+                let chain_spec = Arc::new(spec);
+                let chain_id = chain_spec.chain().id();
+                wallet = wallet.with_chain_id(Some(chain_id));
+
+                let node_config = NodeConfig::new(chain_spec.clone())
+                    .with_unused_ports()
+                    .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+                let node_handle = NodeBuilder::new(node_config)
+                    .testing_node(exec)
+                    .node(LoadNode::default())
+                    .launch()
+                    .await?;
+
+                let node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+                // 2 txs × 2 blobs, each carrying cell proofs.
+                for nonce in 0..2u64 {
+                    let tx = blob_tx_7594_with_nonce(chain_id, wallet.clone(), nonce, 2).await?;
+                    node.rpc.inject_tx(tx).await?;
+                }
+
+                // Start a payload job through the Engine API, as the CL would.
+                let engine_client = node.inner.engine_http_client();
+                let head = node.inner.provider.latest_header()?.expect("genesis header");
+                let forkchoice = ForkchoiceState {
+                    head_block_hash: head.hash(),
+                    safe_block_hash: head.hash(),
+                    finalized_block_hash: head.hash(),
+                };
+                let attributes = LoadPayloadAttributes::from(PayloadAttributes {
+                    timestamp: head.timestamp + 1,
+                    prev_randao: B256::from(LOAD_PREVRANDAO),
+                    suggested_fee_recipient: Address::ZERO,
+                    withdrawals: Some(vec![]),
+                    parent_beacon_block_root: Some(B256::ZERO),
+                });
+                let updated: ForkchoiceUpdated = ClientT::request(
+                    &engine_client,
+                    "engine_forkchoiceUpdatedV3",
+                    rpc_params![forkchoice, attributes],
+                )
+                .await?;
+                let payload_id = updated.payload_id.expect("payload job started");
+
+                // Wait until the job has picked up the blob transactions.
+                timeout(Duration::from_secs(10), async {
+                    loop {
+                        let best = node.inner.payload_builder_handle.best_payload(payload_id).await;
+                        if best
+                            .and_then(Result::ok)
+                            .is_some_and(|payload| payload.block().body().transactions.len() == 2)
+                        {
+                            break;
+                        }
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                    }
+                })
+                .await?;
+
+                let envelope: ExecutionPayloadEnvelopeV5 = ClientT::request(
+                    &engine_client,
+                    "engine_getPayloadV5",
+                    rpc_params![payload_id],
+                )
+                .await?;
+                let bundle = &envelope.blobs_bundle;
+                assert_eq!(bundle.blobs.len(), 4);
+                assert_eq!(bundle.commitments.len(), 4);
+                assert_eq!(bundle.proofs.len(), 4 * CELLS_PER_EXT_BLOB);
+
+                let versioned_hashes: Vec<B256> = bundle
+                    .commitments
+                    .iter()
+                    .map(|commitment| kzg_to_versioned_hash(commitment.as_slice()))
+                    .collect();
+
+                let blobs_v2: Option<Vec<BlobAndProofV2>> = ClientT::request(
+                    &engine_client,
+                    "engine_getBlobsV2",
+                    rpc_params![versioned_hashes.clone()],
+                )
+                .await?;
+                let blobs_v2 = blobs_v2.expect("every requested blob is stored");
+                assert_eq!(blobs_v2.len(), versioned_hashes.len());
+                for (idx, entry) in blobs_v2.iter().enumerate() {
+                    assert_eq!(*entry.blob, bundle.blobs[idx], "blob {idx} out of order");
+                    assert_eq!(
+                        entry.proofs,
+                        bundle.proofs[idx * CELLS_PER_EXT_BLOB..(idx + 1) * CELLS_PER_EXT_BLOB],
+                        "cell proofs of blob {idx} out of order"
+                    );
+                }
+
+                // V3 answers per hash, so an unknown hash only blanks its own entry.
+                let mut requested = versioned_hashes.clone();
+                requested.push(B256::random());
+                let blobs_v3: Option<Vec<Option<BlobAndProofV2>>> =
+                    ClientT::request(&engine_client, "engine_getBlobsV3", rpc_params![requested])
+                        .await?;
+                let blobs_v3 = blobs_v3.expect("node is not syncing");
+                assert_eq!(blobs_v3.len(), versioned_hashes.len() + 1);
+                assert!(blobs_v3[..versioned_hashes.len()].iter().all(Option::is_some));
+                assert!(blobs_v3[versioned_hashes.len()].is_none());
+
+                Ok::<_, eyre::Report>(())
+            }) {
+                Ok(()) => {}
+                Err(err) => panic!("EIP-7594 blob retrieval test failed: {err:?}"),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn engine_get_blobs_rejects_requests_over_cap() -> eyre::Result<()> {
     let tasks = TaskManager::current();
@@ -254,6 +400,62 @@ async fn engine_get_blobs_v3_rejected_before_osaka() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn engine_get_blobs_v2_follows_head_across_osaka() -> eyre::Result<()> {
+    // Far beyond the wall clock, so only the chain head can activate Osaka.
+    const OSAKA_TIMESTAMP: u64 = 4_000_000_000;
+
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let mut spec = LoadChainSpec::from_genesis(genesis)?;
+    spec.inner.hardforks.insert(EthereumHardfork::Osaka, ForkCondition::Timestamp(OSAKA_TIMESTAMP));
+    let chain_spec = Arc::new(spec);
+    let chain_id = chain_spec.chain().id();
+    wallet = wallet.with_chain_id(Some(chain_id));
+
+    let node_config = NodeConfig::new(chain_spec.clone())
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::default()).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    // Block 1 lands two seconds before activation, block 2 one second before it, so the block
+    // built on block 2 is the first Osaka block.
+    node.payload.timestamp = OSAKA_TIMESTAMP - 3;
+    let engine_client = node.inner.engine_http_client();
+
+    for nonce in 0..2 {
+        let res: Result<Option<Vec<BlobAndProofV2>>, _> =
+            ClientT::request(&engine_client, "engine_getBlobsV2", rpc_params![vec![B256::ZERO]])
+                .await;
+        let err = res
+            .expect_err("engine_getBlobsV2 should be rejected while the next block is pre-Osaka");
+        if let RpcError::Call(obj) = err {
+            assert_eq!(obj.code(), -38005, "expected UnsupportedFork error code");
+        } else {
+            panic!("unexpected error variant: {err:?}");
+        }
+
+        let tx = transfer_tx_with_nonce(chain_id, wallet.clone(), nonce).await?;
+        node.rpc.inject_tx(tx).await?;
+        node.advance_block().await?;
+    }
+
+    // The head is the last pre-Osaka block, so the CL is fetching blobs for an Osaka block; an
+    // unknown hash yields null instead of an error.
+    let res: Option<Vec<BlobAndProofV2>> =
+        ClientT::request(&engine_client, "engine_getBlobsV2", rpc_params![vec![B256::ZERO]])
+            .await?;
+    assert!(res.is_none(), "unknown blob should make the whole V2 response null");
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn engine_get_blobs_v3_rejects_requests_over_cap() -> eyre::Result<()> {
     let tasks = TaskManager::current();
@@ -0,0 +1,377 @@
+//! `eth_sendBundle` end to end: bundles land atomically ahead of the pool, listed transactions may
+//! revert, a bundle over the block gas limit is left out as a whole, and a bundle whose execution
+//! diverges from its simulation is dropped while the build restarts without it.
+
+mod common;
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use alloy_genesis::{Genesis, GenesisAccount};
+use alloy_network::{eip2718::Encodable2718, Ethereum, EthereumWallet, TransactionBuilder};
+use alloy_primitives::{address, keccak256, Address, Bytes, TxKind, B256, U256};
+use alloy_rpc_types_engine::PayloadAttributes;
+use alloy_rpc_types_eth::TransactionRequest;
+use alloy_signer::Signer;
+use alloy_signer_local::PrivateKeySigner;
+use common::{funded_genesis, load_payload_attributes, transfer_tx_with_nonce};
+use eyre::Result;
+use jsonrpsee::{core::client::ClientT, http_client::HttpClient, rpc_params};
+use load_reth::{
+    bundle::{LoadBundleStatus, LoadSendBundle, LoadSendBundleResponse},
+    chainspec::LoadChainSpec,
+    engine::{
+        builder::{default_load_payload, LoadBuilderConfig},
+        payload::{LoadPayloadAttributes, LoadPayloadBuilderAttributes},
+    },
+    metrics::LoadPayloadBuilderMetrics,
+    node::LoadNode,
+    LoadEvmConfig, LOAD_PREVRANDAO,
+};
+use reth::revm::{
+    cached::CachedReads,
+    state::{AccountInfo, Bytecode},
+};
+use reth_basic_payload_builder::{BuildArguments, BuildOutcome, PayloadConfig};
+use reth_chainspec::EthChainSpec;
+use reth_e2e_test_utils::{node::NodeTestContext, wallet::Wallet};
+use reth_ethereum_payload_builder::EthereumBuilderConfig;
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_payload_primitives::BuiltPayload;
+use reth_provider::HeaderProvider;
+use reth_tasks::TaskManager;
+use reth_transaction_pool::TransactionPool;
+use serde_json::Value;
+
+/// Contract that always reverts: `REVERT(0, 0)`.
+const REVERTER: Address = address!("0x00000000000000000000000000000000000000ee");
+/// Contract that reverts unless storage slot 0 is set.
+const GUARDED: Address = address!("0x00000000000000000000000000000000000000ef");
+
+/// Dev genesis funding `wallets`, with [`REVERTER`] deployed and [`GUARDED`] deployed with its
+/// slot set.
+fn bundle_genesis(wallets: &[PrivateKeySigner]) -> Genesis {
+    let mut genesis =
+        funded_genesis(&wallets.iter().map(|wallet| wallet.address()).collect::<Vec<_>>());
+    genesis.alloc.insert(
+        REVERTER,
+        GenesisAccount {
+            code: Some(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd])),
+            ..Default::default()
+        },
+    );
+    genesis.alloc.insert(
+        GUARDED,
+        GenesisAccount {
+            code: Some(guarded_code()),
+            storage: Some(BTreeMap::from([(B256::ZERO, B256::with_last_byte(1))])),
+            ..Default::default()
+        },
+    );
+    genesis
+}
+
+/// `if sload(0) { stop } revert(0, 0)`
+fn guarded_code() -> Bytes {
+    Bytes::from_static(&[
+        0x60, 0x00, 0x54, 0x60, 0x0b, 0x57, 0x60, 0x00, 0x60, 0x00, 0xfd, 0x5b, 0x00,
+    ])
+}
+
+/// Builds a signed EIP-1559 call to `to` with the given gas limit.
+async fn call_tx_with_nonce(
+    chain_id: u64,
+    wallet: PrivateKeySigner,
+    nonce: u64,
+    to: Address,
+    gas: u64,
+) -> Result<Bytes> {
+    let tx = TransactionRequest {
+        nonce: Some(nonce),
+        chain_id: Some(chain_id),
+        gas: Some(gas),
+        max_fee_per_gas: Some(20e9 as u128),
+        max_priority_fee_per_gas: Some(1e9 as u128),
+        to: Some(TxKind::Call(to)),
+        ..Default::default()
+    };
+
+    let signer = EthereumWallet::from(wallet);
+    let signed = <TransactionRequest as TransactionBuilder<Ethereum>>::build(tx, &signer).await?;
+    Ok(signed.encoded_2718().into())
+}
+
+fn node_config(chain_spec: Arc<LoadChainSpec>) -> NodeConfig<LoadChainSpec> {
+    NodeConfig::new(chain_spec)
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http())
+}
+
+async fn send_bundle(
+    client: &HttpClient,
+    txs: Vec<Bytes>,
+    reverting_tx_hashes: Vec<B256>,
+) -> Result<B256> {
+    let bundle = LoadSendBundle {
+        txs,
+        block_number: 1,
+        min_timestamp: None,
+        max_timestamp: None,
+        reverting_tx_hashes,
+    };
+    let response: LoadSendBundleResponse =
+        ClientT::request(client, "eth_sendBundle", rpc_params![bundle]).await?;
+    Ok(response.bundle_hash)
+}
+
+async fn bundle_status(client: &HttpClient, hash: B256) -> Result<Option<LoadBundleStatus>> {
+    Ok(ClientT::request(client, "load_getBundleStatus", rpc_params![hash]).await?)
+}
+
+/// Polls until the bundle pool has settled the bundle against a canonical block.
+async fn settled_status(client: &HttpClient, hash: B256) -> Result<LoadBundleStatus> {
+    for _ in 0..100 {
+        match bundle_status(client, hash).await? {
+            Some(LoadBundleStatus::Pending { .. } | LoadBundleStatus::Built { .. }) | None => {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            Some(status) => return Ok(status),
+        }
+    }
+    eyre::bail!("bundle {hash} was not settled")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundles_land_atomically_ahead_of_the_pool() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallets = Wallet::new(3).wallet_gen();
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(bundle_genesis(&wallets))?);
+    let chain_id = chain_spec.chain().id();
+    wallets.iter_mut().for_each(|wallet| wallet.set_chain_id(Some(chain_id)));
+
+    let node_handle = NodeBuilder::new(node_config(chain_spec))
+        .testing_node(exec)
+        .node(LoadNode::default())
+        .launch()
+        .await?;
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let client = node.inner.rpc_server_handle().http_client().expect("http rpc enabled");
+
+    let landing = vec![
+        transfer_tx_with_nonce(chain_id, wallets[0].clone(), 0).await?,
+        transfer_tx_with_nonce(chain_id, wallets[0].clone(), 1).await?,
+    ];
+    let landing_hash = send_bundle(&client, landing.clone(), Vec::new()).await?;
+    // The transfer would succeed on its own, but the call after it reverts without being listed.
+    let reverting = vec![
+        transfer_tx_with_nonce(chain_id, wallets[1].clone(), 0).await?,
+        call_tx_with_nonce(chain_id, wallets[1].clone(), 1, REVERTER, 50_000).await?,
+    ];
+    let reverting_hash = send_bundle(&client, reverting, Vec::new()).await?;
+    let pooled = transfer_tx_with_nonce(chain_id, wallets[2].clone(), 0).await?;
+    node.rpc.inject_tx(pooled.clone()).await?;
+
+    let payload = node.new_payload().await?;
+    let transactions: Vec<Vec<u8>> =
+        payload.block().body().transactions.iter().map(|tx| tx.encoded_2718()).collect();
+    assert_eq!(transactions, vec![landing[0].to_vec(), landing[1].to_vec(), pooled.to_vec()]);
+
+    let new_head = payload.block().hash();
+    let parent = payload.block().header().parent_hash;
+    node.submit_payload(payload).await?;
+    node.update_forkchoice(parent, new_head).await?;
+
+    assert_eq!(
+        settled_status(&client, landing_hash).await?,
+        LoadBundleStatus::Landed { block_number: 1, block_hash: new_head }
+    );
+    let status = settled_status(&client, reverting_hash).await?;
+    assert!(
+        matches!(
+            &status,
+            LoadBundleStatus::Expired { last_error: Some(error) } if error.contains("reverted")
+        ),
+        "{status:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn listed_transactions_may_revert() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallets = Wallet::new(1).wallet_gen();
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(bundle_genesis(&wallets))?);
+    let chain_id = chain_spec.chain().id();
+    wallets.iter_mut().for_each(|wallet| wallet.set_chain_id(Some(chain_id)));
+
+    let node_handle = NodeBuilder::new(node_config(chain_spec))
+        .testing_node(exec)
+        .node(LoadNode::default())
+        .launch()
+        .await?;
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let client = node.inner.rpc_server_handle().http_client().expect("http rpc enabled");
+
+    let transfer = transfer_tx_with_nonce(chain_id, wallets[0].clone(), 0).await?;
+    let call = call_tx_with_nonce(chain_id, wallets[0].clone(), 1, REVERTER, 50_000).await?;
+    let call_hash = keccak256(&call);
+    let bundle_hash = send_bundle(&client, vec![transfer, call], vec![call_hash]).await?;
+
+    let payload = node.new_payload().await?;
+    let included: Vec<B256> =
+        payload.block().body().transactions.iter().map(|tx| *tx.tx_hash()).collect();
+    assert_eq!(included.len(), 2);
+    assert_eq!(included[1], call_hash);
+
+    let new_head = payload.block().hash();
+    let parent = payload.block().header().parent_hash;
+    node.submit_payload(payload).await?;
+    node.update_forkchoice(parent, new_head).await?;
+
+    assert_eq!(
+        settled_status(&client, bundle_hash).await?,
+        LoadBundleStatus::Landed { block_number: 1, block_hash: new_head }
+    );
+    let receipt: Value =
+        ClientT::request(&client, "eth_getTransactionReceipt", rpc_params![call_hash]).await?;
+    assert_eq!(receipt["status"], "0x0", "the listed call reverts in the block: {receipt}");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bundle_over_block_gas_is_left_out_as_a_unit() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallets = Wallet::new(2).wallet_gen();
+    let mut genesis = bundle_genesis(&wallets);
+    // Block 1 can grow only by 1/1024 of this, so it holds the first bundle transaction but not
+    // both.
+    genesis.gas_limit = 100_000;
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+    let chain_id = chain_spec.chain().id();
+    wallets.iter_mut().for_each(|wallet| wallet.set_chain_id(Some(chain_id)));
+
+    let node_handle = NodeBuilder::new(node_config(chain_spec))
+        .testing_node(exec)
+        .node(LoadNode::default())
+        .launch()
+        .await?;
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let client = node.inner.rpc_server_handle().http_client().expect("http rpc enabled");
+
+    let bundle = vec![
+        transfer_tx_with_nonce(chain_id, wallets[0].clone(), 0).await?,
+        call_tx_with_nonce(chain_id, wallets[0].clone(), 1, GUARDED, 90_000).await?,
+    ];
+    let bundle_hash = send_bundle(&client, bundle, Vec::new()).await?;
+    let pooled = transfer_tx_with_nonce(chain_id, wallets[1].clone(), 0).await?;
+    node.rpc.inject_tx(pooled.clone()).await?;
+
+    let payload = node.new_payload().await?;
+    let transactions: Vec<Vec<u8>> =
+        payload.block().body().transactions.iter().map(|tx| tx.encoded_2718()).collect();
+    assert_eq!(transactions, vec![pooled.to_vec()], "no bundle transaction is included");
+    let status = bundle_status(&client, bundle_hash).await?;
+    assert!(
+        matches!(
+            &status,
+            Some(LoadBundleStatus::Pending { last_error: Some(error) })
+                if error.contains("gas limit")
+        ),
+        "{status:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn diverging_bundle_is_dropped_and_the_build_restarts() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallets = Wallet::new(2).wallet_gen();
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(bundle_genesis(&wallets))?);
+    let chain_id = chain_spec.chain().id();
+    wallets.iter_mut().for_each(|wallet| wallet.set_chain_id(Some(chain_id)));
+
+    let load_node = LoadNode::default();
+    let bundle_pool = load_node.bundle_pool.clone();
+    let node_handle = NodeBuilder::new(node_config(chain_spec.clone()))
+        .testing_node(exec)
+        .node(load_node)
+        .launch()
+        .await?;
+    let node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let client = node.inner.rpc_server_handle().http_client().expect("http rpc enabled");
+
+    let bundle = vec![
+        transfer_tx_with_nonce(chain_id, wallets[0].clone(), 0).await?,
+        call_tx_with_nonce(chain_id, wallets[0].clone(), 1, GUARDED, 50_000).await?,
+    ];
+    let bundle_hash = send_bundle(&client, bundle.clone(), Vec::new()).await?;
+    let pooled = transfer_tx_with_nonce(chain_id, wallets[1].clone(), 0).await?;
+    node.rpc.inject_tx(pooled.clone()).await?;
+
+    // Simulation reads the parent state, where the guard slot is set. The block reads through
+    // cached reads that clear it, so the bundle's call reverts after its transfer is already in
+    // the block.
+    let mut cached_reads = CachedReads::default();
+    let code = Bytecode::new_raw(guarded_code());
+    cached_reads.insert_account(
+        GUARDED,
+        AccountInfo::new(U256::ZERO, 0, code.hash_slow(), code),
+        [(U256::ZERO, U256::ZERO)].into_iter().collect(),
+    );
+
+    let parent = node.inner.provider.sealed_header(0)?.expect("genesis header");
+    let attributes = LoadPayloadBuilderAttributes::try_new(
+        parent.hash(),
+        LoadPayloadAttributes::from(PayloadAttributes {
+            timestamp: parent.timestamp + 1,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: Address::ZERO,
+            withdrawals: Some(vec![]),
+            parent_beacon_block_root: Some(B256::ZERO),
+        }),
+        3,
+    )?;
+    let pool = node.inner.pool.clone();
+    let outcome = default_load_payload(
+        LoadEvmConfig::new(chain_spec),
+        node.inner.provider.clone(),
+        pool.clone(),
+        LoadBuilderConfig::new(EthereumBuilderConfig::new()).with_bundle_pool(bundle_pool),
+        &LoadPayloadBuilderMetrics::new(),
+        BuildArguments::new(
+            cached_reads,
+            PayloadConfig::new(Arc::new(parent), attributes),
+            Default::default(),
+            None,
+        ),
+        |attributes| pool.best_transactions_with_attributes(attributes),
+    )?;
+    let BuildOutcome::Better { payload, .. } = outcome else {
+        panic!("restarted build must produce a payload: {outcome:?}");
+    };
+
+    let transactions: Vec<Vec<u8>> =
+        payload.block().body().transactions.iter().map(|tx| tx.encoded_2718()).collect();
+    assert_eq!(transactions, vec![pooled.to_vec()], "the bundle's transfer is rolled back");
+    let status = bundle_status(&client, bundle_hash).await?;
+    assert!(
+        matches!(
+            &status,
+            Some(LoadBundleStatus::Dropped { reason }) if reason.contains("reverted")
+        ),
+        "{status:?}"
+    );
+
+    Ok(())
+}
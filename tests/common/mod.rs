@@ -34,7 +34,7 @@ pub(crate) fn load_payload_attributes(timestamp: u64) -> LoadPayloadBuilderAttri
         parent_beacon_block_root: Some(B256::ZERO),
     };
 
-    LoadPayloadBuilderAttributes::try_new(B256::ZERO, LoadPayloadAttributes { inner: rpc_attrs }, 3)
+    LoadPayloadBuilderAttributes::try_new(B256::ZERO, LoadPayloadAttributes::from(rpc_attrs), 3)
         .expect("valid payload attributes")
 }
 
@@ -90,3 +90,60 @@ pub(crate) async fn blob_tx_with_nonce(
     let signed = <TransactionRequest as TransactionBuilder<Ethereum>>::build(tx, &signer).await?;
     Ok(signed.encoded_2718().into())
 }
+
+/// Builds a signed blob transaction carrying an EIP-7594 (cell proof) sidecar.
+///
+/// The signature only covers the versioned hashes, so the EIP-4844 sidecar of
+/// [`blob_tx_with_nonce`] is swapped for its cell-proof form after signing.
+#[allow(dead_code)]
+pub(crate) async fn blob_tx_7594_with_nonce(
+    chain_id: u64,
+    wallet: PrivateKeySigner,
+    nonce: u64,
+    blob_count: usize,
+) -> eyre::Result<Bytes> {
+    use alloy_consensus::{
+        transaction::PooledTransaction, EthereumTxEnvelope, Signed, TxEip4844WithSidecar,
+    };
+    use alloy_eips::{
+        eip2718::Decodable2718,
+        eip4844::{env_settings::EnvKzgSettings, BlobTransactionSidecarVariant},
+    };
+
+    let encoded = blob_tx_with_nonce(chain_id, wallet, nonce, blob_count).await?;
+    let PooledTransaction::Eip4844(signed) = PooledTransaction::decode_2718(&mut encoded.as_ref())?
+    else {
+        eyre::bail!("expected a blob transaction");
+    };
+    let (tx, signature, hash) = signed.into_parts();
+    let sidecar = tx.sidecar.try_into_7594(EnvKzgSettings::Default.get())?;
+    let tx = TxEip4844WithSidecar::from_tx_and_sidecar(
+        tx.tx,
+        BlobTransactionSidecarVariant::Eip7594(sidecar),
+    );
+    let envelope = EthereumTxEnvelope::Eip4844(Signed::new_unchecked(tx, signature, hash));
+    Ok(envelope.encoded_2718().into())
+}
+
+/// Builds a signed EIP-1559 value transfer with the requested nonce.
+#[allow(dead_code)]
+pub(crate) async fn transfer_tx_with_nonce(
+    chain_id: u64,
+    wallet: PrivateKeySigner,
+    nonce: u64,
+) -> eyre::Result<Bytes> {
+    let tx = TransactionRequest {
+        nonce: Some(nonce),
+        chain_id: Some(chain_id),
+        gas: Some(21_000),
+        max_fee_per_gas: Some(20e9 as u128),
+        max_priority_fee_per_gas: Some(1e9 as u128),
+        to: Some(TxKind::Call(Address::random())),
+        value: Some(U256::from(100)),
+        ..Default::default()
+    };
+
+    let signer = EthereumWallet::from(wallet);
+    let signed = <TransactionRequest as TransactionBuilder<Ethereum>>::build(tx, &signer).await?;
+    Ok(signed.encoded_2718().into())
+}
@@ -0,0 +1,81 @@
+//! `--load.dev`: the embedded miner builds on transaction arrival and finalizes every block.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use alloy_primitives::Address;
+use alloy_signer::Signer;
+use common::{funded_genesis, load_payload_attributes, test_wallet, transfer_tx_with_nonce};
+use eyre::Result;
+use load_reth::{
+    chainspec::LoadChainSpec,
+    node::{
+        dev::{LoadDevConfig, LoadDevMiner},
+        LoadNode,
+    },
+};
+use reth::providers::{BlockIdReader, CanonStateSubscriptions};
+use reth_chainspec::EthChainSpec;
+use reth_e2e_test_utils::node::NodeTestContext;
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_tasks::TaskManager;
+use tokio::time::timeout;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dev_miner_builds_on_tx_arrival_with_instant_finality() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+    let chain_id = chain_spec.chain().id();
+    wallet = wallet.with_chain_id(Some(chain_id));
+
+    let node_config = NodeConfig::new(chain_spec.clone())
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+    let node_handle = NodeBuilder::new(node_config)
+        .testing_node(exec.clone())
+        .node(LoadNode::default())
+        .launch()
+        .await?;
+    let node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+    let fee_recipient = Address::with_last_byte(0xfe);
+    let miner = LoadDevMiner::new(
+        node.inner.provider.clone(),
+        node.inner.pool.clone(),
+        node.inner.add_ons_handle.beacon_engine_handle.clone(),
+        node.inner.payload_builder_handle.clone(),
+        chain_spec,
+        LoadDevConfig::default().with_fee_recipient(fee_recipient),
+    );
+    let mut canon_state = node.inner.provider.subscribe_to_canonical_state();
+    exec.spawn(Box::pin(async move { miner.run().await.expect("dev miner failed") }));
+
+    for nonce in 0..2 {
+        let tx = transfer_tx_with_nonce(chain_id, wallet.clone(), nonce).await?;
+        let tx_hash = node.rpc.inject_tx(tx).await?;
+
+        let notification = timeout(Duration::from_secs(10), canon_state.recv()).await??;
+        let block = notification.tip();
+        assert_eq!(block.number, nonce + 1, "one block per transaction");
+        assert_eq!(block.beneficiary, fee_recipient);
+        assert!(block.body().transactions.iter().any(|tx| *tx.tx_hash() == tx_hash));
+
+        // The block is finalized by the forkchoice update that follows its import.
+        timeout(Duration::from_secs(10), async {
+            while node.inner.provider.finalized_block_number()? != Some(block.number) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            eyre::Ok(())
+        })
+        .await??;
+        assert_eq!(node.inner.provider.safe_block_number()?, Some(block.number));
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
-//! Engine API ingress guard tests (prev_randao constant enforcement).
+//! Engine API ingress guard tests (prev_randao constant enforcement, finality guard, pinned gas
+//! limit window, capability-refused builds).
 
 mod common;
 
@@ -8,12 +9,26 @@ use alloy_primitives::{Address, B256};
 use alloy_rpc_types_engine::{ForkchoiceState, PayloadAttributes};
 use common::{funded_genesis, load_payload_attributes, test_wallet};
 use eyre::Result;
-use load_reth::{chainspec::LoadChainSpec, node::LoadNode, LOAD_PREVRANDAO};
+use jsonrpsee::core::client::Error as RpcError;
+use load_reth::{
+    chainspec::LoadChainSpec,
+    engine::{
+        capabilities::LOAD_CAPABILITY_MISMATCH_CODE,
+        finality::LOAD_FINALITY_VIOLATION_CODE,
+        payload::{LoadEngineTypes, LoadPayloadAttributes},
+        rpc::INVALID_PAYLOAD_ATTRIBUTES_CODE,
+    },
+    node::LoadNode,
+    LoadArgs, LOAD_PREVRANDAO,
+};
 use reth_chainspec::EthChainSpec;
 use reth_e2e_test_utils::node::NodeTestContext;
 use reth_node_api::EngineApiMessageVersion;
 use reth_node_builder::NodeBuilder;
 use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_payload_primitives::BuiltPayload;
+use reth_provider::BlockNumReader;
+use reth_rpc_api::clients::EngineApiClient;
 use reth_tasks::TaskManager;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -55,7 +70,7 @@ async fn forkchoice_rejects_wrong_prev_randao() -> Result<()> {
     let result = handle
         .fork_choice_updated(
             state,
-            Some(load_reth::engine::payload::LoadPayloadAttributes { inner: attrs }),
+            Some(load_reth::engine::payload::LoadPayloadAttributes::from(attrs)),
             EngineApiMessageVersion::default(),
         )
         .await;
@@ -102,7 +117,7 @@ async fn forkchoice_accepts_constant_prev_randao() -> Result<()> {
     let result = handle
         .fork_choice_updated(
             state,
-            Some(load_reth::engine::payload::LoadPayloadAttributes { inner: attrs }),
+            Some(load_reth::engine::payload::LoadPayloadAttributes::from(attrs)),
             EngineApiMessageVersion::default(),
         )
         .await;
@@ -111,3 +126,280 @@ async fn forkchoice_accepts_constant_prev_randao() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forkchoice_cannot_reorg_finalized_blocks() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+    let genesis_hash = chain_spec.genesis_hash();
+
+    let node_config = NodeConfig::new(chain_spec)
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let args = LoadArgs { instant_finality: true, ..Default::default() };
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::new(args)).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let engine = node.inner.engine_http_client();
+    let finalize = |hash: B256| ForkchoiceState {
+        head_block_hash: hash,
+        safe_block_hash: hash,
+        finalized_block_hash: hash,
+    };
+
+    // Build and finalize two blocks the way Ultramarine does.
+    let mut blocks = Vec::new();
+    for _ in 0..2 {
+        let payload = node.new_payload().await?;
+        let hash = payload.block().hash();
+        node.submit_payload(payload).await?;
+        let updated = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+            &engine,
+            finalize(hash),
+            None,
+        )
+        .await?;
+        assert!(
+            updated.payload_status.status.is_valid(),
+            "finalizing block {hash} failed: {updated:?}"
+        );
+        blocks.push(hash);
+    }
+
+    let violation = |err: RpcError| match err {
+        RpcError::Call(obj) => obj.code() == LOAD_FINALITY_VIOLATION_CODE,
+        _ => false,
+    };
+
+    // Finalized moving back to block 1 or genesis.
+    for hash in [blocks[0], genesis_hash] {
+        let err = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+            &engine,
+            finalize(hash),
+            None,
+        )
+        .await
+        .expect_err("finalized block must not move backwards");
+        assert!(violation(err));
+    }
+
+    // Head ahead of safe and finalized under --load.engine.instant-finality.
+    let split = ForkchoiceState { safe_block_hash: blocks[0], ..finalize(blocks[1]) };
+    let err = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(&engine, split, None)
+        .await
+        .expect_err("head, safe and finalized must match");
+    assert!(violation(err));
+
+    // Re-sending the finalized head is fine.
+    let updated = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        finalize(blocks[1]),
+        None,
+    )
+    .await?;
+    assert!(updated.payload_status.status.is_valid());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forkchoice_cannot_switch_to_a_sibling_payload() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+
+    let node_config = NodeConfig::new(chain_spec)
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::default()).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let engine = node.inner.engine_http_client();
+    let finalize = |hash: B256| ForkchoiceState {
+        head_block_hash: hash,
+        safe_block_hash: hash,
+        finalized_block_hash: hash,
+    };
+
+    // Two payloads on the same parent, differing only in their timestamp.
+    let payload = node.new_payload().await?;
+    let sibling = node.new_payload().await?;
+    let finalized = payload.block().hash();
+    let sibling_hash = sibling.block().hash();
+    assert_eq!(payload.block().header().parent_hash, sibling.block().header().parent_hash);
+    assert_ne!(finalized, sibling_hash);
+
+    // Both are valid; once the first is final, the sibling is only known to the engine tree.
+    node.submit_payload(payload).await?;
+    node.submit_payload(sibling).await?;
+    let updated = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        finalize(finalized),
+        None,
+    )
+    .await?;
+    assert!(updated.payload_status.status.is_valid(), "finalizing failed: {updated:?}");
+
+    let violation = |err: RpcError| match err {
+        RpcError::Call(obj) => obj.code() == LOAD_FINALITY_VIOLATION_CODE,
+        _ => false,
+    };
+    let err = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        finalize(sibling_hash),
+        None,
+    )
+    .await
+    .expect_err("finalized block must not switch to a sibling");
+    assert!(violation(err));
+
+    let head_only = ForkchoiceState { head_block_hash: sibling_hash, ..finalize(finalized) };
+    let err = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(&engine, head_only, None)
+        .await
+        .expect_err("head must build on the finalized block");
+    assert!(violation(err));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forkchoice_refuses_unreachable_gas_limit() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+
+    let node_config = NodeConfig::new(chain_spec)
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::default()).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let engine = node.inner.engine_http_client();
+
+    let payload = node.new_payload().await?;
+    let head = payload.block().hash();
+    let parent_gas_limit = payload.block().header().gas_limit;
+    let timestamp = payload.block().header().timestamp + 1;
+    node.submit_payload(payload).await?;
+
+    let state = ForkchoiceState {
+        head_block_hash: head,
+        safe_block_hash: head,
+        finalized_block_hash: head,
+    };
+    let attributes = |gas_limit: u64| LoadPayloadAttributes {
+        gas_limit: Some(gas_limit),
+        ..LoadPayloadAttributes::from(PayloadAttributes {
+            timestamp,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: Address::ZERO,
+            withdrawals: Some(vec![]),
+            parent_beacon_block_root: Some(B256::ZERO),
+        })
+    };
+
+    // Halving the limit is far outside the parent / 1024 window: no payload ID is handed out,
+    // but the head still moves.
+    let err = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        state,
+        Some(attributes(parent_gas_limit / 2)),
+    )
+    .await
+    .expect_err("gas limit outside the parent window must be refused");
+    assert!(matches!(err, RpcError::Call(obj) if obj.code() == INVALID_PAYLOAD_ATTRIBUTES_CODE));
+    assert_eq!(node.inner.provider.best_block_number()?, 1, "forkchoice must be applied");
+
+    let updated = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        state,
+        Some(attributes(parent_gas_limit)),
+    )
+    .await?;
+    assert!(updated.payload_id.is_some(), "reachable gas limit must start a payload job");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn refused_build_still_applies_forkchoice() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+
+    let node_config = NodeConfig::new(chain_spec)
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let args = LoadArgs { require_capabilities: true, ..Default::default() };
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::new(args)).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+    let engine = node.inner.engine_http_client();
+
+    let payload = node.new_payload().await?;
+    let head = payload.block().hash();
+    let timestamp = payload.block().header().timestamp + 1;
+    node.submit_payload(payload).await?;
+
+    let state = ForkchoiceState {
+        head_block_hash: head,
+        safe_block_hash: head,
+        finalized_block_hash: head,
+    };
+    let attributes = LoadPayloadAttributes::from(PayloadAttributes {
+        timestamp,
+        prev_randao: B256::from(LOAD_PREVRANDAO),
+        suggested_fee_recipient: Address::ZERO,
+        withdrawals: Some(vec![]),
+        parent_beacon_block_root: Some(B256::ZERO),
+    });
+
+    // No capabilities exchanged yet: the build is refused, but the head still moves.
+    let err = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        state,
+        Some(attributes.clone()),
+    )
+    .await
+    .expect_err("payload build must be refused before a capability exchange");
+    assert!(matches!(err, RpcError::Call(obj) if obj.code() == LOAD_CAPABILITY_MISMATCH_CODE));
+    assert_eq!(node.inner.provider.best_block_number()?, 1, "forkchoice must be applied");
+
+    // Echoing the node's capabilities back makes the consensus client compatible.
+    let required = EngineApiClient::<LoadEngineTypes>::exchange_capabilities(&engine, Vec::new())
+        .await?
+        .into_iter()
+        .filter(|capability| capability.starts_with("load."))
+        .collect();
+    EngineApiClient::<LoadEngineTypes>::exchange_capabilities(&engine, required).await?;
+    let updated = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+        &engine,
+        state,
+        Some(attributes),
+    )
+    .await?;
+    assert!(updated.payload_id.is_some(), "compatible capabilities must allow building");
+
+    Ok(())
+}
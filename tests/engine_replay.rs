@@ -0,0 +1,156 @@
+//! `load-reth engine replay`: a session recorded with `--load.engine-recorder` on one node replays
+//! into a fresh node from the same genesis without divergences.
+
+mod common;
+
+use std::{path::Path, process::Command, sync::Arc, time::Duration};
+
+use alloy_eips::eip7685::RequestsOrHash;
+use alloy_primitives::{Address, B256};
+use alloy_rpc_types_engine::{ForkchoiceState, PayloadAttributes};
+use alloy_signer::Signer;
+use common::{funded_genesis, load_payload_attributes, test_wallet, transfer_tx_with_nonce};
+use eyre::Result;
+use load_reth::{
+    chainspec::LoadChainSpec,
+    engine::payload::{LoadEngineTypes, LoadPayloadAttributes},
+    node::LoadNode,
+    LoadArgs, LOAD_PREVRANDAO,
+};
+use reth_chainspec::EthChainSpec;
+use reth_e2e_test_utils::node::NodeTestContext;
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_rpc_api::clients::EngineApiClient;
+use reth_tasks::TaskManager;
+use serde_json::{json, Value};
+
+const BLOCKS: u64 = 2;
+/// Forkchoice update with attributes, getPayload, newPayload and the forkchoice update that
+/// makes the block canonical.
+const CALLS_PER_BLOCK: usize = 4;
+
+/// Counts the recorded lines in a recorder directory.
+fn recorded_calls(dir: &Path) -> Result<usize> {
+    let mut calls = 0;
+    for entry in std::fs::read_dir(dir)? {
+        calls += std::fs::read_to_string(entry?.path())?.lines().count();
+    }
+    Ok(calls)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn recorded_session_replays_without_divergence() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallet = test_wallet();
+    let genesis = funded_genesis(&[wallet.address()]);
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+    let chain_id = chain_spec.chain().id();
+    wallet = wallet.with_chain_id(Some(chain_id));
+    let temp_dir = tempfile::tempdir()?;
+    let recording = temp_dir.path().join("recording");
+
+    let recorded_config = NodeConfig::new(chain_spec.clone())
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+    let args = LoadArgs {
+        engine_recorder: true,
+        engine_recorder_dir: Some(recording.clone()),
+        ..Default::default()
+    };
+    let recorded_handle = NodeBuilder::new(recorded_config)
+        .testing_node(exec.clone())
+        .node(LoadNode::new(args))
+        .launch()
+        .await?;
+    let recorded = NodeTestContext::new(recorded_handle.node, load_payload_attributes).await?;
+    let engine = recorded.inner.engine_http_client();
+
+    // Drive blocks through the Engine API the way the consensus client does, with a pool
+    // transaction only the recorded node has.
+    recorded.rpc.inject_tx(transfer_tx_with_nonce(chain_id, wallet, 0).await?).await?;
+    let state = |hash| ForkchoiceState {
+        head_block_hash: hash,
+        safe_block_hash: hash,
+        finalized_block_hash: hash,
+    };
+    let mut head = chain_spec.genesis_hash();
+    let mut timestamp = chain_spec.genesis_header().timestamp;
+    for _ in 0..BLOCKS {
+        timestamp += 1;
+        let attributes = LoadPayloadAttributes::from(PayloadAttributes {
+            timestamp,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: Address::ZERO,
+            withdrawals: Some(vec![]),
+            parent_beacon_block_root: Some(B256::ZERO),
+        });
+        let updated = EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(
+            &engine,
+            state(head),
+            Some(attributes),
+        )
+        .await?;
+        let payload_id = updated.payload_id.expect("payload job started");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let envelope =
+            EngineApiClient::<LoadEngineTypes>::get_payload_v4(&engine, payload_id).await?;
+        let payload = envelope.envelope_inner.execution_payload;
+        let hash = payload.payload_inner.payload_inner.block_hash;
+        let status = EngineApiClient::<LoadEngineTypes>::new_payload_v4(
+            &engine,
+            payload,
+            Vec::new(),
+            B256::ZERO,
+            RequestsOrHash::Requests(envelope.execution_requests),
+        )
+        .await?;
+        assert!(status.is_valid(), "recorded block {hash} is invalid: {status:?}");
+        let updated =
+            EngineApiClient::<LoadEngineTypes>::fork_choice_updated_v3(&engine, state(hash), None)
+                .await?;
+        assert!(updated.is_valid(), "forkchoice to {hash} failed: {updated:?}");
+        head = hash;
+    }
+
+    let expected = BLOCKS as usize * CALLS_PER_BLOCK;
+    for _ in 0..100 {
+        if recorded_calls(&recording)? >= expected {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(recorded_calls(&recording)?, expected);
+
+    // A fresh node from the same genesis, with a JWT secret the replay command can read.
+    let jwt_secret = temp_dir.path().join("jwt.hex");
+    std::fs::write(&jwt_secret, "ab".repeat(32))?;
+    let mut rpc = RpcServerArgs::default().with_unused_ports().with_http();
+    rpc.auth_jwtsecret = Some(jwt_secret.clone());
+    let fresh_config = NodeConfig::new(chain_spec.clone()).with_unused_ports().with_rpc(rpc);
+    let fresh_handle = NodeBuilder::new(fresh_config)
+        .testing_node(exec)
+        .node(LoadNode::default())
+        .launch()
+        .await?;
+    let url = format!("http://{}", fresh_handle.node.auth_server_handle().local_addr());
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_load-reth"))
+            .args(["engine", "replay"])
+            .arg(&recording)
+            .args(["--engine.url", &url])
+            .arg("--engine.jwt-secret")
+            .arg(&jwt_secret)
+            .output()
+    })
+    .await??;
+    assert!(output.status.success(), "replay failed: {}", String::from_utf8_lossy(&output.stderr));
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["replayed"], expected);
+    assert_eq!(report["divergences"], json!([]));
+
+    Ok(())
+}
@@ -44,18 +44,17 @@ async fn engine_rpc_smoke_serves_caps_and_prev_randao_guard() -> eyre::Result<()
     let has_load_caps = exchange.contains(&"load.blobs.1024".to_string()) &&
         exchange.contains(&"load.prev_randao.0x01".to_string());
     assert!(has_load_caps, "capabilities should include Load flags: {exchange:?}");
+    assert!(!exchange.contains(&"eth".to_string()), "peer capabilities must not be echoed");
 
     // 2. forkchoiceUpdatedV3 should reject payload attributes with wrong prev_randao (0x00).
     let forkchoice = ForkchoiceState::default();
-    let attrs = LoadPayloadAttributes {
-        inner: PayloadAttributes {
-            timestamp: 1,
-            prev_randao: B256::ZERO,
-            suggested_fee_recipient: Address::ZERO,
-            withdrawals: Some(vec![]),
-            parent_beacon_block_root: Some(B256::ZERO),
-        },
-    };
+    let attrs = LoadPayloadAttributes::from(PayloadAttributes {
+        timestamp: 1,
+        prev_randao: B256::ZERO,
+        suggested_fee_recipient: Address::ZERO,
+        withdrawals: Some(vec![]),
+        parent_beacon_block_root: Some(B256::ZERO),
+    });
 
     let response = engine.fork_choice_updated_v3(forkchoice, Some(attrs)).await;
     assert!(response.is_err(), "forkchoiceUpdatedV3 should reject wrong prev_randao");
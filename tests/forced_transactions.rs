@@ -0,0 +1,151 @@
+//! CL-forced transactions: attributes-supplied transactions lead the built block.
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Address, B256};
+use alloy_rpc_types_engine::PayloadAttributes;
+use alloy_signer::Signer;
+use common::{funded_genesis, transfer_tx_with_nonce};
+use eyre::Result;
+use load_reth::{
+    chainspec::{LoadChainSpec, LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY},
+    engine::payload::{LoadPayloadAttributes, LoadPayloadBuilderAttributes},
+    node::LoadNode,
+    LOAD_PREVRANDAO,
+};
+use reth_chainspec::EthChainSpec;
+use reth_e2e_test_utils::{node::NodeTestContext, wallet::Wallet};
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
+use reth_tasks::TaskManager;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forced_transactions_execute_before_pool() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallets = Wallet::new(2).wallet_gen();
+    let genesis =
+        funded_genesis(&wallets.iter().map(|wallet| wallet.address()).collect::<Vec<_>>());
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+    let chain_id = chain_spec.chain().id();
+    for wallet in &mut wallets {
+        wallet.set_chain_id(Some(chain_id));
+    }
+
+    // The CL forces one sender's transfer; another sender's transfer arrives through the pool
+    // and must be ordered after it.
+    let forced = transfer_tx_with_nonce(chain_id, wallets[0].clone(), 0).await?;
+    let pooled = transfer_tx_with_nonce(chain_id, wallets[1].clone(), 0).await?;
+
+    let attrs_gen = {
+        let forced = forced.clone();
+        move |timestamp| {
+            let rpc_attrs = PayloadAttributes {
+                timestamp,
+                prev_randao: B256::from(LOAD_PREVRANDAO),
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: Some(vec![]),
+                parent_beacon_block_root: Some(B256::ZERO),
+            };
+            LoadPayloadBuilderAttributes::try_new(
+                B256::ZERO,
+                LoadPayloadAttributes {
+                    transactions: Some(vec![forced.clone()]),
+                    ..LoadPayloadAttributes::from(rpc_attrs)
+                },
+                3,
+            )
+            .expect("valid payload attributes")
+        }
+    };
+
+    let node_config = NodeConfig::new(chain_spec.clone())
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::default()).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, attrs_gen).await?;
+    node.rpc.inject_tx(pooled.clone()).await?;
+
+    let payload = node.new_payload().await?;
+    let transactions = &payload.block().body().transactions;
+    assert_eq!(transactions.len(), 2, "forced and pooled transactions must both be included");
+    assert_eq!(transactions[0].encoded_2718(), forced.to_vec());
+    assert_eq!(transactions[1].encoded_2718(), pooled.to_vec());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forced_transactions_past_the_block_size_are_skipped() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallet = Wallet::new(1).wallet_gen().remove(0);
+    let mut genesis = funded_genesis(&[wallet.address()]);
+    let chain_id = genesis.config.chain_id;
+    wallet.set_chain_id(Some(chain_id));
+
+    let mut forced = Vec::new();
+    for nonce in 0..3 {
+        forced.push(transfer_tx_with_nonce(chain_id, wallet.clone(), nonce).await?);
+    }
+    // Room for the header margin, the empty withdrawals list and the first two transfers (each
+    // wrapped in a short RLP string header), but not the third.
+    let max_rlp_block_size =
+        1024 + 1 + forced[..2].iter().map(|tx| tx.len() + 2).sum::<usize>() + 16;
+    genesis.config.extra_fields.insert(
+        LOAD_MAX_RLP_BLOCK_SIZE_GENESIS_KEY.to_string(),
+        serde_json::json!(max_rlp_block_size),
+    );
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+
+    let attrs_gen = {
+        let forced = forced.clone();
+        move |timestamp| {
+            let rpc_attrs = PayloadAttributes {
+                timestamp,
+                prev_randao: B256::from(LOAD_PREVRANDAO),
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: Some(vec![]),
+                parent_beacon_block_root: Some(B256::ZERO),
+            };
+            LoadPayloadBuilderAttributes::try_new(
+                B256::ZERO,
+                LoadPayloadAttributes {
+                    transactions: Some(forced.clone()),
+                    ..LoadPayloadAttributes::from(rpc_attrs)
+                },
+                3,
+            )
+            .expect("valid payload attributes")
+        }
+    };
+
+    let node_config = NodeConfig::new(chain_spec.clone())
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let node_handle =
+        NodeBuilder::new(node_config).testing_node(exec).node(LoadNode::default()).launch().await?;
+
+    let mut node = NodeTestContext::new(node_handle.node, attrs_gen).await?;
+
+    // The build succeeds with the transactions that fit instead of failing on the sealed size.
+    let payload = node.new_payload().await?;
+    let block = payload.block();
+    let transactions = &block.body().transactions;
+    assert_eq!(transactions.len(), 2, "the forced transaction past the size limit is skipped");
+    assert_eq!(transactions[0].encoded_2718(), forced[0].to_vec());
+    assert_eq!(transactions[1].encoded_2718(), forced[1].to_vec());
+    assert!(block.rlp_length() <= max_rlp_block_size);
+
+    Ok(())
+}
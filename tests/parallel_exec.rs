@@ -0,0 +1,157 @@
+//! Parallel execution (`parallel-exec` feature) against sequential building: `default_load_payload`
+//! builds the same block from the same pool whether or not batch results are committed, with
+//! transfers that share recipients, feed other senders and pay the fee recipient.
+
+#![cfg(feature = "parallel-exec")]
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy_network::{eip2718::Encodable2718, Ethereum, EthereumWallet, TransactionBuilder};
+use alloy_primitives::{address, Address, Bytes, TxKind, B256, U256};
+use alloy_rpc_types_engine::PayloadAttributes;
+use alloy_rpc_types_eth::TransactionRequest;
+use alloy_signer::Signer;
+use alloy_signer_local::PrivateKeySigner;
+use common::{funded_genesis, load_payload_attributes};
+use eyre::Result;
+use load_reth::{
+    chainspec::LoadChainSpec,
+    engine::{
+        builder::{default_load_payload, LoadBuilderConfig},
+        parallel::LoadParallelConfig,
+        payload::{LoadBuiltPayload, LoadPayloadAttributes, LoadPayloadBuilderAttributes},
+    },
+    metrics::LoadPayloadBuilderMetrics,
+    node::LoadNode,
+    LoadEvmConfig, LOAD_PREVRANDAO,
+};
+use reth::revm::cached::CachedReads;
+use reth_basic_payload_builder::{BuildArguments, BuildOutcome, PayloadConfig};
+use reth_chainspec::EthChainSpec;
+use reth_e2e_test_utils::{node::NodeTestContext, wallet::Wallet};
+use reth_ethereum_payload_builder::EthereumBuilderConfig;
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_payload_primitives::BuiltPayload;
+use reth_provider::HeaderProvider;
+use reth_tasks::TaskManager;
+use reth_transaction_pool::TransactionPool;
+
+const WALLETS: usize = 8;
+const NONCES: u64 = 4;
+/// Recipient shared by a quarter of the transfers.
+const HOT: Address = address!("0x00000000000000000000000000000000000000aa");
+const FEE_RECIPIENT: Address = address!("0x00000000000000000000000000000000000000fe");
+
+async fn transfer_to(
+    chain_id: u64,
+    wallet: PrivateKeySigner,
+    nonce: u64,
+    to: Address,
+) -> Result<Bytes> {
+    let tx = TransactionRequest {
+        nonce: Some(nonce),
+        chain_id: Some(chain_id),
+        gas: Some(21_000),
+        max_fee_per_gas: Some(20e9 as u128),
+        max_priority_fee_per_gas: Some(1e9 as u128),
+        to: Some(TxKind::Call(to)),
+        value: Some(U256::from(100)),
+        ..Default::default()
+    };
+
+    let signer = EthereumWallet::from(wallet);
+    let signed = <TransactionRequest as TransactionBuilder<Ethereum>>::build(tx, &signer).await?;
+    Ok(signed.encoded_2718().into())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parallel_builds_match_sequential_builds() -> Result<()> {
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut wallets = Wallet::new(WALLETS).wallet_gen();
+    let addresses: Vec<Address> = wallets.iter().map(|wallet| wallet.address()).collect();
+    let chain_spec = Arc::new(LoadChainSpec::from_genesis(funded_genesis(&addresses))?);
+    let chain_id = chain_spec.chain().id();
+    wallets.iter_mut().for_each(|wallet| wallet.set_chain_id(Some(chain_id)));
+
+    let node_handle = NodeBuilder::new(
+        NodeConfig::new(chain_spec.clone())
+            .with_unused_ports()
+            .with_rpc(RpcServerArgs::default().with_unused_ports().with_http()),
+    )
+    .testing_node(exec)
+    .node(LoadNode::default())
+    .launch()
+    .await?;
+    let node = NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+    let mut injected = 0;
+    for (index, wallet) in wallets.iter().enumerate() {
+        for nonce in 0..NONCES {
+            let to = match nonce {
+                0 => HOT,
+                1 => addresses[(index + 1) % WALLETS],
+                2 => FEE_RECIPIENT,
+                _ => Address::random(),
+            };
+            node.rpc.inject_tx(transfer_to(chain_id, wallet.clone(), nonce, to).await?).await?;
+            injected += 1;
+        }
+    }
+
+    let parent = Arc::new(node.inner.provider.sealed_header(0)?.expect("genesis header"));
+    let attributes = LoadPayloadBuilderAttributes::try_new(
+        parent.hash(),
+        LoadPayloadAttributes::from(PayloadAttributes {
+            timestamp: parent.timestamp + 1,
+            prev_randao: B256::from(LOAD_PREVRANDAO),
+            suggested_fee_recipient: FEE_RECIPIENT,
+            withdrawals: Some(vec![]),
+            parent_beacon_block_root: Some(B256::ZERO),
+        }),
+        3,
+    )?;
+    let pool = node.inner.pool.clone();
+    let build = |builder_config: LoadBuilderConfig| -> Result<LoadBuiltPayload> {
+        let outcome = default_load_payload(
+            LoadEvmConfig::new(chain_spec.clone()),
+            node.inner.provider.clone(),
+            pool.clone(),
+            builder_config,
+            &LoadPayloadBuilderMetrics::new(),
+            BuildArguments::new(
+                CachedReads::default(),
+                PayloadConfig::new(parent.clone(), attributes.clone()),
+                Default::default(),
+                None,
+            ),
+            |attributes| pool.best_transactions_with_attributes(attributes),
+        )?;
+        let BuildOutcome::Better { payload, .. } = outcome else {
+            eyre::bail!("build must produce a payload: {outcome:?}");
+        };
+        Ok(payload)
+    };
+
+    let sequential = build(LoadBuilderConfig::new(EthereumBuilderConfig::new()))?;
+    assert_eq!(sequential.block().body().transactions.len(), injected);
+    for threads in [2, 4] {
+        let parallel = build(
+            LoadBuilderConfig::new(EthereumBuilderConfig::new())
+                .with_parallel(Some(LoadParallelConfig::new(threads).with_batch_size(8))),
+        )?;
+        assert_eq!(
+            parallel.block().body().transactions,
+            sequential.block().body().transactions,
+            "{threads} threads"
+        );
+        assert_eq!(parallel.block().hash(), sequential.block().hash(), "{threads} threads");
+        assert_eq!(parallel.fees(), sequential.fees(), "{threads} threads");
+    }
+
+    Ok(())
+}
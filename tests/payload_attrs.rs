@@ -19,7 +19,6 @@ use load_reth::{
 use reth::chainspec::{EthereumHardfork, ForkCondition};
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_e2e_test_utils::node::NodeTestContext;
-use reth_ethereum_engine_primitives::BlobSidecars;
 use reth_node_builder::NodeBuilder;
 use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
 use reth_payload_primitives::PayloadBuilderAttributes;
@@ -102,7 +101,7 @@ async fn prague_requests_accepted_after_activation() -> Result<()> {
             };
             LoadPayloadBuilderAttributes::try_new(
                 B256::ZERO,
-                LoadPayloadAttributes { inner: rpc_attrs },
+                LoadPayloadAttributes::from(rpc_attrs),
                 3,
             )
             .expect("valid payload attributes")
@@ -124,12 +123,7 @@ async fn prague_requests_accepted_after_activation() -> Result<()> {
 
     let payload = node.new_payload().await?;
 
-    let versioned_hashes: Vec<B256> = match payload.inner.sidecars() {
-        BlobSidecars::Eip4844(sidecars) => {
-            sidecars.iter().flat_map(|sidecar| sidecar.versioned_hashes()).collect()
-        }
-        _ => Vec::new(),
-    };
+    let versioned_hashes = payload.versioned_hashes();
     assert!(
         !versioned_hashes.is_empty(),
         "Prague acceptance test requires blob versioned hashes in the sidecar"
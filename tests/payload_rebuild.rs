@@ -0,0 +1,235 @@
+//! `load-reth payload rebuild` against a real datadir: a block carrying blobs is rebuilt with its
+//! sidecars read back from the blob archive, and a pending blob transaction with its sidecar read
+//! from the datadir's blob store.
+//! Uses a high-stack thread due to blob size (131 KB each).
+
+mod common;
+
+use std::{
+    path::Path,
+    process::Command,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_signer::Signer;
+use common::{blob_tx_with_nonce, funded_genesis, load_payload_attributes, test_wallet};
+use load_reth::{chainspec::LoadChainSpec, node::LoadNode, LoadArgs, LOAD_PREVRANDAO};
+use reth_chainspec::EthChainSpec;
+use reth_db::init_db;
+use reth_e2e_test_utils::node::NodeTestContext;
+use reth_node_builder::NodeBuilder;
+use reth_node_core::{
+    args::{DatadirArgs, DefaultEngineValues, RpcServerArgs},
+    node_config::NodeConfig,
+};
+use reth_payload_primitives::BuiltPayload;
+use reth_tasks::TaskManager;
+use reth_transaction_pool::{PoolTransaction, TransactionPool};
+use serde_json::{json, Value};
+
+/// Waits until the archive has written a block manifest.
+async fn wait_for_archived_block(dir: &Path) -> eyre::Result<()> {
+    for _ in 0..100 {
+        if std::fs::read_dir(dir.join("blocks"))?
+            .filter_map(Result::ok)
+            .any(|entry| !entry.file_name().to_string_lossy().ends_with(".tmp"))
+        {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    eyre::bail!("block was not archived")
+}
+
+/// Runs `load-reth payload rebuild` and returns its JSON report.
+fn rebuild(
+    chain: &Path,
+    datadir: &Path,
+    parent: B256,
+    timestamp: u64,
+    transactions: &[Bytes],
+    extra_args: &[&str],
+) -> eyre::Result<Value> {
+    let scratch = tempfile::tempdir()?;
+    let attributes = scratch.path().join("attributes.json");
+    std::fs::write(
+        &attributes,
+        json!({
+            "timestamp": format!("{timestamp:#x}"),
+            "prevRandao": B256::from(LOAD_PREVRANDAO),
+            "suggestedFeeRecipient": Address::ZERO,
+            "withdrawals": [],
+            "parentBeaconBlockRoot": B256::ZERO,
+        })
+        .to_string(),
+    )?;
+    let transactions_path = scratch.path().join("transactions.json");
+    std::fs::write(&transactions_path, serde_json::to_string(transactions)?)?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_load-reth"))
+        .args(["payload", "rebuild", "--chain"])
+        .arg(chain)
+        .arg("--datadir")
+        .arg(datadir)
+        .arg("--parent")
+        .arg(parent.to_string())
+        .arg("--attributes")
+        .arg(&attributes)
+        .arg("--transactions")
+        .arg(&transactions_path)
+        .args(extra_args)
+        .output()?;
+    if !output.status.success() {
+        eyre::bail!("payload rebuild failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+fn included_hashes(report: &Value) -> Vec<String> {
+    report["transactions"]
+        .as_array()
+        .expect("transactions array")
+        .iter()
+        .map(|tx| tx["hash"].as_str().expect("hash").to_string())
+        .collect()
+}
+
+#[test]
+fn rebuilds_blob_payloads_from_datadir() {
+    std::thread::Builder::new()
+        .name("payload_rebuild".into())
+        .stack_size(32 * 1024 * 1024)
+        .spawn(|| {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .thread_stack_size(8 * 1024 * 1024)
+                .build()
+                .expect("tokio runtime");
+            match rt.block_on(async move {
+                let _ = DefaultEngineValues::default().with_persistence_threshold(0).try_init();
+                let tasks = TaskManager::current();
+                let exec = tasks.executor();
+
+                let mut wallet = test_wallet();
+                let genesis = funded_genesis(&[wallet.address()]);
+                let temp_dir = tempfile::tempdir()?;
+                let chain_path = temp_dir.path().join("genesis.json");
+                std::fs::write(&chain_path, serde_json::to_string(&genesis)?)?;
+                let chain_spec = Arc::new(LoadChainSpec::from_genesis(genesis)?);
+                let chain_id = chain_spec.chain().id();
+                wallet = wallet.with_chain_id(Some(chain_id));
+
+                let datadir = temp_dir.path().join("datadir");
+                let node_config = NodeConfig::new(chain_spec.clone())
+                    .with_datadir_args(DatadirArgs {
+                        datadir: datadir.clone().into(),
+                        ..Default::default()
+                    })
+                    .with_unused_ports()
+                    .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+                let data_dir = node_config.datadir();
+                let db = Arc::new(init_db(data_dir.db(), node_config.db.database_args())?);
+
+                let node_handle = NodeBuilder::new(node_config)
+                    .with_database(db.clone())
+                    .with_launch_context(exec)
+                    .node(LoadNode::new(LoadArgs { blob_archive: true, ..Default::default() }))
+                    .launch()
+                    .await?;
+                let mut node =
+                    NodeTestContext::new(node_handle.node, load_payload_attributes).await?;
+
+                // Block 1 carries two blobs and is archived at newPayload.
+                let tx = blob_tx_with_nonce(chain_id, wallet.clone(), 0, 2).await?;
+                node.rpc.inject_tx(tx).await?;
+                let payload = node.new_payload().await?;
+                let block = payload.block().clone();
+                node.submit_payload(payload).await?;
+                node.update_forkchoice(block.header().parent_hash, block.hash()).await?;
+                wait_for_archived_block(&data_dir.data_dir().join("blob-archive")).await?;
+
+                // A second blob transaction stays pending, with its sidecar in the blob store.
+                let tx = blob_tx_with_nonce(chain_id, wallet, 1, 1).await?;
+                let pending_hash = node.rpc.inject_tx(tx).await?;
+                let pending = node.inner.pool.get(&pending_hash).expect("pending blob tx");
+                let pending_tx: Bytes =
+                    pending.transaction.clone_into_consensus().into_inner().encoded_2718().into();
+
+                if let Some(done_rx) = node.inner.add_ons_handle.engine_shutdown.shutdown() {
+                    tokio::time::timeout(Duration::from_secs(2), done_rx)
+                        .await
+                        .expect("engine shutdown timed out")
+                        .expect("engine shutdown channel closed");
+                }
+                drop(node);
+                drop(db);
+                assert!(tasks.graceful_shutdown_with_timeout(Duration::from_secs(2)));
+
+                // Whatever finalization left of block 1's sidecars in the blob store goes, so
+                // only the archive can serve them.
+                let blob_tx_hash = *block.body().transactions[0].tx_hash();
+                let stored = data_dir.blobstore().join(format!("{blob_tx_hash:x}"));
+                if let Err(err) = std::fs::remove_file(stored) {
+                    if err.kind() != std::io::ErrorKind::NotFound {
+                        return Err(err.into());
+                    }
+                }
+                let block_txs: Vec<Bytes> =
+                    block.body().transactions.iter().map(|tx| tx.encoded_2718().into()).collect();
+                let parent = block.header().parent_hash;
+                let timestamp = block.header().timestamp;
+
+                let deadline = Instant::now() + Duration::from_secs(5);
+                let from_archive = loop {
+                    // The database lock may outlive the node briefly.
+                    match rebuild(
+                        &chain_path,
+                        &datadir,
+                        parent,
+                        timestamp,
+                        &block_txs,
+                        &["--load.blob-archive"],
+                    ) {
+                        Ok(report) => break report,
+                        Err(_) if Instant::now() < deadline => {
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                        }
+                        Err(err) => return Err(err),
+                    }
+                };
+                assert_eq!(from_archive["blobCount"], 2, "{from_archive}");
+                assert_eq!(included_hashes(&from_archive), vec![blob_tx_hash.to_string()]);
+                assert_eq!(from_archive["skipped"], json!([]));
+
+                // Without the archive the same transaction has no sidecar to include.
+                let without_archive =
+                    rebuild(&chain_path, &datadir, parent, timestamp, &block_txs, &[])?;
+                assert_eq!(without_archive["blobCount"], 0, "{without_archive}");
+                assert!(included_hashes(&without_archive).is_empty());
+                assert_eq!(without_archive["skipped"].as_array().map(Vec::len), Some(1));
+
+                // The pending transaction's sidecar comes from the datadir's blob store.
+                let next = rebuild(
+                    &chain_path,
+                    &datadir,
+                    block.hash(),
+                    timestamp + 1,
+                    std::slice::from_ref(&pending_tx),
+                    &[],
+                )?;
+                assert_eq!(next["blobCount"], 1, "{next}");
+                assert_eq!(included_hashes(&next), vec![pending_hash.to_string()]);
+
+                Ok::<_, eyre::Report>(())
+            }) {
+                Ok(()) => {}
+                Err(err) => panic!("payload rebuild test failed: {err:?}"),
+            }
+        })
+        .expect("spawn payload rebuild thread")
+        .join()
+        .expect("payload rebuild thread panicked");
+}